@@ -28,6 +28,11 @@ pub struct Cli {
 	#[allow(missing_docs)]
 	#[structopt(flatten)]
 	pub run: RunCmd,
+
+	/// Index contract events by contract and topic in the client database, enabling the
+	/// `contracts_getEvents` and `contracts_subscribeEvents` RPC methods.
+	#[structopt(long = "contracts-event-index")]
+	pub contracts_event_index: bool,
 }
 
 /// Possible subcommands of the main binary.
@@ -72,7 +72,7 @@ pub fn run() -> Result<()> {
 			let runner = cli.create_runner(&cli.run)?;
 			runner.run_node_until_exit(|config| match config.role {
 				Role::Light => service::new_light(config),
-				_ => service::new_full(config),
+				_ => service::new_full(config, cli.contracts_event_index),
 			})
 		}
 		Some(Subcommand::Inspect(cmd)) => {
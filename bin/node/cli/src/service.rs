@@ -46,6 +46,9 @@ use sp_core::traits::BareCryptoStorePtr;
 /// be able to perform chain operations.
 macro_rules! new_full_start {
 	($config:expr) => {{
+		new_full_start!($config, false)
+	}};
+	($config:expr, $contracts_event_index:expr) => {{
 		use std::sync::Arc;
 
 		let mut import_setup = None;
@@ -115,7 +118,13 @@ macro_rules! new_full_start {
 				let shared_authority_set = grandpa_link.shared_authority_set().clone();
 				let shared_voter_state = grandpa::SharedVoterState::empty();
 
-				rpc_setup = Some((shared_voter_state.clone()));
+				let contract_events = if $contracts_event_index {
+					Some(Arc::new(node_rpc::ContractEventsIndex::new(builder.client().clone())))
+				} else {
+					None
+				};
+
+				rpc_setup = Some((shared_voter_state.clone(), contract_events.clone()));
 
 				let babe_link = import_setup.as_ref().map(|s| &s.2)
 					.expect("BabeLink is present for full services or set up failed; qed.");
@@ -144,6 +153,7 @@ macro_rules! new_full_start {
 							shared_voter_state: shared_voter_state.clone(),
 							shared_authority_set: shared_authority_set.clone(),
 						},
+						contract_events: contract_events.clone(),
 					};
 
 					node_rpc::create_full(deps)
@@ -164,6 +174,7 @@ type BabeBlockImport = sc_consensus_babe::BabeBlockImport<Block, FullClient, Gra
 /// Creates a full service from the configuration.
 pub fn new_full_base(
 	config: Configuration,
+	contracts_event_index: bool,
 	with_startup_data: impl FnOnce(&BabeBlockImport, &sc_consensus_babe::BabeLink<Block>)
 ) -> Result<(
 	TaskManager,
@@ -184,7 +195,7 @@ pub fn new_full_base(
 	);
 
 	let (builder, mut import_setup, inherent_data_providers, mut rpc_setup) =
-		new_full_start!(config);
+		new_full_start!(config, contracts_event_index);
 
 	let ServiceComponents {
 		client, transaction_pool, task_manager, keystore, network, select_chain,
@@ -200,9 +211,14 @@ pub fn new_full_base(
 	let (block_import, grandpa_link, babe_link) = import_setup.take()
 		.expect("Link Half and Block Import are present for Full Services or setup failed before. qed");
 
-	let shared_voter_state = rpc_setup.take()
+	let (shared_voter_state, contract_events) = rpc_setup.take()
 		.expect("The SharedVoterState is present for Full Services or setup failed before. qed");
 
+	if let Some(contract_events) = contract_events {
+		task_manager.spawn_handle().spawn("contracts-event-index", contract_events.clone().run());
+		task_manager.spawn_handle().spawn_blocking("contracts-event-backfill", contract_events.backfill());
+	}
+
 	(with_startup_data)(&block_import, &babe_link);
 
 	if let sc_service::config::Role::Authority { .. } = &role {
@@ -323,9 +339,12 @@ pub fn new_full_base(
 }
 
 /// Builds a new service for a full client.
-pub fn new_full(config: Configuration)
+///
+/// With `contracts_event_index` the node indexes contract events to serve `contracts_getEvents`
+/// and `contracts_subscribeEvents`.
+pub fn new_full(config: Configuration, contracts_event_index: bool)
 -> Result<TaskManager, ServiceError> {
-	new_full_base(config, |_, _| ()).map(|(task_manager, _, _, _, _)| {
+	new_full_base(config, contracts_event_index, |_, _| ()).map(|(task_manager, _, _, _, _)| {
 		task_manager
 	})
 }
@@ -499,7 +518,7 @@ mod tests {
 			|config| {
 				let mut setup_handles = None;
 				let (keep_alive, inherent_data_providers, client, network, transaction_pool) =
-					new_full_base(config,
+					new_full_base(config, false,
 						|
 							block_import: &sc_consensus_babe::BabeBlockImport<Block, _, _>,
 							babe_link: &sc_consensus_babe::BabeLink<Block>,
@@ -626,7 +645,7 @@ mod tests {
 				let check_era = frame_system::CheckEra::from(Era::Immortal);
 				let check_nonce = frame_system::CheckNonce::from(index);
 				let check_weight = frame_system::CheckWeight::new();
-				let payment = pallet_transaction_payment::ChargeTransactionPayment::from(0);
+				let payment = pallet_asset_tx_payment::ChargeAssetTxPayment::from(0, None);
 				let validate_grandpa_equivocation = pallet_grandpa::ValidateEquivocationReport::new();
 				let extra = (
 					check_spec_version,
@@ -664,7 +683,7 @@ mod tests {
 		sc_service_test::consensus(
 			crate::chain_spec::tests::integration_test_config_with_two_authorities(),
 			|config| {
-				let (keep_alive, _, client, network, transaction_pool) = new_full_base(config, |_, _| ())?;
+				let (keep_alive, _, client, network, transaction_pool) = new_full_base(config, false, |_, _| ())?;
 				Ok(sc_service_test::TestNetComponents::new(keep_alive, client, network, transaction_pool))
 			},
 			|config| {
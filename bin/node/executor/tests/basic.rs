@@ -32,8 +32,9 @@ use frame_system::{self, EventRecord, Phase};
 
 use node_runtime::{
 	Header, Block, UncheckedExtrinsic, CheckedExtrinsic, Call, Runtime, Balances,
-	System, TransactionPayment, Event,
+	System, TransactionPayment, Event, Contracts, Origin,
 	constants::currency::*,
+	impls::ContractsChainExtension,
 };
 use node_primitives::{Balance, Hash};
 use wabt;
@@ -647,6 +648,76 @@ fn deploying_wasm_contract_should_work() {
 	});
 }
 
+const CODE_CHAIN_EXTENSION: &str = r#"
+(module
+	(import "env" "ext_scratch_size" (func $ext_scratch_size (result i32)))
+	(import "env" "ext_scratch_read" (func $ext_scratch_read (param i32 i32 i32)))
+	(import "env" "ext_call_chain_extension" (func $ext_call_chain_extension (param i32 i32 i32) (result i32)))
+	(import "env" "memory" (memory 1 1))
+
+	(func (export "deploy") (result i32)
+		(i32.const 0)
+	)
+
+	;; Calls the chain extension function whose id is the first 4 bytes (LE) of the input with
+	;; the rest of the input, and returns the return value of `ext_call_chain_extension`.
+	(func (export "call") (result i32)
+		(local $buf_size i32)
+		(set_local $buf_size (call $ext_scratch_size))
+		(call $ext_scratch_read
+			(i32.const 0)
+			(i32.const 0)
+			(get_local $buf_size)
+		)
+		(call $ext_call_chain_extension
+			(i32.load (i32.const 0))
+			(i32.const 4)
+			(i32.sub
+				(get_local $buf_size)
+				(i32.const 4)
+			)
+		)
+	)
+)
+"#;
+
+#[test]
+fn contracts_can_only_dispatch_keep_alive_transfers() {
+	let code = wabt::wat2wasm(CODE_CHAIN_EXTENSION).unwrap();
+	let code_hash = <Runtime as frame_system::Trait>::Hashing::hash(&code);
+	let addr = <Runtime as pallet_contracts::Trait>::DetermineContractAddress::contract_address_for(
+		&code_hash,
+		&[],
+		&charlie(),
+	);
+	let dispatch = |call: pallet_balances::Call<Runtime>| {
+		let mut input = ContractsChainExtension::DISPATCH.to_le_bytes().to_vec();
+		input.extend(Call::Balances(call).encode());
+		Contracts::bare_call(charlie(), addr.clone(), 0, 500_000_000, input).unwrap().status
+	};
+
+	new_test_ext(COMPACT_CODE, false).execute_with(|| {
+		Contracts::put_code(Origin::signed(charlie()), code).unwrap();
+		Contracts::instantiate(
+			Origin::signed(charlie()),
+			10 * DOLLARS,
+			500_000_000,
+			code_hash,
+			Vec::new(),
+		).unwrap();
+		let endowment = Balances::free_balance(&addr);
+		let dest = pallet_indices::address::Address::Id(bob());
+
+		// Keep-alive transfers are dispatched as signed by the contract.
+		assert_eq!(dispatch(pallet_balances::Call::transfer_keep_alive(dest.clone(), 1 * DOLLARS)), 0);
+		assert_eq!(Balances::free_balance(&addr), endowment - 1 * DOLLARS);
+
+		// Transfers that could reap the contract are rejected.
+		assert_eq!(dispatch(pallet_balances::Call::transfer(dest, 1 * DOLLARS)), 1);
+		assert_eq!(Balances::free_balance(&addr), endowment - 1 * DOLLARS);
+	});
+}
+
 #[test]
 fn wasm_big_block_import_fails() {
 	let mut t = new_test_ext(COMPACT_CODE, false);
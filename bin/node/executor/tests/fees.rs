@@ -19,13 +19,13 @@ use codec::{Encode, Joiner};
 use frame_support::{
 	StorageValue, StorageMap,
 	traits::Currency,
-	weights::{GetDispatchInfo, constants::ExtrinsicBaseWeight, IdentityFee, WeightToFeePolynomial},
+	weights::{GetDispatchInfo, IdentityFee, WeightToFeePolynomial},
 };
 use sp_core::NeverNativeValue;
 use sp_runtime::{Perbill, FixedPointNumber};
 use node_runtime::{
 	CheckedExtrinsic, Call, Runtime, Balances, TransactionPayment, Multiplier,
-	TransactionByteFee,
+	TransactionByteFee, ExtrinsicBaseWeight,
 	constants::currency::*,
 };
 use node_primitives::Balance;
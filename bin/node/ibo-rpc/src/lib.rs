@@ -0,0 +1,103 @@
+// This file is part of Substrate.
+
+// Copyright (C) 2020 Parity Technologies (UK) Ltd.
+// SPDX-License-Identifier: Apache-2.0
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// 	http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Node-specific RPC methods for interaction with ibo proposals.
+
+use std::sync::Arc;
+
+use codec::Codec;
+use jsonrpc_core::{Error, ErrorCode, Result};
+use jsonrpc_derive::rpc;
+use sp_api::ProvideRuntimeApi;
+use sp_blockchain::HeaderBackend;
+use sp_core::U256;
+use sp_rpc::number::NumberOrHex;
+use sp_runtime::{generic::BlockId, traits::Block as BlockT};
+
+pub use self::gen_client::Client as IboClient;
+pub use ibo_rpc_runtime_api::{self as runtime_api, IboApi as IboRuntimeApi};
+
+const RUNTIME_ERROR: i64 = 1;
+
+/// Ibo RPC methods.
+#[rpc]
+pub trait IboApi<BlockHash, AccountId, Balance> {
+	/// Returns the voting power of `who` for the proposal `id`.
+	///
+	/// This is the total balance `who` had when the proposal started voting, and caps the amount
+	/// it can vote with. Zero if the proposal is not being voted on.
+	#[rpc(name = "ibo_votingPower")]
+	fn voting_power(
+		&self,
+		id: u32,
+		who: AccountId,
+		at: Option<BlockHash>,
+	) -> Result<NumberOrHex>;
+}
+
+/// An implementation of ibo specific RPC methods.
+pub struct Ibo<C, B> {
+	client: Arc<C>,
+	_marker: std::marker::PhantomData<B>,
+}
+
+impl<C, B> Ibo<C, B> {
+	/// Create new `Ibo` with the given reference to the client.
+	pub fn new(client: Arc<C>) -> Self {
+		Ibo {
+			client,
+			_marker: Default::default(),
+		}
+	}
+}
+
+impl<C, Block, AccountId, Balance> IboApi<<Block as BlockT>::Hash, AccountId, Balance>
+	for Ibo<C, Block>
+where
+	Block: BlockT,
+	C: Send + Sync + 'static + ProvideRuntimeApi<Block> + HeaderBackend<Block>,
+	C::Api: IboRuntimeApi<Block, AccountId, Balance>,
+	AccountId: Codec,
+	Balance: Codec + Into<U256>,
+{
+	fn voting_power(
+		&self,
+		id: u32,
+		who: AccountId,
+		at: Option<<Block as BlockT>::Hash>,
+	) -> Result<NumberOrHex> {
+		let api = self.client.runtime_api();
+		let at = BlockId::hash(at.unwrap_or_else(||
+			// If the block hash is not supplied assume the best block.
+			self.client.info().best_hash));
+
+		let power = api
+			.voting_power(&at, id, who)
+			.map_err(|e| runtime_error_into_rpc_err(e))?;
+
+		Ok(NumberOrHex::Hex(power.into()))
+	}
+}
+
+/// Converts a runtime trap into an RPC error.
+fn runtime_error_into_rpc_err(err: impl std::fmt::Debug) -> Error {
+	Error {
+		code: ErrorCode::ServerError(RUNTIME_ERROR),
+		message: "Runtime trapped".into(),
+		data: Some(format!("{:?}", err).into()),
+	}
+}
@@ -32,8 +32,8 @@
 
 use std::sync::Arc;
 
-use node_primitives::{Block, BlockNumber, AccountId, Index, Balance, Hash};
-use node_runtime::UncheckedExtrinsic;
+use node_primitives::{Block, BlockNumber, AccountId, Index, Balance, Hash, Moment};
+use node_runtime::{UncheckedExtrinsic, impls::SwapAction};
 use sp_api::ProvideRuntimeApi;
 use sp_transaction_pool::TransactionPool;
 use sp_blockchain::{Error as BlockChainError, HeaderMetadata, HeaderBackend};
@@ -47,6 +47,9 @@ use sc_finality_grandpa::{SharedVoterState, SharedAuthoritySet};
 use sc_finality_grandpa_rpc::GrandpaRpcHandler;
 use sc_rpc_api::DenyUnsafe;
 use sp_block_builder::BlockBuilder;
+use sc_client_api::AuxStore;
+
+pub use pallet_contracts_rpc::ContractEventsIndex;
 
 /// Light client extra dependencies.
 pub struct LightDeps<C, F, P> {
@@ -92,6 +95,8 @@ pub struct FullDeps<C, P, SC> {
 	pub babe: BabeDeps,
 	/// GRANDPA specific dependencies.
 	pub grandpa: GrandpaDeps,
+	/// The contract events index, if the node maintains one.
+	pub contract_events: Option<Arc<ContractEventsIndex<C, Block, AccountId, Balance>>>,
 }
 
 /// Instantiate all Full RPC extensions.
@@ -100,18 +105,30 @@ pub fn create_full<C, P, M, SC>(
 ) -> jsonrpc_core::IoHandler<M> where
 	C: ProvideRuntimeApi<Block>,
 	C: HeaderBackend<Block> + HeaderMetadata<Block, Error=BlockChainError> + 'static,
+	C: sc_client_api::BlockBackend<Block>,
+	C: AuxStore,
 	C: Send + Sync + 'static,
 	C::Api: substrate_frame_rpc_system::AccountNonceApi<Block, AccountId, Index>,
-	C::Api: pallet_contracts_rpc::ContractsRuntimeApi<Block, AccountId, Balance, BlockNumber>,
+	C::Api: pallet_atomic_swap_rpc::AtomicSwapRuntimeApi<Block, AccountId, SwapAction, BlockNumber, Moment>,
+	C::Api: pallet_contracts_rpc::ContractsRuntimeApi<Block, AccountId, Balance, BlockNumber, Hash>,
+	C::Api: pallet_evm_rpc::EvmRuntimeApi<Block>,
+	C::Api: ibo_rpc::IboRuntimeApi<Block, AccountId, Balance>,
+	C::Api: pallet_recovery_rpc::RecoveryRuntimeApi<Block, AccountId, BlockNumber, Balance>,
+	C::Api: pallet_staking_rpc::StakingRuntimeApi<Block, AccountId>,
 	C::Api: pallet_transaction_payment_rpc::TransactionPaymentRuntimeApi<Block, Balance, UncheckedExtrinsic>,
 	C::Api: BabeApi<Block>,
 	C::Api: BlockBuilder<Block>,
 	P: TransactionPool + 'static,
-	M: jsonrpc_core::Metadata + Default,
+	M: jsonrpc_core::Metadata + jsonrpc_pubsub::PubSubMetadata + Default,
 	SC: SelectChain<Block> +'static,
 {
 	use substrate_frame_rpc_system::{FullSystem, SystemApi};
-	use pallet_contracts_rpc::{Contracts, ContractsApi};
+	use pallet_atomic_swap_rpc::{AtomicSwap, AtomicSwapApi};
+	use pallet_contracts_rpc::{Contracts, ContractsApi, ContractsEvents, ContractsEventsApi};
+	use pallet_evm_rpc::{Evm, EvmApi};
+	use ibo_rpc::{Ibo, IboApi};
+	use pallet_recovery_rpc::{Recovery, RecoveryApi};
+	use pallet_staking_rpc::{Staking, StakingApi};
 	use pallet_transaction_payment_rpc::{TransactionPayment, TransactionPaymentApi};
 
 	let mut io = jsonrpc_core::IoHandler::default();
@@ -122,6 +139,7 @@ pub fn create_full<C, P, M, SC>(
 		deny_unsafe,
 		babe,
 		grandpa,
+		contract_events,
 	} = deps;
 	let BabeDeps {
 		keystore,
@@ -142,6 +160,26 @@ pub fn create_full<C, P, M, SC>(
 	io.extend_with(
 		ContractsApi::to_delegate(Contracts::new(client.clone()))
 	);
+	if let Some(contract_events) = contract_events {
+		io.extend_with(
+			ContractsEventsApi::to_delegate(ContractsEvents::new(contract_events))
+		);
+	}
+	io.extend_with(
+		EvmApi::to_delegate(Evm::new(client.clone()))
+	);
+	io.extend_with(
+		AtomicSwapApi::to_delegate(AtomicSwap::<_, _, SwapAction>::new(client.clone()))
+	);
+	io.extend_with(
+		IboApi::to_delegate(Ibo::new(client.clone()))
+	);
+	io.extend_with(
+		RecoveryApi::to_delegate(Recovery::new(client.clone()))
+	);
+	io.extend_with(
+		StakingApi::to_delegate(Staking::new(client.clone()))
+	);
 	io.extend_with(
 		TransactionPaymentApi::to_delegate(TransactionPayment::new(client.clone()))
 	);
@@ -9,14 +9,22 @@ use self::treasury::AccountGetter;
 use crate::constants::{congress::*, referendum::*};
 use codec::{Decode, Encode};
 use collective::Contain;
-use frame_support::traits::{Currency, ReservableCurrency};
+use frame_support::traits::{Currency, ReservableCurrency, StoredMap};
 use frame_support::{
     debug, decl_error, decl_event, decl_module, decl_storage, dispatch::DispatchResult, ensure,
-    storage::IterableStorageMap, StorageMap, StorageValue,
+    storage::{IterableStorageDoubleMap, IterableStorageMap},
+    traits::Get,
+    weights::Weight,
+    StorageDoubleMap, StorageMap, StorageValue,
 };
-use sp_runtime::traits::SaturatedConversion;
+use lite_json::json::JsonValue;
+use sp_core::crypto::KeyTypeId;
+use sp_runtime::offchain::{http, Duration};
+use sp_runtime::traits::{SaturatedConversion, Saturating, Zero};
 use sp_std::convert::TryInto;
+use sp_std::marker::PhantomData;
 use sp_std::vec::Vec;
+use system::offchain::{AppCrypto, CreateSignedTransaction, SendSignedTransaction, Signer};
 use system::{ensure_root, ensure_signed};
 
 pub type BalanceOf<T> =
@@ -28,11 +36,47 @@ pub const ZERO_GOALS_U128: (u128, u128) = (0, 0);
 pub const TOTAL_REWARDS: u64 = 100_000;
 pub const MAX_SUPPLY: u64 = 1_000_000_000;
 
-pub trait Trait: system::Trait + timestamp::Trait {
+/// Key type of the accounts the offchain worker signs metrics reports with.
+pub const KEY_TYPE: KeyTypeId = KeyTypeId(*b"ibo!");
+/// How long the offchain worker waits for the metrics endpoint to answer.
+pub const METRICS_FETCH_TIMEOUT_MS: u64 = 2_000;
+
+/// Crypto of the metrics reporters, `sr25519` keys of type [`KEY_TYPE`].
+pub mod crypto {
+    use super::KEY_TYPE;
+    use sp_runtime::app_crypto::{app_crypto, sr25519};
+    use sp_runtime::{MultiSignature, MultiSigner};
+
+    app_crypto!(sr25519, KEY_TYPE);
+
+    pub struct AuthId;
+
+    impl frame_system::offchain::AppCrypto<MultiSigner, MultiSignature> for AuthId {
+        type RuntimeAppPublic = Public;
+        type GenericSignature = sp_core::sr25519::Signature;
+        type GenericPublic = sp_core::sr25519::Public;
+    }
+}
+
+pub trait Trait: system::Trait + timestamp::Trait + CreateSignedTransaction<Call<Self>> {
     type Event: From<Event<Self>> + Into<<Self as system::Trait>::Event>;
     type Currency: ReservableCurrency<Self::AccountId> + Currency<Self::AccountId>;
     type CouncilMembers: collective::Contain<Self::AccountId>;
     type Treasury: treasury::AccountGetter<Self::AccountId>;
+    /// The identifier type of the keys the offchain worker signs metrics reports with.
+    type AuthorityId: AppCrypto<Self::Public, Self::Signature>;
+    /// The overarching dispatch call type.
+    type Call: From<Call<Self>>;
+    /// Number of blocks between two metrics fetches of the offchain worker.
+    type MetricsInterval: Get<Self::BlockNumber>;
+    /// Number of blocks a metrics report counts towards the aggregated metrics.
+    type MetricsReportLifetime: Get<Self::BlockNumber>;
+    /// Minimum number of fresh reports needed to aggregate the metrics of a token.
+    type MinMetricsReports: Get<u32>;
+    /// Maximum number of metrics reporters.
+    type MaxMetricsReporters: Get<u32>;
+    /// Maximum number of voting snapshots removed per block once voting ended.
+    type MaxSnapshotRemovals: Get<u32>;
 }
 
 #[derive(Encode, Decode, Clone, Default, Debug, PartialEq, Eq)]
@@ -116,6 +160,32 @@ impl Default for ProposalState {
     }
 }
 
+/// Market metrics of a listed token.
+#[derive(Encode, Decode, Clone, Default, Debug, PartialEq, Eq)]
+pub struct TokenMetrics {
+    pub circulating_supply: u128,
+    pub holders: u64,
+    pub volume: u128,
+}
+
+impl TokenMetrics {
+    /// Whether every metric is at least the one of `minimum`.
+    pub fn reaches(&self, minimum: &TokenMetrics) -> bool {
+        self.circulating_supply >= minimum.circulating_supply
+            && self.holders >= minimum.holders
+            && self.volume >= minimum.volume
+    }
+}
+
+/// Metrics thresholds that open Rise and Fall proposals.
+#[derive(Encode, Decode, Clone, Default, Debug, PartialEq, Eq)]
+pub struct MarketThresholds {
+    /// A Growth market token whose metrics all reach these opens a Rise proposal.
+    pub rise: TokenMetrics,
+    /// A Main market token with any metric below these opens a Fall proposal.
+    pub fall: TokenMetrics,
+}
+
 #[derive(Encode, Decode, Clone, Debug, PartialEq, Eq)]
 pub struct StakingInfo<Balance> {
     pub proposal_id: ProposalId,
@@ -140,6 +210,37 @@ decl_storage! {
         pub Staking get(fn staking): map hasher(twox_64_concat) T::AccountId => Vec<StakingInfo<BalanceOf<T>>>;
 
         pub IdGenerator get(fn id_generator): ProposalId = 0;
+
+        /// The total balance of an account when the proposal it is keyed by started voting.
+        /// Recorded the first time the balance changes while the proposal is being voted on.
+        pub VotingSnapshots get(fn voting_snapshot):
+            double_map hasher(twox_64_concat) ProposalId, hasher(blake2_128_concat) T::AccountId
+            => Option<BalanceOf<T>>;
+
+        /// Proposals that ended voting and whose voting snapshots are still being removed, oldest
+        /// first.
+        pub StaleSnapshots get(fn stale_snapshots): Vec<ProposalId>;
+
+        /// Accounts allowed to report token metrics.
+        pub MetricsReporters get(fn metrics_reporters): Vec<T::AccountId>;
+
+        /// Base URL the offchain worker fetches the metrics of a token from, as
+        /// `<endpoint>/<token name>`. Metrics are not fetched while it is empty.
+        pub MetricsEndpoint get(fn metrics_endpoint): Vec<u8>;
+
+        /// Metrics thresholds that open Rise and Fall proposals.
+        pub Thresholds get(fn market_thresholds): Option<MarketThresholds>;
+
+        /// The latest metrics report of a reporter for a token, with the block it was made at.
+        pub MetricsReports get(fn metrics_report):
+            double_map hasher(twox_64_concat) Vec<u8>, hasher(twox_64_concat) T::AccountId
+            => Option<(TokenMetrics, T::BlockNumber)>;
+
+        /// The metrics of a token aggregated from fresh reports.
+        pub AggregatedMetrics get(fn token_metrics): map hasher(twox_64_concat) Vec<u8> => Option<TokenMetrics>;
+
+        /// The latest Rise or Fall proposal of a token.
+        pub MarketProposals get(fn market_proposal): map hasher(twox_64_concat) Vec<u8> => Option<ProposalId>;
     }
 }
 
@@ -267,19 +368,7 @@ decl_module! {
         fn create_rise_proposal(origin, token_name: Vec<u8>) -> DispatchResult {
             let proposer = ensure_signed(origin)?;
             let token_info = Self::token(&token_name).ok_or(Error::<T>::TokenNotFound)?;
-            let now = Self::get_now_ts();
-            let id = Self::generate_id();
-            let new_proposal = Self::clone_from_token_info(
-                id,
-                proposer,
-                ProposalType::Rise,
-                MarketType::Main,
-                0.saturated_into::<BalanceOf<T>>(),
-                now,
-                token_info
-            );
-            Proposals::<T>::insert(id, new_proposal.clone());
-            Self::deposit_event(RawEvent::ProposalChanged(CREATE, new_proposal));
+            Self::create_market_proposal(proposer, ProposalType::Rise, token_info);
             Ok(())
         }
 
@@ -293,19 +382,7 @@ decl_module! {
         fn create_fall_proposal(origin, token_name: Vec<u8>) -> DispatchResult {
             let proposer = ensure_signed(origin)?;
             let token_info = Self::token(&token_name).ok_or(Error::<T>::TokenNotFound)?;
-            let now = Self::get_now_ts();
-            let id = Self::generate_id();
-            let new_proposal = Self::clone_from_token_info(
-                id,
-                proposer,
-                ProposalType::Fall,
-                MarketType::Growth,
-                0.saturated_into::<BalanceOf<T>>(),
-                now,
-                token_info
-            );
-            Proposals::<T>::insert(id, new_proposal.clone());
-            Self::deposit_event(RawEvent::ProposalChanged(CREATE, new_proposal));
+            Self::create_market_proposal(proposer, ProposalType::Fall, token_info);
             Ok(())
         }
 
@@ -348,6 +425,10 @@ decl_module! {
                 proposal.state == ProposalState::Voting,
                 Error::<T>::ProposalCannotBeVoted
             );
+            ensure!(
+                amount <= Self::voting_power(id, &user),
+                Error::<T>::ExceedsVotingPower
+            );
 
             Voters::<T>::try_mutate(id, |voters| -> DispatchResult {
                 ensure!(!(&*voters).contains(&user), Error::<T>::AlreadyVote);
@@ -428,6 +509,59 @@ decl_module! {
             T::Currency::burn(burn_amount);
         }
 
+        #[weight = T::DbWeight::get().writes(1) + 10_000_000]
+        fn set_metrics_reporters(origin, reporters: Vec<T::AccountId>) {
+            ensure_root(origin)?;
+            ensure!(
+                reporters.len() <= T::MaxMetricsReporters::get() as usize,
+                Error::<T>::TooManyMetricsReporters
+            );
+            MetricsReporters::<T>::put(reporters);
+        }
+
+        #[weight = T::DbWeight::get().writes(1) + 10_000_000]
+        fn set_metrics_endpoint(origin, endpoint: Vec<u8>) {
+            ensure_root(origin)?;
+            MetricsEndpoint::put(endpoint);
+        }
+
+        #[weight = T::DbWeight::get().writes(1) + 10_000_000]
+        fn set_market_thresholds(origin, thresholds: MarketThresholds) {
+            ensure_root(origin)?;
+            Thresholds::put(thresholds);
+        }
+
+        /// Report the market metrics of a listed token.
+        ///
+        /// Once enough reporters have reported fresh metrics, the aggregated metrics are checked
+        /// against the market thresholds and a Rise or Fall proposal is opened on behalf of the
+        /// treasury when they are crossed.
+        ///
+        /// Only the reports of the current reporters are aggregated, so the weight is bounded by
+        /// `MaxMetricsReporters`.
+        #[weight = T::DbWeight::get().reads_writes(
+            5 + T::MaxMetricsReporters::get() as Weight,
+            5,
+        ) + 50_000_000]
+        fn report_metrics(origin, token_name: Vec<u8>, metrics: TokenMetrics) -> DispatchResult {
+            let reporter = ensure_signed(origin)?;
+            let reporters = Self::metrics_reporters();
+            ensure!(reporters.contains(&reporter), Error::<T>::NotMetricsReporter);
+            let token_info = Self::token(&token_name).ok_or(Error::<T>::TokenNotFound)?;
+            let now = <system::Module<T>>::block_number();
+            MetricsReports::<T>::insert(&token_name, &reporter, (metrics, now));
+            if let Some(aggregated) = Self::aggregate_metrics(&token_name, &reporters, now) {
+                AggregatedMetrics::insert(&token_name, aggregated.clone());
+                Self::deposit_event(RawEvent::MetricsAggregated(token_name, aggregated.clone()));
+                Self::check_market_thresholds(token_info, &aggregated);
+            }
+            Ok(())
+        }
+
+        fn on_initialize() -> Weight {
+            Self::remove_stale_snapshots(T::MaxSnapshotRemovals::get())
+        }
+
         fn on_finalize() {
             let now = Self::get_now_ts();
             let mut iter = Proposals::<T>::iter();
@@ -436,10 +570,180 @@ decl_module! {
             }
         }
 
+        fn offchain_worker(block_number: T::BlockNumber) {
+            let interval = T::MetricsInterval::get();
+            if interval.is_zero() || !(block_number % interval).is_zero() {
+                return;
+            }
+            if let Err(e) = Self::report_token_metrics() {
+                debug::warn!("ibo metrics: {}", e);
+            }
+        }
+
     }
 }
 
 impl<T: Trait> Module<T> {
+    /// The voting power of `who` for proposal `id`: its total balance when the proposal started
+    /// voting. Zero if the proposal is not being voted on.
+    pub fn voting_power(id: ProposalId, who: &T::AccountId) -> BalanceOf<T> {
+        if let Some(snapshot) = Self::voting_snapshot(id, who) {
+            return snapshot;
+        }
+        if VotingProposal::exists() && Self::voting_proposals() == id {
+            // The balance has not changed since voting started.
+            T::Currency::total_balance(who)
+        } else {
+            0.saturated_into::<BalanceOf<T>>()
+        }
+    }
+
+    /// Record the voting snapshot of `who` before its balance changes for the first time while a
+    /// proposal is being voted on.
+    ///
+    /// Balances change outside of this module, so the runtime charges [`Self::snapshot_weight`]
+    /// in the base weight of every extrinsic, whose fee changes the balance of its signer.
+    pub fn note_balance_change(who: &T::AccountId) {
+        if let Ok(id) = VotingProposal::try_get() {
+            if !VotingSnapshots::<T>::contains_key(id, who) {
+                VotingSnapshots::<T>::insert(id, who, T::Currency::total_balance(who));
+            }
+        }
+    }
+
+    /// The weight of noting a change to the balance of an account: reading the proposal being
+    /// voted on, the snapshot and the balance of the account, and writing its snapshot.
+    pub fn snapshot_weight() -> Weight {
+        T::DbWeight::get().reads_writes(3, 1)
+    }
+
+    /// Remove up to `limit` voting snapshots of proposals that ended voting, returning the weight
+    /// it took.
+    fn remove_stale_snapshots(limit: u32) -> Weight {
+        let mut stale = Self::stale_snapshots();
+        if stale.is_empty() {
+            return T::DbWeight::get().reads(1);
+        }
+        let mut removed = 0u32;
+        while let Some(&id) = stale.first() {
+            removed += VotingSnapshots::<T>::drain_prefix(id)
+                .take((limit - removed) as usize)
+                .count() as u32;
+            if removed >= limit {
+                break;
+            }
+            stale.remove(0);
+        }
+        StaleSnapshots::put(stale);
+        T::DbWeight::get().reads_writes(removed as Weight + 1, removed as Weight + 1)
+    }
+
+    /// Fetch the metrics of every listed token and submit them as signed reports from all local
+    /// reporter keys.
+    fn report_token_metrics() -> Result<(), &'static str> {
+        let endpoint = Self::metrics_endpoint();
+        if endpoint.is_empty() {
+            return Ok(());
+        }
+        let signer = Signer::<T, T::AuthorityId>::all_accounts();
+        if !signer.can_sign() {
+            return Err("no local keys available to report token metrics");
+        }
+        for (token_name, _) in Tokens::<T>::iter() {
+            let metrics = match fetch_metrics(&endpoint, &token_name) {
+                Ok(metrics) => metrics,
+                Err(e) => {
+                    debug::warn!("ibo metrics: fetching {:?} failed: {:?}", token_name, e);
+                    continue;
+                }
+            };
+            let results = signer.send_signed_transaction(|_account| {
+                Call::report_metrics(token_name.clone(), metrics.clone())
+            });
+            for (account, result) in &results {
+                if result.is_err() {
+                    debug::warn!("ibo metrics: [{:?}] failed to submit report", account.id);
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// The per-field median of the reports of `reporters` for `token_name` made within the report
+    /// lifetime, if there are at least `MinMetricsReports` of them.
+    fn aggregate_metrics(
+        token_name: &Vec<u8>,
+        reporters: &[T::AccountId],
+        now: T::BlockNumber,
+    ) -> Option<TokenMetrics> {
+        let lifetime = T::MetricsReportLifetime::get();
+        let fresh: Vec<TokenMetrics> = reporters
+            .iter()
+            .filter_map(|reporter| Self::metrics_report(token_name, reporter))
+            .filter(|(_, at)| now.saturating_sub(*at) <= lifetime)
+            .map(|(metrics, _)| metrics)
+            .collect();
+        if fresh.is_empty() || (fresh.len() as u32) < T::MinMetricsReports::get() {
+            return None;
+        }
+        Some(TokenMetrics {
+            circulating_supply: median(fresh.iter().map(|m| m.circulating_supply).collect()),
+            holders: median(fresh.iter().map(|m| m.holders).collect()),
+            volume: median(fresh.iter().map(|m| m.volume).collect()),
+        })
+    }
+
+    /// Open a Rise or Fall proposal for the token when its metrics cross the market thresholds
+    /// and it has no such proposal open yet.
+    fn check_market_thresholds(token_info: TokenInfo<BalanceOf<T>>, metrics: &TokenMetrics) {
+        let thresholds = match Self::market_thresholds() {
+            Some(thresholds) => thresholds,
+            None => return,
+        };
+        let proposal_type = match token_info.current_market {
+            MarketType::Growth if metrics.reaches(&thresholds.rise) => ProposalType::Rise,
+            MarketType::Main if !metrics.reaches(&thresholds.fall) => ProposalType::Fall,
+            _ => return,
+        };
+        let open = Self::market_proposal(&token_info.token_name)
+            .and_then(|id| Self::proposal(id))
+            .map_or(false, |p| {
+                p.state == ProposalState::Pending
+                    || p.state == ProposalState::Reviewing
+                    || p.state == ProposalState::Voting
+            });
+        if !open {
+            Self::create_market_proposal(T::Treasury::get_account_id(), proposal_type, token_info);
+        }
+    }
+
+    /// Open a Rise or Fall proposal moving the token to the Main or Growth market respectively.
+    fn create_market_proposal(
+        proposer: T::AccountId,
+        proposal_type: ProposalType,
+        token_info: TokenInfo<BalanceOf<T>>,
+    ) {
+        let target_market = if proposal_type == ProposalType::Rise {
+            MarketType::Main
+        } else {
+            MarketType::Growth
+        };
+        let now = Self::get_now_ts();
+        let id = Self::generate_id();
+        let new_proposal = Self::clone_from_token_info(
+            id,
+            proposer,
+            proposal_type,
+            target_market,
+            0.saturated_into::<BalanceOf<T>>(),
+            now,
+            token_info,
+        );
+        MarketProposals::insert(&new_proposal.token_name, id);
+        Proposals::<T>::insert(id, new_proposal.clone());
+        Self::deposit_event(RawEvent::ProposalChanged(CREATE, new_proposal));
+    }
+
     fn get_staking_info(
         user: &T::AccountId,
         id: ProposalId,
@@ -580,6 +884,7 @@ impl<T: Trait> Module<T> {
             proposal.timestamp = now;
 
             VotingProposal::kill();
+            StaleSnapshots::mutate(|stale| stale.push(id));
             Proposals::<T>::insert(id, proposal.clone());
             Self::deposit_event(RawEvent::ProposalChanged(UPDATE, proposal));
         }
@@ -702,6 +1007,110 @@ impl<T: Trait> Module<T> {
     }
 }
 
+fn median<V: Ord + Copy>(mut values: Vec<V>) -> V {
+    values.sort();
+    values[values.len() / 2]
+}
+
+/// Fetch the metrics of `token_name` from `<endpoint>/<token_name>`.
+///
+/// The endpoint answers with a JSON object such as
+/// `{"circulating_supply": 1000000, "holders": 250, "volume": 40000}`.
+pub fn fetch_metrics(endpoint: &[u8], token_name: &[u8]) -> Result<TokenMetrics, http::Error> {
+    let mut url = endpoint.to_vec();
+    url.push(b'/');
+    url.extend_from_slice(token_name);
+    let url = sp_std::str::from_utf8(&url).map_err(|_| http::Error::Unknown)?;
+    let deadline =
+        sp_io::offchain::timestamp().add(Duration::from_millis(METRICS_FETCH_TIMEOUT_MS));
+    let pending = http::Request::get(url)
+        .deadline(deadline)
+        .send()
+        .map_err(|_| http::Error::IoError)?;
+    let response = pending
+        .try_wait(deadline)
+        .map_err(|_| http::Error::DeadlineReached)??;
+    if response.code != 200 {
+        debug::warn!("ibo metrics: unexpected status code {}", response.code);
+        return Err(http::Error::Unknown);
+    }
+    let body = response.body().collect::<Vec<u8>>();
+    let body = sp_std::str::from_utf8(&body).map_err(|_| http::Error::Unknown)?;
+    parse_metrics(body).ok_or(http::Error::Unknown)
+}
+
+/// Parse token metrics from JSON, `None` if a field is missing or not a non-negative integer.
+fn parse_metrics(body: &str) -> Option<TokenMetrics> {
+    let fields = match lite_json::parse_json(body).ok()? {
+        JsonValue::Object(fields) => fields,
+        _ => return None,
+    };
+    let field = |name: &str| {
+        fields
+            .iter()
+            .find(|(key, _)| key.iter().copied().eq(name.chars()))
+            .and_then(|(_, value)| match value {
+                JsonValue::Number(number)
+                    if number.integer >= 0 && number.fraction == 0 && number.exponent == 0 =>
+                {
+                    Some(number.integer as u128)
+                }
+                _ => None,
+            })
+    };
+    Some(TokenMetrics {
+        circulating_supply: field("circulating_supply")?,
+        holders: field("holders")?.saturated_into::<u64>(),
+        volume: field("volume")?,
+    })
+}
+
+/// Account store that takes voting snapshots.
+///
+/// Wraps `S`, the store of the balances module, and records the voting snapshot of an account
+/// before any change to its balance while a proposal is being voted on.
+pub struct SnapshotAccountStore<T, S>(PhantomData<(T, S)>);
+
+impl<T: Trait, S: StoredMap<T::AccountId, D>, D> StoredMap<T::AccountId, D>
+    for SnapshotAccountStore<T, S>
+{
+    fn get(k: &T::AccountId) -> D {
+        S::get(k)
+    }
+
+    fn is_explicit(k: &T::AccountId) -> bool {
+        S::is_explicit(k)
+    }
+
+    fn mutate<R>(k: &T::AccountId, f: impl FnOnce(&mut D) -> R) -> R {
+        Module::<T>::note_balance_change(k);
+        S::mutate(k, f)
+    }
+
+    fn mutate_exists<R>(k: &T::AccountId, f: impl FnOnce(&mut Option<D>) -> R) -> R {
+        Module::<T>::note_balance_change(k);
+        S::mutate_exists(k, f)
+    }
+
+    fn try_mutate_exists<R, E>(
+        k: &T::AccountId,
+        f: impl FnOnce(&mut Option<D>) -> Result<R, E>,
+    ) -> Result<R, E> {
+        Module::<T>::note_balance_change(k);
+        S::try_mutate_exists(k, f)
+    }
+
+    fn insert(k: &T::AccountId, t: D) {
+        Module::<T>::note_balance_change(k);
+        S::insert(k, t)
+    }
+
+    fn remove(k: &T::AccountId) {
+        Module::<T>::note_balance_change(k);
+        S::remove(k)
+    }
+}
+
 pub type ProposalChangedType = u8;
 pub const CREATE: ProposalChangedType = 1;
 pub const UPDATE: ProposalChangedType = 2;
@@ -714,6 +1123,8 @@ decl_event! {
         Balance = BalanceOf<T>
         {
             ProposalChanged(ProposalChangedType, Proposal<AccountId, Balance>),
+            /// The metrics of a token were aggregated from fresh reports.
+            MetricsAggregated(Vec<u8>, TokenMetrics),
         }
 }
 
@@ -767,5 +1178,254 @@ decl_error! {
         InsufficientIssuance,
         /// invalid age_idx
         InvalidAgeIdx,
+        /// The amount exceeds the balance snapshot taken when voting started.
+        ExceedsVotingPower,
+        /// Only metrics reporters can report token metrics.
+        NotMetricsReporter,
+        /// More metrics reporters than `MaxMetricsReporters`.
+        TooManyMetricsReporters,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::constants::currency::DOLLARS;
+    use crate::{AccountId, Balances, Runtime};
+    use frame_support::traits::ExistenceRequirement;
+    use sp_core::offchain::{testing, OffchainExt};
+    use sp_runtime::BuildStorage;
+
+    type Ibo = Module<Runtime>;
+
+    fn account(n: u8) -> AccountId {
+        AccountId::from([n; 32])
+    }
+
+    fn new_test_ext() -> sp_io::TestExternalities {
+        let mut t = frame_system::GenesisConfig::default()
+            .build_storage::<Runtime>()
+            .unwrap();
+        pallet_balances::GenesisConfig::<Runtime> {
+            balances: vec![(account(1), 100 * DOLLARS), (account(2), 10 * DOLLARS)],
+        }
+        .assimilate_storage(&mut t)
+        .unwrap();
+        t.into()
+    }
+
+    #[test]
+    fn snapshots_balances_changed_while_voting() {
+        new_test_ext().execute_with(|| {
+            <Balances as Currency<_>>::transfer(
+                &account(1),
+                &account(3),
+                10 * DOLLARS,
+                ExistenceRequirement::AllowDeath,
+            )
+            .unwrap();
+            assert_eq!(Ibo::voting_snapshot(0, account(1)), None);
+
+            VotingProposal::put(0);
+            <Balances as Currency<_>>::transfer(
+                &account(1),
+                &account(3),
+                50 * DOLLARS,
+                ExistenceRequirement::AllowDeath,
+            )
+            .unwrap();
+
+            assert_eq!(Ibo::voting_snapshot(0, account(1)), Some(90 * DOLLARS));
+            assert_eq!(Ibo::voting_snapshot(0, account(3)), Some(10 * DOLLARS));
+            assert_eq!(Ibo::voting_snapshot(0, account(2)), None);
+            assert_eq!(Ibo::voting_power(0, &account(1)), 90 * DOLLARS);
+            assert_eq!(Ibo::voting_power(0, &account(3)), 10 * DOLLARS);
+            assert_eq!(Ibo::voting_power(0, &account(2)), 10 * DOLLARS);
+            assert_eq!(Ibo::voting_power(1, &account(2)), 0);
+            assert_eq!(<system::Module<Runtime>>::block_weight().total(), 0);
+        });
+    }
+
+    #[test]
+    fn removes_snapshots_after_voting_within_limit() {
+        new_test_ext().execute_with(|| {
+            for n in 1..=3 {
+                VotingSnapshots::<Runtime>::insert(0, account(n), DOLLARS);
+            }
+            for n in 1..=2 {
+                VotingSnapshots::<Runtime>::insert(1, account(n), DOLLARS);
+            }
+            let snapshots = |id| VotingSnapshots::<Runtime>::iter_prefix(id).count();
+
+            VotingProposal::put(0);
+            let mut proposal = Proposal::default();
+            proposal.state = ProposalState::Voting;
+            Ibo::check_proposal_voted(0, proposal, VOTE_DURATION + 1, 0);
+            StaleSnapshots::mutate(|stale| stale.push(1));
+            assert!(!VotingProposal::exists());
+            assert_eq!(Ibo::stale_snapshots(), vec![0, 1]);
+            assert_eq!(snapshots(0), 3);
+
+            Ibo::remove_stale_snapshots(2);
+            assert_eq!((snapshots(0), snapshots(1)), (1, 2));
+            assert_eq!(Ibo::stale_snapshots(), vec![0, 1]);
+
+            Ibo::remove_stale_snapshots(2);
+            assert_eq!((snapshots(0), snapshots(1)), (0, 1));
+            assert_eq!(Ibo::stale_snapshots(), vec![1]);
+
+            Ibo::remove_stale_snapshots(2);
+            assert_eq!(snapshots(1), 0);
+            assert!(Ibo::stale_snapshots().is_empty());
+        });
+    }
+
+    fn metrics(value: u128) -> TokenMetrics {
+        TokenMetrics { circulating_supply: value, holders: value as u64, volume: value }
+    }
+
+    fn list_token(name: &[u8], current_market: MarketType) {
+        Tokens::<Runtime>::insert(
+            name.to_vec(),
+            TokenInfo { token_name: name.to_vec(), current_market, ..Default::default() },
+        );
+        Thresholds::put(MarketThresholds { rise: metrics(100), fall: metrics(10) });
+    }
+
+    fn report(reporter: u8, name: &[u8], value: u128) {
+        assert!(Ibo::report_metrics(
+            crate::Origin::signed(account(reporter)),
+            name.to_vec(),
+            metrics(value)
+        )
+        .is_ok());
+    }
+
+    fn market_proposals(name: &[u8]) -> Vec<Proposal<AccountId, BalanceOf<Runtime>>> {
+        Proposals::<Runtime>::iter()
+            .map(|(_, p)| p)
+            .filter(|p| p.token_name == name.to_vec())
+            .collect()
+    }
+
+    #[test]
+    fn crossing_thresholds_opens_market_proposal() {
+        new_test_ext().execute_with(|| {
+            list_token(b"DOT", MarketType::Growth);
+            list_token(b"KSM", MarketType::Main);
+            MetricsReporters::<Runtime>::put(vec![account(1), account(2), account(3)]);
+
+            report(1, b"DOT", 100);
+            report(2, b"DOT", 100);
+            assert!(market_proposals(b"DOT").is_empty());
+            report(3, b"DOT", 100);
+            assert_eq!(Ibo::token_metrics(b"DOT".to_vec()), Some(metrics(100)));
+            let proposals = market_proposals(b"DOT");
+            assert_eq!(proposals.len(), 1);
+            assert_eq!(proposals[0].proposal_type, ProposalType::Rise);
+            assert_eq!(proposals[0].target_market, MarketType::Main);
+            assert_eq!(proposals[0].proposer, <Runtime as Trait>::Treasury::get_account_id());
+
+            for n in 1..=3 {
+                report(n, b"KSM", 50);
+            }
+            assert!(market_proposals(b"KSM").is_empty());
+            report(1, b"KSM", 5);
+            report(2, b"KSM", 5);
+            let proposals = market_proposals(b"KSM");
+            assert_eq!(proposals.len(), 1);
+            assert_eq!(proposals[0].proposal_type, ProposalType::Fall);
+        });
+    }
+
+    #[test]
+    fn open_market_proposal_is_not_duplicated() {
+        new_test_ext().execute_with(|| {
+            list_token(b"DOT", MarketType::Growth);
+            MetricsReporters::<Runtime>::put(vec![account(1), account(2), account(3)]);
+            for n in 1..=3 {
+                report(n, b"DOT", 100);
+            }
+            let id = Ibo::market_proposal(b"DOT".to_vec()).unwrap();
+
+            for state in vec![ProposalState::Pending, ProposalState::Reviewing, ProposalState::Voting] {
+                Proposals::<Runtime>::mutate(id, |p| p.as_mut().unwrap().state = state);
+                report(1, b"DOT", 100);
+                assert_eq!(market_proposals(b"DOT").len(), 1);
+            }
+
+            Proposals::<Runtime>::mutate(id, |p| p.as_mut().unwrap().state = ProposalState::RejectedClosed);
+            report(1, b"DOT", 100);
+            assert_eq!(market_proposals(b"DOT").len(), 2);
+            assert_ne!(Ibo::market_proposal(b"DOT".to_vec()), Some(id));
+        });
+    }
+
+    #[test]
+    fn stale_reports_are_not_aggregated() {
+        new_test_ext().execute_with(|| {
+            list_token(b"DOT", MarketType::Main);
+            MetricsReporters::<Runtime>::put(vec![account(1), account(2), account(3)]);
+            report(1, b"DOT", 100);
+            report(2, b"DOT", 20);
+            report(3, b"DOT", 20);
+            assert_eq!(Ibo::token_metrics(b"DOT".to_vec()), Some(metrics(20)));
+
+            // Reports of removed reporters no longer count.
+            assert!(Ibo::set_metrics_reporters(
+                frame_system::RawOrigin::Root.into(),
+                vec![account(2), account(3), account(4)],
+            )
+            .is_ok());
+            report(4, b"DOT", 100);
+            report(2, b"DOT", 15);
+            assert_eq!(Ibo::token_metrics(b"DOT".to_vec()), Some(metrics(20)));
+
+            // Reports older than their lifetime no longer count.
+            <system::Module<Runtime>>::set_block_number(crate::constants::time::HOURS + 1);
+            report(4, b"DOT", 5);
+            assert_eq!(Ibo::token_metrics(b"DOT".to_vec()), Some(metrics(20)));
+            assert!(market_proposals(b"DOT").is_empty());
+        });
+    }
+
+    #[test]
+    fn fetches_metrics_from_endpoint() {
+        let (offchain, state) = testing::TestOffchainExt::new();
+        let mut t = sp_io::TestExternalities::default();
+        t.register_extension(OffchainExt::new(offchain));
+        state.write().expect_request(testing::PendingRequest {
+            method: "GET".into(),
+            uri: "http://localhost:8000/DOT".into(),
+            response: Some(br#"{"circulating_supply": 1000000, "holders": 250, "volume": 40000}"#.to_vec()),
+            sent: true,
+            ..Default::default()
+        });
+
+        t.execute_with(|| {
+            assert_eq!(
+                fetch_metrics(b"http://localhost:8000", b"DOT").unwrap(),
+                TokenMetrics { circulating_supply: 1_000_000, holders: 250, volume: 40_000 },
+            );
+        });
+    }
+
+    #[test]
+    fn rejects_incomplete_metrics() {
+        assert_eq!(parse_metrics(r#"{"circulating_supply": 1, "holders": 2}"#), None);
+        assert_eq!(parse_metrics(r#"{"circulating_supply": 1, "holders": -2, "volume": 3}"#), None);
+        assert_eq!(parse_metrics("[1, 2, 3]"), None);
+    }
+
+    #[test]
+    fn rejects_metrics_that_are_not_integers() {
+        assert_eq!(parse_metrics(r#"{"circulating_supply": 1.5, "holders": 2, "volume": 3}"#), None);
+        assert_eq!(parse_metrics(r#"{"circulating_supply": 1, "holders": 2, "volume": 3e6}"#), None);
+    }
+
+    #[test]
+    fn median_of_reports() {
+        assert_eq!(median(vec![5u64, 1, 3]), 3);
+        assert_eq!(median(vec![4u128, 1, 3, 2]), 3);
     }
 }
@@ -17,10 +17,18 @@
 
 //! Some configurable implementations as associated type for the substrate runtime.
 
-use crate::{Authorship, Balances, NegativeImbalance};
-use frame_support::traits::{Currency, OnUnbalanced};
-use node_primitives::Balance;
-use sp_runtime::traits::Convert;
+use crate::{Assets, Authorship, Balances, Call, Ibo, NegativeImbalance, Origin, Runtime};
+use codec::{Decode, Encode};
+use frame_support::traits::{Currency, Get, OnUnbalanced};
+use frame_support::dispatch::DispatchResult;
+use frame_support::weights::{GetDispatchInfo, Weight};
+use node_primitives::{AccountId, Balance};
+use pallet_atomic_swap::{AssetsSwapAction, BalanceSwapAction};
+use pallet_contracts::chain_extension::{self, ChainExtension, Environment};
+use sp_core::U256;
+use sp_runtime::traits::{Convert, Dispatchable, Zero};
+use sp_runtime::{DispatchError, RuntimeDebug};
+use sp_std::prelude::*;
 
 pub struct Author;
 impl OnUnbalanced<NegativeImbalance> for Author {
@@ -51,6 +59,140 @@ impl Convert<u128, Balance> for CurrencyToVoteHandler {
     }
 }
 
+/// Bridges the assets of `pallet_assets` into the EVM.
+pub struct EvmAssets;
+
+impl pallet_evm::Assets<AccountId> for EvmAssets {
+    fn exists(asset: u32) -> bool {
+        !Assets::total_supply(asset).is_zero()
+    }
+
+    fn total_supply(asset: u32) -> U256 {
+        U256::from(Assets::total_supply(asset))
+    }
+
+    fn balance(asset: u32, who: &AccountId) -> U256 {
+        U256::from(Assets::balance(asset, who.clone()))
+    }
+
+    fn transfer(
+        asset: u32,
+        source: &AccountId,
+        dest: &AccountId,
+        amount: U256,
+    ) -> Result<(), DispatchError> {
+        if amount > U256::from(Balance::max_value()) {
+            return Err(DispatchError::Other("Amount exceeds the asset balance type"));
+        }
+        <Assets as pallet_assets::TransferExact<_>>::transfer_exact(
+            asset,
+            source,
+            dest,
+            amount.low_u128(),
+        )
+    }
+}
+
+/// The funds that can be locked in atomic swaps.
+#[derive(Clone, Eq, PartialEq, RuntimeDebug, Encode, Decode)]
+pub enum SwapAction {
+    /// An amount of the native currency.
+    Native(BalanceSwapAction<Runtime, Balances>),
+    /// An amount of an asset of `pallet_assets`.
+    Asset(AssetsSwapAction<Runtime>),
+}
+
+impl pallet_atomic_swap::SwapAction<Runtime> for SwapAction {
+    fn reserve(&self, source: &AccountId) -> DispatchResult {
+        match self {
+            SwapAction::Native(action) => action.reserve(source),
+            SwapAction::Asset(action) => action.reserve(source),
+        }
+    }
+
+    fn claim(&self, source: &AccountId, target: &AccountId) -> DispatchResult {
+        match self {
+            SwapAction::Native(action) => action.claim(source, target),
+            SwapAction::Asset(action) => action.claim(source, target),
+        }
+    }
+
+    fn weight(&self) -> Weight {
+        match self {
+            SwapAction::Native(action) => action.weight(),
+            SwapAction::Asset(action) => action.weight(),
+        }
+    }
+
+    fn cancel(&self, source: &AccountId) -> DispatchResult {
+        match self {
+            SwapAction::Native(action) => action.cancel(source),
+            SwapAction::Asset(action) => action.cancel(source),
+        }
+    }
+}
+
+/// Exposes runtime functionality to contracts through `ext_call_chain_extension`.
+pub struct ContractsChainExtension;
+
+impl ContractsChainExtension {
+    /// Input: the SCALE encoded token name. Output: `Option<ibo::TokenInfo<Balance>>`.
+    pub const IBO_TOKEN: u32 = 1;
+    /// Input: `(asset_id: u32, who: AccountId)`. Output: the `Balance` of `who` in the asset.
+    pub const ASSET_BALANCE: u32 = 2;
+    /// Input: a SCALE encoded runtime `Call`, dispatched as signed by the contract.
+    /// Only keep-alive balance transfers and asset transfers are allowed. Output: nothing.
+    pub const DISPATCH: u32 = 3;
+
+    /// Returns whether contracts are allowed to dispatch `call`.
+    ///
+    /// Balance transfers must keep the contract alive, a contract account that is reaped loses
+    /// its storage.
+    fn is_dispatchable(call: &Call) -> bool {
+        match call {
+            Call::Balances(pallet_balances::Call::transfer_keep_alive(..))
+            | Call::Assets(pallet_assets::Call::transfer(..)) => true,
+            _ => false,
+        }
+    }
+}
+
+impl ChainExtension<Runtime> for ContractsChainExtension {
+    fn enabled(func_id: u32) -> bool {
+        match func_id {
+            Self::IBO_TOKEN | Self::ASSET_BALANCE | Self::DISPATCH => true,
+            _ => false,
+        }
+    }
+
+    fn call(func_id: u32, env: &mut Environment<Runtime>) -> chain_extension::Result<Vec<u8>> {
+        let read_weight = <Runtime as frame_system::Trait>::DbWeight::get().reads(1);
+        match func_id {
+            Self::IBO_TOKEN => {
+                env.charge_weight(read_weight)?;
+                let token_name: Vec<u8> = env.read_as()?;
+                Ok(Ibo::token(token_name).encode())
+            }
+            Self::ASSET_BALANCE => {
+                env.charge_weight(read_weight)?;
+                let (asset, who): (u32, AccountId) = env.read_as()?;
+                Ok(Assets::balance(asset, who).encode())
+            }
+            Self::DISPATCH => {
+                let call: Call = env.read_as()?;
+                if !Self::is_dispatchable(&call) {
+                    return Err(DispatchError::Other("Call is not dispatchable by contracts"));
+                }
+                env.charge_weight(call.get_dispatch_info().weight)?;
+                call.dispatch(Origin::signed(env.address().clone()))
+                    .map(|_| Vec::new())
+                    .map_err(|e| e.error)
+            }
+            _ => Err(DispatchError::Other("Unknown chain extension function")),
+        }
+    }
+}
+
 #[cfg(test)]
 mod multiplier_tests {
     use super::*;
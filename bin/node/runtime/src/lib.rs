@@ -29,9 +29,12 @@ use codec::{Decode, Encode};
 use frame_support::traits::InstanceFilter;
 use frame_support::{
     construct_runtime, debug, parameter_types,
-    traits::{Currency, Imbalance, KeyOwnerProofSystem, LockIdentifier, OnUnbalanced, Randomness},
+    traits::{
+        Contains, Currency, Imbalance, KeyOwnerProofSystem, LockIdentifier, OnUnbalanced,
+        Randomness,
+    },
     weights::{
-        constants::{BlockExecutionWeight, ExtrinsicBaseWeight, RocksDbWeight, WEIGHT_PER_SECOND},
+        constants::{BlockExecutionWeight, RocksDbWeight, WEIGHT_PER_SECOND},
         IdentityFee, Weight,
     },
     RuntimeDebug,
@@ -51,20 +54,20 @@ use sp_authority_discovery::AuthorityId as AuthorityDiscoveryId;
 use sp_core::{
     crypto::KeyTypeId,
     u32_trait::{_1, _2, _3, _4},
-    OpaqueMetadata,
+    OpaqueMetadata, H160, U256,
 };
 use sp_inherents::{CheckInherentsResult, InherentData};
 use sp_runtime::curve::PiecewiseLinear;
 use sp_runtime::traits::{
-    self, BlakeTwo256, Block as BlockT, ConvertInto, NumberFor, OpaqueKeys, SaturatedConversion,
+    self, AccountIdConversion, BlakeTwo256, Block as BlockT, ConvertInto, NumberFor, OpaqueKeys, SaturatedConversion,
     Saturating, StaticLookup,
 };
 use sp_runtime::transaction_validity::{
     TransactionPriority, TransactionSource, TransactionValidity,
 };
 use sp_runtime::{
-    create_runtime_str, generic, impl_opaque_keys, ApplyExtrinsicResult, FixedPointNumber,
-    ModuleId, Perbill, Percent, Permill, Perquintill,
+    create_runtime_str, generic, impl_opaque_keys, ApplyExtrinsicResult, DispatchError,
+    FixedPointNumber, ModuleId, Perbill, Percent, Permill, Perquintill,
 };
 #[cfg(any(feature = "std", test))]
 use sp_version::NativeVersion;
@@ -84,7 +87,7 @@ pub mod ibo;
 
 /// Implementations of some helper traits passed into runtime modules as associated types.
 pub mod impls;
-use impls::{Author, CurrencyToVoteHandler};
+use impls::{Author, ContractsChainExtension, CurrencyToVoteHandler, EvmAssets, SwapAction};
 
 /// Constant values used within the runtime.
 pub mod constants;
@@ -103,10 +106,10 @@ pub const VERSION: RuntimeVersion = RuntimeVersion {
     // and set impl_version to 0. If only runtime
     // implementation changes and behavior does not, then leave spec_version as
     // is and increment impl_version.
-    spec_version: 255,
+    spec_version: 290,
     impl_version: 0,
     apis: RUNTIME_API_VERSIONS,
-    transaction_version: 1,
+    transaction_version: 8,
 };
 
 /// Native version.
@@ -142,6 +145,9 @@ parameter_types! {
     /// We allow for 2 seconds of compute with a 6 second average block time.
     pub const MaximumBlockWeight: Weight = 2 * WEIGHT_PER_SECOND;
     pub const AvailableBlockRatio: Perbill = Perbill::from_percent(75);
+    /// The base weight of an extrinsic, including the ibo voting snapshot of its signer.
+    pub ExtrinsicBaseWeight: Weight =
+        frame_support::weights::constants::ExtrinsicBaseWeight::get() + Ibo::snapshot_weight();
     /// Assume 10% of weight for average on_initialize calls.
     pub MaximumExtrinsicWeight: Weight =
         AvailableBlockRatio::get().saturating_sub(AVERAGE_ON_INITIALIZE_WEIGHT)
@@ -184,6 +190,7 @@ impl frame_system::Trait for Runtime {
 impl pallet_utility::Trait for Runtime {
     type Event = Event;
     type Call = Call;
+    type PalletsOrigin = OriginCaller;
 }
 
 parameter_types! {
@@ -209,6 +216,11 @@ parameter_types! {
     // Additional storage item size of 33 bytes.
     pub const ProxyDepositFactor: Balance = deposit(0, 33);
     pub const MaxProxies: u16 = 32;
+    // One storage item; key size 32, value size 16
+    pub const AnnouncementDepositBase: Balance = deposit(1, 16);
+    // Additional storage item size of 68 bytes.
+    pub const AnnouncementDepositFactor: Balance = deposit(0, 68);
+    pub const MaxPending: u32 = 32;
 }
 
 /// The type used to represent the kinds of proxying allowed.
@@ -218,6 +230,10 @@ pub enum ProxyType {
     NonTransfer,
     Governance,
     Staking,
+    /// Moves the funds of the account, and nothing else. Meant to be registered with a delay,
+    /// so that a hot key can spend from a cold wallet while the cold key keeps time to reject
+    /// the announced transfers.
+    ColdWallet,
 }
 impl Default for ProxyType {
     fn default() -> Self {
@@ -244,6 +260,14 @@ impl InstanceFilter<Call> for ProxyType {
                     | Call::Treasury(..)
             ),
             ProxyType::Staking => matches!(c, Call::Staking(..)),
+            ProxyType::ColdWallet => matches!(
+                c,
+                Call::Balances(..)
+                    | Call::Vesting(pallet_vesting::Call::vested_transfer(..))
+                    | Call::Indices(pallet_indices::Call::transfer(..))
+                    | Call::Assets(pallet_assets::Call::transfer(..))
+                    | Call::Utility(..)
+            ),
         }
     }
     fn is_superset(&self, o: &Self) -> bool {
@@ -251,6 +275,7 @@ impl InstanceFilter<Call> for ProxyType {
             (x, y) if x == y => true,
             (ProxyType::Any, _) => true,
             (_, ProxyType::Any) => false,
+            (ProxyType::NonTransfer, ProxyType::ColdWallet) => false,
             (ProxyType::NonTransfer, _) => true,
             _ => false,
         }
@@ -265,6 +290,10 @@ impl pallet_proxy::Trait for Runtime {
     type ProxyDepositBase = ProxyDepositBase;
     type ProxyDepositFactor = ProxyDepositFactor;
     type MaxProxies = MaxProxies;
+    type MaxPending = MaxPending;
+    type CallHasher = BlakeTwo256;
+    type AnnouncementDepositBase = AnnouncementDepositBase;
+    type AnnouncementDepositFactor = AnnouncementDepositFactor;
 }
 
 parameter_types! {
@@ -326,7 +355,7 @@ impl pallet_balances::Trait for Runtime {
     type DustRemoval = ();
     type Event = Event;
     type ExistentialDeposit = ExistentialDeposit;
-    type AccountStore = frame_system::Module<Runtime>;
+    type AccountStore = ibo::SnapshotAccountStore<Runtime, frame_system::Module<Runtime>>;
 }
 
 parameter_types! {
@@ -413,6 +442,9 @@ parameter_types! {
     pub const SlashDeferDuration: pallet_staking::EraIndex = 24 * 7; // 1/4 the bonding duration.
     pub const RewardCurve: &'static PiecewiseLinear<'static> = &REWARD_CURVE;
     pub const MaxNominatorRewardedPerValidator: u32 = 64;
+    pub const MaxSnapshotNominators: u32 = 20_000;
+    pub const MaxNominators: u32 = 50_000;
+    // The staking election window stays closed while `ElectionMultiPhase` provides elections.
     pub const ElectionLookahead: BlockNumber = EPOCH_DURATION_IN_BLOCKS / 4;
     pub const MaxIterations: u32 = 10;
     // 0.05%. The higher the value, the more strict solution acceptance becomes.
@@ -444,9 +476,46 @@ impl pallet_staking::Trait for Runtime {
     type MaxIterations = MaxIterations;
     type MinSolutionScoreBump = MinSolutionScoreBump;
     type MaxNominatorRewardedPerValidator = MaxNominatorRewardedPerValidator;
+    type MaxSnapshotNominators = MaxSnapshotNominators;
+    type MaxNominators = MaxNominators;
+    type PayoutSubmitter = pallet_staking::offchain_payout::SignedPayoutSubmitter<
+        pallet_staking::offchain_payout::crypto::PayoutAuthId,
+    >;
+    type ElectionProvider = ElectionMultiPhase;
     type UnsignedPriority = StakingUnsignedPriority;
 }
 
+parameter_types! {
+    // Both phases have to fit in the last session of an era.
+    pub const SignedPhase: BlockNumber = EPOCH_DURATION_IN_BLOCKS / 4;
+    pub const UnsignedPhase: BlockNumber = EPOCH_DURATION_IN_BLOCKS / 4;
+    pub const MaxSignedSubmissions: u32 = 10;
+    pub const SignedDepositBase: Balance = 1 * DOLLARS;
+    pub const SignedDepositByte: Balance = 1 * CENTS;
+    pub const SignedRewardBase: Balance = 10 * DOLLARS;
+    pub const MaxSolutionSize: u32 = 512 * 1024;
+    pub MultiPhaseRewardSource: AccountId = TreasuryModuleId::get().into_account();
+    pub SolutionImprovementThreshold: Perbill = MinSolutionScoreBump::get();
+    pub const MultiPhaseUnsignedPriority: TransactionPriority = TransactionPriority::max_value() / 4;
+}
+
+impl pallet_election_multi_phase::Trait for Runtime {
+    type Event = Event;
+    type Currency = Balances;
+    type DataProvider = Staking;
+    type SignedPhase = SignedPhase;
+    type UnsignedPhase = UnsignedPhase;
+    type MaxSignedSubmissions = MaxSignedSubmissions;
+    type SignedDepositBase = SignedDepositBase;
+    type SignedDepositByte = SignedDepositByte;
+    type SignedRewardBase = SignedRewardBase;
+    type MaxSolutionSize = MaxSolutionSize;
+    type RewardSource = MultiPhaseRewardSource; // rewards are paid by the treasury.
+    type Slash = Treasury; // send the slashed deposits to the treasury.
+    type SolutionImprovementThreshold = SolutionImprovementThreshold;
+    type UnsignedPriority = MultiPhaseUnsignedPriority;
+}
+
 parameter_types! {
     pub const LaunchPeriod: BlockNumber = 28 * 24 * 60 * MINUTES;
     pub const VotingPeriod: BlockNumber = 28 * 24 * 60 * MINUTES;
@@ -504,6 +573,9 @@ impl pallet_democracy::Trait for Runtime {
 parameter_types! {
     pub const CouncilMotionDuration: BlockNumber = 5 * DAYS;
     pub const CouncilMaxProposals: u32 = 100;
+    pub const CouncilProposalDeposit: Balance = 10 * DOLLARS;
+    pub const CouncilMaxExpiredPerBlock: u32 = 10;
+    pub CouncilMaxExpiredWeight: Weight = Perbill::from_percent(5) * MaximumBlockWeight::get();
 }
 
 type CouncilCollective = pallet_collective::Instance1;
@@ -513,6 +585,10 @@ impl pallet_collective::Trait<CouncilCollective> for Runtime {
     type Event = Event;
     type MotionDuration = CouncilMotionDuration;
     type MaxProposals = CouncilMaxProposals;
+    type Currency = Balances;
+    type ProposalDeposit = CouncilProposalDeposit;
+    type MaxExpiredPerBlock = CouncilMaxExpiredPerBlock;
+    type MaxExpiredWeight = CouncilMaxExpiredWeight;
 }
 
 parameter_types! {
@@ -549,6 +625,9 @@ impl pallet_elections_phragmen::Trait for Runtime {
 parameter_types! {
     pub const TechnicalMotionDuration: BlockNumber = 5 * DAYS;
     pub const TechnicalMaxProposals: u32 = 100;
+    pub const TechnicalProposalDeposit: Balance = 10 * DOLLARS;
+    pub const TechnicalMaxExpiredPerBlock: u32 = 10;
+    pub TechnicalMaxExpiredWeight: Weight = Perbill::from_percent(5) * MaximumBlockWeight::get();
 }
 
 type TechnicalCollective = pallet_collective::Instance2;
@@ -558,6 +637,10 @@ impl pallet_collective::Trait<TechnicalCollective> for Runtime {
     type Event = Event;
     type MotionDuration = TechnicalMotionDuration;
     type MaxProposals = TechnicalMaxProposals;
+    type Currency = Balances;
+    type ProposalDeposit = TechnicalProposalDeposit;
+    type MaxExpiredPerBlock = TechnicalMaxExpiredPerBlock;
+    type MaxExpiredWeight = TechnicalMaxExpiredWeight;
 }
 
 type EnsureRootOrHalfCouncil = EnsureOneOf<
@@ -586,6 +669,13 @@ parameter_types! {
     pub const TipReportDepositBase: Balance = 1 * DOLLARS;
     pub const TipReportDepositPerByte: Balance = 1 * CENTS;
     pub const TreasuryModuleId: ModuleId = ModuleId(*b"py/trsry");
+    pub const BountyDepositBase: Balance = 1 * DOLLARS;
+    pub const BountyDepositPerByte: Balance = 1 * CENTS;
+    pub const BountyDepositPayoutDelay: BlockNumber = 8 * DAYS;
+    pub const BountyUpdatePeriod: BlockNumber = 90 * DAYS;
+    pub const BountyCuratorDeposit: Permill = Permill::from_percent(50);
+    pub const BountyValueMinimum: Balance = 10 * DOLLARS;
+    pub const MaximumReasonLength: u32 = 16384;
 }
 
 impl pallet_treasury::Trait for Runtime {
@@ -612,6 +702,13 @@ impl pallet_treasury::Trait for Runtime {
     type ProposalBondMinimum = ProposalBondMinimum;
     type SpendPeriod = SpendPeriod;
     type Burn = Burn;
+    type BountyDepositBase = BountyDepositBase;
+    type BountyDepositPerByte = BountyDepositPerByte;
+    type BountyDepositPayoutDelay = BountyDepositPayoutDelay;
+    type BountyUpdatePeriod = BountyUpdatePeriod;
+    type BountyCuratorDeposit = BountyCuratorDeposit;
+    type BountyValueMinimum = BountyValueMinimum;
+    type MaximumReasonLength = MaximumReasonLength;
 }
 
 parameter_types! {
@@ -619,6 +716,7 @@ parameter_types! {
     pub const RentByteFee: Balance = 4 * MILLICENTS;
     pub const RentDepositOffset: Balance = 1000 * MILLICENTS;
     pub const SurchargeReward: Balance = 150 * MILLICENTS;
+    pub const CodeDepositPerByte: Balance = 10 * MILLICENTS;
 }
 
 impl pallet_contracts::Trait for Runtime {
@@ -637,7 +735,81 @@ impl pallet_contracts::Trait for Runtime {
     type SurchargeReward = SurchargeReward;
     type MaxDepth = pallet_contracts::DefaultMaxDepth;
     type MaxValueSize = pallet_contracts::DefaultMaxValueSize;
+    type CodeDepositPerByte = CodeDepositPerByte;
+    type MaxMigratedContracts = pallet_contracts::DefaultMaxMigratedContracts;
     type WeightPrice = pallet_transaction_payment::Module<Self>;
+    type ChainExtension = ContractsChainExtension;
+}
+
+parameter_types! {
+    pub const EvmModuleId: ModuleId = ModuleId(*b"py/evmpa");
+    pub const EvmStorageDepositPerByte: Balance = 10 * MILLICENTS;
+    pub EvmDeletionWeightLimit: Weight = Perbill::from_percent(10) * MaximumBlockWeight::get();
+}
+
+impl pallet_evm::Trait for Runtime {
+    type ModuleId = EvmModuleId;
+    type FeeCalculator = ();
+    type ConvertAccountId = pallet_evm::HashTruncateConvertAccountId<BlakeTwo256>;
+    type Currency = Balances;
+    type Event = Event;
+    type Precompiles = ();
+    type ChainId = pallet_evm::SystemChainId;
+    type StorageDepositPerByte = EvmStorageDepositPerByte;
+    type DeletionWeightLimit = EvmDeletionWeightLimit;
+    type Assets = EvmAssets;
+    type AddressMapping = pallet_evm::HashedAddressMapping<BlakeTwo256>;
+}
+
+parameter_types! {
+    pub const AssetMetadataDepositBase: Balance = 1 * DOLLARS;
+    pub const AssetMetadataDepositPerByte: Balance = 10 * CENTS;
+    pub const AssetStringLimit: u32 = 50;
+    pub const AssetApprovalDeposit: Balance = 1 * DOLLARS;
+}
+
+impl pallet_assets::Trait for Runtime {
+    type Event = Event;
+    type Balance = Balance;
+    type AssetId = u32;
+    type Currency = Balances;
+    type MetadataDepositBase = AssetMetadataDepositBase;
+    type MetadataDepositPerByte = AssetMetadataDepositPerByte;
+    type StringLimit = AssetStringLimit;
+    type ApprovalDeposit = AssetApprovalDeposit;
+    type MinBalanceExempt = AssetMinBalanceExempt;
+}
+
+/// Module accounts which may hold less than the minimum balance of an asset.
+pub struct AssetMinBalanceExempt;
+
+impl Contains<AccountId> for AssetMinBalanceExempt {
+    fn sorted_members() -> Vec<AccountId> {
+        vec![AssetFeeCollector::get()]
+    }
+}
+
+parameter_types! {
+    pub AssetFeeCollector: AccountId = TreasuryModuleId::get().into_account();
+}
+
+impl pallet_asset_tx_payment::Trait for Runtime {
+    type Event = Event;
+    type Assets = pallet_asset_tx_payment::AssetsAdapter<Runtime>;
+    type FeeCollector = AssetFeeCollector;
+    type PriceOracle = ();
+    type RateOrigin = EnsureRootOrHalfCouncil;
+}
+
+parameter_types! {
+    pub const SwapProofLimit: u32 = 1024;
+}
+
+impl pallet_atomic_swap::Trait for Runtime {
+    type Event = Event;
+    type SwapAction = SwapAction;
+    type Time = Timestamp;
+    type ProofLimit = SwapProofLimit;
 }
 
 impl pallet_sudo::Trait for Runtime {
@@ -683,7 +855,7 @@ where
             frame_system::CheckEra::<Runtime>::from(generic::Era::mortal(period, current_block)),
             frame_system::CheckNonce::<Runtime>::from(nonce),
             frame_system::CheckWeight::<Runtime>::new(),
-            pallet_transaction_payment::ChargeTransactionPayment::<Runtime>::from(tip),
+            pallet_asset_tx_payment::ChargeAssetTxPayment::<Runtime>::from(tip, None),
             pallet_grandpa::ValidateEquivocationReport::<Runtime>::new(),
         );
         let raw_payload = SignedPayload::new(call, extra)
@@ -772,6 +944,7 @@ parameter_types! {
     pub const MaxSubAccounts: u32 = 100;
     pub const MaxAdditionalFields: u32 = 100;
     pub const MaxRegistrars: u32 = 20;
+    pub const JudgementTimeout: BlockNumber = 14 * DAYS;
 }
 
 impl pallet_identity::Trait for Runtime {
@@ -786,6 +959,7 @@ impl pallet_identity::Trait for Runtime {
     type Slashed = Treasury;
     type ForceOrigin = EnsureRootOrHalfCouncil;
     type RegistrarOrigin = EnsureRootOrHalfCouncil;
+    type JudgementTimeout = JudgementTimeout;
 }
 
 parameter_types! {
@@ -793,6 +967,8 @@ parameter_types! {
     pub const FriendDepositFactor: Balance = 50 * CENTS;
     pub const MaxFriends: u16 = 9;
     pub const RecoveryDeposit: Balance = 5 * DOLLARS;
+    pub const FriendChangeDelay: BlockNumber = 7 * DAYS;
+    pub const MaxActiveRecoveries: u32 = 8;
 }
 
 impl pallet_recovery::Trait for Runtime {
@@ -803,6 +979,8 @@ impl pallet_recovery::Trait for Runtime {
     type FriendDepositFactor = FriendDepositFactor;
     type MaxFriends = MaxFriends;
     type RecoveryDeposit = RecoveryDeposit;
+    type FriendChangeDelay = FriendChangeDelay;
+    type MaxActiveRecoveries = MaxActiveRecoveries;
 }
 
 parameter_types! {
@@ -836,6 +1014,7 @@ impl pallet_society::Trait for Runtime {
 
 parameter_types! {
     pub const MinVestedTransfer: Balance = 100 * DOLLARS;
+    pub const MaxVestingSchedules: u32 = 28;
 }
 
 impl pallet_vesting::Trait for Runtime {
@@ -843,6 +1022,15 @@ impl pallet_vesting::Trait for Runtime {
     type Currency = Balances;
     type BlockNumberToBalance = ConvertInto;
     type MinVestedTransfer = MinVestedTransfer;
+    type MaxVestingSchedules = MaxVestingSchedules;
+}
+
+parameter_types! {
+    pub const IboMetricsInterval: BlockNumber = 10 * MINUTES;
+    pub const IboMetricsReportLifetime: BlockNumber = HOURS;
+    pub const IboMinMetricsReports: u32 = 3;
+    pub const IboMaxMetricsReporters: u32 = 16;
+    pub const IboMaxSnapshotRemovals: u32 = 500;
 }
 
 impl ibo::Trait for Runtime {
@@ -850,6 +1038,13 @@ impl ibo::Trait for Runtime {
     type Currency = Balances;
     type CouncilMembers = Council;
     type Treasury = Treasury;
+    type AuthorityId = ibo::crypto::AuthId;
+    type Call = Call;
+    type MetricsInterval = IboMetricsInterval;
+    type MetricsReportLifetime = IboMetricsReportLifetime;
+    type MinMetricsReports = IboMinMetricsReports;
+    type MaxMetricsReporters = IboMaxMetricsReporters;
+    type MaxSnapshotRemovals = IboMaxSnapshotRemovals;
 }
 
 construct_runtime!(
@@ -891,6 +1086,11 @@ construct_runtime!(
         Proxy: pallet_proxy::{Module, Call, Storage, Event<T>},
         Multisig: pallet_multisig::{Module, Call, Storage, Event<T>},
         Ibo: ibo::{Module, Call, Storage, Event<T>},
+        ElectionMultiPhase: pallet_election_multi_phase::{Module, Call, Storage, Event<T>, ValidateUnsigned},
+        EVM: pallet_evm::{Module, Call, Storage, Event<T>},
+        Assets: pallet_assets::{Module, Call, Storage, Event<T>},
+        AssetTxPayment: pallet_asset_tx_payment::{Module, Call, Storage, Event<T>},
+        AtomicSwap: pallet_atomic_swap::{Module, Call, Storage, Event<T>},
     }
 );
 
@@ -916,7 +1116,7 @@ pub type SignedExtra = (
     frame_system::CheckEra<Runtime>,
     frame_system::CheckNonce<Runtime>,
     frame_system::CheckWeight<Runtime>,
-    pallet_transaction_payment::ChargeTransactionPayment<Runtime>,
+    pallet_asset_tx_payment::ChargeAssetTxPayment<Runtime>,
     pallet_grandpa::ValidateEquivocationReport<Runtime>,
 );
 /// Unchecked extrinsic type as expected by this runtime.
@@ -1081,7 +1281,7 @@ impl_runtime_apis! {
         }
     }
 
-    impl pallet_contracts_rpc_runtime_api::ContractsApi<Block, AccountId, Balance, BlockNumber>
+    impl pallet_contracts_rpc_runtime_api::ContractsApi<Block, AccountId, Balance, BlockNumber, Hash>
         for Runtime
     {
         fn call(
@@ -1114,6 +1314,135 @@ impl_runtime_apis! {
         ) -> pallet_contracts_primitives::RentProjectionResult<BlockNumber> {
             Contracts::rent_projection(address)
         }
+
+        fn instantiate(
+            origin: AccountId,
+            endowment: Balance,
+            gas_limit: u64,
+            code: pallet_contracts_primitives::Code<Hash>,
+            data: Vec<u8>,
+        ) -> pallet_contracts_primitives::ContractInstantiateResult<AccountId, Balance, BlockNumber> {
+            Contracts::bare_instantiate(origin, endowment, gas_limit, code, data)
+        }
+
+        fn upload_code(code: Vec<u8>) -> pallet_contracts_primitives::CodeUploadResult<Hash> {
+            Contracts::bare_upload_code(code)
+        }
+
+        fn events() -> Vec<pallet_contracts_primitives::ContractEvent<AccountId, Hash>> {
+            System::events()
+                .into_iter()
+                .filter_map(|record| match record.event {
+                    Event::pallet_contracts(pallet_contracts::RawEvent::ContractExecution(
+                        contract,
+                        data,
+                    )) => Some(pallet_contracts_primitives::ContractEvent {
+                        contract,
+                        topics: record.topics,
+                        data,
+                        extrinsic_index: match record.phase {
+                            frame_system::Phase::ApplyExtrinsic(index) => Some(index),
+                            _ => None,
+                        },
+                    }),
+                    _ => None,
+                })
+                .collect()
+        }
+    }
+
+    impl ibo_rpc_runtime_api::IboApi<Block, AccountId, Balance> for Runtime {
+        fn voting_power(id: u32, who: AccountId) -> Balance {
+            Ibo::voting_power(id, &who)
+        }
+    }
+
+    impl pallet_atomic_swap_rpc_runtime_api::AtomicSwapApi<
+        Block,
+        AccountId,
+        SwapAction,
+        BlockNumber,
+        Moment,
+    > for Runtime {
+        fn pending_swaps(
+            who: AccountId,
+        ) -> Vec<pallet_atomic_swap::SwapInfo<AccountId, SwapAction, BlockNumber, Moment>> {
+            AtomicSwap::pending_swaps(&who)
+        }
+    }
+
+    impl pallet_evm_rpc_runtime_api::EvmApi<Block> for Runtime {
+        fn trace(
+            source: H160,
+            action: pallet_evm::TraceAction,
+            value: U256,
+            gas_limit: u32,
+            gas_price: U256,
+        ) -> Result<pallet_evm::TraceResult, DispatchError> {
+            EVM::trace(source, action, value, gas_limit, gas_price).map_err(Into::into)
+        }
+
+        fn trace_extrinsic(
+            header: &<Block as BlockT>::Header,
+            mut extrinsics: Vec<<Block as BlockT>::Extrinsic>,
+            index: u32,
+        ) -> Result<pallet_evm::TraceResult, DispatchError> {
+            use pallet_evm::{ConvertAccountId, TraceAction};
+
+            let index = index as usize;
+            if index >= extrinsics.len() {
+                return Err("Extrinsic index out of bounds".into());
+            }
+
+            Executive::initialize_block(header);
+            let traced = extrinsics.remove(index);
+            for extrinsic in extrinsics.into_iter().take(index) {
+                let _ = Executive::apply_extrinsic(extrinsic);
+            }
+
+            let (address, _, _) = traced.signature.ok_or("Extrinsic is not signed")?;
+            let who = Indices::lookup(address)?;
+            let source = <Runtime as pallet_evm::Trait>::ConvertAccountId::convert_account_id(&who);
+            let (action, value, gas_limit, gas_price) = match traced.function {
+                Call::EVM(pallet_evm::Call::call(target, input, value, gas_limit, gas_price, _)) => {
+                    (TraceAction::Call { target, input }, value, gas_limit, gas_price)
+                }
+                Call::EVM(pallet_evm::Call::create(init, value, gas_limit, gas_price, _)) => {
+                    (TraceAction::Create { init }, value, gas_limit, gas_price)
+                }
+                Call::EVM(pallet_evm::Call::create2(init, salt, value, gas_limit, gas_price, _)) => {
+                    (TraceAction::Create2 { init, salt }, value, gas_limit, gas_price)
+                }
+                _ => return Err("Extrinsic is not an EVM transaction".into()),
+            };
+
+            EVM::trace(source, action, value, gas_limit, gas_price).map_err(Into::into)
+        }
+    }
+
+    impl pallet_recovery_rpc_runtime_api::RecoveryApi<Block, AccountId, BlockNumber, Balance> for Runtime {
+        fn active_recoveries(
+            account: AccountId,
+        ) -> Vec<pallet_recovery_rpc_runtime_api::RecoveryAttempt<AccountId, BlockNumber, Balance>> {
+            Recovery::active_recoveries_of(&account)
+                .into_iter()
+                .map(|(rescuer, active, claimable_at)| pallet_recovery_rpc_runtime_api::RecoveryAttempt {
+                    rescuer,
+                    created: active.created,
+                    claimable_at,
+                    deposit: active.deposit,
+                    vouched: active.friends,
+                })
+                .collect()
+        }
+    }
+
+    impl pallet_staking_rpc_runtime_api::StakingApi<Block, AccountId> for Runtime {
+        fn unclaimed_payouts(
+            stash: AccountId,
+        ) -> Vec<pallet_staking_rpc_runtime_api::UnclaimedPayout<AccountId>> {
+            Staking::unclaimed_payouts(&stash).into_iter().map(Into::into).collect()
+        }
     }
 
     impl pallet_transaction_payment_rpc_runtime_api::TransactionPaymentApi<
@@ -1180,6 +1509,7 @@ impl_runtime_apis! {
             let mut batches = Vec::<BenchmarkBatch>::new();
             let params = (&pallet, &benchmark, &lowest_range_values, &highest_range_values, &steps, repeat, &whitelist);
 
+            add_benchmark!(params, batches, pallet_assets, Assets);
             add_benchmark!(params, batches, pallet_babe, Babe);
             add_benchmark!(params, batches, pallet_balances, Balances);
             add_benchmark!(params, batches, pallet_collective, Council);
@@ -724,7 +724,7 @@ fn create_extrinsic<C: Crypto>(
 			frame_system::CheckEra::<Runtime>::from(Era::Immortal),
 			frame_system::CheckNonce::<Runtime>::from(i),
 			frame_system::CheckWeight::<Runtime>::new(),
-			pallet_transaction_payment::ChargeTransactionPayment::<Runtime>::from(f),
+			pallet_asset_tx_payment::ChargeAssetTxPayment::<Runtime>::from(f, None),
 			pallet_grandpa::ValidateEquivocationReport::<Runtime>::new(),
 		)
 	};
@@ -0,0 +1,397 @@
+// This file is part of Substrate.
+
+// Copyright (C) 2020 Parity Technologies (UK) Ltd.
+// SPDX-License-Identifier: Apache-2.0
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// 	http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! # Asset Transaction Payment Module
+//!
+//! This module allows transaction fees to be paid in assets other than the native currency.
+//!
+//! The [`ChargeAssetTxPayment`] signed extension replaces
+//! [`pallet_transaction_payment::ChargeTransactionPayment`]. It carries an optional asset id:
+//!   - Without an asset id, the fee is charged in the native currency exactly like
+//!     `ChargeTransactionPayment` does.
+//!   - With an asset id, the fee computed by `pallet_transaction_payment` is converted into
+//!     units of the asset and transferred from the transactor to [`Trait::FeeCollector`]. Unused
+//!     weight is refunded in the same asset after dispatch.
+//!
+//! The conversion uses the rate set for the asset with `set_fee_rate`. Assets without a rate are
+//! priced by [`Trait::PriceOracle`], e.g. a liquidity pool. Transactions paying with an asset that
+//! has neither are invalid.
+//!
+//! The assets themselves are accessed through [`FungibleAssets`], which is implemented for
+//! `pallet_assets` by [`AssetsAdapter`] and for `pallet_generic_asset` by
+//! [`GenericAssetAdapter`].
+
+#![cfg_attr(not(feature = "std"), no_std)]
+
+use codec::{Encode, Decode};
+use frame_support::{
+	decl_module, decl_storage, decl_event, Parameter,
+	traits::{Currency, Get, EnsureOrigin},
+	weights::{DispatchInfo, PostDispatchInfo},
+	dispatch::DispatchResult,
+};
+use sp_runtime::{
+	FixedU128, FixedPointNumber, FixedPointOperand,
+	transaction_validity::{
+		TransactionPriority, ValidTransaction, InvalidTransaction, TransactionValidityError,
+		TransactionValidity,
+	},
+	traits::{
+		Zero, Saturating, SignedExtension, SaturatedConversion, Dispatchable, DispatchInfoOf,
+		PostDispatchInfoOf, Member, AtLeast32BitUnsigned,
+	},
+};
+use pallet_transaction_payment::ChargeTransactionPayment;
+
+#[cfg(test)]
+mod tests;
+
+/// The rate at which fees in the native currency are converted into units of an asset.
+pub type FeeRate = FixedU128;
+
+/// The native balance type in which fees are computed.
+pub type BalanceOf<T> = <<T as pallet_transaction_payment::Trait>::Currency as Currency<
+	<T as frame_system::Trait>::AccountId,
+>>::Balance;
+type NegativeImbalanceOf<T> = <<T as pallet_transaction_payment::Trait>::Currency as Currency<
+	<T as frame_system::Trait>::AccountId,
+>>::NegativeImbalance;
+/// The identifier of an asset fees can be paid in.
+pub type AssetIdOf<T> = <<T as Trait>::Assets as FungibleAssets<
+	<T as frame_system::Trait>::AccountId,
+>>::AssetId;
+/// The balance type of the assets fees can be paid in.
+pub type AssetBalanceOf<T> = <<T as Trait>::Assets as FungibleAssets<
+	<T as frame_system::Trait>::AccountId,
+>>::Balance;
+
+/// Assets which can be moved between accounts.
+pub trait FungibleAssets<AccountId> {
+	/// The identifier of an asset.
+	type AssetId: Parameter + Member + Copy + Default;
+	/// The balance of an asset.
+	type Balance: Parameter + Member + AtLeast32BitUnsigned + Copy + Default;
+
+	/// Transfer exactly `amount` of `asset_id` from `source` to `dest`.
+	///
+	/// Must fail rather than move more than `amount`, e.g. if the asset reaps accounts left with
+	/// a dust balance.
+	fn transfer(
+		asset_id: Self::AssetId,
+		source: &AccountId,
+		dest: &AccountId,
+		amount: Self::Balance,
+	) -> DispatchResult;
+}
+
+/// Prices fees in the native currency in units of an asset.
+pub trait AssetPrice<AssetId, Balance, AssetBalance> {
+	/// The amount of `asset_id` worth `fee`, or `None` if the asset cannot be priced.
+	fn to_asset_balance(asset_id: AssetId, fee: Balance) -> Option<AssetBalance>;
+}
+
+impl<AssetId, Balance, AssetBalance> AssetPrice<AssetId, Balance, AssetBalance> for () {
+	fn to_asset_balance(_asset_id: AssetId, _fee: Balance) -> Option<AssetBalance> {
+		None
+	}
+}
+
+/// Pays fees with the assets of `pallet_assets`.
+///
+/// The fee collector should be in `pallet_assets::Trait::MinBalanceExempt`, so that it can
+/// collect fees below the minimum balance of an asset and refund them.
+pub struct AssetsAdapter<T>(sp_std::marker::PhantomData<T>);
+
+impl<T: pallet_assets::Trait> FungibleAssets<T::AccountId> for AssetsAdapter<T> where
+	T::AssetId: Member,
+{
+	type AssetId = T::AssetId;
+	type Balance = T::Balance;
+
+	fn transfer(
+		asset_id: T::AssetId,
+		source: &T::AccountId,
+		dest: &T::AccountId,
+		amount: T::Balance,
+	) -> DispatchResult {
+		<pallet_assets::Module<T> as pallet_assets::TransferExact<T::AccountId>>::transfer_exact(
+			asset_id,
+			source,
+			dest,
+			amount,
+		)
+	}
+}
+
+/// Pays fees with the assets of `pallet_generic_asset`.
+pub struct GenericAssetAdapter<T>(sp_std::marker::PhantomData<T>);
+
+impl<T: pallet_generic_asset::Trait> FungibleAssets<T::AccountId> for GenericAssetAdapter<T> {
+	type AssetId = T::AssetId;
+	type Balance = T::Balance;
+
+	fn transfer(
+		asset_id: T::AssetId,
+		source: &T::AccountId,
+		dest: &T::AccountId,
+		amount: T::Balance,
+	) -> DispatchResult {
+		pallet_generic_asset::Module::<T>::make_transfer(&asset_id, source, dest, amount)
+	}
+}
+
+/// The module configuration trait.
+pub trait Trait: pallet_transaction_payment::Trait {
+	/// The overarching event type.
+	type Event: From<Event<Self>> + Into<<Self as frame_system::Trait>::Event>;
+
+	/// The assets fees can be paid in.
+	type Assets: FungibleAssets<Self::AccountId>;
+
+	/// The account receiving fees paid in assets.
+	///
+	/// It must be able to hold less than the minimum balance of every asset fees are paid in.
+	type FeeCollector: Get<Self::AccountId>;
+
+	/// Prices fees of assets without a fee rate, e.g. by the price of a liquidity pool.
+	type PriceOracle: AssetPrice<AssetIdOf<Self>, BalanceOf<Self>, AssetBalanceOf<Self>>;
+
+	/// The origin which may set the fee rate of an asset.
+	type RateOrigin: EnsureOrigin<Self::Origin>;
+}
+
+decl_storage! {
+	trait Store for Module<T: Trait> as AssetTxPayment {
+		/// Units of an asset charged per unit of fee in the native currency.
+		pub FeeRates get(fn fee_rate): map hasher(blake2_128_concat) AssetIdOf<T> => Option<FeeRate>;
+	}
+}
+
+decl_event! {
+	pub enum Event<T> where
+		AccountId = <T as frame_system::Trait>::AccountId,
+		AssetId = AssetIdOf<T>,
+		AssetBalance = AssetBalanceOf<T>,
+	{
+		/// The fee rate of an asset was set or removed. \[asset_id, rate\]
+		FeeRateSet(AssetId, Option<FeeRate>),
+		/// A transaction fee was paid in an asset. \[who, asset_id, actual_fee\]
+		AssetTxFeePaid(AccountId, AssetId, AssetBalance),
+	}
+}
+
+decl_module! {
+	pub struct Module<T: Trait> for enum Call where origin: T::Origin {
+		fn deposit_event() = default;
+
+		/// Set the rate at which fees are converted into `asset_id`, or remove it with `None`.
+		///
+		/// The dispatch origin for this call must be `RateOrigin`.
+		///
+		/// # <weight>
+		/// - `O(1)`
+		/// - 1 storage write.
+		/// - 1 event.
+		/// # </weight>
+		#[weight = T::DbWeight::get().writes(1)]
+		fn set_fee_rate(origin, asset_id: AssetIdOf<T>, rate: Option<FeeRate>) {
+			T::RateOrigin::ensure_origin(origin)?;
+
+			match rate {
+				Some(rate) => FeeRates::<T>::insert(asset_id, rate),
+				None => FeeRates::<T>::remove(asset_id),
+			}
+			Self::deposit_event(RawEvent::FeeRateSet(asset_id, rate));
+		}
+	}
+}
+
+impl<T: Trait> Module<T> where
+	BalanceOf<T>: FixedPointOperand,
+{
+	/// Convert `fee` in the native currency into units of `asset_id`.
+	///
+	/// Returns `None` if the asset can be priced neither by its fee rate nor by `PriceOracle`.
+	pub fn to_asset_balance(asset_id: AssetIdOf<T>, fee: BalanceOf<T>) -> Option<AssetBalanceOf<T>> {
+		match Self::fee_rate(asset_id) {
+			Some(rate) => Some(rate.saturating_mul_int(fee).saturated_into::<u128>().saturated_into()),
+			None => T::PriceOracle::to_asset_balance(asset_id, fee),
+		}
+	}
+}
+
+/// The payment withdrawn before dispatch, passed on to `post_dispatch`.
+pub enum InitialPayment<T: Trait> {
+	/// No fee was withdrawn.
+	Nothing,
+	/// The fee was withdrawn in the native currency, as by `ChargeTransactionPayment`.
+	Native((BalanceOf<T>, T::AccountId, Option<NegativeImbalanceOf<T>>, BalanceOf<T>)),
+	/// The fee was withdrawn in an asset. \[who, asset_id, paid, tip\]
+	Asset(T::AccountId, AssetIdOf<T>, AssetBalanceOf<T>, BalanceOf<T>),
+}
+
+impl<T: Trait> Default for InitialPayment<T> {
+	fn default() -> Self {
+		InitialPayment::Nothing
+	}
+}
+
+/// Require the transactor pay for themselves, in the native currency or in an asset, and maybe
+/// include a tip to gain additional priority in the queue.
+///
+/// The tip is denominated in the native currency and converted together with the fee.
+#[derive(Encode, Decode, Clone, Eq, PartialEq)]
+pub struct ChargeAssetTxPayment<T: Trait + Send + Sync> {
+	#[codec(compact)]
+	tip: BalanceOf<T>,
+	asset_id: Option<AssetIdOf<T>>,
+}
+
+impl<T: Trait + Send + Sync> ChargeAssetTxPayment<T> where
+	T::Call: Dispatchable<Info=DispatchInfo, PostInfo=PostDispatchInfo>,
+	BalanceOf<T>: Send + Sync + FixedPointOperand,
+{
+	/// utility constructor. Used only in client/factory code.
+	pub fn from(tip: BalanceOf<T>, asset_id: Option<AssetIdOf<T>>) -> Self {
+		Self { tip, asset_id }
+	}
+
+	fn withdraw_fee_in_asset(
+		&self,
+		asset_id: AssetIdOf<T>,
+		who: &T::AccountId,
+		info: &DispatchInfoOf<T::Call>,
+		len: usize,
+	) -> Result<(BalanceOf<T>, AssetBalanceOf<T>), TransactionValidityError> {
+		let fee = pallet_transaction_payment::Module::<T>::compute_fee(len as u32, info, self.tip);
+
+		// Only mess with assets if fee is not zero.
+		if fee.is_zero() {
+			return Ok((fee, Zero::zero()));
+		}
+
+		let asset_fee = Module::<T>::to_asset_balance(asset_id, fee)
+			.ok_or(TransactionValidityError::from(InvalidTransaction::Payment))?;
+		T::Assets::transfer(asset_id, who, &T::FeeCollector::get(), asset_fee)
+			.map_err(|_| TransactionValidityError::from(InvalidTransaction::Payment))?;
+		Ok((fee, asset_fee))
+	}
+}
+
+impl<T: Trait + Send + Sync> sp_std::fmt::Debug for ChargeAssetTxPayment<T> {
+	#[cfg(feature = "std")]
+	fn fmt(&self, f: &mut sp_std::fmt::Formatter) -> sp_std::fmt::Result {
+		write!(f, "ChargeAssetTxPayment<{:?}, {:?}>", self.tip, self.asset_id)
+	}
+	#[cfg(not(feature = "std"))]
+	fn fmt(&self, _: &mut sp_std::fmt::Formatter) -> sp_std::fmt::Result {
+		Ok(())
+	}
+}
+
+impl<T: Trait + Send + Sync> SignedExtension for ChargeAssetTxPayment<T> where
+	BalanceOf<T>: Send + Sync + From<u64> + FixedPointOperand,
+	T::Call: Dispatchable<Info=DispatchInfo, PostInfo=PostDispatchInfo>,
+{
+	const IDENTIFIER: &'static str = "ChargeAssetTxPayment";
+	type AccountId = T::AccountId;
+	type Call = T::Call;
+	type AdditionalSigned = ();
+	type Pre = InitialPayment<T>;
+	fn additional_signed(&self) -> sp_std::result::Result<(), TransactionValidityError> { Ok(()) }
+
+	fn validate(
+		&self,
+		who: &Self::AccountId,
+		call: &Self::Call,
+		info: &DispatchInfoOf<Self::Call>,
+		len: usize,
+	) -> TransactionValidity {
+		match self.asset_id {
+			None => ChargeTransactionPayment::<T>::from(self.tip).validate(who, call, info, len),
+			Some(asset_id) => {
+				let (fee, _) = self.withdraw_fee_in_asset(asset_id, who, info, len)?;
+
+				let mut r = ValidTransaction::default();
+				// The priority is based on the fee in the native currency so that transactions
+				// paying in different assets compete fairly.
+				r.priority = fee.saturated_into::<TransactionPriority>();
+				Ok(r)
+			}
+		}
+	}
+
+	fn pre_dispatch(
+		self,
+		who: &Self::AccountId,
+		call: &Self::Call,
+		info: &DispatchInfoOf<Self::Call>,
+		len: usize
+	) -> Result<Self::Pre, TransactionValidityError> {
+		match self.asset_id {
+			None => ChargeTransactionPayment::<T>::from(self.tip)
+				.pre_dispatch(who, call, info, len)
+				.map(InitialPayment::Native),
+			Some(asset_id) => {
+				let (_, paid) = self.withdraw_fee_in_asset(asset_id, who, info, len)?;
+				if paid.is_zero() {
+					return Ok(InitialPayment::Nothing);
+				}
+				Ok(InitialPayment::Asset(who.clone(), asset_id, paid, self.tip))
+			}
+		}
+	}
+
+	fn post_dispatch(
+		pre: Self::Pre,
+		info: &DispatchInfoOf<Self::Call>,
+		post_info: &PostDispatchInfoOf<Self::Call>,
+		len: usize,
+		result: &DispatchResult,
+	) -> Result<(), TransactionValidityError> {
+		match pre {
+			InitialPayment::Nothing => Ok(()),
+			InitialPayment::Native(pre) =>
+				ChargeTransactionPayment::<T>::post_dispatch(pre, info, post_info, len, result),
+			InitialPayment::Asset(who, asset_id, paid, tip) => {
+				let actual_fee = pallet_transaction_payment::Module::<T>::compute_actual_fee(
+					len as u32,
+					info,
+					post_info,
+					tip,
+				);
+				// Without a price the whole up front payment is kept.
+				let actual_asset_fee = Module::<T>::to_asset_balance(asset_id, actual_fee)
+					.unwrap_or(paid);
+				let refund = paid.saturating_sub(actual_asset_fee);
+				// The up front payment is kept if the refund cannot be transferred back, e.g.
+				// because it would leave the transactor below the minimum balance.
+				let refunded = if !refund.is_zero() &&
+					T::Assets::transfer(asset_id, &T::FeeCollector::get(), &who, refund).is_ok()
+				{
+					refund
+				} else {
+					Zero::zero()
+				};
+				Module::<T>::deposit_event(
+					RawEvent::AssetTxFeePaid(who, asset_id, paid.saturating_sub(refunded)),
+				);
+				Ok(())
+			}
+		}
+	}
+}
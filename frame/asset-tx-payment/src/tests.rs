@@ -0,0 +1,295 @@
+// This file is part of Substrate.
+
+// Copyright (C) 2020 Parity Technologies (UK) Ltd.
+// SPDX-License-Identifier: Apache-2.0
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// 	http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+// Tests for Asset Transaction Payment Module
+
+use super::*;
+use frame_support::{
+	impl_outer_dispatch, impl_outer_origin, parameter_types, ord_parameter_types, assert_ok, assert_noop,
+	weights::{
+		DispatchInfo, PostDispatchInfo, Weight, WeightToFeePolynomial, WeightToFeeCoefficients,
+		WeightToFeeCoefficient,
+	},
+};
+use frame_system::EnsureRoot;
+use pallet_balances::Call as BalancesCall;
+use sp_core::H256;
+use sp_runtime::{
+	Perbill, FixedPointNumber,
+	traits::{BlakeTwo256, IdentityLookup, BadOrigin},
+	testing::Header,
+};
+use smallvec::smallvec;
+
+const CALL: &<Runtime as frame_system::Trait>::Call =
+	&Call::Balances(BalancesCall::transfer(2, 69));
+
+impl_outer_dispatch! {
+	pub enum Call for Runtime where origin: Origin {
+		pallet_balances::Balances,
+		frame_system::System,
+	}
+}
+
+impl_outer_origin! {
+	pub enum Origin for Runtime where system = frame_system {}
+}
+
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct Runtime;
+
+parameter_types! {
+	pub const BlockHashCount: u64 = 250;
+	pub const MaximumBlockWeight: Weight = 1024;
+	pub const MaximumBlockLength: u32 = 2 * 1024;
+	pub const AvailableBlockRatio: Perbill = Perbill::one();
+	pub const ExtrinsicBaseWeight: Weight = 5;
+}
+
+impl frame_system::Trait for Runtime {
+	type BaseCallFilter = ();
+	type Origin = Origin;
+	type Index = u64;
+	type BlockNumber = u64;
+	type Call = Call;
+	type Hash = H256;
+	type Hashing = BlakeTwo256;
+	type AccountId = u64;
+	type Lookup = IdentityLookup<Self::AccountId>;
+	type Header = Header;
+	type Event = ();
+	type BlockHashCount = BlockHashCount;
+	type MaximumBlockWeight = MaximumBlockWeight;
+	type DbWeight = ();
+	type BlockExecutionWeight = ();
+	type ExtrinsicBaseWeight = ExtrinsicBaseWeight;
+	type MaximumExtrinsicWeight = MaximumBlockWeight;
+	type MaximumBlockLength = MaximumBlockLength;
+	type AvailableBlockRatio = AvailableBlockRatio;
+	type Version = ();
+	type ModuleToIndex = ();
+	type AccountData = pallet_balances::AccountData<u64>;
+	type OnNewAccount = ();
+	type OnKilledAccount = ();
+}
+
+parameter_types! {
+	pub const ExistentialDeposit: u64 = 1;
+}
+
+impl pallet_balances::Trait for Runtime {
+	type Balance = u64;
+	type Event = ();
+	type DustRemoval = ();
+	type ExistentialDeposit = ExistentialDeposit;
+	type AccountStore = System;
+}
+
+parameter_types! {
+	pub const TransactionByteFee: u64 = 1;
+}
+
+pub struct WeightToFee;
+impl WeightToFeePolynomial for WeightToFee {
+	type Balance = u64;
+
+	fn polynomial() -> WeightToFeeCoefficients<Self::Balance> {
+		smallvec![WeightToFeeCoefficient {
+			degree: 1,
+			coeff_frac: Perbill::zero(),
+			coeff_integer: 1,
+			negative: false,
+		}]
+	}
+}
+
+impl pallet_transaction_payment::Trait for Runtime {
+	type Currency = Balances;
+	type OnTransactionPayment = ();
+	type TransactionByteFee = TransactionByteFee;
+	type WeightToFee = WeightToFee;
+	type FeeMultiplierUpdate = ();
+}
+
+parameter_types! {
+	pub const MetadataDepositBase: u64 = 1;
+	pub const MetadataDepositPerByte: u64 = 1;
+	pub const StringLimit: u32 = 50;
+	pub const ApprovalDeposit: u64 = 1;
+}
+
+impl pallet_assets::Trait for Runtime {
+	type Event = ();
+	type Balance = u64;
+	type AssetId = u32;
+	type Currency = Balances;
+	type MetadataDepositBase = MetadataDepositBase;
+	type MetadataDepositPerByte = MetadataDepositPerByte;
+	type StringLimit = StringLimit;
+	type ApprovalDeposit = ApprovalDeposit;
+	type MinBalanceExempt = FeeCollector;
+}
+
+ord_parameter_types! {
+	pub const FeeCollector: u64 = 99;
+}
+
+impl Trait for Runtime {
+	type Event = ();
+	type Assets = AssetsAdapter<Runtime>;
+	type FeeCollector = FeeCollector;
+	type PriceOracle = ();
+	type RateOrigin = EnsureRoot<u64>;
+}
+
+type System = frame_system::Module<Runtime>;
+type Balances = pallet_balances::Module<Runtime>;
+type Assets = pallet_assets::Module<Runtime>;
+type AssetTxPayment = Module<Runtime>;
+
+const ASSET: u32 = 0;
+
+fn new_test_ext() -> sp_io::TestExternalities {
+	let mut t = frame_system::GenesisConfig::default().build_storage::<Runtime>().unwrap();
+	pallet_balances::GenesisConfig::<Runtime> {
+		balances: vec![(1, 1000), (2, 1000)],
+	}.assimilate_storage(&mut t).unwrap();
+	let mut ext: sp_io::TestExternalities = t.into();
+	ext.execute_with(|| {
+		// Account 2 holds the asset. Fees below its minimum balance are collected as well.
+		assert_ok!(Assets::issue(Origin::signed(2), 1000, 50));
+	});
+	ext
+}
+
+fn info_from_weight(w: Weight) -> DispatchInfo {
+	DispatchInfo { weight: w, ..Default::default() }
+}
+
+fn post_info_from_weight(w: Weight) -> PostDispatchInfo {
+	PostDispatchInfo { actual_weight: Some(w) }
+}
+
+#[test]
+fn paying_without_asset_uses_native_currency() {
+	new_test_ext().execute_with(|| {
+		let len = 10;
+		let pre = ChargeAssetTxPayment::<Runtime>::from(0, None)
+			.pre_dispatch(&1, CALL, &info_from_weight(5), len)
+			.unwrap();
+		// 5 base weight + 10 length + 5 weight
+		assert_eq!(Balances::free_balance(1), 1000 - 20);
+
+		assert_ok!(ChargeAssetTxPayment::<Runtime>::post_dispatch(
+			pre,
+			&info_from_weight(5),
+			&post_info_from_weight(2),
+			len,
+			&Ok(()),
+		));
+		assert_eq!(Balances::free_balance(1), 1000 - 17);
+	});
+}
+
+#[test]
+fn paying_with_asset_converts_fee_and_refunds_in_asset() {
+	new_test_ext().execute_with(|| {
+		assert_ok!(AssetTxPayment::set_fee_rate(
+			Origin::root(),
+			ASSET,
+			Some(FeeRate::saturating_from_integer(2)),
+		));
+
+		let len = 10;
+		let pre = ChargeAssetTxPayment::<Runtime>::from(0, Some(ASSET))
+			.pre_dispatch(&2, CALL, &info_from_weight(5), len)
+			.unwrap();
+		assert_eq!(Balances::free_balance(2), 1000);
+		assert_eq!(Assets::balance(ASSET, 2), 1000 - 40);
+		assert_eq!(Assets::balance(ASSET, 99), 40);
+
+		assert_ok!(ChargeAssetTxPayment::<Runtime>::post_dispatch(
+			pre,
+			&info_from_weight(5),
+			&post_info_from_weight(2),
+			len,
+			&Ok(()),
+		));
+		assert_eq!(Assets::balance(ASSET, 2), 1000 - 34);
+		assert_eq!(Assets::balance(ASSET, 99), 34);
+	});
+}
+
+#[test]
+fn paying_with_asset_does_not_sweep_dust() {
+	new_test_ext().execute_with(|| {
+		assert_ok!(AssetTxPayment::set_fee_rate(
+			Origin::root(),
+			ASSET,
+			Some(FeeRate::saturating_from_integer(2)),
+		));
+		assert_ok!(Assets::transfer(Origin::signed(2), ASSET, 1, 940));
+
+		// The fee of 40 would leave 20, below the minimum balance of 50.
+		assert_noop!(
+			ChargeAssetTxPayment::<Runtime>::from(0, Some(ASSET))
+				.pre_dispatch(&2, CALL, &info_from_weight(5), 10)
+				.map(|_| ()),
+			TransactionValidityError::from(InvalidTransaction::Payment),
+		);
+		assert_eq!(Assets::balance(ASSET, 2), 60);
+		assert_eq!(Assets::balance(ASSET, 99), 0);
+	});
+}
+
+#[test]
+fn paying_with_unpriced_asset_is_invalid() {
+	new_test_ext().execute_with(|| {
+		assert_noop!(
+			ChargeAssetTxPayment::<Runtime>::from(0, Some(ASSET))
+				.pre_dispatch(&2, CALL, &info_from_weight(5), 10)
+				.map(|_| ()),
+			TransactionValidityError::from(InvalidTransaction::Payment),
+		);
+		// The transactor holds none of the asset.
+		assert_ok!(AssetTxPayment::set_fee_rate(
+			Origin::root(),
+			ASSET,
+			Some(FeeRate::saturating_from_integer(2)),
+		));
+		assert_noop!(
+			ChargeAssetTxPayment::<Runtime>::from(0, Some(ASSET))
+				.pre_dispatch(&1, CALL, &info_from_weight(5), 10)
+				.map(|_| ()),
+			TransactionValidityError::from(InvalidTransaction::Payment),
+		);
+	});
+}
+
+#[test]
+fn setting_fee_rate_requires_rate_origin() {
+	new_test_ext().execute_with(|| {
+		assert_noop!(
+			AssetTxPayment::set_fee_rate(Origin::signed(2), ASSET, Some(FeeRate::one())),
+			BadOrigin,
+		);
+		assert_ok!(AssetTxPayment::set_fee_rate(Origin::root(), ASSET, Some(FeeRate::one())));
+		assert_eq!(AssetTxPayment::fee_rate(ASSET), Some(FeeRate::one()));
+		assert_ok!(AssetTxPayment::set_fee_rate(Origin::root(), ASSET, None));
+		assert_eq!(AssetTxPayment::fee_rate(ASSET), None);
+	});
+}
@@ -0,0 +1,193 @@
+// This file is part of Substrate.
+
+// Copyright (C) 2020 Parity Technologies (UK) Ltd.
+// SPDX-License-Identifier: Apache-2.0
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// 	http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+// Benchmarks for Assets Pallet
+
+#![cfg(feature = "runtime-benchmarks")]
+
+use super::*;
+use frame_system::RawOrigin;
+use frame_benchmarking::{benchmarks, account};
+use sp_runtime::traits::{Bounded, StaticLookup};
+use crate::Module as Assets;
+
+const SEED: u32 = 0;
+
+fn create_default_asset<T: Trait>() -> (T::AccountId, <T::Lookup as StaticLookup>::Source) {
+	let caller: T::AccountId = account("caller", 0, SEED);
+	let caller_lookup = T::Lookup::unlookup(caller.clone());
+	T::Currency::make_free_balance_be(&caller, DepositBalanceOf::<T>::max_value());
+	let _ = Assets::<T>::issue(RawOrigin::Signed(caller.clone()).into(), 1_000_000.into(), 1.into());
+	(caller, caller_lookup)
+}
+
+benchmarks! {
+	_ { }
+
+	issue {
+		let caller: T::AccountId = account("caller", 0, SEED);
+	}: _(RawOrigin::Signed(caller.clone()), 1_000_000.into(), 1.into())
+	verify {
+		assert_eq!(Assets::<T>::balance(Zero::zero(), caller), 1_000_000.into());
+	}
+
+	transfer {
+		let (caller, _) = create_default_asset::<T>();
+		let target: T::AccountId = account("target", 0, SEED);
+		let target_lookup = T::Lookup::unlookup(target.clone());
+	}: _(RawOrigin::Signed(caller), Zero::zero(), target_lookup, 500_000.into())
+	verify {
+		assert_eq!(Assets::<T>::balance(Zero::zero(), target), 500_000.into());
+	}
+
+	mint {
+		let (caller, caller_lookup) = create_default_asset::<T>();
+	}: _(RawOrigin::Signed(caller.clone()), Zero::zero(), caller_lookup, 1_000.into())
+	verify {
+		assert_eq!(Assets::<T>::total_supply(Zero::zero()), 1_001_000.into());
+	}
+
+	burn {
+		let (caller, caller_lookup) = create_default_asset::<T>();
+	}: _(RawOrigin::Signed(caller.clone()), Zero::zero(), caller_lookup, 1_000.into())
+	verify {
+		assert_eq!(Assets::<T>::total_supply(Zero::zero()), 999_000.into());
+	}
+
+	freeze {
+		let (caller, caller_lookup) = create_default_asset::<T>();
+	}: _(RawOrigin::Signed(caller.clone()), Zero::zero(), caller_lookup)
+	verify {
+		assert!(Assets::<T>::is_frozen(T::AssetId::zero(), caller));
+	}
+
+	thaw {
+		let (caller, caller_lookup) = create_default_asset::<T>();
+		Assets::<T>::freeze(RawOrigin::Signed(caller.clone()).into(), Zero::zero(), caller_lookup.clone())?;
+	}: _(RawOrigin::Signed(caller.clone()), Zero::zero(), caller_lookup)
+	verify {
+		assert!(!Assets::<T>::is_frozen(T::AssetId::zero(), caller));
+	}
+
+	freeze_asset {
+		let (caller, _) = create_default_asset::<T>();
+	}: _(RawOrigin::Signed(caller), Zero::zero())
+	verify {
+		assert!(Assets::<T>::asset(T::AssetId::zero()).unwrap().is_frozen);
+	}
+
+	thaw_asset {
+		let (caller, _) = create_default_asset::<T>();
+		Assets::<T>::freeze_asset(RawOrigin::Signed(caller.clone()).into(), Zero::zero())?;
+	}: _(RawOrigin::Signed(caller), Zero::zero())
+	verify {
+		assert!(!Assets::<T>::asset(T::AssetId::zero()).unwrap().is_frozen);
+	}
+
+	transfer_ownership {
+		let (caller, _) = create_default_asset::<T>();
+		let target: T::AccountId = account("target", 0, SEED);
+		let target_lookup = T::Lookup::unlookup(target.clone());
+	}: _(RawOrigin::Signed(caller), Zero::zero(), target_lookup)
+	verify {
+		assert_eq!(Assets::<T>::asset(T::AssetId::zero()).unwrap().owner, target);
+	}
+
+	set_team {
+		let (caller, _) = create_default_asset::<T>();
+		let target: T::AccountId = account("target", 0, SEED);
+		let target_lookup = T::Lookup::unlookup(target.clone());
+	}: _(RawOrigin::Signed(caller), Zero::zero(), target_lookup.clone(), target_lookup)
+	verify {
+		assert_eq!(Assets::<T>::asset(T::AssetId::zero()).unwrap().admin, target);
+	}
+
+	assign_roles {
+		let (caller, caller_lookup) = create_default_asset::<T>();
+		<Asset<T>>::mutate(T::AssetId::zero(), |details| {
+			details.as_mut().unwrap().owner = T::AccountId::default();
+		});
+	}: _(RawOrigin::Root, Zero::zero(), caller_lookup.clone(), caller_lookup.clone(), caller_lookup)
+	verify {
+		assert_eq!(Assets::<T>::asset(T::AssetId::zero()).unwrap().owner, caller);
+	}
+
+	set_metadata {
+		let n in 0 .. T::StringLimit::get();
+		let s in 0 .. T::StringLimit::get();
+
+		let name = vec![0u8; n as usize];
+		let symbol = vec![0u8; s as usize];
+		let decimals = 12;
+
+		let (caller, _) = create_default_asset::<T>();
+	}: _(RawOrigin::Signed(caller), Zero::zero(), name, symbol, decimals)
+
+	approve_transfer {
+		let (caller, _) = create_default_asset::<T>();
+		let delegate: T::AccountId = account("delegate", 0, SEED);
+		let delegate_lookup = T::Lookup::unlookup(delegate.clone());
+	}: _(RawOrigin::Signed(caller.clone()), Zero::zero(), delegate_lookup, 100.into())
+	verify {
+		let approval = Assets::<T>::approval((T::AssetId::zero(), caller, delegate));
+		assert_eq!(approval.map(|approval| approval.amount), Some(100.into()));
+	}
+
+	transfer_approved {
+		let (owner, owner_lookup) = create_default_asset::<T>();
+		let delegate: T::AccountId = account("delegate", 0, SEED);
+		let delegate_lookup = T::Lookup::unlookup(delegate.clone());
+		Assets::<T>::approve_transfer(
+			RawOrigin::Signed(owner).into(),
+			Zero::zero(),
+			delegate_lookup,
+			100.into(),
+		)?;
+		let dest: T::AccountId = account("dest", 0, SEED);
+		let dest_lookup = T::Lookup::unlookup(dest.clone());
+	}: _(RawOrigin::Signed(delegate), Zero::zero(), owner_lookup, dest_lookup, 100.into())
+	verify {
+		assert_eq!(Assets::<T>::balance(Zero::zero(), dest), 100.into());
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::tests::{new_test_ext, Test};
+	use frame_support::assert_ok;
+
+	#[test]
+	fn test_benchmarks() {
+		new_test_ext().execute_with(|| {
+			assert_ok!(test_benchmark_issue::<Test>());
+			assert_ok!(test_benchmark_transfer::<Test>());
+			assert_ok!(test_benchmark_mint::<Test>());
+			assert_ok!(test_benchmark_burn::<Test>());
+			assert_ok!(test_benchmark_freeze::<Test>());
+			assert_ok!(test_benchmark_thaw::<Test>());
+			assert_ok!(test_benchmark_freeze_asset::<Test>());
+			assert_ok!(test_benchmark_thaw_asset::<Test>());
+			assert_ok!(test_benchmark_transfer_ownership::<Test>());
+			assert_ok!(test_benchmark_set_team::<Test>());
+			assert_ok!(test_benchmark_assign_roles::<Test>());
+			assert_ok!(test_benchmark_set_metadata::<Test>());
+			assert_ok!(test_benchmark_approve_transfer::<Test>());
+			assert_ok!(test_benchmark_transfer_approved::<Test>());
+		});
+	}
+}
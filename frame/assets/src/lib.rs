@@ -38,6 +38,14 @@
 //!   account that issues the asset.
 //! * **Asset transfer:** The action of transferring assets from one account to another.
 //! * **Asset destruction:** The process of an account removing its entire holding of an asset.
+//! * **Owner:** The account that can change the team and the metadata of an asset. Reserves the
+//!   deposit for the metadata.
+//! * **Admin:** The account that can mint and burn units of an asset and thaw frozen accounts.
+//! * **Freezer:** The account that can suspend transfers of an asset from an account or of the
+//!   asset as a whole.
+//! * **Minimum balance:** The smallest balance of an asset an account may hold. Accounts whose
+//!   balance would fall below it are reaped.
+//! * **Approval:** An amount of an asset an owner allows a delegate to transfer on their behalf.
 //! * **Fungible asset:** An asset whose units are interchangeable.
 //! * **Non-fungible asset:** An asset for which each unit has unique characteristics.
 //!
@@ -49,6 +57,9 @@
 //! * Move assets between accounts.
 //! * Remove an account's balance of an asset when requested by that account's owner and update
 //!   the asset's total supply.
+//! * Describe an asset with a name, a symbol and a number of decimals.
+//! * Let a team of privileged accounts manage the supply of an asset and freeze it.
+//! * Let holders of an asset approve others to spend it on their behalf.
 //!
 //! ## Interface
 //!
@@ -59,6 +70,17 @@
 //! the function caller's account (`origin`) to a `target` account.
 //! * `destroy` - Destroys the entire holding of a fungible asset `id` associated with the account
 //! that called the function.
+//! * `transfer_ownership` - Changes the owner of an asset.
+//! * `set_team` - Changes the admin and freezer of an asset.
+//! * `set_metadata` - Sets or clears the name, symbol and decimals of an asset.
+//! * `mint` - Creates new units of an asset, callable by its admin.
+//! * `burn` - Destroys units of an asset held by an account, callable by its admin.
+//! * `freeze` / `thaw` - Suspends or resumes transfers of an asset from an account.
+//! * `freeze_asset` / `thaw_asset` - Suspends or resumes all transfers of an asset.
+//! * `approve_transfer` - Allows a delegate to transfer some units of an asset of the caller.
+//! * `transfer_approved` - Transfers units of an asset on behalf of an owner who approved it.
+//! * `assign_roles` - Assigns the roles of an asset issued before roles were introduced, callable
+//! by root.
 //!
 //! Please refer to the [`Call`](./enum.Call.html) enum and its associated variants for documentation on each function.
 //!
@@ -67,6 +89,12 @@
 //!
 //! * `balance` - Get the asset `id` balance of `who`.
 //! * `total_supply` - Get the total supply of an asset `id`.
+//! * `asset` - Get the roles and settings of an asset `id`.
+//! * `metadata` - Get the metadata of an asset `id`.
+//! * `is_frozen` - Whether transfers of an asset `id` from `who` are suspended.
+//! * `approval` - Get the amount of an asset `id` a delegate may transfer on behalf of an owner.
+//! * `transfer_exact` - Transfer an exact amount of an asset on behalf of another module, as
+//!   implemented for [`TransferExact`](./trait.TransferExact.html).
 //!
 //! Please refer to the [`Module`](./struct.Module.html) struct for details on publicly available functions.
 //!
@@ -133,11 +161,24 @@
 // Ensure we're `no_std` when compiling for Wasm.
 #![cfg_attr(not(feature = "std"), no_std)]
 
-use frame_support::{Parameter, decl_module, decl_event, decl_storage, decl_error, ensure};
-use sp_runtime::traits::{Member, AtLeast32Bit, AtLeast32BitUnsigned, Zero, StaticLookup};
-use frame_system::{self as system, ensure_signed};
+mod benchmarking;
+
+use sp_std::prelude::*;
+use codec::{Encode, Decode};
+use frame_support::{
+	Parameter, decl_module, decl_event, decl_storage, decl_error, ensure,
+	dispatch::DispatchResult,
+	traits::{Currency, ReservableCurrency, BalanceStatus, Get, Contains},
+	weights::Weight,
+};
+use sp_runtime::{RuntimeDebug, traits::{
+	Member, AtLeast32Bit, AtLeast32BitUnsigned, Zero, StaticLookup, Saturating, CheckedAdd,
+}};
+use frame_system::{self as system, ensure_signed, ensure_root};
 use sp_runtime::traits::One;
 
+type DepositBalanceOf<T> = <<T as Trait>::Currency as Currency<<T as frame_system::Trait>::AccountId>>::Balance;
+
 /// The module configuration trait.
 pub trait Trait: frame_system::Trait {
 	/// The overarching event type.
@@ -148,30 +189,148 @@ pub trait Trait: frame_system::Trait {
 
 	/// The arithmetic type of asset identifier.
 	type AssetId: Parameter + AtLeast32Bit + Default + Copy;
+
+	/// The currency in which the deposit for asset metadata is reserved.
+	type Currency: ReservableCurrency<Self::AccountId>;
+
+	/// The base deposit reserved for setting the metadata of an asset.
+	type MetadataDepositBase: Get<DepositBalanceOf<Self>>;
+
+	/// The additional deposit reserved per byte of the name and symbol of an asset.
+	type MetadataDepositPerByte: Get<DepositBalanceOf<Self>>;
+
+	/// The maximum length of the name and of the symbol of an asset.
+	type StringLimit: Get<u32>;
+
+	/// The deposit reserved from an owner for each delegate they approve.
+	type ApprovalDeposit: Get<DepositBalanceOf<Self>>;
+
+	/// Accounts which may hold less than the minimum balance of an asset, e.g. module accounts
+	/// collecting fees or holding assets in escrow.
+	type MinBalanceExempt: Contains<Self::AccountId>;
+}
+
+/// Transfers of assets made on behalf of other modules.
+pub trait TransferExact<AccountId> {
+	/// The identifier of an asset.
+	type AssetId;
+	/// The balance of an asset.
+	type Balance;
+
+	/// Transfer exactly `amount` units of asset `id` from `source` to `dest`.
+	///
+	/// Fails rather than move more than `amount` if `source` would be left with less than the
+	/// minimum balance of the asset.
+	fn transfer_exact(
+		id: Self::AssetId,
+		source: &AccountId,
+		dest: &AccountId,
+		amount: Self::Balance,
+	) -> DispatchResult;
+}
+
+/// The roles and settings of an asset class.
+#[derive(Clone, Encode, Decode, Eq, PartialEq, RuntimeDebug)]
+pub struct AssetDetails<Balance, AccountId> {
+	/// Can change `owner`, `admin`, `freezer` and the metadata of the asset.
+	pub owner: AccountId,
+	/// Can mint and burn units of the asset and thaw accounts and the asset.
+	pub admin: AccountId,
+	/// Can freeze accounts and the asset.
+	pub freezer: AccountId,
+	/// Accounts holding less than this amount are reaped.
+	pub min_balance: Balance,
+	/// Whether transfers of the asset are suspended.
+	pub is_frozen: bool,
+}
+
+/// The human readable description of an asset class.
+#[derive(Clone, Encode, Decode, Eq, PartialEq, Default, RuntimeDebug)]
+pub struct AssetMetadata<DepositBalance> {
+	/// The balance reserved from the owner of the asset for storing this metadata.
+	pub deposit: DepositBalance,
+	/// The user friendly name of the asset.
+	pub name: Vec<u8>,
+	/// The ticker symbol of the asset.
+	pub symbol: Vec<u8>,
+	/// The number of decimals used to display balances of the asset.
+	pub decimals: u8,
+}
+
+/// An amount of an asset a delegate may transfer on behalf of an owner.
+#[derive(Clone, Encode, Decode, Eq, PartialEq, Default, RuntimeDebug)]
+pub struct Approval<Balance, DepositBalance> {
+	/// The amount the delegate may still transfer.
+	pub amount: Balance,
+	/// The balance reserved from the owner for storing this approval.
+	pub deposit: DepositBalance,
+}
+
+// A value placed in storage that represents the current version of the Assets storage.
+// This value is used by the `on_runtime_upgrade` logic to determine whether we run
+// storage migration logic.
+#[derive(Encode, Decode, Clone, Copy, PartialEq, Eq, RuntimeDebug)]
+enum Releases {
+	V1,
+	V2,
+}
+
+impl Default for Releases {
+	fn default() -> Self {
+		Releases::V1
+	}
 }
 
 decl_module! {
 	pub struct Module<T: Trait> for enum Call where origin: T::Origin {
 		type Error = Error<T>;
 
+		/// The base deposit reserved for setting the metadata of an asset.
+		const MetadataDepositBase: DepositBalanceOf<T> = T::MetadataDepositBase::get();
+
+		/// The additional deposit reserved per byte of the name and symbol of an asset.
+		const MetadataDepositPerByte: DepositBalanceOf<T> = T::MetadataDepositPerByte::get();
+
+		/// The maximum length of the name and of the symbol of an asset.
+		const StringLimit: u32 = T::StringLimit::get();
+
+		/// The deposit reserved from an owner for each delegate they approve.
+		const ApprovalDeposit: DepositBalanceOf<T> = T::ApprovalDeposit::get();
+
 		fn deposit_event() = default;
+
+		fn on_runtime_upgrade() -> Weight {
+			migrate_to_asset_details::<T>()
+		}
+
 		/// Issue a new class of fungible assets. There are, and will only ever be, `total`
 		/// such assets and they'll all belong to the `origin` initially. It will have an
 		/// identifier `AssetId` instance: this will be specified in the `Issued` event.
 		///
+		/// The `origin` becomes the owner, admin and freezer of the asset. Accounts holding less
+		/// than `min_balance` units of the asset are reaped.
+		///
 		/// # <weight>
 		/// - `O(1)`
 		/// - 1 storage mutation (codec `O(1)`).
-		/// - 2 storage writes (condec `O(1)`).
+		/// - 3 storage writes (condec `O(1)`).
 		/// - 1 event.
 		/// # </weight>
-		#[weight = 0]
-		fn issue(origin, #[compact] total: T::Balance) {
+		#[weight = T::DbWeight::get().reads_writes(1, 4)]
+		fn issue(origin, #[compact] total: T::Balance, #[compact] min_balance: T::Balance) {
 			let origin = ensure_signed(origin)?;
+			ensure!(total >= min_balance, Error::<T>::BelowMinimum);
 
 			let id = Self::next_asset_id();
 			<NextAssetId<T>>::mutate(|id| *id += One::one());
 
+			<Asset<T>>::insert(id, AssetDetails {
+				owner: origin.clone(),
+				admin: origin.clone(),
+				freezer: origin.clone(),
+				min_balance,
+				is_frozen: false,
+			});
 			<Balances<T>>::insert((id, &origin), total);
 			<TotalSupply<T>>::insert(id, total);
 
@@ -180,47 +339,446 @@ decl_module! {
 
 		/// Move some assets from one holder to another.
 		///
+		/// If the holder would be left with less than the minimum balance of the asset, their
+		/// whole balance is moved and their account is reaped.
+		///
 		/// # <weight>
 		/// - `O(1)`
 		/// - 1 static lookup
 		/// - 2 storage mutations (codec `O(1)`).
 		/// - 1 event.
 		/// # </weight>
-		#[weight = 0]
+		#[weight = T::DbWeight::get().reads_writes(4, 2)]
 		fn transfer(origin,
 			#[compact] id: T::AssetId,
 			target: <T::Lookup as StaticLookup>::Source,
 			#[compact] amount: T::Balance
 		) {
 			let origin = ensure_signed(origin)?;
-			let origin_account = (id, origin.clone());
-			let origin_balance = <Balances<T>>::get(&origin_account);
 			let target = T::Lookup::lookup(target)?;
-			ensure!(!amount.is_zero(), Error::<T>::AmountZero);
-			ensure!(origin_balance >= amount, Error::<T>::BalanceLow);
-
-			Self::deposit_event(RawEvent::Transferred(id, origin, target.clone(), amount));
-			<Balances<T>>::insert(origin_account, origin_balance - amount);
-			<Balances<T>>::mutate((id, target), |balance| *balance += amount);
+			let amount = Self::do_transfer(id, &origin, &target, amount, true)?;
+			Self::deposit_event(RawEvent::Transferred(id, origin, target, amount));
 		}
 
 		/// Destroy any assets of `id` owned by `origin`.
 		///
+		/// Fails if the asset or the account of `origin` is frozen.
+		///
 		/// # <weight>
 		/// - `O(1)`
+		/// - 2 storage reads.
 		/// - 1 storage mutation (codec `O(1)`).
 		/// - 1 storage deletion (codec `O(1)`).
 		/// - 1 event.
 		/// # </weight>
-		#[weight = 0]
+		#[weight = T::DbWeight::get().reads_writes(3, 3)]
 		fn destroy(origin, #[compact] id: T::AssetId) {
 			let origin = ensure_signed(origin)?;
+			ensure!(!<FrozenAccounts<T>>::get(id, &origin), Error::<T>::Frozen);
+			ensure!(!<Asset<T>>::get(id).map_or(false, |details| details.is_frozen), Error::<T>::Frozen);
+
 			let balance = <Balances<T>>::take((id, &origin));
 			ensure!(!balance.is_zero(), Error::<T>::BalanceZero);
 
+			<FrozenAccounts<T>>::remove(id, &origin);
 			<TotalSupply<T>>::mutate(id, |total_supply| *total_supply -= balance);
 			Self::deposit_event(RawEvent::Destroyed(id, origin, balance));
 		}
+
+		/// Change the owner of an asset.
+		///
+		/// The dispatch origin for this call must be _Signed_ by the owner of the asset. The
+		/// deposit reserved for the metadata of the asset moves to the new owner.
+		///
+		/// # <weight>
+		/// - `O(1)`
+		/// - 2 storage reads, 1 storage write.
+		/// - 1 reserved balance repatriation.
+		/// - 1 event.
+		/// # </weight>
+		#[weight = T::DbWeight::get().reads_writes(2, 3)]
+		fn transfer_ownership(origin,
+			#[compact] id: T::AssetId,
+			owner: <T::Lookup as StaticLookup>::Source
+		) {
+			let origin = ensure_signed(origin)?;
+			let owner = T::Lookup::lookup(owner)?;
+
+			<Asset<T>>::try_mutate(id, |maybe_details| -> DispatchResult {
+				let details = maybe_details.as_mut().ok_or(Error::<T>::Unknown)?;
+				ensure!(&origin == &details.owner, Error::<T>::NoPermission);
+				if details.owner == owner { return Ok(()) }
+
+				let deposit = <Metadata<T>>::get(id).deposit;
+				T::Currency::repatriate_reserved(
+					&details.owner,
+					&owner,
+					deposit,
+					BalanceStatus::Reserved,
+				)?;
+
+				details.owner = owner.clone();
+				Ok(())
+			})?;
+			Self::deposit_event(RawEvent::OwnerChanged(id, owner));
+		}
+
+		/// Change the admin and the freezer of an asset.
+		///
+		/// The dispatch origin for this call must be _Signed_ by the owner of the asset.
+		///
+		/// # <weight>
+		/// - `O(1)`
+		/// - 1 storage read, 1 storage write.
+		/// - 1 event.
+		/// # </weight>
+		#[weight = T::DbWeight::get().reads_writes(1, 1)]
+		fn set_team(origin,
+			#[compact] id: T::AssetId,
+			admin: <T::Lookup as StaticLookup>::Source,
+			freezer: <T::Lookup as StaticLookup>::Source
+		) {
+			let origin = ensure_signed(origin)?;
+			let admin = T::Lookup::lookup(admin)?;
+			let freezer = T::Lookup::lookup(freezer)?;
+
+			<Asset<T>>::try_mutate(id, |maybe_details| -> DispatchResult {
+				let details = maybe_details.as_mut().ok_or(Error::<T>::Unknown)?;
+				ensure!(&origin == &details.owner, Error::<T>::NoPermission);
+				details.admin = admin.clone();
+				details.freezer = freezer.clone();
+				Ok(())
+			})?;
+			Self::deposit_event(RawEvent::TeamChanged(id, admin, freezer));
+		}
+
+		/// Set the name, symbol and decimals of an asset.
+		///
+		/// The dispatch origin for this call must be _Signed_ by the owner of the asset.
+		///
+		/// `MetadataDepositBase` plus `MetadataDepositPerByte` for every byte of `name` and
+		/// `symbol` is reserved from the owner, adjusting any deposit reserved before. Passing an
+		/// empty `name` and `symbol` clears the metadata and refunds the deposit.
+		///
+		/// # <weight>
+		/// - `O(N + S)` where `N` and `S` are the lengths of `name` and `symbol`.
+		/// - 2 storage reads, 1 storage write.
+		/// - 1 balance reserve change.
+		/// - 1 event.
+		/// # </weight>
+		#[weight = T::DbWeight::get().reads_writes(2, 2)]
+		fn set_metadata(origin,
+			#[compact] id: T::AssetId,
+			name: Vec<u8>,
+			symbol: Vec<u8>,
+			decimals: u8
+		) {
+			let origin = ensure_signed(origin)?;
+			ensure!(name.len() <= T::StringLimit::get() as usize, Error::<T>::BadMetadata);
+			ensure!(symbol.len() <= T::StringLimit::get() as usize, Error::<T>::BadMetadata);
+
+			let details = <Asset<T>>::get(id).ok_or(Error::<T>::Unknown)?;
+			ensure!(&origin == &details.owner, Error::<T>::NoPermission);
+
+			let old_deposit = <Metadata<T>>::get(id).deposit;
+			if name.is_empty() && symbol.is_empty() {
+				T::Currency::unreserve(&origin, old_deposit);
+				<Metadata<T>>::remove(id);
+				Self::deposit_event(RawEvent::MetadataCleared(id));
+				return Ok(());
+			}
+
+			let new_deposit = T::MetadataDepositPerByte::get()
+				.saturating_mul(((name.len() + symbol.len()) as u32).into())
+				.saturating_add(T::MetadataDepositBase::get());
+			if new_deposit > old_deposit {
+				T::Currency::reserve(&origin, new_deposit - old_deposit)?;
+			} else {
+				T::Currency::unreserve(&origin, old_deposit - new_deposit);
+			}
+
+			<Metadata<T>>::insert(id, AssetMetadata {
+				deposit: new_deposit,
+				name: name.clone(),
+				symbol: symbol.clone(),
+				decimals,
+			});
+			Self::deposit_event(RawEvent::MetadataSet(id, name, symbol, decimals));
+		}
+
+		/// Create `amount` new units of an asset and credit them to `beneficiary`.
+		///
+		/// The dispatch origin for this call must be _Signed_ by the admin of the asset.
+		///
+		/// # <weight>
+		/// - `O(1)`
+		/// - 2 storage reads, 2 storage writes.
+		/// - 1 event.
+		/// # </weight>
+		#[weight = T::DbWeight::get().reads_writes(3, 2)]
+		fn mint(origin,
+			#[compact] id: T::AssetId,
+			beneficiary: <T::Lookup as StaticLookup>::Source,
+			#[compact] amount: T::Balance
+		) {
+			let origin = ensure_signed(origin)?;
+			let beneficiary = T::Lookup::lookup(beneficiary)?;
+			ensure!(!amount.is_zero(), Error::<T>::AmountZero);
+
+			let details = <Asset<T>>::get(id).ok_or(Error::<T>::Unknown)?;
+			ensure!(&origin == &details.admin, Error::<T>::NoPermission);
+
+			let supply = <TotalSupply<T>>::get(id).checked_add(&amount).ok_or(Error::<T>::Overflow)?;
+			let balance = <Balances<T>>::get((id, &beneficiary));
+			let new_balance = balance.checked_add(&amount).ok_or(Error::<T>::Overflow)?;
+			ensure!(new_balance >= details.min_balance, Error::<T>::BelowMinimum);
+
+			<Balances<T>>::insert((id, &beneficiary), new_balance);
+			<TotalSupply<T>>::insert(id, supply);
+			Self::deposit_event(RawEvent::Minted(id, beneficiary, amount));
+		}
+
+		/// Destroy up to `amount` units of an asset held by `who`.
+		///
+		/// The dispatch origin for this call must be _Signed_ by the admin of the asset.
+		///
+		/// If `who` would be left with less than the minimum balance of the asset, their whole
+		/// balance is burned and their account is reaped.
+		///
+		/// # <weight>
+		/// - `O(1)`
+		/// - 2 storage reads, 2 storage writes.
+		/// - 1 event.
+		/// # </weight>
+		#[weight = T::DbWeight::get().reads_writes(2, 3)]
+		fn burn(origin,
+			#[compact] id: T::AssetId,
+			who: <T::Lookup as StaticLookup>::Source,
+			#[compact] amount: T::Balance
+		) {
+			let origin = ensure_signed(origin)?;
+			let who = T::Lookup::lookup(who)?;
+			ensure!(!amount.is_zero(), Error::<T>::AmountZero);
+
+			let details = <Asset<T>>::get(id).ok_or(Error::<T>::Unknown)?;
+			ensure!(&origin == &details.admin, Error::<T>::NoPermission);
+
+			let balance = <Balances<T>>::get((id, &who));
+			ensure!(!balance.is_zero(), Error::<T>::BalanceZero);
+			let mut burned = amount.min(balance);
+			if balance - burned < details.min_balance {
+				burned = balance;
+			}
+
+			Self::set_balance(id, &who, balance - burned);
+			<TotalSupply<T>>::mutate(id, |total_supply| *total_supply -= burned);
+			Self::deposit_event(RawEvent::Burned(id, who, burned));
+		}
+
+		/// Suspend transfers of an asset from `who`.
+		///
+		/// The dispatch origin for this call must be _Signed_ by the freezer of the asset.
+		///
+		/// # <weight>
+		/// - `O(1)`
+		/// - 1 storage read, 1 storage write.
+		/// - 1 event.
+		/// # </weight>
+		#[weight = T::DbWeight::get().reads_writes(1, 1)]
+		fn freeze(origin, #[compact] id: T::AssetId, who: <T::Lookup as StaticLookup>::Source) {
+			let origin = ensure_signed(origin)?;
+			let who = T::Lookup::lookup(who)?;
+
+			let details = <Asset<T>>::get(id).ok_or(Error::<T>::Unknown)?;
+			ensure!(&origin == &details.freezer, Error::<T>::NoPermission);
+
+			<FrozenAccounts<T>>::insert(id, &who, true);
+			Self::deposit_event(RawEvent::Frozen(id, who));
+		}
+
+		/// Resume transfers of an asset from `who`.
+		///
+		/// The dispatch origin for this call must be _Signed_ by the admin of the asset.
+		///
+		/// # <weight>
+		/// - `O(1)`
+		/// - 1 storage read, 1 storage write.
+		/// - 1 event.
+		/// # </weight>
+		#[weight = T::DbWeight::get().reads_writes(1, 1)]
+		fn thaw(origin, #[compact] id: T::AssetId, who: <T::Lookup as StaticLookup>::Source) {
+			let origin = ensure_signed(origin)?;
+			let who = T::Lookup::lookup(who)?;
+
+			let details = <Asset<T>>::get(id).ok_or(Error::<T>::Unknown)?;
+			ensure!(&origin == &details.admin, Error::<T>::NoPermission);
+
+			<FrozenAccounts<T>>::remove(id, &who);
+			Self::deposit_event(RawEvent::Thawed(id, who));
+		}
+
+		/// Suspend all transfers of an asset.
+		///
+		/// The dispatch origin for this call must be _Signed_ by the freezer of the asset.
+		///
+		/// # <weight>
+		/// - `O(1)`
+		/// - 1 storage read, 1 storage write.
+		/// - 1 event.
+		/// # </weight>
+		#[weight = T::DbWeight::get().reads_writes(1, 1)]
+		fn freeze_asset(origin, #[compact] id: T::AssetId) {
+			let origin = ensure_signed(origin)?;
+
+			<Asset<T>>::try_mutate(id, |maybe_details| -> DispatchResult {
+				let details = maybe_details.as_mut().ok_or(Error::<T>::Unknown)?;
+				ensure!(&origin == &details.freezer, Error::<T>::NoPermission);
+				details.is_frozen = true;
+				Ok(())
+			})?;
+			Self::deposit_event(RawEvent::AssetFrozen(id));
+		}
+
+		/// Resume transfers of an asset.
+		///
+		/// The dispatch origin for this call must be _Signed_ by the admin of the asset.
+		///
+		/// # <weight>
+		/// - `O(1)`
+		/// - 1 storage read, 1 storage write.
+		/// - 1 event.
+		/// # </weight>
+		#[weight = T::DbWeight::get().reads_writes(1, 1)]
+		fn thaw_asset(origin, #[compact] id: T::AssetId) {
+			let origin = ensure_signed(origin)?;
+
+			<Asset<T>>::try_mutate(id, |maybe_details| -> DispatchResult {
+				let details = maybe_details.as_mut().ok_or(Error::<T>::Unknown)?;
+				ensure!(&origin == &details.admin, Error::<T>::NoPermission);
+				details.is_frozen = false;
+				Ok(())
+			})?;
+			Self::deposit_event(RawEvent::AssetThawed(id));
+		}
+
+		/// Allow `delegate` to transfer up to `amount` units of an asset held by the sender.
+		///
+		/// Replaces any amount approved before. Approving zero revokes the approval.
+		///
+		/// `ApprovalDeposit` is reserved from the sender when a delegate is approved, and
+		/// refunded when the approval is revoked or used up.
+		///
+		/// # <weight>
+		/// - `O(1)`
+		/// - 1 storage read, 1 storage write.
+		/// - 1 balance reserve change.
+		/// - 1 event.
+		/// # </weight>
+		#[weight = T::DbWeight::get().reads_writes(2, 2)]
+		fn approve_transfer(origin,
+			#[compact] id: T::AssetId,
+			delegate: <T::Lookup as StaticLookup>::Source,
+			#[compact] amount: T::Balance
+		) {
+			let origin = ensure_signed(origin)?;
+			let delegate = T::Lookup::lookup(delegate)?;
+
+			let key = (id, &origin, &delegate);
+			if amount.is_zero() {
+				if let Some(approval) = <Approvals<T>>::take(key) {
+					T::Currency::unreserve(&origin, approval.deposit);
+				}
+			} else {
+				let deposit = match <Approvals<T>>::get(key) {
+					Some(approval) => approval.deposit,
+					None => {
+						let deposit = T::ApprovalDeposit::get();
+						T::Currency::reserve(&origin, deposit)?;
+						deposit
+					},
+				};
+				<Approvals<T>>::insert(key, Approval { amount, deposit });
+			}
+			Self::deposit_event(RawEvent::ApprovedTransfer(id, origin, delegate, amount));
+		}
+
+		/// Transfer `amount` units of an asset held by `owner` to `destination`, spending an
+		/// approval `owner` gave to the sender with `approve_transfer`.
+		///
+		/// Unlike `transfer`, this never moves more than `amount`: it fails if `owner` would be left
+		/// with less than the minimum balance of the asset. The deposit of the approval is refunded
+		/// to `owner` once it is used up.
+		///
+		/// # <weight>
+		/// - `O(1)`
+		/// - 2 static lookups
+		/// - 5 storage reads, 3 storage writes.
+		/// - 1 balance reserve change.
+		/// - 1 event.
+		/// # </weight>
+		#[weight = T::DbWeight::get().reads_writes(6, 4)]
+		fn transfer_approved(origin,
+			#[compact] id: T::AssetId,
+			owner: <T::Lookup as StaticLookup>::Source,
+			destination: <T::Lookup as StaticLookup>::Source,
+			#[compact] amount: T::Balance
+		) {
+			let delegate = ensure_signed(origin)?;
+			let owner = T::Lookup::lookup(owner)?;
+			let destination = T::Lookup::lookup(destination)?;
+
+			let key = (id, &owner, &delegate);
+			let approval = <Approvals<T>>::get(key)
+				.filter(|approval| approval.amount >= amount)
+				.ok_or(Error::<T>::Unapproved)?;
+
+			Self::do_transfer(id, &owner, &destination, amount, false)?;
+			let remaining = approval.amount - amount;
+			if remaining.is_zero() {
+				<Approvals<T>>::remove(key);
+				T::Currency::unreserve(&owner, approval.deposit);
+			} else {
+				<Approvals<T>>::insert(key, Approval { amount: remaining, ..approval });
+			}
+			Self::deposit_event(RawEvent::TransferredApproved(id, owner, delegate, destination, amount));
+		}
+
+		/// Assign the roles of an asset issued before roles were introduced.
+		///
+		/// The dispatch origin for this call must be _Root_.
+		///
+		/// The issuer of such assets was never recorded, so the migration gives their roles to the
+		/// default account. Once assigned, the roles are changed by the owner as usual.
+		///
+		/// # <weight>
+		/// - `O(1)`
+		/// - 3 static lookups
+		/// - 1 storage read, 1 storage write.
+		/// - 2 events.
+		/// # </weight>
+		#[weight = T::DbWeight::get().reads_writes(1, 1)]
+		fn assign_roles(origin,
+			#[compact] id: T::AssetId,
+			owner: <T::Lookup as StaticLookup>::Source,
+			admin: <T::Lookup as StaticLookup>::Source,
+			freezer: <T::Lookup as StaticLookup>::Source
+		) {
+			ensure_root(origin)?;
+			let owner = T::Lookup::lookup(owner)?;
+			let admin = T::Lookup::lookup(admin)?;
+			let freezer = T::Lookup::lookup(freezer)?;
+
+			<Asset<T>>::try_mutate(id, |maybe_details| -> DispatchResult {
+				let details = maybe_details.as_mut().ok_or(Error::<T>::Unknown)?;
+				ensure!(details.owner == T::AccountId::default(), Error::<T>::RolesAssigned);
+				details.owner = owner.clone();
+				details.admin = admin.clone();
+				details.freezer = freezer.clone();
+				Ok(())
+			})?;
+			Self::deposit_event(RawEvent::OwnerChanged(id, owner));
+			Self::deposit_event(RawEvent::TeamChanged(id, admin, freezer));
+		}
 	}
 }
 
@@ -236,6 +794,30 @@ decl_event! {
 		Transferred(AssetId, AccountId, AccountId, Balance),
 		/// Some assets were destroyed.
 		Destroyed(AssetId, AccountId, Balance),
+		/// The owner of an asset changed. \[asset_id, owner\]
+		OwnerChanged(AssetId, AccountId),
+		/// The admin and freezer of an asset changed. \[asset_id, admin, freezer\]
+		TeamChanged(AssetId, AccountId, AccountId),
+		/// The metadata of an asset was set. \[asset_id, name, symbol, decimals\]
+		MetadataSet(AssetId, Vec<u8>, Vec<u8>, u8),
+		/// The metadata of an asset was cleared. \[asset_id\]
+		MetadataCleared(AssetId),
+		/// Some assets were minted. \[asset_id, beneficiary, amount\]
+		Minted(AssetId, AccountId, Balance),
+		/// Some assets were burned. \[asset_id, who, amount\]
+		Burned(AssetId, AccountId, Balance),
+		/// Transfers from an account were suspended. \[asset_id, who\]
+		Frozen(AssetId, AccountId),
+		/// Transfers from an account were resumed. \[asset_id, who\]
+		Thawed(AssetId, AccountId),
+		/// All transfers of an asset were suspended. \[asset_id\]
+		AssetFrozen(AssetId),
+		/// All transfers of an asset were resumed. \[asset_id\]
+		AssetThawed(AssetId),
+		/// A delegate was approved to transfer assets of an owner. \[asset_id, owner, delegate, amount\]
+		ApprovedTransfer(AssetId, AccountId, AccountId, Balance),
+		/// A delegate transferred assets of an owner. \[asset_id, owner, delegate, destination, amount\]
+		TransferredApproved(AssetId, AccountId, AccountId, AccountId, Balance),
 	}
 }
 
@@ -247,6 +829,22 @@ decl_error! {
 		BalanceLow,
 		/// Balance should be non-zero
 		BalanceZero,
+		/// The asset has no owner, admin and freezer
+		Unknown,
+		/// The signing account is not allowed to perform this operation on the asset
+		NoPermission,
+		/// The asset or the account is frozen
+		Frozen,
+		/// The resulting balance would be below the minimum balance of the asset
+		BelowMinimum,
+		/// The name or symbol is too long
+		BadMetadata,
+		/// The delegate was not approved to transfer the amount
+		Unapproved,
+		/// The amount would overflow the balance or the total supply
+		Overflow,
+		/// The roles of the asset were already assigned
+		RolesAssigned,
 	}
 }
 
@@ -260,6 +858,25 @@ decl_storage! {
 		///
 		/// TWOX-NOTE: `AssetId` is trusted, so this is safe.
 		TotalSupply: map hasher(twox_64_concat) T::AssetId => T::Balance;
+		/// The roles and settings of an asset.
+		///
+		/// Assets issued before roles were introduced are given details whose roles are held by
+		/// the default account when the module is upgraded.
+		Asset get(fn asset): map hasher(twox_64_concat) T::AssetId
+			=> Option<AssetDetails<T::Balance, T::AccountId>>;
+		/// The metadata of an asset.
+		Metadata get(fn metadata): map hasher(twox_64_concat) T::AssetId
+			=> AssetMetadata<DepositBalanceOf<T>>;
+		/// Whether transfers of an asset from an account are suspended.
+		FrozenAccounts get(fn is_frozen): double_map hasher(twox_64_concat) T::AssetId,
+			hasher(blake2_128_concat) T::AccountId => bool;
+		/// The amount of an asset a delegate may transfer on behalf of an owner.
+		Approvals get(fn approval): map hasher(blake2_128_concat) (T::AssetId, T::AccountId, T::AccountId)
+			=> Option<Approval<T::Balance, DepositBalanceOf<T>>>;
+		/// Storage version of the module.
+		///
+		/// New networks start with the last version.
+		StorageVersion build(|_| Releases::V2): Releases;
 	}
 }
 
@@ -276,17 +893,124 @@ impl<T: Trait> Module<T> {
 	pub fn total_supply(id: T::AssetId) -> T::Balance {
 		<TotalSupply<T>>::get(id)
 	}
+
+	/// Move `amount` units of asset `id` from `source` to `dest`, enforcing freezes and the
+	/// minimum balance of the asset.
+	///
+	/// If `source` would be left with less than the minimum balance, its whole balance is moved
+	/// when `allow_sweep` is set, and the transfer fails otherwise. Accounts in `MinBalanceExempt`
+	/// are neither swept nor required to reach the minimum balance. Returns the amount actually
+	/// moved.
+	fn do_transfer(
+		id: T::AssetId,
+		source: &T::AccountId,
+		dest: &T::AccountId,
+		amount: T::Balance,
+		allow_sweep: bool,
+	) -> Result<T::Balance, Error<T>> {
+		ensure!(!amount.is_zero(), Error::<T>::AmountZero);
+		let source_balance = <Balances<T>>::get((id, source));
+		ensure!(source_balance >= amount, Error::<T>::BalanceLow);
+		ensure!(!<FrozenAccounts<T>>::get(id, source), Error::<T>::Frozen);
+
+		let details = <Asset<T>>::get(id);
+		let min_balance = match details {
+			Some(ref details) => {
+				ensure!(!details.is_frozen, Error::<T>::Frozen);
+				details.min_balance
+			},
+			None => Zero::zero(),
+		};
+
+		let mut amount = amount;
+		if source_balance - amount < min_balance && !T::MinBalanceExempt::contains(source) {
+			ensure!(allow_sweep, Error::<T>::BelowMinimum);
+			amount = source_balance;
+		}
+		if source == dest {
+			return Ok(amount);
+		}
+
+		let dest_balance = <Balances<T>>::get((id, dest))
+			.checked_add(&amount)
+			.ok_or(Error::<T>::Overflow)?;
+		ensure!(
+			dest_balance >= min_balance || T::MinBalanceExempt::contains(dest),
+			Error::<T>::BelowMinimum,
+		);
+
+		Self::set_balance(id, source, source_balance - amount);
+		<Balances<T>>::insert((id, dest), dest_balance);
+		Ok(amount)
+	}
+
+	/// Set the balance of `who`, reaping the account if the balance is zero.
+	fn set_balance(id: T::AssetId, who: &T::AccountId, balance: T::Balance) {
+		if balance.is_zero() {
+			<Balances<T>>::remove((id, who));
+			<FrozenAccounts<T>>::remove(id, who);
+		} else {
+			<Balances<T>>::insert((id, who), balance);
+		}
+	}
+}
+
+impl<T: Trait> TransferExact<T::AccountId> for Module<T> {
+	type AssetId = T::AssetId;
+	type Balance = T::Balance;
+
+	fn transfer_exact(
+		id: T::AssetId,
+		source: &T::AccountId,
+		dest: &T::AccountId,
+		amount: T::Balance,
+	) -> DispatchResult {
+		Self::do_transfer(id, source, dest, amount, false)?;
+		Ok(())
+	}
+}
+
+/// Give the assets issued before roles were introduced their details.
+///
+/// Such assets had no owner, admin or freezer and no minimum balance. Their roles are given to
+/// the default account, so that their supply stays fixed and they cannot be frozen until
+/// `assign_roles` gives them to actual accounts.
+fn migrate_to_asset_details<T: Trait>() -> Weight {
+	if StorageVersion::get() != Releases::V1 {
+		return 0;
+	}
+
+	let mut assets: Weight = 0;
+	let mut migrated: Weight = 0;
+	for (id, _) in <TotalSupply<T>>::iter() {
+		assets += 1;
+		if !<Asset<T>>::contains_key(id) {
+			migrated += 1;
+			<Asset<T>>::insert(id, AssetDetails {
+				owner: T::AccountId::default(),
+				admin: T::AccountId::default(),
+				freezer: T::AccountId::default(),
+				min_balance: Zero::zero(),
+				is_frozen: false,
+			});
+		}
+	}
+	StorageVersion::put(Releases::V2);
+
+	T::DbWeight::get().reads_writes(2 * assets + 1, migrated + 1)
 }
 
 #[cfg(test)]
 mod tests {
 	use super::*;
 
-	use frame_support::{impl_outer_origin, assert_ok, assert_noop, parameter_types, weights::Weight};
+	use frame_support::{
+		impl_outer_origin, assert_ok, assert_noop, parameter_types, ord_parameter_types, weights::Weight,
+	};
 	use sp_core::H256;
 	// The testing primitives are very useful for avoiding having to work with signatures
 	// or public keys. `u64` is used as the `AccountId` and no `Signature`s are required.
-	use sp_runtime::{Perbill, traits::{BlakeTwo256, IdentityLookup}, testing::Header};
+	use sp_runtime::{Perbill, traits::{BlakeTwo256, IdentityLookup, BadOrigin}, testing::Header};
 
 	impl_outer_origin! {
 		pub enum Origin for Test  where system = frame_system {}
@@ -325,27 +1049,56 @@ mod tests {
 		type MaximumBlockLength = MaximumBlockLength;
 		type Version = ();
 		type ModuleToIndex = ();
-		type AccountData = ();
+		type AccountData = pallet_balances::AccountData<u64>;
 		type OnNewAccount = ();
 		type OnKilledAccount = ();
 	}
+	parameter_types! {
+		pub const ExistentialDeposit: u64 = 1;
+	}
+	impl pallet_balances::Trait for Test {
+		type Balance = u64;
+		type Event = ();
+		type DustRemoval = ();
+		type ExistentialDeposit = ExistentialDeposit;
+		type AccountStore = frame_system::Module<Test>;
+	}
+	parameter_types! {
+		pub const MetadataDepositBase: u64 = 1;
+		pub const MetadataDepositPerByte: u64 = 1;
+		pub const StringLimit: u32 = 50;
+		pub const ApprovalDeposit: u64 = 1;
+	}
+	ord_parameter_types! {
+		pub const Collector: u64 = 99;
+	}
 	impl Trait for Test {
 		type Event = ();
 		type Balance = u64;
 		type AssetId = u32;
+		type Currency = pallet_balances::Module<Test>;
+		type MetadataDepositBase = MetadataDepositBase;
+		type MetadataDepositPerByte = MetadataDepositPerByte;
+		type StringLimit = StringLimit;
+		type ApprovalDeposit = ApprovalDeposit;
+		type MinBalanceExempt = Collector;
 	}
 	type Assets = Module<Test>;
 
 	// This function basically just builds a genesis storage key/value store according to
 	// our desired mockup.
-	fn new_test_ext() -> sp_io::TestExternalities {
-		frame_system::GenesisConfig::default().build_storage::<Test>().unwrap().into()
+	pub fn new_test_ext() -> sp_io::TestExternalities {
+		let mut t = frame_system::GenesisConfig::default().build_storage::<Test>().unwrap();
+		pallet_balances::GenesisConfig::<Test> {
+			balances: vec![(1, 100), (2, 100)],
+		}.assimilate_storage(&mut t).unwrap();
+		t.into()
 	}
 
 	#[test]
 	fn issuing_asset_units_to_issuer_should_work() {
 		new_test_ext().execute_with(|| {
-			assert_ok!(Assets::issue(Origin::signed(1), 100));
+			assert_ok!(Assets::issue(Origin::signed(1), 100, 1));
 			assert_eq!(Assets::balance(0, 1), 100);
 		});
 	}
@@ -353,7 +1106,7 @@ mod tests {
 	#[test]
 	fn querying_total_supply_should_work() {
 		new_test_ext().execute_with(|| {
-			assert_ok!(Assets::issue(Origin::signed(1), 100));
+			assert_ok!(Assets::issue(Origin::signed(1), 100, 1));
 			assert_eq!(Assets::balance(0, 1), 100);
 			assert_ok!(Assets::transfer(Origin::signed(1), 0, 2, 50));
 			assert_eq!(Assets::balance(0, 1), 50);
@@ -370,7 +1123,7 @@ mod tests {
 	#[test]
 	fn transferring_amount_above_available_balance_should_work() {
 		new_test_ext().execute_with(|| {
-			assert_ok!(Assets::issue(Origin::signed(1), 100));
+			assert_ok!(Assets::issue(Origin::signed(1), 100, 1));
 			assert_eq!(Assets::balance(0, 1), 100);
 			assert_ok!(Assets::transfer(Origin::signed(1), 0, 2, 50));
 			assert_eq!(Assets::balance(0, 1), 50);
@@ -381,7 +1134,7 @@ mod tests {
 	#[test]
 	fn transferring_amount_more_than_available_balance_should_not_work() {
 		new_test_ext().execute_with(|| {
-			assert_ok!(Assets::issue(Origin::signed(1), 100));
+			assert_ok!(Assets::issue(Origin::signed(1), 100, 1));
 			assert_eq!(Assets::balance(0, 1), 100);
 			assert_ok!(Assets::transfer(Origin::signed(1), 0, 2, 50));
 			assert_eq!(Assets::balance(0, 1), 50);
@@ -395,7 +1148,7 @@ mod tests {
 	#[test]
 	fn transferring_less_than_one_unit_should_not_work() {
 		new_test_ext().execute_with(|| {
-			assert_ok!(Assets::issue(Origin::signed(1), 100));
+			assert_ok!(Assets::issue(Origin::signed(1), 100, 1));
 			assert_eq!(Assets::balance(0, 1), 100);
 			assert_noop!(Assets::transfer(Origin::signed(1), 0, 2, 0), Error::<Test>::AmountZero);
 		});
@@ -404,7 +1157,7 @@ mod tests {
 	#[test]
 	fn transferring_more_units_than_total_supply_should_not_work() {
 		new_test_ext().execute_with(|| {
-			assert_ok!(Assets::issue(Origin::signed(1), 100));
+			assert_ok!(Assets::issue(Origin::signed(1), 100, 1));
 			assert_eq!(Assets::balance(0, 1), 100);
 			assert_noop!(Assets::transfer(Origin::signed(1), 0, 2, 101), Error::<Test>::BalanceLow);
 		});
@@ -413,7 +1166,7 @@ mod tests {
 	#[test]
 	fn destroying_asset_balance_with_positive_balance_should_work() {
 		new_test_ext().execute_with(|| {
-			assert_ok!(Assets::issue(Origin::signed(1), 100));
+			assert_ok!(Assets::issue(Origin::signed(1), 100, 1));
 			assert_eq!(Assets::balance(0, 1), 100);
 			assert_ok!(Assets::destroy(Origin::signed(1), 0));
 		});
@@ -422,9 +1175,256 @@ mod tests {
 	#[test]
 	fn destroying_asset_balance_with_zero_balance_should_not_work() {
 		new_test_ext().execute_with(|| {
-			assert_ok!(Assets::issue(Origin::signed(1), 100));
+			assert_ok!(Assets::issue(Origin::signed(1), 100, 1));
 			assert_eq!(Assets::balance(0, 2), 0);
 			assert_noop!(Assets::destroy(Origin::signed(2), 0), Error::<Test>::BalanceZero);
 		});
 	}
+
+	#[test]
+	fn issuing_below_minimum_balance_should_not_work() {
+		new_test_ext().execute_with(|| {
+			assert_noop!(Assets::issue(Origin::signed(1), 10, 11), Error::<Test>::BelowMinimum);
+		});
+	}
+
+	#[test]
+	fn transferring_leaving_less_than_minimum_balance_should_reap_sender() {
+		new_test_ext().execute_with(|| {
+			assert_ok!(Assets::issue(Origin::signed(1), 100, 10));
+			assert_ok!(Assets::transfer(Origin::signed(1), 0, 2, 95));
+			assert_eq!(Assets::balance(0, 1), 0);
+			assert_eq!(Assets::balance(0, 2), 100);
+			assert!(!<Balances<Test>>::contains_key((0u32, 1u64)));
+			assert_noop!(Assets::transfer(Origin::signed(2), 0, 3, 5), Error::<Test>::BelowMinimum);
+		});
+	}
+
+	#[test]
+	fn minting_and_burning_should_work() {
+		new_test_ext().execute_with(|| {
+			assert_ok!(Assets::issue(Origin::signed(1), 100, 10));
+			assert_noop!(Assets::mint(Origin::signed(2), 0, 2, 100), Error::<Test>::NoPermission);
+			assert_noop!(Assets::mint(Origin::signed(1), 0, 2, 5), Error::<Test>::BelowMinimum);
+			assert_ok!(Assets::mint(Origin::signed(1), 0, 2, 100));
+			assert_eq!(Assets::balance(0, 2), 100);
+			assert_eq!(Assets::total_supply(0), 200);
+
+			assert_noop!(Assets::burn(Origin::signed(2), 0, 2, 50), Error::<Test>::NoPermission);
+			assert_ok!(Assets::burn(Origin::signed(1), 0, 2, 50));
+			assert_eq!(Assets::balance(0, 2), 50);
+			// burning below the minimum balance burns everything
+			assert_ok!(Assets::burn(Origin::signed(1), 0, 2, 45));
+			assert_eq!(Assets::balance(0, 2), 0);
+			assert_eq!(Assets::total_supply(0), 100);
+			assert_noop!(Assets::burn(Origin::signed(1), 0, 2, 1), Error::<Test>::BalanceZero);
+		});
+	}
+
+	#[test]
+	fn team_can_be_changed_by_owner_only() {
+		new_test_ext().execute_with(|| {
+			assert_ok!(Assets::issue(Origin::signed(1), 100, 1));
+			assert_noop!(Assets::set_team(Origin::signed(2), 0, 2, 2), Error::<Test>::NoPermission);
+			assert_ok!(Assets::set_team(Origin::signed(1), 0, 2, 3));
+			assert_noop!(Assets::mint(Origin::signed(1), 0, 1, 10), Error::<Test>::NoPermission);
+			assert_ok!(Assets::mint(Origin::signed(2), 0, 1, 10));
+			assert_noop!(Assets::freeze(Origin::signed(2), 0, 1), Error::<Test>::NoPermission);
+			assert_ok!(Assets::freeze(Origin::signed(3), 0, 1));
+
+			assert_ok!(Assets::transfer_ownership(Origin::signed(1), 0, 2));
+			assert_noop!(Assets::set_team(Origin::signed(1), 0, 1, 1), Error::<Test>::NoPermission);
+			assert_eq!(Assets::asset(0).unwrap().owner, 2);
+			assert_noop!(Assets::set_team(Origin::signed(1), 1, 1, 1), Error::<Test>::Unknown);
+		});
+	}
+
+	#[test]
+	fn frozen_accounts_and_assets_cannot_transfer() {
+		new_test_ext().execute_with(|| {
+			assert_ok!(Assets::issue(Origin::signed(1), 100, 1));
+			assert_ok!(Assets::transfer(Origin::signed(1), 0, 2, 50));
+
+			assert_ok!(Assets::freeze(Origin::signed(1), 0, 1));
+			assert_noop!(Assets::transfer(Origin::signed(1), 0, 2, 10), Error::<Test>::Frozen);
+			assert_ok!(Assets::transfer(Origin::signed(2), 0, 1, 10));
+			assert_ok!(Assets::thaw(Origin::signed(1), 0, 1));
+			assert_ok!(Assets::transfer(Origin::signed(1), 0, 2, 10));
+
+			assert_ok!(Assets::freeze_asset(Origin::signed(1), 0));
+			assert_noop!(Assets::transfer(Origin::signed(2), 0, 1, 10), Error::<Test>::Frozen);
+			assert_noop!(Assets::thaw_asset(Origin::signed(2), 0), Error::<Test>::NoPermission);
+			assert_ok!(Assets::thaw_asset(Origin::signed(1), 0));
+			assert_ok!(Assets::transfer(Origin::signed(2), 0, 1, 10));
+		});
+	}
+
+	#[test]
+	fn metadata_deposit_is_reserved_and_refunded() {
+		new_test_ext().execute_with(|| {
+			assert_ok!(Assets::issue(Origin::signed(1), 100, 1));
+			assert_noop!(
+				Assets::set_metadata(Origin::signed(2), 0, b"Token".to_vec(), b"TKN".to_vec(), 12),
+				Error::<Test>::NoPermission,
+			);
+			assert_noop!(
+				Assets::set_metadata(Origin::signed(1), 0, vec![0u8; 51], b"TKN".to_vec(), 12),
+				Error::<Test>::BadMetadata,
+			);
+
+			assert_ok!(Assets::set_metadata(Origin::signed(1), 0, b"Token".to_vec(), b"TKN".to_vec(), 12));
+			assert_eq!(<Test as Trait>::Currency::reserved_balance(1), 9);
+			assert_eq!(Assets::metadata(0).symbol, b"TKN".to_vec());
+
+			assert_ok!(Assets::set_metadata(Origin::signed(1), 0, b"T".to_vec(), b"TKN".to_vec(), 12));
+			assert_eq!(<Test as Trait>::Currency::reserved_balance(1), 5);
+
+			assert_ok!(Assets::transfer_ownership(Origin::signed(1), 0, 2));
+			assert_eq!(<Test as Trait>::Currency::reserved_balance(1), 0);
+			assert_eq!(<Test as Trait>::Currency::reserved_balance(2), 5);
+
+			assert_ok!(Assets::set_metadata(Origin::signed(2), 0, vec![], vec![], 0));
+			assert_eq!(<Test as Trait>::Currency::reserved_balance(2), 0);
+			assert_eq!(Assets::metadata(0), Default::default());
+		});
+	}
+
+	#[test]
+	fn transferring_ownership_moves_the_metadata_deposit() {
+		new_test_ext().execute_with(|| {
+			assert_ok!(Assets::issue(Origin::signed(1), 100, 1));
+			assert_ok!(Assets::set_metadata(Origin::signed(1), 0, b"T".to_vec(), b"TKN".to_vec(), 12));
+			assert_eq!(<Test as Trait>::Currency::free_balance(1), 95);
+
+			assert_ok!(Assets::transfer_ownership(Origin::signed(1), 0, 2));
+			assert_eq!(<Test as Trait>::Currency::free_balance(1), 95);
+			assert_eq!(<Test as Trait>::Currency::reserved_balance(1), 0);
+			assert_eq!(<Test as Trait>::Currency::free_balance(2), 100);
+			assert_eq!(<Test as Trait>::Currency::reserved_balance(2), 5);
+		});
+	}
+
+	#[test]
+	fn approved_transfers_should_work() {
+		new_test_ext().execute_with(|| {
+			assert_ok!(Assets::issue(Origin::signed(1), 100, 1));
+			assert_ok!(Assets::approve_transfer(Origin::signed(1), 0, 2, 50));
+			assert_eq!(Assets::approval((0u32, 1u64, 2u64)), Some(Approval { amount: 50, deposit: 1 }));
+			assert_eq!(<Test as Trait>::Currency::reserved_balance(1), 1);
+
+			assert_noop!(Assets::transfer_approved(Origin::signed(2), 0, 1, 3, 51), Error::<Test>::Unapproved);
+			assert_noop!(Assets::transfer_approved(Origin::signed(3), 0, 1, 3, 10), Error::<Test>::Unapproved);
+			assert_ok!(Assets::transfer_approved(Origin::signed(2), 0, 1, 3, 40));
+			assert_eq!(Assets::balance(0, 1), 60);
+			assert_eq!(Assets::balance(0, 3), 40);
+			assert_eq!(Assets::approval((0u32, 1u64, 2u64)), Some(Approval { amount: 10, deposit: 1 }));
+
+			// changing the amount keeps the deposit
+			assert_ok!(Assets::approve_transfer(Origin::signed(1), 0, 2, 20));
+			assert_eq!(<Test as Trait>::Currency::reserved_balance(1), 1);
+
+			assert_ok!(Assets::approve_transfer(Origin::signed(1), 0, 2, 0));
+			assert!(!<Approvals<Test>>::contains_key((0u32, 1u64, 2u64)));
+			assert_eq!(<Test as Trait>::Currency::reserved_balance(1), 0);
+			assert_noop!(Assets::transfer_approved(Origin::signed(2), 0, 1, 3, 10), Error::<Test>::Unapproved);
+		});
+	}
+
+	#[test]
+	fn approval_deposit_is_refunded_when_used_up() {
+		new_test_ext().execute_with(|| {
+			assert_ok!(Assets::issue(Origin::signed(1), 100, 1));
+			assert_ok!(Assets::approve_transfer(Origin::signed(1), 0, 2, 50));
+			assert_ok!(Assets::transfer_approved(Origin::signed(2), 0, 1, 3, 50));
+			assert!(!<Approvals<Test>>::contains_key((0u32, 1u64, 2u64)));
+			assert_eq!(<Test as Trait>::Currency::reserved_balance(1), 0);
+		});
+	}
+
+	#[test]
+	fn approved_transfers_do_not_sweep_dust() {
+		new_test_ext().execute_with(|| {
+			assert_ok!(Assets::issue(Origin::signed(1), 100, 10));
+			assert_ok!(Assets::approve_transfer(Origin::signed(1), 0, 2, 95));
+			assert_noop!(Assets::transfer_approved(Origin::signed(2), 0, 1, 3, 95), Error::<Test>::BelowMinimum);
+			assert_ok!(Assets::transfer_approved(Origin::signed(2), 0, 1, 3, 90));
+			assert_eq!(Assets::balance(0, 1), 10);
+			assert_eq!(Assets::balance(0, 3), 90);
+			assert_eq!(Assets::approval((0u32, 1u64, 2u64)), Some(Approval { amount: 5, deposit: 1 }));
+		});
+	}
+
+	#[test]
+	fn exact_transfers_do_not_sweep_dust() {
+		new_test_ext().execute_with(|| {
+			assert_ok!(Assets::issue(Origin::signed(1), 100, 10));
+			assert_noop!(Assets::transfer_exact(0, &1, &2, 95), Error::<Test>::BelowMinimum);
+			assert_ok!(Assets::transfer_exact(0, &1, &2, 90));
+			assert_eq!(Assets::balance(0, 1), 10);
+			assert_eq!(Assets::balance(0, 2), 90);
+		});
+	}
+
+	#[test]
+	fn exempt_accounts_may_hold_less_than_minimum_balance() {
+		new_test_ext().execute_with(|| {
+			assert_ok!(Assets::issue(Origin::signed(1), 100, 10));
+			assert_noop!(Assets::transfer(Origin::signed(1), 0, 2, 5), Error::<Test>::BelowMinimum);
+			assert_ok!(Assets::transfer(Origin::signed(1), 0, 99, 5));
+			assert_eq!(Assets::balance(0, 99), 5);
+			assert_ok!(Assets::transfer(Origin::signed(1), 0, 99, 10));
+			assert_ok!(Assets::transfer_exact(0, &99, &1, 10));
+			assert_eq!(Assets::balance(0, 99), 5);
+			assert_eq!(Assets::balance(0, 1), 95);
+		});
+	}
+
+	#[test]
+	fn frozen_accounts_and_assets_cannot_destroy() {
+		new_test_ext().execute_with(|| {
+			assert_ok!(Assets::issue(Origin::signed(1), 100, 1));
+			assert_ok!(Assets::transfer(Origin::signed(1), 0, 2, 50));
+
+			assert_ok!(Assets::freeze(Origin::signed(1), 0, 2));
+			assert_noop!(Assets::destroy(Origin::signed(2), 0), Error::<Test>::Frozen);
+			assert_ok!(Assets::thaw(Origin::signed(1), 0, 2));
+
+			assert_ok!(Assets::freeze_asset(Origin::signed(1), 0));
+			assert_noop!(Assets::destroy(Origin::signed(2), 0), Error::<Test>::Frozen);
+			assert_ok!(Assets::thaw_asset(Origin::signed(1), 0));
+			assert_ok!(Assets::destroy(Origin::signed(2), 0));
+			assert_eq!(Assets::total_supply(0), 50);
+		});
+	}
+
+	#[test]
+	fn migration_gives_details_to_assets_without_roles() {
+		new_test_ext().execute_with(|| {
+			StorageVersion::put(Releases::V1);
+			assert_ok!(Assets::issue(Origin::signed(1), 100, 10));
+			<NextAssetId<Test>>::put(2);
+			<TotalSupply<Test>>::insert(1, 100);
+			<Balances<Test>>::insert((1u32, 1u64), 100);
+
+			migrate_to_asset_details::<Test>();
+
+			assert_eq!(StorageVersion::get(), Releases::V2);
+			assert_eq!(Assets::asset(0).unwrap().owner, 1);
+			assert_eq!(Assets::asset(1), Some(AssetDetails {
+				owner: 0,
+				admin: 0,
+				freezer: 0,
+				min_balance: 0,
+				is_frozen: false,
+			}));
+			assert_noop!(Assets::mint(Origin::signed(1), 1, 1, 10), Error::<Test>::NoPermission);
+			assert_ok!(Assets::transfer(Origin::signed(1), 1, 2, 99));
+
+			assert_noop!(Assets::assign_roles(Origin::signed(1), 1, 1, 1, 1), BadOrigin);
+			assert_noop!(Assets::assign_roles(Origin::root(), 0, 2, 2, 2), Error::<Test>::RolesAssigned);
+			assert_ok!(Assets::assign_roles(Origin::root(), 1, 1, 1, 1));
+			assert_ok!(Assets::mint(Origin::signed(1), 1, 1, 10));
+			assert_noop!(Assets::assign_roles(Origin::root(), 1, 2, 2, 2), Error::<Test>::RolesAssigned);
+		});
+	}
 }
@@ -0,0 +1,44 @@
+// This file is part of Substrate.
+
+// Copyright (C) 2020 Parity Technologies (UK) Ltd.
+// SPDX-License-Identifier: Apache-2.0
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// 	http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Runtime API definition required by atomic swap RPC extensions.
+//!
+//! This API should be imported and implemented by the runtime,
+//! of a node that wants to use the custom RPC extension
+//! adding atomic swap access methods.
+
+#![cfg_attr(not(feature = "std"), no_std)]
+
+use sp_std::prelude::*;
+use codec::Codec;
+
+pub use pallet_atomic_swap::{HashAlgorithm, SwapInfo, Timelock};
+
+sp_api::decl_runtime_apis! {
+	/// The API to query the pending atomic swaps of an account.
+	pub trait AtomicSwapApi<AccountId, SwapAction, BlockNumber, Moment> where
+		AccountId: Codec,
+		SwapAction: Codec,
+		BlockNumber: Codec,
+		Moment: Codec,
+	{
+		/// Get the pending swaps and two-leg swaps `who` is the source or the target of.
+		///
+		/// See the atomic swap module's `pending_swaps` function for more details.
+		fn pending_swaps(who: AccountId) -> Vec<SwapInfo<AccountId, SwapAction, BlockNumber, Moment>>;
+	}
+}
@@ -0,0 +1,165 @@
+// This file is part of Substrate.
+
+// Copyright (C) 2020 Parity Technologies (UK) Ltd.
+// SPDX-License-Identifier: Apache-2.0
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// 	http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Node-specific RPC methods for querying atomic swaps.
+
+use std::sync::Arc;
+
+use codec::{Codec, Encode};
+use jsonrpc_core::{Error, ErrorCode, Result};
+use jsonrpc_derive::rpc;
+use serde::{Serialize, Deserialize};
+use sp_api::ProvideRuntimeApi;
+use sp_blockchain::HeaderBackend;
+use sp_core::{Bytes, H256};
+use sp_runtime::{generic::BlockId, traits::Block as BlockT};
+
+pub use self::gen_client::Client as AtomicSwapClient;
+pub use pallet_atomic_swap_rpc_runtime_api::{
+	self as runtime_api, HashAlgorithm, SwapInfo, Timelock, AtomicSwapApi as AtomicSwapRuntimeApi,
+};
+
+const RUNTIME_ERROR: i64 = 1;
+
+/// A pending swap as returned over RPC.
+///
+/// The swap actions are runtime specific and are returned SCALE encoded.
+#[derive(Eq, PartialEq, Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PendingSwap<AccountId, BlockNumber, Moment> {
+	/// Source of the swap.
+	pub source: AccountId,
+	/// Target of the swap.
+	pub target: AccountId,
+	/// Hashed proof of the swap.
+	pub hashed_proof: H256,
+	/// SCALE encoded action locked by the source.
+	pub action: Bytes,
+	/// For two-leg swaps, the SCALE encoded action the target has to lock in exchange.
+	pub counter_action: Option<Bytes>,
+	/// For two-leg swaps, whether the target locked `counter_action`.
+	pub accepted: bool,
+	/// Algorithm the proof is hashed with.
+	pub hash_algorithm: HashAlgorithm,
+	/// End of the lock.
+	pub end: Timelock<BlockNumber, Moment>,
+}
+
+impl<AccountId, SwapAction: Encode, BlockNumber, Moment>
+	From<SwapInfo<AccountId, SwapAction, BlockNumber, Moment>>
+	for PendingSwap<AccountId, BlockNumber, Moment>
+{
+	fn from(info: SwapInfo<AccountId, SwapAction, BlockNumber, Moment>) -> Self {
+		PendingSwap {
+			source: info.source,
+			target: info.target,
+			hashed_proof: info.hashed_proof.into(),
+			action: info.action.encode().into(),
+			counter_action: info.counter_action.map(|action| action.encode().into()),
+			accepted: info.accepted,
+			hash_algorithm: info.hash_algorithm,
+			end: info.end,
+		}
+	}
+}
+
+/// Atomic swap RPC methods.
+#[rpc]
+pub trait AtomicSwapApi<BlockHash, AccountId, BlockNumber, Moment> {
+	/// Returns the pending swaps and two-leg swaps `who` is the source or the target of.
+	#[rpc(name = "atomicSwap_pendingSwaps")]
+	fn pending_swaps(
+		&self,
+		who: AccountId,
+		at: Option<BlockHash>,
+	) -> Result<Vec<PendingSwap<AccountId, BlockNumber, Moment>>>;
+}
+
+/// An implementation of atomic swap specific RPC methods.
+pub struct AtomicSwap<C, B, S> {
+	client: Arc<C>,
+	_marker: std::marker::PhantomData<(B, S)>,
+}
+
+impl<C, B, S> AtomicSwap<C, B, S> {
+	/// Create new `AtomicSwap` with the given reference to the client.
+	pub fn new(client: Arc<C>) -> Self {
+		AtomicSwap { client, _marker: Default::default() }
+	}
+}
+
+impl<C, Block, AccountId, SwapAction, BlockNumber, Moment>
+	AtomicSwapApi<<Block as BlockT>::Hash, AccountId, BlockNumber, Moment>
+	for AtomicSwap<C, Block, SwapAction>
+where
+	Block: BlockT,
+	C: Send + Sync + 'static + ProvideRuntimeApi<Block> + HeaderBackend<Block>,
+	C::Api: AtomicSwapRuntimeApi<Block, AccountId, SwapAction, BlockNumber, Moment>,
+	AccountId: Codec,
+	SwapAction: Codec + Send + Sync + 'static,
+	BlockNumber: Codec,
+	Moment: Codec,
+{
+	fn pending_swaps(
+		&self,
+		who: AccountId,
+		at: Option<<Block as BlockT>::Hash>,
+	) -> Result<Vec<PendingSwap<AccountId, BlockNumber, Moment>>> {
+		let api = self.client.runtime_api();
+		let at = BlockId::hash(at.unwrap_or_else(||
+			// If the block hash is not supplied assume the best block.
+			self.client.info().best_hash
+		));
+
+		api.pending_swaps(&at, who)
+			.map(|swaps| swaps.into_iter().map(Into::into).collect())
+			.map_err(runtime_error_into_rpc_err)
+	}
+}
+
+/// Converts a runtime trap into an RPC error.
+fn runtime_error_into_rpc_err(err: impl std::fmt::Debug) -> Error {
+	Error {
+		code: ErrorCode::ServerError(RUNTIME_ERROR),
+		message: "Runtime trapped".into(),
+		data: Some(format!("{:?}", err).into()),
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn should_serialize_actions_as_bytes() {
+		let swap = PendingSwap::from(SwapInfo {
+			source: 1u64,
+			target: 2u64,
+			hashed_proof: [0u8; 32],
+			action: 5u8,
+			counter_action: Some(7u8),
+			accepted: true,
+			hash_algorithm: HashAlgorithm::Keccak256,
+			end: Timelock::<u32, u64>::Timestamp(1_600_000_000_000),
+		});
+
+		let json_str = r#"{"source":1,"target":2,"hashedProof":"0x0000000000000000000000000000000000000000000000000000000000000000","action":"0x05","counterAction":"0x07","accepted":true,"hashAlgorithm":"Keccak256","end":{"Timestamp":1600000000000}}"#;
+
+		assert_eq!(serde_json::to_string(&swap).unwrap(), json_str);
+		assert_eq!(serde_json::from_str::<PendingSwap<u64, u32, u64>>(json_str).unwrap(), swap);
+	}
+}
@@ -29,6 +29,13 @@
 //! is used to allow the target to approve (claim) the swap. If the swap is not
 //! claimed within a specified duration of time, the sender may cancel it.
 //!
+//! The proof is locked by its hash, computed with the [`HashAlgorithm`] chosen for each swap.
+//! Besides the default blake2_256, SHA-256 and Keccak-256 hashlocks are supported, so that swaps
+//! can be coordinated with HTLCs on chains such as Bitcoin and Ethereum. For the same reason a
+//! swap's [`Timelock`] can be expressed either in blocks or in the time units of the timestamp.
+//! Claim events carry the revealed proof, so that a counterparty only has to watch events to
+//! learn it.
+//!
 //! ## Interface
 //!
 //! ### Dispatchable Functions
@@ -36,6 +43,24 @@
 //! * `create_swap` - called by a sender to register a new atomic swap
 //! * `claim_swap` - called by the target to approve a swap
 //! * `cancel_swap` - may be called by a sender after a specified duration
+//! * `create_two_leg_swap` - called by a sender to offer a swap of one action against another
+//! * `accept_two_leg_swap` - called by the target to lock the action asked for in exchange
+//! * `claim_two_leg_swap` - called with the proof to execute both actions of an accepted swap
+//! * `cancel_two_leg_swap` - may be called by either party after a specified duration
+//!
+//! ### Swap Actions
+//!
+//! * [`BalanceSwapAction`] - reserves an amount of a currency such as `pallet_balances`.
+//! * [`GenericAssetSwapAction`] - reserves an amount of an asset of `pallet_generic_asset`.
+//! * [`AssetsSwapAction`] - moves an amount of an asset of `pallet_assets` into an escrow
+//!   account, as that pallet has no reserves.
+//!
+//! A runtime that swaps several kinds of funds uses an enum of these actions as `SwapAction`.
+//!
+//! ### Public Functions
+//!
+//! * `pending_swaps` - the pending swaps an account is a party of. Served by the
+//!   `atomicSwap_pendingSwaps` RPC.
 
 // Ensure we're `no_std` when compiling for Wasm.
 #![cfg_attr(not(feature = "std"), no_std)]
@@ -43,16 +68,28 @@
 mod tests;
 
 use sp_std::{prelude::*, marker::PhantomData, ops::{Deref, DerefMut}};
-use sp_io::hashing::blake2_256;
+use sp_io::hashing::{blake2_256, keccak_256, sha2_256};
 use frame_support::{
 	Parameter, decl_module, decl_storage, decl_event, decl_error, ensure,
-	traits::{Get, Currency, ReservableCurrency, BalanceStatus},
+	traits::{Get, Currency, ReservableCurrency, BalanceStatus, Time},
 	weights::Weight,
 	dispatch::DispatchResult,
+	storage::{with_transaction, TransactionOutcome, IterableStorageDoubleMap, IterableStorageMap},
 };
 use frame_system::{self as system, ensure_signed};
 use codec::{Encode, Decode};
-use sp_runtime::RuntimeDebug;
+use sp_runtime::{RuntimeDebug, ModuleId, traits::{AccountIdConversion, CheckedAdd, Zero}};
+use pallet_assets::TransferExact;
+#[cfg(feature = "std")]
+use serde::{Serialize, Deserialize};
+
+/// The id of the escrow account holding the assets of pending `AssetsSwapAction`s.
+const ESCROW_ID: ModuleId = ModuleId(*b"py/atswp");
+
+type MomentOf<T> = <<T as Trait>::Time as Time>::Moment;
+
+/// The end of the lock of a swap.
+pub type TimelockOf<T> = Timelock<<T as frame_system::Trait>::BlockNumber, MomentOf<T>>;
 
 /// Pending atomic swap operation.
 #[derive(Clone, Eq, PartialEq, RuntimeDebug, Encode, Decode)]
@@ -61,13 +98,119 @@ pub struct PendingSwap<T: Trait> {
 	pub source: T::AccountId,
 	/// Action of this swap.
 	pub action: T::SwapAction,
-	/// End block of the lock.
-	pub end_block: T::BlockNumber,
+	/// Algorithm the proof is hashed with.
+	pub hash_algorithm: HashAlgorithm,
+	/// End of the lock.
+	pub end: TimelockOf<T>,
 }
 
 /// Hashed proof type.
 pub type HashedProof = [u8; 32];
 
+/// The algorithm a swap's proof is hashed with.
+#[derive(Clone, Copy, Eq, PartialEq, RuntimeDebug, Encode, Decode)]
+#[cfg_attr(feature = "std", derive(Serialize, Deserialize))]
+pub enum HashAlgorithm {
+	/// BLAKE2b-256, native to Substrate chains.
+	Blake2_256,
+	/// SHA-256, as used by Bitcoin HTLCs.
+	Sha2_256,
+	/// Keccak-256, as used by Ethereum HTLCs.
+	Keccak256,
+}
+
+impl Default for HashAlgorithm {
+	fn default() -> Self {
+		HashAlgorithm::Blake2_256
+	}
+}
+
+impl HashAlgorithm {
+	/// All supported algorithms.
+	pub const ALL: [HashAlgorithm; 3] = [
+		HashAlgorithm::Blake2_256,
+		HashAlgorithm::Sha2_256,
+		HashAlgorithm::Keccak256,
+	];
+
+	/// Hash `proof` with this algorithm.
+	pub fn hash(&self, proof: &[u8]) -> HashedProof {
+		match self {
+			HashAlgorithm::Blake2_256 => blake2_256(proof),
+			HashAlgorithm::Sha2_256 => sha2_256(proof),
+			HashAlgorithm::Keccak256 => keccak_256(proof),
+		}
+	}
+}
+
+/// A lock on a swap, measured in blocks or in the time units of the chain's timestamp.
+///
+/// Passed to the calls creating swaps, it is the duration of the lock. Stored in swaps, it is the
+/// block number or the timestamp from which the swap can be cancelled.
+#[derive(Clone, Copy, Eq, PartialEq, RuntimeDebug, Encode, Decode)]
+#[cfg_attr(feature = "std", derive(Serialize, Deserialize))]
+pub enum Timelock<BlockNumber, Moment> {
+	/// Measured in blocks.
+	Block(BlockNumber),
+	/// Measured in the time units of the timestamp, milliseconds with `pallet_timestamp`.
+	Timestamp(Moment),
+}
+
+/// Pending swap of two actions between a source and a target.
+#[derive(Clone, Eq, PartialEq, RuntimeDebug, Encode, Decode)]
+pub struct PendingTwoLegSwap<T: Trait> {
+	/// Source of the swap.
+	pub source: T::AccountId,
+	/// Target of the swap.
+	pub target: T::AccountId,
+	/// Action the source locked for the target.
+	pub give: T::SwapAction,
+	/// Action the target has to lock for the source.
+	pub want: T::SwapAction,
+	/// Whether the target locked `want`.
+	pub accepted: bool,
+	/// Algorithm the proof is hashed with.
+	pub hash_algorithm: HashAlgorithm,
+	/// End of the lock.
+	pub end: TimelockOf<T>,
+}
+
+/// A pending swap an account is a party of, as returned by `Module::pending_swaps`.
+#[derive(Clone, Eq, PartialEq, RuntimeDebug, Encode, Decode)]
+pub struct SwapInfo<AccountId, SwapAction, BlockNumber, Moment> {
+	/// Source of the swap.
+	pub source: AccountId,
+	/// Target of the swap.
+	pub target: AccountId,
+	/// Hashed proof of the swap.
+	pub hashed_proof: HashedProof,
+	/// Action locked by the source.
+	pub action: SwapAction,
+	/// For two-leg swaps, the action the target has to lock in exchange.
+	pub counter_action: Option<SwapAction>,
+	/// For two-leg swaps, whether the target locked `counter_action`.
+	pub accepted: bool,
+	/// Algorithm the proof is hashed with.
+	pub hash_algorithm: HashAlgorithm,
+	/// End of the lock.
+	pub end: Timelock<BlockNumber, Moment>,
+}
+
+// A value placed in storage that represents the current version of the AtomicSwap storage.
+// This value is used by the `on_runtime_upgrade` logic to determine whether we run
+// storage migration logic.
+#[derive(Encode, Decode, Clone, Copy, PartialEq, Eq, RuntimeDebug)]
+enum Releases {
+	V1,
+	V2,
+}
+
+impl Default for Releases {
+	fn default() -> Self {
+		Releases::V1
+	}
+}
+
 /// Definition of a pending atomic swap action. It contains the following three phrases:
 ///
 /// - **Reserve**: reserve the resources needed for a swap. This is to make sure that **Claim**
@@ -78,13 +221,13 @@ pub trait SwapAction<T: Trait> {
 	/// Reserve the resources needed for the swap, from the given `source`. The reservation is
 	/// allowed to fail. If that is the case, the the full swap creation operation is cancelled.
 	fn reserve(&self, source: &T::AccountId) -> DispatchResult;
-	/// Claim the reserved resources, with `source` and `target`. Returns whether the claim
-	/// succeeds.
-	fn claim(&self, source: &T::AccountId, target: &T::AccountId) -> bool;
+	/// Claim the reserved resources, with `source` and `target`. The swap is kept pending if the
+	/// claim fails, and changes made by a failed claim are reverted.
+	fn claim(&self, source: &T::AccountId, target: &T::AccountId) -> DispatchResult;
 	/// Weight for executing the operation.
 	fn weight(&self) -> Weight;
-	/// Cancel the resources reserved in `source`.
-	fn cancel(&self, source: &T::AccountId);
+	/// Cancel the resources reserved in `source`. The swap is kept pending if this fails.
+	fn cancel(&self, source: &T::AccountId) -> DispatchResult;
 }
 
 /// A swap action that only allows transferring balances.
@@ -128,16 +271,122 @@ impl<T: Trait, C> SwapAction<T> for BalanceSwapAction<T, C> where
 		C::reserve(&source, self.value)
 	}
 
-	fn claim(&self, source: &T::AccountId, target: &T::AccountId) -> bool {
-		C::repatriate_reserved(source, target, self.value, BalanceStatus::Free).is_ok()
+	fn claim(&self, source: &T::AccountId, target: &T::AccountId) -> DispatchResult {
+		let remaining = C::repatriate_reserved(source, target, self.value, BalanceStatus::Free)?;
+		ensure!(remaining.is_zero(), Error::<T>::ClaimFailed);
+		Ok(())
 	}
 
 	fn weight(&self) -> Weight {
 		T::DbWeight::get().reads_writes(1, 1)
 	}
 
-	fn cancel(&self, source: &T::AccountId) {
+	fn cancel(&self, source: &T::AccountId) -> DispatchResult {
 		C::unreserve(source, self.value);
+		Ok(())
+	}
+}
+
+/// A swap action that only allows transferring an asset of `pallet_generic_asset`.
+#[derive(Clone, RuntimeDebug, Eq, PartialEq, Encode, Decode)]
+pub struct GenericAssetSwapAction<T: pallet_generic_asset::Trait> {
+	asset_id: T::AssetId,
+	value: T::Balance,
+}
+
+impl<T: pallet_generic_asset::Trait> GenericAssetSwapAction<T> {
+	/// Create a new swap action value of an asset.
+	pub fn new(asset_id: T::AssetId, value: T::Balance) -> Self {
+		Self { asset_id, value }
+	}
+}
+
+impl<T: Trait + pallet_generic_asset::Trait> SwapAction<T> for GenericAssetSwapAction<T> {
+	fn reserve(&self, source: &T::AccountId) -> DispatchResult {
+		pallet_generic_asset::Module::<T>::reserve(&self.asset_id, source, self.value)
+	}
+
+	fn claim(&self, source: &T::AccountId, target: &T::AccountId) -> DispatchResult {
+		let remaining = pallet_generic_asset::Module::<T>::repatriate_reserved(
+			&self.asset_id,
+			source,
+			target,
+			self.value,
+			BalanceStatus::Free,
+		);
+		ensure!(remaining.is_zero(), Error::<T>::ClaimFailed);
+		Ok(())
+	}
+
+	fn weight(&self) -> Weight {
+		T::DbWeight::get().reads_writes(2, 3)
+	}
+
+	fn cancel(&self, source: &T::AccountId) -> DispatchResult {
+		pallet_generic_asset::Module::<T>::unreserve(&self.asset_id, source, self.value);
+		Ok(())
+	}
+}
+
+/// A swap action that only allows transferring an asset of `pallet_assets`.
+///
+/// `pallet_assets` cannot reserve balances, so the value is moved into an escrow account owned by
+/// this pallet until the swap is claimed or cancelled. The value must be at least the minimum
+/// balance of the asset and must not leave the source with a dust balance, so that the escrow
+/// account never has to release more than it holds for a swap.
+#[derive(Clone, RuntimeDebug, Eq, PartialEq, Encode, Decode)]
+pub struct AssetsSwapAction<T: pallet_assets::Trait> {
+	asset_id: T::AssetId,
+	value: T::Balance,
+}
+
+impl<T: pallet_assets::Trait> AssetsSwapAction<T> {
+	/// Create a new swap action value of an asset.
+	pub fn new(asset_id: T::AssetId, value: T::Balance) -> Self {
+		Self { asset_id, value }
+	}
+}
+
+impl<T: Trait + pallet_assets::Trait> SwapAction<T> for AssetsSwapAction<T> {
+	fn reserve(&self, source: &T::AccountId) -> DispatchResult {
+		let min_balance = pallet_assets::Module::<T>::asset(self.asset_id)
+			.map(|details| details.min_balance)
+			.unwrap_or_else(Zero::zero);
+		let balance = pallet_assets::Module::<T>::balance(self.asset_id, source.clone());
+		ensure!(self.value >= min_balance, Error::<T>::ValueBelowMinimum);
+		ensure!(
+			balance <= self.value || balance - self.value >= min_balance,
+			Error::<T>::ValueBelowMinimum,
+		);
+
+		<pallet_assets::Module<T> as TransferExact<T::AccountId>>::transfer_exact(
+			self.asset_id,
+			source,
+			&Module::<T>::escrow_account(),
+			self.value,
+		)
+	}
+
+	fn claim(&self, _source: &T::AccountId, target: &T::AccountId) -> DispatchResult {
+		<pallet_assets::Module<T> as TransferExact<T::AccountId>>::transfer_exact(
+			self.asset_id,
+			&Module::<T>::escrow_account(),
+			target,
+			self.value,
+		)
+	}
+
+	fn weight(&self) -> Weight {
+		T::DbWeight::get().reads_writes(4, 2)
+	}
+
+	fn cancel(&self, source: &T::AccountId) -> DispatchResult {
+		<pallet_assets::Module<T> as TransferExact<T::AccountId>>::transfer_exact(
+			self.asset_id,
+			&Module::<T>::escrow_account(),
+			source,
+			self.value,
+		)
 	}
 }
 
@@ -147,6 +396,8 @@ pub trait Trait: frame_system::Trait {
 	type Event: From<Event<Self>> + Into<<Self as frame_system::Trait>::Event>;
 	/// Swap action.
 	type SwapAction: SwapAction<Self> + Parameter;
+	/// The time source of timestamp timelocks.
+	type Time: Time;
 	/// Limit of proof size.
 	///
 	/// Atomic swap is only atomic if once the proof is revealed, both parties can submit the proofs
@@ -165,6 +416,15 @@ decl_storage! {
 		pub PendingSwaps: double_map
 			hasher(twox_64_concat) T::AccountId, hasher(blake2_128_concat) HashedProof
 			=> Option<PendingSwap<T>>;
+
+		/// Pending two-leg swaps, by their hashed proof.
+		pub PendingTwoLegSwaps get(fn pending_two_leg_swaps):
+			map hasher(blake2_128_concat) HashedProof => Option<PendingTwoLegSwap<T>>;
+
+		/// Storage version of the pallet.
+		///
+		/// New networks start with the last version.
+		StorageVersion build(|_| Releases::V2): Releases;
 	}
 }
 
@@ -186,6 +446,20 @@ decl_error! {
 		ClaimActionMismatch,
 		/// Duration has not yet passed for the swap to be cancelled.
 		DurationNotPassed,
+		/// Duration has passed and the swap can no longer be accepted.
+		DurationPassed,
+		/// Target does not match.
+		TargetMismatch,
+		/// Two-leg swap has already been accepted.
+		AlreadyAccepted,
+		/// Two-leg swap has not been accepted by the target yet.
+		NotAccepted,
+		/// The reserved funds of a swap could not be claimed in full.
+		ClaimFailed,
+		/// The value is below the minimum balance of the asset or would leave a dust balance.
+		ValueBelowMinimum,
+		/// The end of the lock is out of range.
+		LockOverflow,
 	}
 }
 
@@ -194,13 +468,22 @@ decl_event!(
 	pub enum Event<T> where
 		AccountId = <T as system::Trait>::AccountId,
 		PendingSwap = PendingSwap<T>,
+		PendingTwoLegSwap = PendingTwoLegSwap<T>,
 	{
 		/// Swap created.
 		NewSwap(AccountId, HashedProof, PendingSwap),
-		/// Swap claimed. The last parameter indicates whether the execution succeeds.
-		SwapClaimed(AccountId, HashedProof, bool),
+		/// Swap claimed. \[target, hashed_proof, proof\]
+		SwapClaimed(AccountId, HashedProof, Vec<u8>),
 		/// Swap cancelled.
 		SwapCancelled(AccountId, HashedProof),
+		/// Two-leg swap created. \[hashed_proof, swap\]
+		NewTwoLegSwap(HashedProof, PendingTwoLegSwap),
+		/// Two-leg swap accepted by its target. \[hashed_proof\]
+		TwoLegSwapAccepted(HashedProof),
+		/// Two-leg swap claimed. \[hashed_proof, proof\]
+		TwoLegSwapClaimed(HashedProof, Vec<u8>),
+		/// Two-leg swap cancelled. \[hashed_proof\]
+		TwoLegSwapCancelled(HashedProof),
 	}
 );
 
@@ -211,25 +494,32 @@ decl_module! {
 
 		fn deposit_event() = default;
 
+		fn on_runtime_upgrade() -> Weight {
+			migrate_to_selectable_locks::<T>()
+		}
+
 		/// Register a new atomic swap, declaring an intention to send funds from origin to target
 		/// on the current blockchain. The target can claim the fund using the revealed proof. If
-		/// the fund is not claimed after `duration` blocks, then the sender can cancel the swap.
+		/// the fund is not claimed after `duration`, then the sender can cancel the swap.
 		///
 		/// The dispatch origin for this call must be _Signed_.
 		///
 		/// - `target`: Receiver of the atomic swap.
-		/// - `hashed_proof`: The blake2_256 hash of the secret proof.
+		/// - `hashed_proof`: The hash of the secret proof.
+		/// - `hash_algorithm`: The algorithm `hashed_proof` was computed with.
 		/// - `balance`: Funds to be sent from origin.
-		/// - `duration`: Locked duration of the atomic swap. For safety reasons, it is recommended
-		///   that the revealer uses a shorter duration than the counterparty, to prevent the
-		///   situation where the revealer reveals the proof too late around the end block.
-		#[weight = T::DbWeight::get().reads_writes(1, 1).saturating_add(40_000_000)]
+		/// - `duration`: Locked duration of the atomic swap, in blocks or in time. For safety
+		///   reasons, it is recommended that the revealer uses a shorter duration than the
+		///   counterparty, to prevent the situation where the revealer reveals the proof too late
+		///   around the end of the lock.
+		#[weight = T::DbWeight::get().reads_writes(3, 1).saturating_add(40_000_000)]
 		fn create_swap(
 			origin,
 			target: T::AccountId,
 			hashed_proof: HashedProof,
+			hash_algorithm: HashAlgorithm,
 			action: T::SwapAction,
-			duration: T::BlockNumber,
+			duration: TimelockOf<T>,
 		) {
 			let source = ensure_signed(origin)?;
 			ensure!(
@@ -237,12 +527,14 @@ decl_module! {
 				Error::<T>::AlreadyExist
 			);
 
+			let end = Self::lock_end(duration)?;
 			action.reserve(&source)?;
 
 			let swap = PendingSwap {
 				source,
 				action,
-				end_block: frame_system::Module::<T>::block_number() + duration,
+				hash_algorithm,
+				end,
 			};
 			PendingSwaps::<T>::insert(target.clone(), hashed_proof.clone(), swap.clone());
 
@@ -253,14 +545,17 @@ decl_module! {
 
 		/// Claim an atomic swap.
 		///
+		/// The proof is hashed with each supported algorithm to find the swap locked by it. The
+		/// swap is left pending if its action cannot be executed.
+		///
 		/// The dispatch origin for this call must be _Signed_.
 		///
 		/// - `proof`: Revealed proof of the claim.
 		/// - `action`: Action defined in the swap, it must match the entry in blockchain. Otherwise
 		///   the operation fails. This is used for weight calculation.
-		#[weight = T::DbWeight::get().reads_writes(1, 1)
+		#[weight = T::DbWeight::get().reads_writes(HashAlgorithm::ALL.len() as Weight, 1)
 		  .saturating_add(40_000_000)
-		  .saturating_add((proof.len() as Weight).saturating_mul(100))
+		  .saturating_add((proof.len() as Weight).saturating_mul(300))
 		  .saturating_add(action.weight())
 		]
 		fn claim_swap(
@@ -274,18 +569,23 @@ decl_module! {
 			);
 
 			let target = ensure_signed(origin)?;
-			let hashed_proof = blake2_256(&proof);
 
-			let swap = PendingSwaps::<T>::get(&target, hashed_proof)
+			let (hashed_proof, swap) = HashAlgorithm::ALL.iter()
+				.find_map(|algorithm| {
+					let hashed_proof = algorithm.hash(&proof);
+					PendingSwaps::<T>::get(&target, hashed_proof)
+						.filter(|swap| swap.hash_algorithm == *algorithm)
+						.map(|swap| (hashed_proof, swap))
+				})
 				.ok_or(Error::<T>::InvalidProof)?;
 			ensure!(swap.action == action, Error::<T>::ClaimActionMismatch);
 
-			let succeeded = swap.action.claim(&swap.source, &target);
+			Self::transactional(|| swap.action.claim(&swap.source, &target))?;
 
 			PendingSwaps::<T>::remove(target.clone(), hashed_proof.clone());
 
 			Self::deposit_event(
-				RawEvent::SwapClaimed(target, hashed_proof, succeeded)
+				RawEvent::SwapClaimed(target, hashed_proof, proof)
 			);
 
 			Ok(())
@@ -293,6 +593,8 @@ decl_module! {
 
 		/// Cancel an atomic swap. Only possible after the originally set duration has passed.
 		///
+		/// The swap is left pending if its action cannot be cancelled.
+		///
 		/// The dispatch origin for this call must be _Signed_.
 		///
 		/// - `target`: Target of the original atomic swap.
@@ -311,17 +613,270 @@ decl_module! {
 				swap.source == source,
 				Error::<T>::SourceMismatch,
 			);
-			ensure!(
-				frame_system::Module::<T>::block_number() >= swap.end_block,
-				Error::<T>::DurationNotPassed,
-			);
+			ensure!(Self::lock_ended(&swap.end), Error::<T>::DurationNotPassed);
 
-			swap.action.cancel(&swap.source);
+			swap.action.cancel(&swap.source)?;
 			PendingSwaps::<T>::remove(&target, hashed_proof.clone());
 
 			Self::deposit_event(
 				RawEvent::SwapCancelled(target, hashed_proof)
 			);
 		}
+
+		/// Offer to swap `give`, locked from origin now, against `want`, to be locked by `target`.
+		///
+		/// The target accepts by locking `want` with `accept_two_leg_swap`. Revealing the proof
+		/// with `claim_two_leg_swap` then executes both actions at once. If the swap is not
+		/// claimed after `duration`, either party can cancel it.
+		///
+		/// The dispatch origin for this call must be _Signed_.
+		///
+		/// - `target`: Counterparty of the atomic swap.
+		/// - `hashed_proof`: The hash of the secret proof.
+		/// - `hash_algorithm`: The algorithm `hashed_proof` was computed with.
+		/// - `give`: Action executed from origin to target.
+		/// - `want`: Action executed from target to origin.
+		/// - `duration`: Locked duration of the atomic swap, in blocks or in time.
+		#[weight = T::DbWeight::get().reads_writes(3, 1).saturating_add(40_000_000)]
+		fn create_two_leg_swap(
+			origin,
+			target: T::AccountId,
+			hashed_proof: HashedProof,
+			hash_algorithm: HashAlgorithm,
+			give: T::SwapAction,
+			want: T::SwapAction,
+			duration: TimelockOf<T>,
+		) {
+			let source = ensure_signed(origin)?;
+			ensure!(
+				!PendingTwoLegSwaps::<T>::contains_key(hashed_proof),
+				Error::<T>::AlreadyExist
+			);
+
+			let end = Self::lock_end(duration)?;
+			give.reserve(&source)?;
+
+			let swap = PendingTwoLegSwap {
+				source,
+				target,
+				give,
+				want,
+				accepted: false,
+				hash_algorithm,
+				end,
+			};
+			PendingTwoLegSwaps::<T>::insert(hashed_proof, swap.clone());
+
+			Self::deposit_event(RawEvent::NewTwoLegSwap(hashed_proof, swap));
+		}
+
+		/// Accept a two-leg swap by locking the action asked for in exchange.
+		///
+		/// The dispatch origin for this call must be _Signed_ by the target of the swap.
+		///
+		/// - `hashed_proof`: Hashed proof of the two-leg swap.
+		/// - `want`: Action asked for in the swap, it must match the entry in blockchain.
+		#[weight = T::DbWeight::get().reads_writes(1, 1)
+		  .saturating_add(40_000_000)
+		  .saturating_add(want.weight())
+		]
+		fn accept_two_leg_swap(origin, hashed_proof: HashedProof, want: T::SwapAction) {
+			let target = ensure_signed(origin)?;
+
+			PendingTwoLegSwaps::<T>::try_mutate(hashed_proof, |maybe_swap| -> DispatchResult {
+				let swap = maybe_swap.as_mut().ok_or(Error::<T>::NotExist)?;
+				ensure!(swap.target == target, Error::<T>::TargetMismatch);
+				ensure!(!swap.accepted, Error::<T>::AlreadyAccepted);
+				ensure!(swap.want == want, Error::<T>::ClaimActionMismatch);
+				ensure!(!Self::lock_ended(&swap.end), Error::<T>::DurationPassed);
+
+				swap.want.reserve(&target)?;
+				swap.accepted = true;
+				Ok(())
+			})?;
+
+			Self::deposit_event(RawEvent::TwoLegSwapAccepted(hashed_proof));
+		}
+
+		/// Claim an accepted two-leg swap, executing both of its actions.
+		///
+		/// Either both actions are executed or the swap is left pending.
+		///
+		/// The dispatch origin for this call must be _Signed_.
+		///
+		/// - `proof`: Revealed proof of the claim.
+		/// - `give`: Action locked by the source, it must match the entry in blockchain.
+		/// - `want`: Action locked by the target, it must match the entry in blockchain.
+		#[weight = T::DbWeight::get().reads_writes(HashAlgorithm::ALL.len() as Weight, 1)
+		  .saturating_add(40_000_000)
+		  .saturating_add((proof.len() as Weight).saturating_mul(300))
+		  .saturating_add(give.weight())
+		  .saturating_add(want.weight())
+		]
+		fn claim_two_leg_swap(
+			origin,
+			proof: Vec<u8>,
+			give: T::SwapAction,
+			want: T::SwapAction,
+		) -> DispatchResult {
+			ensure!(
+				proof.len() <= T::ProofLimit::get() as usize,
+				Error::<T>::ProofTooLarge,
+			);
+
+			ensure_signed(origin)?;
+
+			let (hashed_proof, swap) = HashAlgorithm::ALL.iter()
+				.find_map(|algorithm| {
+					let hashed_proof = algorithm.hash(&proof);
+					PendingTwoLegSwaps::<T>::get(hashed_proof)
+						.filter(|swap| swap.hash_algorithm == *algorithm)
+						.map(|swap| (hashed_proof, swap))
+				})
+				.ok_or(Error::<T>::InvalidProof)?;
+			ensure!(swap.give == give && swap.want == want, Error::<T>::ClaimActionMismatch);
+			ensure!(swap.accepted, Error::<T>::NotAccepted);
+
+			Self::transactional(|| {
+				swap.give.claim(&swap.source, &swap.target)?;
+				swap.want.claim(&swap.target, &swap.source)
+			})?;
+
+			PendingTwoLegSwaps::<T>::remove(hashed_proof);
+
+			Self::deposit_event(RawEvent::TwoLegSwapClaimed(hashed_proof, proof));
+
+			Ok(())
+		}
+
+		/// Cancel a two-leg swap, releasing the actions locked by either party. Only possible
+		/// after the originally set duration has passed.
+		///
+		/// Either both actions are released or the swap is left pending.
+		///
+		/// The dispatch origin for this call must be _Signed_ by the source or the target of the
+		/// swap.
+		///
+		/// - `hashed_proof`: Hashed proof of the two-leg swap.
+		#[weight = T::DbWeight::get().reads_writes(1, 1).saturating_add(40_000_000)]
+		fn cancel_two_leg_swap(origin, hashed_proof: HashedProof) {
+			let who = ensure_signed(origin)?;
+
+			let swap = PendingTwoLegSwaps::<T>::get(hashed_proof)
+				.ok_or(Error::<T>::NotExist)?;
+			ensure!(
+				swap.source == who || swap.target == who,
+				Error::<T>::SourceMismatch,
+			);
+			ensure!(Self::lock_ended(&swap.end), Error::<T>::DurationNotPassed);
+
+			Self::transactional(|| {
+				swap.give.cancel(&swap.source)?;
+				if swap.accepted {
+					swap.want.cancel(&swap.target)?;
+				}
+				Ok(())
+			})?;
+			PendingTwoLegSwaps::<T>::remove(hashed_proof);
+
+			Self::deposit_event(RawEvent::TwoLegSwapCancelled(hashed_proof));
+		}
 	}
 }
+
+impl<T: Trait> Module<T> {
+	/// The account holding the assets of pending `AssetsSwapAction`s.
+	pub fn escrow_account() -> T::AccountId {
+		ESCROW_ID.into_account()
+	}
+
+	/// Run `f` in a storage transaction, reverting its changes if it fails.
+	fn transactional(f: impl FnOnce() -> DispatchResult) -> DispatchResult {
+		with_transaction(|| match f() {
+			Ok(()) => TransactionOutcome::Commit(Ok(())),
+			Err(e) => TransactionOutcome::Rollback(Err(e)),
+		})
+	}
+
+	/// The end of a lock of `duration` starting now.
+	fn lock_end(duration: TimelockOf<T>) -> Result<TimelockOf<T>, Error<T>> {
+		match duration {
+			Timelock::Block(blocks) => frame_system::Module::<T>::block_number()
+				.checked_add(&blocks)
+				.map(Timelock::Block),
+			Timelock::Timestamp(time) => T::Time::now()
+				.checked_add(&time)
+				.map(Timelock::Timestamp),
+		}.ok_or(Error::<T>::LockOverflow)
+	}
+
+	/// Whether the lock ending at `end` has ended.
+	fn lock_ended(end: &TimelockOf<T>) -> bool {
+		match end {
+			Timelock::Block(block) => frame_system::Module::<T>::block_number() >= *block,
+			Timelock::Timestamp(moment) => T::Time::now() >= *moment,
+		}
+	}
+
+	/// The pending swaps and two-leg swaps `who` is the source or the target of.
+	///
+	/// Meant to be called by runtime APIs; it iterates over all pending swaps.
+	pub fn pending_swaps(
+		who: &T::AccountId,
+	) -> Vec<SwapInfo<T::AccountId, T::SwapAction, T::BlockNumber, MomentOf<T>>> {
+		let swaps = PendingSwaps::<T>::iter()
+			.filter(|(target, _, swap)| target == who || &swap.source == who)
+			.map(|(target, hashed_proof, swap)| SwapInfo {
+				source: swap.source,
+				target,
+				hashed_proof,
+				action: swap.action,
+				counter_action: None,
+				accepted: false,
+				hash_algorithm: swap.hash_algorithm,
+				end: swap.end,
+			});
+		let two_leg_swaps = PendingTwoLegSwaps::<T>::iter()
+			.filter(|(_, swap)| &swap.target == who || &swap.source == who)
+			.map(|(hashed_proof, swap)| SwapInfo {
+				source: swap.source,
+				target: swap.target,
+				hashed_proof,
+				action: swap.give,
+				counter_action: Some(swap.want),
+				accepted: swap.accepted,
+				hash_algorithm: swap.hash_algorithm,
+				end: swap.end,
+			});
+		swaps.chain(two_leg_swaps).collect()
+	}
+}
+
+/// The layout of `PendingSwap` before `Releases::V2`.
+#[derive(Decode)]
+struct PendingSwapV1<T: Trait> {
+	source: T::AccountId,
+	action: T::SwapAction,
+	end_block: T::BlockNumber,
+}
+
+/// Migrate the swaps created before hash algorithms and timelocks were selectable. Their proofs
+/// were hashed with blake2_256 and their locks ended at a block number.
+///
+/// Two-leg swaps were introduced together with selectable locks and need no migration.
+fn migrate_to_selectable_locks<T: Trait>() -> Weight {
+	if StorageVersion::get() != Releases::V1 {
+		return 0;
+	}
+
+	PendingSwaps::<T>::translate::<PendingSwapV1<T>, _>(|swap| Some(PendingSwap {
+		source: swap.source,
+		action: swap.action,
+		hash_algorithm: HashAlgorithm::Blake2_256,
+		end: Timelock::Block(swap.end_block),
+	}));
+	StorageVersion::put(Releases::V2);
+
+	let swaps = PendingSwaps::<T>::iter().count() as Weight;
+	T::DbWeight::get().reads_writes(2 * swaps + 1, swaps + 1)
+}
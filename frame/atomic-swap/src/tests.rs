@@ -3,8 +3,10 @@
 use super::*;
 
 use frame_support::{
-	impl_outer_origin, parameter_types, weights::Weight,
+	assert_noop, assert_ok, impl_outer_origin, parameter_types, weights::Weight,
+	storage::unhashed, traits::Contains,
 };
+use hex_literal::hex;
 use sp_core::H256;
 // The testing primitives are very useful for avoiding having to work with signatures
 // or public keys. `u64` is used as the `AccountId` and no `Signature`s are required.
@@ -65,22 +67,120 @@ impl pallet_balances::Trait for Test {
 	type ExistentialDeposit = ExistentialDeposit;
 	type AccountStore = System;
 }
+parameter_types! {
+	pub const MinimumPeriod: u64 = 1;
+}
+impl pallet_timestamp::Trait for Test {
+	type Moment = u64;
+	type OnTimestampSet = ();
+	type MinimumPeriod = MinimumPeriod;
+}
+impl pallet_generic_asset::Trait for Test {
+	type Balance = u64;
+	type AssetId = u32;
+	type Event = ();
+}
+parameter_types! {
+	pub const MetadataDepositBase: u64 = 1;
+	pub const MetadataDepositPerByte: u64 = 1;
+	pub const StringLimit: u32 = 50;
+	pub const ApprovalDeposit: u64 = 1;
+}
+impl pallet_assets::Trait for Test {
+	type Event = ();
+	type Balance = u64;
+	type AssetId = u32;
+	type Currency = Balances;
+	type MetadataDepositBase = MetadataDepositBase;
+	type MetadataDepositPerByte = MetadataDepositPerByte;
+	type StringLimit = StringLimit;
+	type ApprovalDeposit = ApprovalDeposit;
+	type MinBalanceExempt = NoAccounts;
+}
+
+/// Exempts no account from the minimum balance of assets.
+pub struct NoAccounts;
+impl Contains<u64> for NoAccounts {
+	fn sorted_members() -> Vec<u64> {
+		Vec::new()
+	}
+}
+
+/// Swap action of the mock runtime, swapping any of the supported kinds of funds.
+#[derive(Clone, Eq, PartialEq, Debug, Encode, Decode)]
+pub enum TestSwapAction {
+	Balance(BalanceSwapAction<Test, Balances>),
+	GenericAsset(GenericAssetSwapAction<Test>),
+	Asset(AssetsSwapAction<Test>),
+}
+
+impl From<BalanceSwapAction<Test, Balances>> for TestSwapAction {
+	fn from(action: BalanceSwapAction<Test, Balances>) -> Self {
+		TestSwapAction::Balance(action)
+	}
+}
+
+impl SwapAction<Test> for TestSwapAction {
+	fn reserve(&self, source: &u64) -> DispatchResult {
+		match self {
+			TestSwapAction::Balance(action) => action.reserve(source),
+			TestSwapAction::GenericAsset(action) => action.reserve(source),
+			TestSwapAction::Asset(action) => action.reserve(source),
+		}
+	}
+
+	fn claim(&self, source: &u64, target: &u64) -> DispatchResult {
+		match self {
+			TestSwapAction::Balance(action) => action.claim(source, target),
+			TestSwapAction::GenericAsset(action) => action.claim(source, target),
+			TestSwapAction::Asset(action) => action.claim(source, target),
+		}
+	}
+
+	fn weight(&self) -> Weight {
+		match self {
+			TestSwapAction::Balance(action) => action.weight(),
+			TestSwapAction::GenericAsset(action) => action.weight(),
+			TestSwapAction::Asset(action) => action.weight(),
+		}
+	}
+
+	fn cancel(&self, source: &u64) -> DispatchResult {
+		match self {
+			TestSwapAction::Balance(action) => action.cancel(source),
+			TestSwapAction::GenericAsset(action) => action.cancel(source),
+			TestSwapAction::Asset(action) => action.cancel(source),
+		}
+	}
+}
+
 parameter_types! {
 	pub const ProofLimit: u32 = 1024;
 	pub const ExpireDuration: u64 = 100;
 }
 impl Trait for Test {
 	type Event = ();
-	type SwapAction = BalanceSwapAction<Test, Balances>;
+	type SwapAction = TestSwapAction;
+	type Time = Timestamp;
 	type ProofLimit = ProofLimit;
 }
 type System = frame_system::Module<Test>;
 type Balances = pallet_balances::Module<Test>;
+type Timestamp = pallet_timestamp::Module<Test>;
+type GenericAsset = pallet_generic_asset::Module<Test>;
+type Assets = pallet_assets::Module<Test>;
 type AtomicSwap = Module<Test>;
 
 const A: u64 = 1;
 const B: u64 = 2;
 
+const BLAKE2: HashAlgorithm = HashAlgorithm::Blake2_256;
+
+/// Asset of `pallet_generic_asset` endowed to `A` and `B`.
+const GA_ASSET: u32 = 16000;
+/// Asset of `pallet_assets` issued by `B`, see `issue_asset`.
+const ASSET: u32 = 0;
+
 pub fn new_test_ext() -> sp_io::TestExternalities {
 	let mut t = frame_system::GenesisConfig::default().build_storage::<Test>().unwrap();
 	let genesis = pallet_balances::GenesisConfig::<Test> {
@@ -90,9 +190,22 @@ pub fn new_test_ext() -> sp_io::TestExternalities {
 		],
 	};
 	genesis.assimilate_storage(&mut t).unwrap();
+	pallet_generic_asset::GenesisConfig::<Test> {
+		assets: vec![GA_ASSET],
+		initial_balance: 100,
+		endowed_accounts: vec![A, B],
+		next_asset_id: GA_ASSET + 1,
+		staking_asset_id: GA_ASSET,
+		spending_asset_id: GA_ASSET,
+	}.assimilate_storage(&mut t).unwrap();
 	t.into()
 }
 
+/// Issues 1000 of `ASSET` to `B`, with a minimum balance of 10.
+fn issue_asset() {
+	assert_ok!(Assets::issue(Origin::signed(B), 1000, 10));
+}
+
 #[test]
 fn two_party_successful_swap() {
 	let mut chain1 = new_test_ext();
@@ -109,8 +222,9 @@ fn two_party_successful_swap() {
 			Origin::signed(A),
 			B,
 			hashed_proof.clone(),
-			BalanceSwapAction::new(50),
-			1000,
+			BLAKE2,
+			BalanceSwapAction::new(50).into(),
+			Timelock::Block(1000),
 		).unwrap();
 
 		assert_eq!(Balances::free_balance(A), 100 - 50);
@@ -123,8 +237,9 @@ fn two_party_successful_swap() {
 			Origin::signed(B),
 			A,
 			hashed_proof.clone(),
-			BalanceSwapAction::new(75),
-			1000,
+			BLAKE2,
+			BalanceSwapAction::new(75).into(),
+			Timelock::Block(1000),
 		).unwrap();
 
 		assert_eq!(Balances::free_balance(A), 100);
@@ -136,7 +251,7 @@ fn two_party_successful_swap() {
 		AtomicSwap::claim_swap(
 			Origin::signed(A),
 			proof.to_vec(),
-			BalanceSwapAction::new(75),
+			BalanceSwapAction::new(75).into(),
 		).unwrap();
 
 		assert_eq!(Balances::free_balance(A), 100 + 75);
@@ -148,10 +263,417 @@ fn two_party_successful_swap() {
 		AtomicSwap::claim_swap(
 			Origin::signed(B),
 			proof.to_vec(),
-			BalanceSwapAction::new(50),
+			BalanceSwapAction::new(50).into(),
 		).unwrap();
 
 		assert_eq!(Balances::free_balance(A), 100 - 50);
 		assert_eq!(Balances::free_balance(B), 200 + 50);
 	});
 }
+
+#[test]
+fn generic_asset_swap_action_works() {
+	new_test_ext().execute_with(|| {
+		let proof: [u8; 2] = [4, 2];
+		let hashed_proof = blake2_256(&proof);
+		let action = TestSwapAction::GenericAsset(GenericAssetSwapAction::new(GA_ASSET, 40));
+
+		assert_ok!(AtomicSwap::create_swap(
+			Origin::signed(A), B, hashed_proof, BLAKE2, action.clone(), Timelock::Block(10),
+		));
+		assert_eq!(GenericAsset::free_balance(&GA_ASSET, &A), 60);
+		assert_eq!(GenericAsset::reserved_balance(&GA_ASSET, &A), 40);
+
+		assert_ok!(AtomicSwap::claim_swap(Origin::signed(B), proof.to_vec(), action));
+		assert_eq!(GenericAsset::reserved_balance(&GA_ASSET, &A), 0);
+		assert_eq!(GenericAsset::free_balance(&GA_ASSET, &B), 140);
+	});
+}
+
+#[test]
+fn assets_swap_action_escrows_and_refunds() {
+	new_test_ext().execute_with(|| {
+		issue_asset();
+		let hashed_proof = blake2_256(&[4, 2]);
+		let escrow = AtomicSwap::escrow_account();
+
+		// Below the minimum balance.
+		assert_noop!(
+			AtomicSwap::create_swap(
+				Origin::signed(B),
+				A,
+				hashed_proof,
+				BLAKE2,
+				TestSwapAction::Asset(AssetsSwapAction::new(ASSET, 5)),
+				Timelock::Block(10),
+			),
+			Error::<Test>::ValueBelowMinimum,
+		);
+		// Would leave dust behind.
+		assert_noop!(
+			AtomicSwap::create_swap(
+				Origin::signed(B),
+				A,
+				hashed_proof,
+				BLAKE2,
+				TestSwapAction::Asset(AssetsSwapAction::new(ASSET, 995)),
+				Timelock::Block(10),
+			),
+			Error::<Test>::ValueBelowMinimum,
+		);
+
+		let action = TestSwapAction::Asset(AssetsSwapAction::new(ASSET, 300));
+		assert_ok!(AtomicSwap::create_swap(
+			Origin::signed(B), A, hashed_proof, BLAKE2, action, Timelock::Block(10),
+		));
+		assert_eq!(Assets::balance(ASSET, B), 700);
+		assert_eq!(Assets::balance(ASSET, escrow), 300);
+
+		assert_noop!(
+			AtomicSwap::cancel_swap(Origin::signed(B), A, hashed_proof),
+			Error::<Test>::DurationNotPassed,
+		);
+		System::set_block_number(11);
+		assert_ok!(AtomicSwap::cancel_swap(Origin::signed(B), A, hashed_proof));
+		assert_eq!(Assets::balance(ASSET, B), 1000);
+		assert_eq!(Assets::balance(ASSET, escrow), 0);
+	});
+}
+
+#[test]
+fn failed_claims_and_cancellations_keep_the_swap() {
+	new_test_ext().execute_with(|| {
+		issue_asset();
+		let proof: [u8; 2] = [4, 2];
+		let hashed_proof = blake2_256(&proof);
+		let action = TestSwapAction::Asset(AssetsSwapAction::new(ASSET, 300));
+
+		assert_ok!(AtomicSwap::create_swap(
+			Origin::signed(B), A, hashed_proof, BLAKE2, action.clone(), Timelock::Block(10),
+		));
+		assert_ok!(Assets::freeze_asset(Origin::signed(B), ASSET));
+
+		assert_noop!(
+			AtomicSwap::claim_swap(Origin::signed(A), proof.to_vec(), action.clone()),
+			pallet_assets::Error::<Test>::Frozen,
+		);
+		System::set_block_number(11);
+		assert_noop!(
+			AtomicSwap::cancel_swap(Origin::signed(B), A, hashed_proof),
+			pallet_assets::Error::<Test>::Frozen,
+		);
+		assert!(PendingSwaps::<Test>::contains_key(A, hashed_proof));
+
+		assert_ok!(Assets::thaw_asset(Origin::signed(B), ASSET));
+		assert_ok!(AtomicSwap::claim_swap(Origin::signed(A), proof.to_vec(), action));
+		assert_eq!(Assets::balance(ASSET, A), 300);
+		assert!(!PendingSwaps::<Test>::contains_key(A, hashed_proof));
+	});
+}
+
+#[test]
+fn two_leg_swap_works() {
+	new_test_ext().execute_with(|| {
+		issue_asset();
+		let proof: [u8; 2] = [4, 2];
+		let hashed_proof = blake2_256(&proof);
+		let give: TestSwapAction = BalanceSwapAction::new(50).into();
+		let want = TestSwapAction::Asset(AssetsSwapAction::new(ASSET, 300));
+
+		assert_ok!(AtomicSwap::create_two_leg_swap(
+			Origin::signed(A), B, hashed_proof, BLAKE2, give.clone(), want.clone(), Timelock::Block(10),
+		));
+		assert_eq!(Balances::reserved_balance(A), 50);
+		assert_noop!(
+			AtomicSwap::create_two_leg_swap(
+				Origin::signed(A), B, hashed_proof, BLAKE2, give.clone(), want.clone(), Timelock::Block(10),
+			),
+			Error::<Test>::AlreadyExist,
+		);
+
+		// Cannot be claimed before the target locked its side.
+		assert_noop!(
+			AtomicSwap::claim_two_leg_swap(Origin::signed(A), proof.to_vec(), give.clone(), want.clone()),
+			Error::<Test>::NotAccepted,
+		);
+		assert_noop!(
+			AtomicSwap::accept_two_leg_swap(Origin::signed(A), hashed_proof, want.clone()),
+			Error::<Test>::TargetMismatch,
+		);
+		assert_noop!(
+			AtomicSwap::accept_two_leg_swap(Origin::signed(B), hashed_proof, give.clone()),
+			Error::<Test>::ClaimActionMismatch,
+		);
+		assert_ok!(AtomicSwap::accept_two_leg_swap(Origin::signed(B), hashed_proof, want.clone()));
+		assert_eq!(Assets::balance(ASSET, B), 700);
+		assert_noop!(
+			AtomicSwap::accept_two_leg_swap(Origin::signed(B), hashed_proof, want.clone()),
+			Error::<Test>::AlreadyAccepted,
+		);
+
+		assert_noop!(
+			AtomicSwap::claim_two_leg_swap(Origin::signed(B), vec![4, 3], give.clone(), want.clone()),
+			Error::<Test>::InvalidProof,
+		);
+		assert_ok!(AtomicSwap::claim_two_leg_swap(Origin::signed(B), proof.to_vec(), give, want));
+
+		assert_eq!(Balances::free_balance(A), 50);
+		assert_eq!(Balances::reserved_balance(A), 0);
+		assert_eq!(Balances::free_balance(B), 250);
+		assert_eq!(Assets::balance(ASSET, A), 300);
+		assert_eq!(Assets::balance(ASSET, B), 700);
+		assert!(AtomicSwap::pending_two_leg_swaps(hashed_proof).is_none());
+	});
+}
+
+#[test]
+fn two_leg_swap_can_be_cancelled_by_either_party() {
+	new_test_ext().execute_with(|| {
+		issue_asset();
+		let hashed_proof = blake2_256(&[4, 2]);
+		let give: TestSwapAction = BalanceSwapAction::new(50).into();
+		let want = TestSwapAction::Asset(AssetsSwapAction::new(ASSET, 300));
+
+		assert_ok!(AtomicSwap::create_two_leg_swap(
+			Origin::signed(A), B, hashed_proof, BLAKE2, give, want.clone(), Timelock::Block(10),
+		));
+		assert_ok!(AtomicSwap::accept_two_leg_swap(Origin::signed(B), hashed_proof, want));
+
+		assert_noop!(
+			AtomicSwap::cancel_two_leg_swap(Origin::signed(B), hashed_proof),
+			Error::<Test>::DurationNotPassed,
+		);
+		System::set_block_number(11);
+		assert_noop!(
+			AtomicSwap::cancel_two_leg_swap(Origin::signed(3), hashed_proof),
+			Error::<Test>::SourceMismatch,
+		);
+		assert_ok!(AtomicSwap::cancel_two_leg_swap(Origin::signed(B), hashed_proof));
+
+		assert_eq!(Balances::free_balance(A), 100);
+		assert_eq!(Balances::reserved_balance(A), 0);
+		assert_eq!(Assets::balance(ASSET, B), 1000);
+		assert!(AtomicSwap::pending_two_leg_swaps(hashed_proof).is_none());
+	});
+}
+
+#[test]
+fn two_leg_swap_claim_is_all_or_nothing() {
+	new_test_ext().execute_with(|| {
+		issue_asset();
+		let proof: [u8; 2] = [4, 2];
+		let hashed_proof = blake2_256(&proof);
+		let give: TestSwapAction = BalanceSwapAction::new(50).into();
+		let want = TestSwapAction::Asset(AssetsSwapAction::new(ASSET, 300));
+
+		assert_ok!(AtomicSwap::create_two_leg_swap(
+			Origin::signed(A), B, hashed_proof, BLAKE2, give.clone(), want.clone(), Timelock::Block(10),
+		));
+		assert_ok!(AtomicSwap::accept_two_leg_swap(Origin::signed(B), hashed_proof, want.clone()));
+
+		// The asset is frozen, so the second leg cannot be claimed.
+		assert_ok!(Assets::freeze_asset(Origin::signed(B), ASSET));
+		assert_noop!(
+			AtomicSwap::claim_two_leg_swap(Origin::signed(A), proof.to_vec(), give.clone(), want.clone()),
+			pallet_assets::Error::<Test>::Frozen,
+		);
+		assert_eq!(Balances::reserved_balance(A), 50);
+
+		assert_ok!(Assets::thaw_asset(Origin::signed(B), ASSET));
+		assert_ok!(AtomicSwap::claim_two_leg_swap(Origin::signed(A), proof.to_vec(), give, want));
+		assert_eq!(Balances::free_balance(B), 250);
+		assert_eq!(Assets::balance(ASSET, A), 300);
+	});
+}
+
+#[test]
+fn pending_swaps_lists_swaps_of_an_account() {
+	new_test_ext().execute_with(|| {
+		let proof_1 = blake2_256(&[1]);
+		let proof_2 = blake2_256(&[2]);
+		let give: TestSwapAction = BalanceSwapAction::new(50).into();
+		let want = TestSwapAction::GenericAsset(GenericAssetSwapAction::new(GA_ASSET, 20));
+
+		assert_ok!(AtomicSwap::create_swap(
+			Origin::signed(A), B, proof_1, BLAKE2, give.clone(), Timelock::Block(10),
+		));
+		assert_ok!(AtomicSwap::create_two_leg_swap(
+			Origin::signed(B), A, proof_2, HashAlgorithm::Keccak256, want.clone(), give.clone(),
+			Timelock::Timestamp(500),
+		));
+
+		let mut swaps = AtomicSwap::pending_swaps(&A);
+		swaps.sort_by_key(|swap| swap.counter_action.is_some());
+		assert_eq!(swaps, vec![
+			SwapInfo {
+				source: A,
+				target: B,
+				hashed_proof: proof_1,
+				action: give.clone(),
+				counter_action: None,
+				accepted: false,
+				hash_algorithm: BLAKE2,
+				end: Timelock::Block(10),
+			},
+			SwapInfo {
+				source: B,
+				target: A,
+				hashed_proof: proof_2,
+				action: want,
+				counter_action: Some(give),
+				accepted: false,
+				hash_algorithm: HashAlgorithm::Keccak256,
+				end: Timelock::Timestamp(500),
+			},
+		]);
+		assert_eq!(AtomicSwap::pending_swaps(&B).len(), 2);
+		assert!(AtomicSwap::pending_swaps(&3).is_empty());
+	});
+}
+
+#[test]
+fn ethereum_and_bitcoin_hashlocks_work() {
+	new_test_ext().execute_with(|| {
+		let proof = b"secret".to_vec();
+		let action: TestSwapAction = BalanceSwapAction::new(10).into();
+
+		for (i, algorithm) in HashAlgorithm::ALL.iter().enumerate() {
+			let hashed_proof = algorithm.hash(&proof);
+			assert_ok!(AtomicSwap::create_swap(
+				Origin::signed(A), B, hashed_proof, *algorithm, action.clone(), Timelock::Block(10),
+			));
+			assert_ok!(AtomicSwap::claim_swap(Origin::signed(B), proof.clone(), action.clone()));
+			assert_eq!(Balances::free_balance(B), 200 + 10 * (i as u64 + 1));
+		}
+		assert_eq!(
+			HashAlgorithm::Sha2_256.hash(b""),
+			hex!("e3b0c44298fc1c149afbf4c8996fb92427ae41e4649b934ca495991b7852b855"),
+		);
+		assert_eq!(
+			HashAlgorithm::Keccak256.hash(b""),
+			hex!("c5d2460186f7233c927e7db2dcc703c0e500b653ca82273b7bfad8045d85a470"),
+		);
+	});
+}
+
+#[test]
+fn proof_must_match_the_hash_algorithm_of_the_swap() {
+	new_test_ext().execute_with(|| {
+		let proof = b"secret".to_vec();
+		let action: TestSwapAction = BalanceSwapAction::new(10).into();
+
+		// Locked with the SHA-256 hash but declared as Keccak-256.
+		assert_ok!(AtomicSwap::create_swap(
+			Origin::signed(A),
+			B,
+			HashAlgorithm::Sha2_256.hash(&proof),
+			HashAlgorithm::Keccak256,
+			action.clone(),
+			Timelock::Block(10),
+		));
+		assert_noop!(
+			AtomicSwap::claim_swap(Origin::signed(B), proof, action),
+			Error::<Test>::InvalidProof,
+		);
+	});
+}
+
+#[test]
+fn timestamp_timelock_works() {
+	new_test_ext().execute_with(|| {
+		let hashed_proof = blake2_256(&[4, 2]);
+		let action: TestSwapAction = BalanceSwapAction::new(10).into();
+
+		Timestamp::set_timestamp(1_000);
+		assert_ok!(AtomicSwap::create_swap(
+			Origin::signed(A), B, hashed_proof, BLAKE2, action, Timelock::Timestamp(6_000),
+		));
+		assert_eq!(
+			PendingSwaps::<Test>::get(B, hashed_proof).unwrap().end,
+			Timelock::Timestamp(7_000),
+		);
+
+		// Blocks passing do not matter.
+		System::set_block_number(100);
+		Timestamp::set_timestamp(6_999);
+		assert_noop!(
+			AtomicSwap::cancel_swap(Origin::signed(A), B, hashed_proof),
+			Error::<Test>::DurationNotPassed,
+		);
+		Timestamp::set_timestamp(7_000);
+		assert_ok!(AtomicSwap::cancel_swap(Origin::signed(A), B, hashed_proof));
+		assert_eq!(Balances::free_balance(A), 100);
+	});
+}
+
+#[test]
+fn overflowing_locks_are_rejected() {
+	new_test_ext().execute_with(|| {
+		let hashed_proof = blake2_256(&[4, 2]);
+		let action: TestSwapAction = BalanceSwapAction::new(50).into();
+		System::set_block_number(1);
+		Timestamp::set_timestamp(1);
+
+		assert_noop!(
+			AtomicSwap::create_swap(
+				Origin::signed(A), B, hashed_proof, BLAKE2, action.clone(), Timelock::Block(u64::max_value()),
+			),
+			Error::<Test>::LockOverflow,
+		);
+		assert_noop!(
+			AtomicSwap::create_two_leg_swap(
+				Origin::signed(A),
+				B,
+				hashed_proof,
+				BLAKE2,
+				action.clone(),
+				action,
+				Timelock::Timestamp(u64::max_value()),
+			),
+			Error::<Test>::LockOverflow,
+		);
+		assert_eq!(Balances::reserved_balance(A), 0);
+	});
+}
+
+#[test]
+fn two_leg_swap_cannot_be_accepted_after_the_lock_ended() {
+	new_test_ext().execute_with(|| {
+		let hashed_proof = blake2_256(&[4, 2]);
+		let give: TestSwapAction = BalanceSwapAction::new(50).into();
+		let want = TestSwapAction::GenericAsset(GenericAssetSwapAction::new(GA_ASSET, 20));
+
+		assert_ok!(AtomicSwap::create_two_leg_swap(
+			Origin::signed(A), B, hashed_proof, BLAKE2, give, want.clone(), Timelock::Block(10),
+		));
+		System::set_block_number(10);
+		assert_noop!(
+			AtomicSwap::accept_two_leg_swap(Origin::signed(B), hashed_proof, want),
+			Error::<Test>::DurationPassed,
+		);
+	});
+}
+
+#[test]
+fn migration_to_selectable_locks_works() {
+	new_test_ext().execute_with(|| {
+		let hashed_proof = blake2_256(&[4, 2]);
+		let action: TestSwapAction = BalanceSwapAction::new(10).into();
+		StorageVersion::put(Releases::V1);
+		// A swap in the layout of `PendingSwapV1`.
+		unhashed::put(
+			&PendingSwaps::<Test>::hashed_key_for(B, hashed_proof),
+			&(A, action.clone(), 10u64),
+		);
+
+		migrate_to_selectable_locks::<Test>();
+
+		assert_eq!(StorageVersion::get(), Releases::V2);
+		assert_eq!(PendingSwaps::<Test>::get(B, hashed_proof), Some(PendingSwap {
+			source: A,
+			action,
+			hash_algorithm: BLAKE2,
+			end: Timelock::Block(10),
+		}));
+	});
+}
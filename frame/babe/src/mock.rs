@@ -174,6 +174,8 @@ parameter_types! {
 	pub const AttestationPeriod: u64 = 100;
 	pub const RewardCurve: &'static PiecewiseLinear<'static> = &REWARD_CURVE;
 	pub const MaxNominatorRewardedPerValidator: u32 = 64;
+	pub const MaxSnapshotNominators: u32 = 10_000;
+	pub const MaxNominators: u32 = 20_000;
 	pub const ElectionLookahead: u64 = 0;
 	pub const StakingUnsignedPriority: u64 = u64::max_value() / 2;
 }
@@ -207,6 +209,10 @@ impl pallet_staking::Trait for Test {
 	type UnixTime = pallet_timestamp::Module<Test>;
 	type RewardCurve = RewardCurve;
 	type MaxNominatorRewardedPerValidator = MaxNominatorRewardedPerValidator;
+	type MaxSnapshotNominators = MaxSnapshotNominators;
+	type MaxNominators = MaxNominators;
+	type PayoutSubmitter = ();
+	type ElectionProvider = ();
 	type NextNewSession = Session;
 	type ElectionLookahead = ElectionLookahead;
 	type Call = Call;
@@ -58,6 +58,7 @@ benchmarks_instance! {
 			old_members.push(last_old_member.clone());
 		}
 		let old_members_count = old_members.len() as u32;
+		T::Currency::make_free_balance_be(&last_old_member, BalanceOf::<T, I>::max_value());
 
 		Collective::<T, _>::set_members(
 			SystemOrigin::Root.into(),
@@ -186,6 +187,7 @@ benchmarks_instance! {
 		}
 		let caller: T::AccountId = account("caller", 0, SEED);
 		members.push(caller.clone());
+		T::Currency::make_free_balance_be(&caller, BalanceOf::<T, I>::max_value());
 		Collective::<T, _>::set_members(SystemOrigin::Root.into(), members, None, MAX_MEMBERS)?;
 
 		let threshold = m;
@@ -225,6 +227,7 @@ benchmarks_instance! {
 		let mut members = vec![];
 		let proposer: T::AccountId = account("proposer", 0, SEED);
 		members.push(proposer.clone());
+		T::Currency::make_free_balance_be(&proposer, BalanceOf::<T, I>::max_value());
 		for i in 1 .. m - 1 {
 			let member = account("member", i, SEED);
 			members.push(member);
@@ -298,6 +301,7 @@ benchmarks_instance! {
 		let mut members = vec![];
 		let proposer: T::AccountId = account("proposer", 0, SEED);
 		members.push(proposer.clone());
+		T::Currency::make_free_balance_be(&proposer, BalanceOf::<T, I>::max_value());
 		for i in 1 .. m - 1 {
 			let member = account("member", i, SEED);
 			members.push(member);
@@ -379,6 +383,7 @@ benchmarks_instance! {
 		}
 		let caller: T::AccountId = account("caller", 0, SEED);
 		members.push(caller.clone());
+		T::Currency::make_free_balance_be(&caller, BalanceOf::<T, I>::max_value());
 		Collective::<T, _>::set_members(SystemOrigin::Root.into(), members.clone(), None, MAX_MEMBERS)?;
 
 		// Threshold is 2 so any two ayes will approve the vote
@@ -460,6 +465,7 @@ benchmarks_instance! {
 		}
 		let caller: T::AccountId = account("caller", 0, SEED);
 		members.push(caller.clone());
+		T::Currency::make_free_balance_be(&caller, BalanceOf::<T, I>::max_value());
 		Collective::<T, _>::set_members(
 			SystemOrigin::Root.into(),
 			members.clone(),
@@ -532,6 +538,7 @@ benchmarks_instance! {
 		}
 		let caller: T::AccountId = account("caller", 0, SEED);
 		members.push(caller.clone());
+		T::Currency::make_free_balance_be(&caller, BalanceOf::<T, I>::max_value());
 		Collective::<T, _>::set_members(
 			SystemOrigin::Root.into(),
 			members.clone(),
@@ -35,6 +35,14 @@
 //! abstentions and the proposal is executed if there are enough approvals counting the new votes.
 //!
 //! If there are not, or if no prime is set, then the motion is dropped without being executed.
+//!
+//! Proposing a motion that is put up for a vote reserves `ProposalDeposit` from the proposer. The
+//! deposit is returned once the motion is closed, whatever its outcome. Motions that are still
+//! open after their voting period has ended are closed automatically at the beginning of a block,
+//! at most `MaxExpiredPerBlock` of them per block, with the prime's default vote applied to any
+//! abstentions exactly as if `close` had been called. Approved proposals are only executed there
+//! while their total weight fits in `MaxExpiredWeight`; motions whose proposal alone exceeds it
+//! must be closed with `close`.
 
 #![cfg_attr(not(feature = "std"), no_std)]
 #![recursion_limit="128"]
@@ -52,7 +60,7 @@ use frame_support::{
 		PostDispatchInfo,
 	},
 	ensure,
-	traits::{ChangeMembers, EnsureOrigin, Get, InitializeMembers},
+	traits::{ChangeMembers, Currency, EnsureOrigin, Get, InitializeMembers, ReservableCurrency},
 	weights::{DispatchClass, GetDispatchInfo, Weight},
 };
 use frame_system::{self as system, ensure_signed, ensure_root};
@@ -76,6 +84,9 @@ pub type MemberCount = u32;
 /// + This pallet assumes that dependents keep to the limit without enforcing it.
 pub const MAX_MEMBERS: MemberCount = 23;
 
+type BalanceOf<T, I> =
+	<<T as Trait<I>>::Currency as Currency<<T as frame_system::Trait>::AccountId>>::Balance;
+
 pub trait Trait<I: Instance=DefaultInstance>: frame_system::Trait {
 	/// The outer origin type.
 	type Origin: From<RawOrigin<Self::AccountId, I>>;
@@ -90,10 +101,27 @@ pub trait Trait<I: Instance=DefaultInstance>: frame_system::Trait {
 	type Event: From<Event<Self, I>> + Into<<Self as frame_system::Trait>::Event>;
 
 	/// The time-out for council motions.
+	///
+	/// Expired motions are closed automatically in the order they were proposed, which is the
+	/// order in which they expire only as long as this duration does not change. Motions
+	/// expiring earlier than the ones proposed before them are closed automatically after those.
 	type MotionDuration: Get<Self::BlockNumber>;
 
 	/// Maximum number of proposals allowed to be active in parallel.
 	type MaxProposals: Get<u32>;
+
+	/// The currency used to pay proposal deposits.
+	type Currency: ReservableCurrency<Self::AccountId>;
+
+	/// The amount held on deposit for a motion put up for a vote. Returned when it is closed.
+	type ProposalDeposit: Get<BalanceOf<Self, I>>;
+
+	/// Maximum number of expired motions closed automatically in a single block.
+	type MaxExpiredPerBlock: Get<u32>;
+
+	/// Maximum total weight of the proposals executed when closing expired motions
+	/// automatically in a single block.
+	type MaxExpiredWeight: Get<Weight>;
 }
 
 /// Origin for the collective module.
@@ -135,6 +163,9 @@ decl_storage! {
 		/// Votes on a given proposal, if it is ongoing.
 		pub Voting get(fn voting):
 			map hasher(identity) T::Hash => Option<Votes<T::AccountId, T::BlockNumber>>;
+		/// The proposer of a given motion and the deposit they have reserved for it.
+		pub DepositOf get(fn deposit_of):
+			map hasher(identity) T::Hash => Option<(T::AccountId, BalanceOf<T, I>)>;
 		/// Proposals so far.
 		pub ProposalCount get(fn proposal_count): u32;
 		/// The current members of the collective. This is stored sorted (just by value).
@@ -171,6 +202,8 @@ decl_event! {
 		MemberExecuted(Hash, DispatchResult),
 		/// A proposal was closed because its threshold was reached or after its duration was up.
 		Closed(Hash, MemberCount, MemberCount),
+		/// A proposal was closed automatically because its duration was up.
+		Expired(Hash),
 	}
 }
 
@@ -271,6 +304,7 @@ mod weight_for {
 	) -> Weight {
 		T::DbWeight::get().reads(2) // `is_member` + `contains_key`
 			.saturating_add(T::DbWeight::get().reads_writes(2, 4)) // `proposal` insertion
+			.saturating_add(T::DbWeight::get().reads_writes(1, 2)) // reserve deposit, `DepositOf`
 			.saturating_add(50_000_000) // constant
 			.saturating_add(length.saturating_mul(6_000)) // B
 			.saturating_add(members.saturating_mul(110_000)) // M
@@ -308,6 +342,7 @@ mod weight_for {
 			.saturating_add(db.reads(1)) // `Prime`
 			.saturating_add(db.writes(1)) // `Proposals`
 			.saturating_add(db.writes(1)) // `Voting`
+			.saturating_add(db.reads_writes(2, 2)) // `DepositOf`, unreserve deposit
 			.saturating_add(proposal_weight) // P1
 			.saturating_add(proposals.saturating_mul(490_000)) // P2
 	}
@@ -340,8 +375,22 @@ decl_module! {
 	pub struct Module<T: Trait<I>, I: Instance=DefaultInstance> for enum Call where origin: <T as frame_system::Trait>::Origin {
 		type Error = Error<T, I>;
 
+		/// The amount held on deposit for a motion put up for a vote.
+		const ProposalDeposit: BalanceOf<T, I> = T::ProposalDeposit::get();
+
+		/// Maximum number of expired motions closed automatically in a single block.
+		const MaxExpiredPerBlock: u32 = T::MaxExpiredPerBlock::get();
+
+		/// Maximum total weight of the proposals executed when closing expired motions
+		/// automatically in a single block.
+		const MaxExpiredWeight: Weight = T::MaxExpiredWeight::get();
+
 		fn deposit_event() = default;
 
+		fn on_initialize(n: T::BlockNumber) -> Weight {
+			Self::close_expired(n)
+		}
+
 		/// Set the collective's membership.
 		///
 		/// - `new_members`: The new member list. Be nice to the chain and provide it sorted.
@@ -454,7 +503,8 @@ decl_module! {
 		/// Requires the sender to be member.
 		///
 		/// `threshold` determines whether `proposal` is executed directly (`threshold < 2`)
-		/// or put up for voting.
+		/// or put up for voting. In the latter case `ProposalDeposit` is reserved from the sender
+		/// until the motion is closed.
 		///
 		/// # <weight>
 		/// ## Weight
@@ -474,6 +524,8 @@ decl_module! {
 		///       - 1 storage mutation `ProposalCount` (codec `O(1)`)
 		///       - 1 storage write `ProposalOf` (codec `O(B)`)
 		///       - 1 storage write `Voting` (codec `O(M)`)
+		///       - 1 storage write `DepositOf` (codec `O(1)`)
+		///       - 1 balance reservation
 		///   - 1 event
 		/// # </weight>
 		#[weight = (
@@ -519,6 +571,7 @@ decl_module! {
 					proposal_len as Weight, // B
 				)).into())
 			} else {
+				let deposit = T::ProposalDeposit::get();
 				let active_proposals =
 					<Proposals<T, I>>::try_mutate(|proposals| -> Result<usize, DispatchError> {
 						proposals.push(proposal_hash);
@@ -526,8 +579,10 @@ decl_module! {
 							proposals.len() <= T::MaxProposals::get() as usize,
 							Error::<T, I>::TooManyProposals
 						);
+						T::Currency::reserve(&who, deposit)?;
 						Ok(proposals.len())
 					})?;
+				<DepositOf<T, I>>::insert(proposal_hash, (who.clone(), deposit));
 				let index = Self::proposal_count();
 				<ProposalCount<I>>::mutate(|i| *i += 1);
 				<ProposalOf<T, I>>::insert(proposal_hash, *proposal);
@@ -629,6 +684,7 @@ decl_module! {
 		/// - DB:
 		///  - 2 storage reads (`Members`: codec `O(M)`, `Prime`: codec `O(1)`)
 		///  - 3 mutations (`Voting`: codec `O(M)`, `ProposalOf`: codec `O(B)`, `Proposals`: codec `O(P2)`)
+		///  - 1 storage removal `DepositOf` (codec `O(1)`) and 1 balance unreservation
 		///  - any mutations done while executing `proposal` (`P1`)
 		/// - up to 3 events
 		/// # </weight>
@@ -652,8 +708,8 @@ decl_module! {
 			let voting = Self::voting(&proposal_hash).ok_or(Error::<T, I>::ProposalMissing)?;
 			ensure!(voting.index == index, Error::<T, I>::WrongIndex);
 
-			let mut no_votes = voting.nays.len() as MemberCount;
-			let mut yes_votes = voting.ayes.len() as MemberCount;
+			let no_votes = voting.nays.len() as MemberCount;
+			let yes_votes = voting.ayes.len() as MemberCount;
 			let seats = Self::members().len() as MemberCount;
 			let approved = yes_votes >= voting.threshold;
 			let disapproved = seats.saturating_sub(no_votes) < voting.threshold;
@@ -682,14 +738,7 @@ decl_module! {
 			// Only allow actual closing of the proposal after the voting period has ended.
 			ensure!(system::Module::<T>::block_number() >= voting.end, Error::<T, I>::TooEarly);
 
-			// default to true only if there's a prime and they voted in favour.
-			let default = Self::prime().map_or(false, |who| voting.ayes.iter().any(|a| a == &who));
-
-			let abstentions = seats - (yes_votes + no_votes);
-			match default {
-				true => yes_votes += abstentions,
-				false => no_votes += abstentions,
-			}
+			let (yes_votes, no_votes) = Self::final_tally(seats, &voting, Self::prime().as_ref());
 			let approved = yes_votes >= voting.threshold;
 
 			if approved {
@@ -727,10 +776,11 @@ decl_module! {
 		/// Complexity: O(P) where P is the number of max proposals
 		/// Base Weight: .49 * P
 		/// DB Weight:
-		/// * Reads: Proposals
-		/// * Writes: Voting, Proposals, ProposalOf
+		/// * Reads: Proposals, DepositOf, proposer account
+		/// * Writes: Voting, Proposals, ProposalOf, DepositOf, proposer account
 		/// # </weight>
 		#[weight = T::DbWeight::get().reads_writes(1, 3) // `Voting`, `Proposals`, `ProposalOf`
+			.saturating_add(T::DbWeight::get().reads_writes(2, 2)) // `DepositOf`, unreserve deposit
 			.saturating_add(490_000 * Weight::from(T::MaxProposals::get())) // P2
 		]
 		fn disapprove_proposal(origin, proposal_hash: T::Hash) -> DispatchResultWithPostInfo {
@@ -823,16 +873,104 @@ impl<T: Trait<I>, I: Instance> Module<T, I> {
 		Self::remove_proposal(proposal_hash)
 	}
 
-	// Removes a proposal from the pallet, cleaning up votes and the vector of proposals.
+	/// The tally of a motion whose voting period has ended, as `(yes_votes, no_votes)`.
+	///
+	/// Abstentions count as approvals only if there is a `prime` and they voted in favour;
+	/// otherwise they count as rejections.
+	fn final_tally(
+		seats: MemberCount,
+		voting: &Votes<T::AccountId, T::BlockNumber>,
+		prime: Option<&T::AccountId>,
+	) -> (MemberCount, MemberCount) {
+		let mut yes_votes = voting.ayes.len() as MemberCount;
+		let mut no_votes = voting.nays.len() as MemberCount;
+		// default to true only if there's a prime and they voted in favour.
+		let default = prime.map_or(false, |who| voting.ayes.iter().any(|a| a == who));
+
+		let abstentions = seats.saturating_sub(yes_votes + no_votes);
+		match default {
+			true => yes_votes += abstentions,
+			false => no_votes += abstentions,
+		}
+		(yes_votes, no_votes)
+	}
+
+	/// Close up to `MaxExpiredPerBlock` motions whose voting period has ended by block `now`.
+	///
+	/// Motions are kept in `Proposals` in the order they were proposed and are assumed to share
+	/// the same `MotionDuration`, so the scan stops at the first motion that is still open.
+	///
+	/// Approved proposals are executed while their total weight fits in `MaxExpiredWeight`. The
+	/// scan stops at the first one that does not fit in what is left of it, and skips those which
+	/// exceed it on their own; they are left for `close`.
+	fn close_expired(now: T::BlockNumber) -> Weight {
+		let db = T::DbWeight::get();
+		let max = T::MaxExpiredPerBlock::get() as usize;
+		if max == 0 {
+			return 0
+		}
+		let budget = T::MaxExpiredWeight::get();
+		let mut executed: Weight = 0;
+
+		let proposals = Self::proposals();
+		if proposals.is_empty() {
+			return db.reads(1) // `Proposals`
+		}
+		let mut weight = db.reads(3); // `Proposals`, `Members`, `Prime`
+		let seats = Self::members().len() as MemberCount;
+		let prime = Self::prime();
+
+		for proposal_hash in proposals.into_iter().take(max) {
+			weight = weight.saturating_add(db.reads(1)); // `Voting`
+			let voting = match Self::voting(&proposal_hash) {
+				Some(voting) => voting,
+				None => continue,
+			};
+			if voting.end > now {
+				break
+			}
+
+			let (yes_votes, no_votes) = Self::final_tally(seats, &voting, prime.as_ref());
+			weight = weight.saturating_add(db.reads(1)); // `ProposalOf`
+			let proposal = Self::proposal_of(&proposal_hash)
+				.filter(|_| yes_votes >= voting.threshold);
+			if let Some(proposal) = &proposal {
+				let proposal_weight = proposal.get_dispatch_info().weight;
+				if proposal_weight > budget {
+					continue
+				}
+				if executed.saturating_add(proposal_weight) > budget {
+					break
+				}
+				executed = executed.saturating_add(proposal_weight);
+			}
+
+			Self::deposit_event(RawEvent::Expired(proposal_hash));
+			Self::deposit_event(RawEvent::Closed(proposal_hash, yes_votes, no_votes));
+			let finalize_weight = match proposal {
+				Some(proposal) => Self::do_approve_proposal(seats, voting, proposal_hash, proposal),
+				None => Self::do_disapprove_proposal(proposal_hash),
+			};
+			weight = weight.saturating_add(finalize_weight);
+		}
+		weight
+	}
+
+	// Removes a proposal from the pallet, cleaning up votes and the vector of proposals, and
+	// returns the proposer's deposit.
 	fn remove_proposal(proposal_hash: T::Hash) -> Weight {
 		// remove proposal and vote
 		ProposalOf::<T, I>::remove(&proposal_hash);
 		Voting::<T, I>::remove(&proposal_hash);
+		if let Some((who, deposit)) = DepositOf::<T, I>::take(&proposal_hash) {
+			let _ = T::Currency::unreserve(&who, deposit);
+		}
 		let num_proposals = Proposals::<T, I>::mutate(|proposals| {
 			proposals.retain(|h| h != &proposal_hash);
 			proposals.len() + 1 // calculate weight based on original length
 		});
 		T::DbWeight::get().reads_writes(1, 3) // `Voting`, `Proposals`, `ProposalOf`
+			.saturating_add(T::DbWeight::get().reads_writes(2, 2)) // `DepositOf`, unreserve deposit
 			.saturating_add(490_000 * num_proposals as Weight) // P2
 	}
 }
@@ -1006,7 +1144,9 @@ impl<
 #[cfg(test)]
 mod tests {
 	use super::*;
-	use frame_support::{Hashable, assert_ok, assert_noop, parameter_types, weights::Weight};
+	use frame_support::{
+		Hashable, assert_ok, assert_noop, parameter_types, traits::OnInitialize, weights::Weight,
+	};
 	use frame_system::{self as system, EventRecord, Phase};
 	use hex_literal::hex;
 	use sp_core::H256;
@@ -1014,8 +1154,18 @@ mod tests {
 		Perbill, traits::{BlakeTwo256, IdentityLookup, Block as BlockT}, testing::Header,
 		BuildStorage,
 	};
+	use std::cell::RefCell;
 	use crate as collective;
 
+	thread_local! {
+		static PROPOSAL_DEPOSIT: RefCell<u64> = RefCell::new(0);
+	}
+
+	pub struct ProposalDeposit;
+	impl Get<u64> for ProposalDeposit {
+		fn get() -> u64 { PROPOSAL_DEPOSIT.with(|v| *v.borrow()) }
+	}
+
 	parameter_types! {
 		pub const BlockHashCount: u64 = 250;
 		pub const MaximumBlockWeight: Weight = 1024;
@@ -1023,6 +1173,9 @@ mod tests {
 		pub const AvailableBlockRatio: Perbill = Perbill::one();
 		pub const MotionDuration: u64 = 3;
 		pub const MaxProposals: u32 = 100;
+		pub const MaxExpiredPerBlock: u32 = 2;
+		pub const MaxExpiredWeight: Weight = 1_000_000;
+		pub const ExistentialDeposit: u64 = 1;
 	}
 	impl frame_system::Trait for Test {
 		type BaseCallFilter = ();
@@ -1046,16 +1199,27 @@ mod tests {
 		type AvailableBlockRatio = AvailableBlockRatio;
 		type Version = ();
 		type ModuleToIndex = ();
-		type AccountData = ();
+		type AccountData = pallet_balances::AccountData<u64>;
 		type OnNewAccount = ();
 		type OnKilledAccount = ();
 	}
+	impl pallet_balances::Trait for Test {
+		type Balance = u64;
+		type Event = Event;
+		type DustRemoval = ();
+		type ExistentialDeposit = ExistentialDeposit;
+		type AccountStore = System;
+	}
 	impl Trait<Instance1> for Test {
 		type Origin = Origin;
 		type Proposal = Call;
 		type Event = Event;
 		type MotionDuration = MotionDuration;
 		type MaxProposals = MaxProposals;
+		type Currency = Balances;
+		type ProposalDeposit = ProposalDeposit;
+		type MaxExpiredPerBlock = MaxExpiredPerBlock;
+		type MaxExpiredWeight = MaxExpiredWeight;
 	}
 	impl Trait for Test {
 		type Origin = Origin;
@@ -1063,6 +1227,10 @@ mod tests {
 		type Event = Event;
 		type MotionDuration = MotionDuration;
 		type MaxProposals = MaxProposals;
+		type Currency = Balances;
+		type ProposalDeposit = ProposalDeposit;
+		type MaxExpiredPerBlock = MaxExpiredPerBlock;
+		type MaxExpiredWeight = MaxExpiredWeight;
 	}
 
 	pub type Block = sp_runtime::generic::Block<Header, UncheckedExtrinsic>;
@@ -1075,6 +1243,7 @@ mod tests {
 			UncheckedExtrinsic = UncheckedExtrinsic
 		{
 			System: system::{Module, Call, Event<T>},
+			Balances: pallet_balances::{Module, Call, Storage, Config<T>, Event<T>},
 			Collective: collective::<Instance1>::{Module, Call, Event<T>, Origin<T>, Config<T>},
 			DefaultCollective: collective::{Module, Call, Event<T>, Origin<T>, Config<T>},
 		}
@@ -1087,6 +1256,9 @@ mod tests {
 				phantom: Default::default(),
 			}),
 			collective: None,
+			pallet_balances: Some(pallet_balances::GenesisConfig {
+				balances: vec![(1, 100), (2, 100), (3, 100), (4, 100), (5, 100)],
+			}),
 		}.build_storage().unwrap().into();
 		ext.execute_with(|| System::set_block_number(1));
 		ext
@@ -1638,4 +1810,170 @@ mod tests {
 			]);
 		})
 	}
+
+	fn set_proposal_deposit(deposit: u64) {
+		PROPOSAL_DEPOSIT.with(|v| *v.borrow_mut() = deposit);
+	}
+
+	#[test]
+	fn proposal_deposit_is_reserved_and_returned_on_close() {
+		new_test_ext().execute_with(|| {
+			set_proposal_deposit(5);
+			let proposal = make_proposal(42);
+			let proposal_len: u32 = proposal.using_encoded(|p| p.len() as u32);
+			let proposal_weight = proposal.get_dispatch_info().weight;
+			let hash = BlakeTwo256::hash_of(&proposal);
+
+			assert_ok!(Collective::propose(Origin::signed(1), 3, Box::new(proposal.clone()), proposal_len));
+			assert_eq!(Balances::reserved_balance(1), 5);
+			assert_eq!(Balances::free_balance(1), 95);
+			assert_eq!(Collective::deposit_of(&hash), Some((1, 5)));
+
+			System::set_block_number(4);
+			assert_ok!(Collective::close(Origin::signed(4), hash.clone(), 0, proposal_weight, proposal_len));
+			assert_eq!(Balances::reserved_balance(1), 0);
+			assert_eq!(Balances::free_balance(1), 100);
+			assert_eq!(Collective::deposit_of(&hash), None);
+		});
+	}
+
+	#[test]
+	fn proposal_deposit_is_returned_on_root_disapproval() {
+		new_test_ext().execute_with(|| {
+			set_proposal_deposit(5);
+			let proposal = make_proposal(42);
+			let proposal_len: u32 = proposal.using_encoded(|p| p.len() as u32);
+			let hash = BlakeTwo256::hash_of(&proposal);
+
+			assert_ok!(Collective::propose(Origin::signed(2), 2, Box::new(proposal.clone()), proposal_len));
+			assert_eq!(Balances::reserved_balance(2), 5);
+			assert_ok!(Collective::disapprove_proposal(Origin::root(), hash.clone()));
+			assert_eq!(Balances::reserved_balance(2), 0);
+			assert_eq!(Balances::free_balance(2), 100);
+		});
+	}
+
+	#[test]
+	fn propose_without_deposit_fails() {
+		new_test_ext().execute_with(|| {
+			set_proposal_deposit(101);
+			let proposal = make_proposal(42);
+			let proposal_len: u32 = proposal.using_encoded(|p| p.len() as u32);
+			assert_noop!(
+				Collective::propose(Origin::signed(1), 3, Box::new(proposal.clone()), proposal_len),
+				pallet_balances::Error::<Test, _>::InsufficientBalance
+			);
+			// Proposals executed straight away require no deposit.
+			assert_ok!(Collective::propose(Origin::signed(1), 1, Box::new(proposal.clone()), proposal_len));
+		});
+	}
+
+	#[test]
+	fn expired_motions_are_closed_on_initialize() {
+		new_test_ext().execute_with(|| {
+			set_proposal_deposit(5);
+			let proposal = make_proposal(42);
+			let proposal_len: u32 = proposal.using_encoded(|p| p.len() as u32);
+			let hash = BlakeTwo256::hash_of(&proposal);
+
+			assert_ok!(Collective::propose(Origin::signed(1), 3, Box::new(proposal.clone()), proposal_len));
+			assert_ok!(Collective::vote(Origin::signed(2), hash.clone(), 0, true));
+
+			Collective::on_initialize(3);
+			assert_eq!(Collective::proposals(), vec![hash]);
+
+			System::reset_events();
+			Collective::on_initialize(4);
+			assert_eq!(Collective::proposals(), Vec::<H256>::new());
+			assert_eq!(Collective::proposal_of(&hash), None);
+			assert_eq!(Collective::voting(&hash), None);
+			assert_eq!(Balances::reserved_balance(1), 0);
+
+			let record = |event| EventRecord { phase: Phase::Initialization, event, topics: vec![] };
+			assert_eq!(System::events(), vec![
+				record(Event::collective_Instance1(RawEvent::Expired(hash.clone()))),
+				record(Event::collective_Instance1(RawEvent::Closed(hash.clone(), 2, 1))),
+				record(Event::collective_Instance1(RawEvent::Disapproved(hash.clone()))),
+				record(Event::pallet_balances(pallet_balances::RawEvent::Unreserved(1, 5))),
+			]);
+		});
+	}
+
+	#[test]
+	fn expired_motions_use_prime_default_vote() {
+		new_test_ext().execute_with(|| {
+			let proposal = make_proposal(42);
+			let proposal_len: u32 = proposal.using_encoded(|p| p.len() as u32);
+			let hash = BlakeTwo256::hash_of(&proposal);
+			assert_ok!(Collective::set_members(Origin::root(), vec![1, 2, 3], Some(1), MAX_MEMBERS));
+
+			assert_ok!(Collective::propose(Origin::signed(1), 3, Box::new(proposal.clone()), proposal_len));
+			System::reset_events();
+			Collective::on_initialize(4);
+
+			let record = |event| EventRecord { phase: Phase::Initialization, event, topics: vec![] };
+			assert_eq!(System::events(), vec![
+				record(Event::collective_Instance1(RawEvent::Expired(hash.clone()))),
+				record(Event::collective_Instance1(RawEvent::Closed(hash.clone(), 3, 0))),
+				record(Event::collective_Instance1(RawEvent::Approved(hash.clone()))),
+				record(Event::collective_Instance1(RawEvent::Executed(hash.clone(), Err(DispatchError::BadOrigin)))),
+			]);
+		});
+	}
+
+	#[test]
+	fn expired_motions_are_closed_in_bounded_batches() {
+		new_test_ext().execute_with(|| {
+			let hashes: Vec<H256> = (0..3).map(|i| {
+				let proposal = make_proposal(i);
+				let proposal_len: u32 = proposal.using_encoded(|p| p.len() as u32);
+				assert_ok!(Collective::propose(Origin::signed(1), 3, Box::new(proposal.clone()), proposal_len));
+				BlakeTwo256::hash_of(&proposal)
+			}).collect();
+			System::set_block_number(2);
+			let late = make_proposal(3);
+			let late_len: u32 = late.using_encoded(|p| p.len() as u32);
+			assert_ok!(Collective::propose(Origin::signed(2), 3, Box::new(late.clone()), late_len));
+			let late_hash = BlakeTwo256::hash_of(&late);
+
+			// At most `MaxExpiredPerBlock` motions are closed per block.
+			Collective::on_initialize(4);
+			assert_eq!(Collective::proposals(), vec![hashes[2], late_hash]);
+			// The scan stops at the first motion that has not yet expired.
+			Collective::on_initialize(4);
+			assert_eq!(Collective::proposals(), vec![late_hash]);
+			Collective::on_initialize(5);
+			assert_eq!(Collective::proposals(), Vec::<H256>::new());
+		});
+	}
+
+	#[test]
+	fn expired_motions_are_executed_within_weight_budget() {
+		new_test_ext().execute_with(|| {
+			assert_ok!(Collective::set_members(Origin::root(), vec![1, 2, 3], Some(1), MAX_MEMBERS));
+			// Each remark weighs 700_000, within the budget of 1_000_000 but not both together.
+			let mut proposals = vec![make_proposal(0), make_proposal(1)];
+			// Weighs 1_500_000, over the budget on its own.
+			proposals.push(Call::System(frame_system::Call::set_heap_pages(0)));
+			let hashes: Vec<H256> = proposals.iter().map(|proposal| {
+				let proposal_len: u32 = proposal.using_encoded(|p| p.len() as u32);
+				assert_ok!(Collective::propose(Origin::signed(1), 3, Box::new(proposal.clone()), proposal_len));
+				BlakeTwo256::hash_of(proposal)
+			}).collect();
+
+			Collective::on_initialize(4);
+			assert_eq!(Collective::proposals(), vec![hashes[1], hashes[2]]);
+			Collective::on_initialize(5);
+			assert_eq!(Collective::proposals(), vec![hashes[2]]);
+			// The heavy proposal is left for `close`.
+			Collective::on_initialize(6);
+			assert_eq!(Collective::proposals(), vec![hashes[2]]);
+
+			System::set_block_number(6);
+			let heavy_len: u32 = proposals[2].using_encoded(|p| p.len() as u32);
+			let heavy_weight = proposals[2].get_dispatch_info().weight;
+			assert_ok!(Collective::close(Origin::signed(2), hashes[2], 2, heavy_weight, heavy_len));
+			assert_eq!(Collective::proposals(), Vec::<H256>::new());
+		});
+	}
 }
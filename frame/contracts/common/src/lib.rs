@@ -45,3 +45,75 @@ pub enum RentProjection<BlockNumber> {
 	/// E.g. because the contract accumulated enough funds to offset the rent storage costs.
 	NoEviction,
 }
+
+/// A result of execution of a contract.
+#[derive(Eq, PartialEq, codec::Encode, codec::Decode, sp_runtime::RuntimeDebug)]
+pub enum ContractExecResult {
+	/// The contract returned successfully.
+	///
+	/// There is a status code and, optionally, some data returned by the contract.
+	Success {
+		/// Status code returned by the contract.
+		status: u8,
+		/// Output data returned by the contract.
+		///
+		/// Can be empty.
+		data: Vec<u8>,
+	},
+	/// The contract execution either trapped or returned an error.
+	Error,
+}
+
+/// The code to instantiate a contract from in a dry run.
+#[derive(Eq, PartialEq, codec::Encode, codec::Decode, sp_runtime::RuntimeDebug)]
+pub enum Code<Hash> {
+	/// Wasm code that is uploaded as part of the dry run.
+	Upload(Vec<u8>),
+	/// The hash of code that is already on chain.
+	Existing(Hash),
+}
+
+/// A result of a dry-run instantiation of a contract.
+#[derive(Eq, PartialEq, codec::Encode, codec::Decode, sp_runtime::RuntimeDebug)]
+pub struct ContractInstantiateResult<AccountId, Balance, BlockNumber> {
+	/// The address the contract is instantiated at.
+	pub address: AccountId,
+	/// The gas consumed by the instantiation.
+	pub gas_consumed: u64,
+	/// The smallest endowment that exempts the instantiated contract from paying rent.
+	///
+	/// Zero if the instantiation failed.
+	pub required_endowment: Balance,
+	/// The projected eviction of the instantiated contract, `None` if the instantiation failed.
+	pub rent_projection: Option<RentProjection<BlockNumber>>,
+	/// The messages printed with `ext_println` during the instantiation, one per line.
+	pub debug_message: Vec<u8>,
+	/// The output of the constructor.
+	pub result: ContractExecResult,
+}
+
+/// A result type of a dry-run code upload: the code hash and the gas `put_code` is charged, or
+/// the reason the code was rejected.
+pub type CodeUploadResult<Hash> = Result<CodeUploadReturnValue<Hash>, Vec<u8>>;
+
+/// The outcome of a successful dry-run code upload.
+#[derive(Eq, PartialEq, codec::Encode, codec::Decode, sp_runtime::RuntimeDebug)]
+pub struct CodeUploadReturnValue<Hash> {
+	/// The hash the code is stored under.
+	pub code_hash: Hash,
+	/// The gas `put_code` is charged for the code.
+	pub gas_consumed: u64,
+}
+
+/// An event deposited by a contract with `ext_deposit_event`.
+#[derive(Eq, PartialEq, Clone, codec::Encode, codec::Decode, sp_runtime::RuntimeDebug)]
+pub struct ContractEvent<AccountId, Hash> {
+	/// The contract that deposited the event.
+	pub contract: AccountId,
+	/// The topics the event was deposited with.
+	pub topics: Vec<Hash>,
+	/// The data of the event.
+	pub data: Vec<u8>,
+	/// The index of the extrinsic that deposited the event, if any.
+	pub extrinsic_index: Option<u32>,
+}
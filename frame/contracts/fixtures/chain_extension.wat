@@ -0,0 +1,37 @@
+(module
+	(import "env" "ext_scratch_size" (func $ext_scratch_size (result i32)))
+	(import "env" "ext_scratch_read" (func $ext_scratch_read (param i32 i32 i32)))
+	(import "env" "ext_call_chain_extension" (func $ext_call_chain_extension (param i32 i32 i32) (result i32)))
+	(import "env" "memory" (memory 1 1))
+
+	(func (export "deploy") (result i32)
+		(i32.const 0)
+	)
+
+	;; Call reads the first 4 bytes (LE) as the function id of the chain extension and passes the
+	;; rest as its input. The output of the chain extension is returned as output data and the
+	;; return value of `ext_call_chain_extension` as the exit status.
+	(func (export "call") (result i32)
+		(local $buf_size i32)
+
+		;; Find out the size of the scratch buffer
+		(set_local $buf_size (call $ext_scratch_size))
+
+		;; Copy scratch buffer into this contract memory.
+		(call $ext_scratch_read
+			(i32.const 0)		;; The pointer where to store the scratch buffer contents,
+			(i32.const 0)		;; Offset from the start of the scratch buffer.
+			(get_local $buf_size)		;; Count of bytes to copy.
+		)
+
+		;; The scratch buffer now holds the output of the chain extension.
+		(call $ext_call_chain_extension
+			(i32.load (i32.const 0))	;; The function id.
+			(i32.const 4)		;; Pointer to the input data.
+			(i32.sub		;; Length of the input data.
+				(get_local $buf_size)
+				(i32.const 4)
+			)
+		)
+	)
+)
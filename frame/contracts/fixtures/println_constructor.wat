@@ -0,0 +1,17 @@
+(module
+	(import "env" "ext_println" (func $ext_println (param i32 i32)))
+	(import "env" "memory" (memory 1 1))
+
+	(func (export "deploy")
+		(call $ext_println
+			(i32.const 8) ;; Pointer to the message
+			(i32.const 9) ;; Length of the message
+		)
+	)
+
+	(func (export "call")
+		(unreachable)
+	)
+
+	(data (i32.const 8) "deploying")
+)
@@ -0,0 +1,25 @@
+(module
+	(import "env" "ext_scratch_read" (func $ext_scratch_read (param i32 i32 i32)))
+	(import "env" "ext_set_code_hash" (func $ext_set_code_hash (param i32 i32) (result i32)))
+	(import "env" "memory" (memory 1 1))
+
+	(func (export "deploy") (result i32)
+		(i32.const 0)
+	)
+
+	;; Call reads the input as the code hash of the new code of this contract and returns the
+	;; return value of `ext_set_code_hash` as the exit status.
+	(func (export "call") (result i32)
+		;; Copy the code hash from the scratch buffer into this contract memory.
+		(call $ext_scratch_read
+			(i32.const 0)		;; The pointer where to store the scratch buffer contents,
+			(i32.const 0)		;; Offset from the start of the scratch buffer.
+			(i32.const 32)		;; Count of bytes to copy.
+		)
+
+		(call $ext_set_code_hash
+			(i32.const 0)		;; Pointer to the new code hash.
+			(i32.const 32)		;; Length of the new code hash.
+		)
+	)
+)
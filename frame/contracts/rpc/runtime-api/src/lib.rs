@@ -23,35 +23,23 @@
 
 #![cfg_attr(not(feature = "std"), no_std)]
 
-use codec::{Codec, Decode, Encode};
-use pallet_contracts_primitives::{GetStorageResult, RentProjectionResult};
-use sp_runtime::RuntimeDebug;
+use codec::Codec;
+use pallet_contracts_primitives::{
+	Code, CodeUploadResult, ContractEvent, ContractInstantiateResult, GetStorageResult,
+	RentProjectionResult,
+};
 use sp_std::vec::Vec;
 
-/// A result of execution of a contract.
-#[derive(Eq, PartialEq, Encode, Decode, RuntimeDebug)]
-pub enum ContractExecResult {
-	/// The contract returned successfully.
-	///
-	/// There is a status code and, optionally, some data returned by the contract.
-	Success {
-		/// Status code returned by the contract.
-		status: u8,
-		/// Output data returned by the contract.
-		///
-		/// Can be empty.
-		data: Vec<u8>,
-	},
-	/// The contract execution either trapped or returned an error.
-	Error,
-}
+pub use pallet_contracts_primitives::ContractExecResult;
 
 sp_api::decl_runtime_apis! {
 	/// The API to interact with contracts without using executive.
-	pub trait ContractsApi<AccountId, Balance, BlockNumber> where
+	#[api_version(3)]
+	pub trait ContractsApi<AccountId, Balance, BlockNumber, Hash> where
 		AccountId: Codec,
 		Balance: Codec,
 		BlockNumber: Codec,
+		Hash: Codec,
 	{
 		/// Perform a call from a specified account to a given contract.
 		///
@@ -82,5 +70,25 @@ sp_api::decl_runtime_apis! {
 		///
 		/// Returns `Err` if the contract is in a tombstone state or doesn't exist.
 		fn rent_projection(address: AccountId) -> RentProjectionResult<BlockNumber>;
+
+		/// Instantiate a contract from a specified account without persisting it.
+		///
+		/// See the contracts' `instantiate` dispatchable function for more details. `code` can
+		/// also be uploaded as part of the dry run.
+		fn instantiate(
+			origin: AccountId,
+			endowment: Balance,
+			gas_limit: u64,
+			code: Code<Hash>,
+			data: Vec<u8>,
+		) -> ContractInstantiateResult<AccountId, Balance, BlockNumber>;
+
+		/// Validate and instrument code without storing it.
+		///
+		/// See the contracts' `put_code` dispatchable function for more details.
+		fn upload_code(code: Vec<u8>) -> CodeUploadResult<Hash>;
+
+		/// Returns the events deposited by contracts in the block.
+		fn events() -> Vec<ContractEvent<AccountId, Hash>>;
 	}
 }
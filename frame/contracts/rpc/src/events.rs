@@ -0,0 +1,525 @@
+// This file is part of Substrate.
+
+// Copyright (C) 2020 Parity Technologies (UK) Ltd.
+// SPDX-License-Identifier: Apache-2.0
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// 	http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! An index of contract events in the auxiliary storage of the client and the RPC methods that
+//! query it.
+//!
+//! The index is optional: a node that wants to serve `contracts_getEvents` and
+//! `contracts_subscribeEvents` spawns [`ContractEventsIndex::run`], which indexes every imported
+//! block, and [`ContractEventsIndex::backfill`], which indexes the canonical chain up to the best
+//! block. The events of a block are stored under its hash, and every block that contains events
+//! of a contract, of a topic, or of a contract with a topic has an entry under that contract
+//! and/or topic and its number. Only events of blocks on the canonical chain are returned.
+
+use std::collections::{BTreeSet, HashMap};
+use std::marker::PhantomData;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Arc;
+
+use codec::{Codec, Decode, Encode};
+use futures::{compat::Future01CompatExt, StreamExt};
+use jsonrpc_core::{Error, ErrorCode, Result};
+use jsonrpc_derive::rpc;
+use jsonrpc_pubsub::{
+	typed::{Sink, Subscriber},
+	PubSubMetadata, SubscriptionId,
+};
+use log::{debug, warn};
+use pallet_contracts_primitives::ContractEvent;
+use parking_lot::Mutex;
+use sc_client_api::{AuxStore, BlockchainEvents};
+use serde::{Deserialize, Serialize};
+use sp_api::ProvideRuntimeApi;
+use sp_blockchain::HeaderBackend;
+use sp_core::Bytes;
+use sp_runtime::{
+	generic::BlockId,
+	traits::{Block as BlockT, Header as HeaderT, NumberFor, One, Zero},
+};
+
+use sp_api::ApiExt;
+
+use crate::{ContractsRuntimeApi, EVENTS_INDEX_ERROR};
+
+pub use self::gen_client::Client as ContractsEventsClient;
+
+/// The maximum number of blocks a single `contracts_getEvents` call can span.
+const MAX_BLOCK_RANGE: u32 = 10_000;
+
+const BLOCK_EVENTS_PREFIX: &[u8] = b"contracts_events_block";
+const BLOCK_NUMBERS_PREFIX: &[u8] = b"contracts_events_index";
+const LAST_INDEXED_KEY: &[u8] = b"contracts_events_last_indexed";
+
+/// A filter of contract events.
+#[derive(Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+#[serde(deny_unknown_fields)]
+pub struct EventFilter<AccountId, Hash> {
+	/// Match the events of any of these contracts, or of all contracts if empty.
+	#[serde(default)]
+	pub contracts: Vec<AccountId>,
+	/// Match the events that were deposited with all of these topics.
+	#[serde(default)]
+	pub topics: Vec<Hash>,
+}
+
+impl<AccountId: PartialEq, Hash: PartialEq> EventFilter<AccountId, Hash> {
+	fn matches(&self, event: &ContractEvent<AccountId, Hash>) -> bool {
+		(self.contracts.is_empty() || self.contracts.contains(&event.contract)) &&
+			self.topics.iter().all(|topic| event.topics.contains(topic))
+	}
+}
+
+/// A contract event and the block it was deposited in.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RpcContractEvent<AccountId, Hash, Number> {
+	/// Hash of the block.
+	pub block_hash: Hash,
+	/// Number of the block.
+	pub block_number: Number,
+	/// Index of the extrinsic that deposited the event, if any.
+	pub extrinsic_index: Option<u32>,
+	/// The contract that deposited the event.
+	pub contract: AccountId,
+	/// The topics the event was deposited with.
+	pub topics: Vec<Hash>,
+	/// The data of the event.
+	pub data: Bytes,
+}
+
+impl<AccountId, Hash, Number> RpcContractEvent<AccountId, Hash, Number> {
+	fn new(block_hash: Hash, block_number: Number, event: ContractEvent<AccountId, Hash>) -> Self {
+		RpcContractEvent {
+			block_hash,
+			block_number,
+			extrinsic_index: event.extrinsic_index,
+			contract: event.contract,
+			topics: event.topics,
+			data: event.data.into(),
+		}
+	}
+}
+
+type RpcEventOf<Block, AccountId> =
+	RpcContractEvent<AccountId, <Block as BlockT>::Hash, NumberFor<Block>>;
+
+type Subscriptions<Block, AccountId> = HashMap<
+	SubscriptionId,
+	(EventFilter<AccountId, <Block as BlockT>::Hash>, Sink<Vec<RpcEventOf<Block, AccountId>>>),
+>;
+
+/// An index of contract events by contract and topic, stored in the auxiliary storage of the
+/// client.
+pub struct ContractEventsIndex<C, Block: BlockT, AccountId, Balance> {
+	client: Arc<C>,
+	subscriptions: Mutex<Subscriptions<Block, AccountId>>,
+	next_subscription_id: AtomicU64,
+	_marker: PhantomData<fn() -> Balance>,
+}
+
+impl<C, Block, AccountId, Balance> ContractEventsIndex<C, Block, AccountId, Balance>
+where
+	Block: BlockT,
+	C: ProvideRuntimeApi<Block> + HeaderBackend<Block> + AuxStore + Send + Sync + 'static,
+	C::Api: ContractsRuntimeApi<Block, AccountId, Balance, NumberFor<Block>, Block::Hash>,
+	AccountId: Codec + Clone + PartialEq + Serialize + Send + Sync + 'static,
+	Balance: Codec,
+{
+	/// Create a new index of the contract events of the blocks imported by `client`.
+	pub fn new(client: Arc<C>) -> Self {
+		ContractEventsIndex {
+			client,
+			subscriptions: Mutex::new(HashMap::new()),
+			next_subscription_id: AtomicU64::new(0),
+			_marker: PhantomData,
+		}
+	}
+
+	/// Index the blocks imported by the client and notify the subscribers of the events of new
+	/// best blocks.
+	pub async fn run(self: Arc<Self>) where C: BlockchainEvents<Block> {
+		let mut imports = self.client.import_notification_stream();
+		while let Some(notification) = imports.next().await {
+			let number = *notification.header.number();
+			match self.index_block(notification.hash, number) {
+				Ok(events) if notification.is_new_best =>
+					self.notify(notification.hash, number, events).await,
+				Ok(_) => {},
+				Err(e) => warn!("Failed to index contract events of block #{}: {:?}", number, e),
+			}
+		}
+	}
+
+	/// Index the canonical chain up to the best block, and again whenever a block is imported.
+	///
+	/// Blocks imported during the initial sync are not announced, so they are only indexed here.
+	/// This calls the runtime of every block it indexes and should be spawned as a blocking task,
+	/// apart from [`ContractEventsIndex::run`].
+	pub async fn backfill(self: Arc<Self>) where C: BlockchainEvents<Block> {
+		let mut imports = self.client.import_notification_stream();
+		loop {
+			let best = self.client.info().best_number;
+			if let Err(e) = self.index_canonical_chain(best) {
+				warn!("Failed to index contract events up to block #{}: {:?}", best, e);
+			}
+			if imports.next().await.is_none() {
+				break;
+			}
+		}
+	}
+
+	/// The events matching `filter` in the canonical blocks `from` to `to`, inclusive.
+	pub fn events(
+		&self,
+		filter: &EventFilter<AccountId, Block::Hash>,
+		from: NumberFor<Block>,
+		to: NumberFor<Block>,
+	) -> sp_blockchain::Result<Vec<RpcEventOf<Block, AccountId>>> {
+		let topic = filter.topics.first();
+		let contracts: Vec<Option<&AccountId>> = if filter.contracts.is_empty() {
+			vec![None]
+		} else {
+			filter.contracts.iter().map(Some).collect()
+		};
+		let indexed = contracts.iter().any(Option::is_some) || topic.is_some();
+
+		let mut events = Vec::new();
+		let mut number = from;
+		while number <= to {
+			let current = number;
+			number += One::one();
+			if indexed {
+				let mut contains = false;
+				for contract in &contracts {
+					if self.read::<bool>(&block_number_key(*contract, topic, &current))?.is_some() {
+						contains = true;
+						break;
+					}
+				}
+				if !contains {
+					continue;
+				}
+			}
+			let hash = match self.client.hash(current)? {
+				Some(hash) => hash,
+				None => continue,
+			};
+			let block_events: Vec<ContractEvent<AccountId, Block::Hash>> =
+				self.read(&block_events_key(&hash))?.unwrap_or_default();
+			events.extend(
+				block_events
+					.into_iter()
+					.filter(|event| filter.matches(event))
+					.map(|event| RpcContractEvent::new(hash, current, event)),
+			);
+		}
+		Ok(events)
+	}
+
+	/// Notify `subscriber` of the events matching `filter` in new best blocks.
+	pub fn subscribe(
+		&self,
+		filter: EventFilter<AccountId, Block::Hash>,
+		subscriber: Subscriber<Vec<RpcEventOf<Block, AccountId>>>,
+	) {
+		let id = SubscriptionId::Number(self.next_subscription_id.fetch_add(1, Ordering::Relaxed));
+		if let Ok(sink) = subscriber.assign_id(id.clone()) {
+			self.subscriptions.lock().insert(id, (filter, sink));
+		}
+	}
+
+	/// Cancel a subscription. Returns whether it existed.
+	pub fn unsubscribe(&self, id: &SubscriptionId) -> bool {
+		self.subscriptions.lock().remove(id).is_some()
+	}
+
+	fn index_canonical_chain(&self, best: NumberFor<Block>) -> sp_blockchain::Result<()> {
+		let mut number = match self.read::<NumberFor<Block>>(LAST_INDEXED_KEY)? {
+			Some(last) => last + One::one(),
+			None => Zero::zero(),
+		};
+		while number <= best {
+			if let Some(hash) = self.client.hash(number)? {
+				self.index_block(hash, number)?;
+			}
+			self.client.insert_aux(&[(LAST_INDEXED_KEY, &number.encode()[..])], &[])?;
+			number += One::one();
+		}
+		Ok(())
+	}
+
+	/// Index the events of a block unless it is indexed already, and return them.
+	///
+	/// Blocks whose runtime predates the events runtime API have no events. Nothing is written if
+	/// the events cannot be read, so that the block is indexed again later.
+	fn index_block(
+		&self,
+		hash: Block::Hash,
+		number: NumberFor<Block>,
+	) -> sp_blockchain::Result<Vec<ContractEvent<AccountId, Block::Hash>>> {
+		let events_key = block_events_key(&hash);
+		if let Some(events) = self.read(&events_key)? {
+			return Ok(events);
+		}
+
+		let at = BlockId::hash(hash);
+		let api = self.client.runtime_api();
+		let runtime_error = |e| sp_blockchain::Error::Msg(
+			format!("Failed to read the contract events of block #{}: {:?}", number, e)
+		);
+		let has_events = api
+			.has_api_with::<dyn ContractsRuntimeApi<
+				Block, AccountId, Balance, NumberFor<Block>, Block::Hash, Error = ()
+			>, _>(&at, |version| version >= 3)
+			.map_err(runtime_error)?;
+		let events = if has_events {
+			api.events(&at).map_err(runtime_error)?
+		} else {
+			debug!("No contract events for block #{}: unsupported runtime", number);
+			Vec::new()
+		};
+
+		let mut keys = BTreeSet::new();
+		for event in &events {
+			keys.insert(block_number_key(Some(&event.contract), None, &number));
+			for topic in &event.topics {
+				keys.insert(block_number_key(Some(&event.contract), Some(topic), &number));
+				keys.insert(block_number_key::<AccountId, _, _>(None, Some(topic), &number));
+			}
+		}
+
+		let contains = true.encode();
+		let encoded_events = events.encode();
+		let mut insert: Vec<(&[u8], &[u8])> = keys.iter().map(|key| (&key[..], &contains[..])).collect();
+		insert.push((&events_key[..], &encoded_events[..]));
+		self.client.insert_aux(&insert, &[])?;
+		Ok(events)
+	}
+
+	async fn notify(
+		&self,
+		hash: Block::Hash,
+		number: NumberFor<Block>,
+		events: Vec<ContractEvent<AccountId, Block::Hash>>,
+	) {
+		if events.is_empty() {
+			return;
+		}
+		let notifications: Vec<_> = self.subscriptions.lock()
+			.iter()
+			.filter_map(|(id, (filter, sink))| {
+				let matching: Vec<_> = events.iter()
+					.filter(|event| filter.matches(event))
+					.map(|event| RpcContractEvent::new(hash, number, event.clone()))
+					.collect();
+				if matching.is_empty() {
+					None
+				} else {
+					Some((id.clone(), sink.notify(Ok(matching))))
+				}
+			})
+			.collect();
+
+		for (id, notification) in notifications {
+			if notification.compat().await.is_err() {
+				// The subscriber went away.
+				self.subscriptions.lock().remove(&id);
+			}
+		}
+	}
+
+	fn read<V: Decode>(&self, key: &[u8]) -> sp_blockchain::Result<Option<V>> {
+		match self.client.get_aux(key)? {
+			Some(raw) => V::decode(&mut &raw[..])
+				.map(Some)
+				.map_err(|e| sp_blockchain::Error::Backend(
+					format!("Corrupt contract events index: {:?}", e)
+				)),
+			None => Ok(None),
+		}
+	}
+}
+
+fn block_events_key<Hash: Encode>(hash: &Hash) -> Vec<u8> {
+	let mut key = BLOCK_EVENTS_PREFIX.to_vec();
+	hash.encode_to(&mut key);
+	key
+}
+
+fn block_number_key<AccountId: Encode, Hash: Encode, Number: Encode>(
+	contract: Option<&AccountId>,
+	topic: Option<&Hash>,
+	number: &Number,
+) -> Vec<u8> {
+	let mut key = BLOCK_NUMBERS_PREFIX.to_vec();
+	(contract, topic, number).encode_to(&mut key);
+	key
+}
+
+/// Contract events RPC methods.
+#[rpc]
+pub trait ContractsEventsApi<BlockHash, BlockNumber, AccountId> {
+	/// RPC metadata
+	type Metadata;
+
+	/// Returns the contract events matching `filter` in the blocks `from` to `to`, inclusive.
+	///
+	/// `to` defaults to the best block and `from` to `to`.
+	#[rpc(name = "contracts_getEvents")]
+	fn get_events(
+		&self,
+		filter: EventFilter<AccountId, BlockHash>,
+		from: Option<BlockNumber>,
+		to: Option<BlockNumber>,
+	) -> Result<Vec<RpcContractEvent<AccountId, BlockHash, BlockNumber>>>;
+
+	/// Subscribe to the contract events matching `filter` in new best blocks.
+	#[pubsub(subscription = "contracts_events", subscribe, name = "contracts_subscribeEvents")]
+	fn subscribe_events(
+		&self,
+		metadata: Self::Metadata,
+		subscriber: Subscriber<Vec<RpcContractEvent<AccountId, BlockHash, BlockNumber>>>,
+		filter: EventFilter<AccountId, BlockHash>,
+	);
+
+	/// Unsubscribe from contract events.
+	#[pubsub(subscription = "contracts_events", unsubscribe, name = "contracts_unsubscribeEvents")]
+	fn unsubscribe_events(
+		&self,
+		metadata: Option<Self::Metadata>,
+		id: SubscriptionId,
+	) -> Result<bool>;
+}
+
+/// An implementation of the contract events RPC methods on top of a [`ContractEventsIndex`].
+pub struct ContractsEvents<C, Block: BlockT, AccountId, Balance, M> {
+	index: Arc<ContractEventsIndex<C, Block, AccountId, Balance>>,
+	_marker: PhantomData<M>,
+}
+
+impl<C, Block: BlockT, AccountId, Balance, M> ContractsEvents<C, Block, AccountId, Balance, M> {
+	/// Create new `ContractsEvents` serving the given index.
+	pub fn new(index: Arc<ContractEventsIndex<C, Block, AccountId, Balance>>) -> Self {
+		ContractsEvents {
+			index,
+			_marker: Default::default(),
+		}
+	}
+}
+
+impl<C, Block, AccountId, Balance, M> ContractsEventsApi<Block::Hash, NumberFor<Block>, AccountId>
+	for ContractsEvents<C, Block, AccountId, Balance, M>
+where
+	Block: BlockT,
+	C: ProvideRuntimeApi<Block> + HeaderBackend<Block> + AuxStore + Send + Sync + 'static,
+	C::Api: ContractsRuntimeApi<Block, AccountId, Balance, NumberFor<Block>, Block::Hash>,
+	AccountId: Codec + Clone + PartialEq + Serialize + Send + Sync + 'static,
+	Balance: Codec,
+	M: PubSubMetadata,
+{
+	type Metadata = M;
+
+	fn get_events(
+		&self,
+		filter: EventFilter<AccountId, Block::Hash>,
+		from: Option<NumberFor<Block>>,
+		to: Option<NumberFor<Block>>,
+	) -> Result<Vec<RpcEventOf<Block, AccountId>>> {
+		let to = to.unwrap_or_else(|| self.index.client.info().best_number);
+		let from = from.unwrap_or(to);
+		if from > to || to - from >= MAX_BLOCK_RANGE.into() {
+			return Err(Error {
+				code: ErrorCode::InvalidParams,
+				message: format!(
+					"Invalid block range {}..={}, it can span at most {} blocks",
+					from, to, MAX_BLOCK_RANGE
+				),
+				data: None,
+			});
+		}
+
+		self.index.events(&filter, from, to).map_err(|e| Error {
+			code: ErrorCode::ServerError(EVENTS_INDEX_ERROR),
+			message: "Failed to read the contract events index".into(),
+			data: Some(format!("{:?}", e).into()),
+		})
+	}
+
+	fn subscribe_events(
+		&self,
+		_metadata: Self::Metadata,
+		subscriber: Subscriber<Vec<RpcEventOf<Block, AccountId>>>,
+		filter: EventFilter<AccountId, Block::Hash>,
+	) {
+		self.index.subscribe(filter, subscriber)
+	}
+
+	fn unsubscribe_events(
+		&self,
+		_metadata: Option<Self::Metadata>,
+		id: SubscriptionId,
+	) -> Result<bool> {
+		Ok(self.index.unsubscribe(&id))
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use sp_core::H256;
+
+	#[test]
+	fn filter_should_deserialize_and_match() {
+		let filter: EventFilter<u64, H256> = serde_json::from_str(r#"
+		{
+			"contracts": [1, 2],
+			"topics": ["0x1111111111111111111111111111111111111111111111111111111111111111"]
+		}
+		"#).unwrap();
+		let event = |contract, topics| ContractEvent {
+			contract,
+			topics,
+			data: vec![],
+			extrinsic_index: None,
+		};
+
+		assert!(filter.matches(&event(1, vec![H256::repeat_byte(0x22), H256::repeat_byte(0x11)])));
+		assert!(!filter.matches(&event(3, vec![H256::repeat_byte(0x11)])));
+		assert!(!filter.matches(&event(2, vec![H256::repeat_byte(0x22)])));
+
+		let filter: EventFilter<u64, H256> = serde_json::from_str("{}").unwrap();
+		assert!(filter.matches(&event(3, vec![])));
+	}
+
+	#[test]
+	fn index_keys_should_not_collide() {
+		let topic = H256::repeat_byte(0x11);
+		assert_ne!(
+			block_number_key(Some(&1u64), None::<&H256>, &1u32),
+			block_number_key(Some(&1u64), Some(&topic), &1u32),
+		);
+		assert_ne!(
+			block_number_key(None::<&u64>, Some(&topic), &1u32),
+			block_number_key(Some(&1u64), Some(&topic), &1u32),
+		);
+		assert_ne!(
+			block_number_key(Some(&1u64), Some(&topic), &1u32),
+			block_number_key(Some(&1u64), Some(&topic), &2u32),
+		);
+	}
+}
@@ -22,7 +22,7 @@ use std::sync::Arc;
 use codec::Codec;
 use jsonrpc_core::{Error, ErrorCode, Result};
 use jsonrpc_derive::rpc;
-use pallet_contracts_primitives::RentProjection;
+use pallet_contracts_primitives::{Code, ContractInstantiateResult, RentProjection};
 use serde::{Deserialize, Serialize};
 use sp_api::ProvideRuntimeApi;
 use sp_blockchain::HeaderBackend;
@@ -34,6 +34,11 @@ use sp_runtime::{
 };
 use std::convert::TryInto;
 
+pub mod events;
+
+pub use self::events::{
+	ContractEventsIndex, ContractsEvents, ContractsEventsApi, EventFilter, RpcContractEvent,
+};
 pub use self::gen_client::Client as ContractsClient;
 pub use pallet_contracts_rpc_runtime_api::{
 	self as runtime_api, ContractExecResult, ContractsApi as ContractsRuntimeApi,
@@ -42,6 +47,8 @@ pub use pallet_contracts_rpc_runtime_api::{
 const RUNTIME_ERROR: i64 = 1;
 const CONTRACT_DOESNT_EXIST: i64 = 2;
 const CONTRACT_IS_A_TOMBSTONE: i64 = 3;
+const CODE_REJECTED: i64 = 4;
+const EVENTS_INDEX_ERROR: i64 = 5;
 
 /// A rough estimate of how much gas a decent hardware consumes per second,
 /// using native execution.
@@ -85,6 +92,87 @@ pub struct CallRequest<AccountId, Balance> {
 	input_data: Bytes,
 }
 
+/// The code to instantiate a contract from in a dry run.
+#[derive(Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+#[serde(deny_unknown_fields)]
+pub enum RpcCode<Hash> {
+	/// Wasm code that is uploaded as part of the dry run.
+	Upload(Bytes),
+	/// The hash of code that is already on chain.
+	Existing(Hash),
+}
+
+impl<Hash> From<RpcCode<Hash>> for Code<Hash> {
+	fn from(code: RpcCode<Hash>) -> Self {
+		match code {
+			RpcCode::Upload(code) => Code::Upload(code.to_vec()),
+			RpcCode::Existing(code_hash) => Code::Existing(code_hash),
+		}
+	}
+}
+
+/// A struct that encodes RPC parameters required to instantiate a new smart-contract.
+#[derive(Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+#[serde(deny_unknown_fields)]
+pub struct InstantiateRequest<AccountId, Balance, Hash> {
+	origin: AccountId,
+	endowment: Balance,
+	gas_limit: number::NumberOrHex,
+	code: RpcCode<Hash>,
+	data: Bytes,
+}
+
+/// An RPC serializable result of a dry-run instantiation.
+#[derive(Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+#[serde(deny_unknown_fields)]
+pub struct RpcInstantiateResult<AccountId, Balance, BlockNumber> {
+	/// The address the contract is instantiated at.
+	address: AccountId,
+	/// The gas consumed by the instantiation.
+	gas_consumed: u64,
+	/// The smallest endowment that exempts the contract from paying rent.
+	required_endowment: Balance,
+	/// The block the contract is projected to be evicted at, `None` if it is exempted from rent
+	/// or was not instantiated.
+	rent_projection: Option<BlockNumber>,
+	/// The messages printed by the contract, one per line.
+	debug_message: String,
+	/// The output of the constructor.
+	result: RpcContractExecResult,
+}
+
+impl<AccountId, Balance, BlockNumber> From<ContractInstantiateResult<AccountId, Balance, BlockNumber>>
+	for RpcInstantiateResult<AccountId, Balance, BlockNumber>
+{
+	fn from(r: ContractInstantiateResult<AccountId, Balance, BlockNumber>) -> Self {
+		RpcInstantiateResult {
+			address: r.address,
+			gas_consumed: r.gas_consumed,
+			required_endowment: r.required_endowment,
+			rent_projection: match r.rent_projection {
+				Some(RentProjection::EvictionAt(block_num)) => Some(block_num),
+				Some(RentProjection::NoEviction) | None => None,
+			},
+			debug_message: String::from_utf8_lossy(&r.debug_message).into_owned(),
+			result: r.result.into(),
+		}
+	}
+}
+
+/// An RPC serializable result of a dry-run code upload.
+#[derive(Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+#[serde(deny_unknown_fields)]
+pub struct RpcCodeUploadResult<Hash> {
+	/// The hash the code is stored under.
+	code_hash: Hash,
+	/// The gas `put_code` is charged for the code.
+	gas_consumed: u64,
+}
+
 /// An RPC serializable result of contract execution
 #[derive(Serialize, Deserialize)]
 #[serde(deny_unknown_fields)]
@@ -151,6 +239,28 @@ pub trait ContractsApi<BlockHash, BlockNumber, AccountId, Balance> {
 		address: AccountId,
 		at: Option<BlockHash>,
 	) -> Result<Option<BlockNumber>>;
+
+	/// Instantiates a new contract.
+	///
+	/// This is performed locally without submitting any transactions, so it can be used to
+	/// estimate the gas limit and endowment of the instantiation. The code can either be uploaded
+	/// as part of the request or already be on chain.
+	#[rpc(name = "contracts_instantiate")]
+	fn instantiate(
+		&self,
+		instantiate_request: InstantiateRequest<AccountId, Balance, BlockHash>,
+		at: Option<BlockHash>,
+	) -> Result<RpcInstantiateResult<AccountId, Balance, BlockNumber>>;
+
+	/// Validates code as `put_code` does, without submitting any transactions.
+	///
+	/// Returns the hash the code would be stored under and the gas it costs to store it.
+	#[rpc(name = "contracts_uploadCode")]
+	fn upload_code(
+		&self,
+		code: Bytes,
+		at: Option<BlockHash>,
+	) -> Result<RpcCodeUploadResult<BlockHash>>;
 }
 
 /// An implementation of contract specific RPC methods.
@@ -183,6 +293,7 @@ where
 		AccountId,
 		Balance,
 		<<Block as BlockT>::Header as HeaderT>::Number,
+		<Block as BlockT>::Hash,
 	>,
 	AccountId: Codec,
 	Balance: Codec,
@@ -205,24 +316,7 @@ where
 			input_data,
 		} = call_request;
 
-		// Make sure that gas_limit fits into 64 bits.
-		let gas_limit: u64 = gas_limit.try_into().map_err(|_| Error {
-			code: ErrorCode::InvalidParams,
-			message: format!("{:?} doesn't fit in 64 bit unsigned value", gas_limit),
-			data: None,
-		})?;
-
-		let max_gas_limit = 5 * GAS_PER_SECOND;
-		if gas_limit > max_gas_limit {
-			return Err(Error {
-				code: ErrorCode::InvalidParams,
-				message: format!(
-					"Requested gas limit is greater than maximum allowed: {} > {}",
-					gas_limit, max_gas_limit
-				),
-				data: None,
-			});
-		}
+		let gas_limit = limit_gas(gas_limit)?;
 
 		let exec_result = api
 			.call(&at, origin, dest, value, gas_limit, input_data.to_vec())
@@ -271,6 +365,84 @@ where
 			RentProjection::EvictionAt(block_num) => Some(block_num),
 		})
 	}
+
+	fn instantiate(
+		&self,
+		instantiate_request: InstantiateRequest<AccountId, Balance, <Block as BlockT>::Hash>,
+		at: Option<<Block as BlockT>::Hash>,
+	) -> Result<RpcInstantiateResult<
+		AccountId,
+		Balance,
+		<<Block as BlockT>::Header as HeaderT>::Number,
+	>> {
+		let api = self.client.runtime_api();
+		let at = BlockId::hash(at.unwrap_or_else(||
+			// If the block hash is not supplied assume the best block.
+			self.client.info().best_hash));
+
+		let InstantiateRequest {
+			origin,
+			endowment,
+			gas_limit,
+			code,
+			data,
+		} = instantiate_request;
+
+		let gas_limit = limit_gas(gas_limit)?;
+
+		let result = api
+			.instantiate(&at, origin, endowment, gas_limit, code.into(), data.to_vec())
+			.map_err(|e| runtime_error_into_rpc_err(e))?;
+
+		Ok(result.into())
+	}
+
+	fn upload_code(
+		&self,
+		code: Bytes,
+		at: Option<<Block as BlockT>::Hash>,
+	) -> Result<RpcCodeUploadResult<<Block as BlockT>::Hash>> {
+		let api = self.client.runtime_api();
+		let at = BlockId::hash(at.unwrap_or_else(||
+			// If the block hash is not supplied assume the best block.
+			self.client.info().best_hash));
+
+		let result = api
+			.upload_code(&at, code.to_vec())
+			.map_err(|e| runtime_error_into_rpc_err(e))?
+			.map_err(|reason| Error {
+				code: ErrorCode::ServerError(CODE_REJECTED),
+				message: "The code was rejected.".into(),
+				data: Some(String::from_utf8_lossy(&reason).into_owned().into()),
+			})?;
+
+		Ok(RpcCodeUploadResult {
+			code_hash: result.code_hash,
+			gas_consumed: result.gas_consumed,
+		})
+	}
+}
+
+/// Makes sure that `gas_limit` fits into 64 bits and does not exceed the maximum allowed.
+fn limit_gas(gas_limit: number::NumberOrHex) -> Result<u64> {
+	let gas_limit: u64 = gas_limit.try_into().map_err(|_| Error {
+		code: ErrorCode::InvalidParams,
+		message: format!("{:?} doesn't fit in 64 bit unsigned value", gas_limit),
+		data: None,
+	})?;
+
+	let max_gas_limit = 5 * GAS_PER_SECOND;
+	if gas_limit > max_gas_limit {
+		return Err(Error {
+			code: ErrorCode::InvalidParams,
+			message: format!(
+				"Requested gas limit is greater than maximum allowed: {} > {}",
+				gas_limit, max_gas_limit
+			),
+			data: None,
+		});
+	}
+	Ok(gas_limit)
 }
 
 /// Converts a runtime trap into an RPC error.
@@ -312,4 +484,37 @@ mod tests {
 		test(r#"{"success":{"status":5,"data":"0x1234"}}"#);
 		test(r#"{"error":null}"#);
 	}
+
+	#[test]
+	fn instantiate_request_should_serialize_deserialize_properly() {
+		type Req = InstantiateRequest<String, u128, H256>;
+		let req: Req = serde_json::from_str(r#"
+		{
+			"origin": "5CiPPseXPECbkjWCa6MnjNokrgYjMqmKndv2rSnekmSK2DjL",
+			"endowment": 1000,
+			"gasLimit": 1000000000000,
+			"code": { "upload": "0x0061736d" },
+			"data": "0x8c97db39"
+		}
+		"#).unwrap();
+		assert_eq!(req.gas_limit.into_u256(), U256::from(0xe8d4a51000u64));
+		match req.code {
+			RpcCode::Upload(code) => assert_eq!(code.0, b"\0asm".to_vec()),
+			RpcCode::Existing(_) => panic!("expected uploaded code"),
+		}
+
+		let req: Req = serde_json::from_str(r#"
+		{
+			"origin": "5CiPPseXPECbkjWCa6MnjNokrgYjMqmKndv2rSnekmSK2DjL",
+			"endowment": 1000,
+			"gasLimit": 1000000000000,
+			"code": { "existing": "0x1111111111111111111111111111111111111111111111111111111111111111" },
+			"data": "0x"
+		}
+		"#).unwrap();
+		match req.code {
+			RpcCode::Existing(code_hash) => assert_eq!(code_hash, H256::repeat_byte(0x11)),
+			RpcCode::Upload(_) => panic!("expected an existing code hash"),
+		}
+	}
 }
@@ -0,0 +1,138 @@
+// Copyright 2020 Parity Technologies (UK) Ltd.
+// This file is part of Substrate.
+
+// Substrate is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Substrate is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Substrate. If not, see <http://www.gnu.org/licenses/>.
+
+//! A mechanism for runtime authors to augment the functionality of contracts.
+//!
+//! The wasm runtime exposes a fixed set of host functions to contracts. Runtimes that want to
+//! give contracts access to their own pallets implement [`ChainExtension`] and configure it as
+//! `Trait::ChainExtension`. Contracts reach the extension through the
+//! `ext_call_chain_extension(func_id, input_ptr, input_len)` host function.
+//!
+//! The `func_id` selects the functionality and is opaque to this module. Only IDs for which
+//! [`ChainExtension::enabled`] returns `true` can be called; calling any other ID traps the
+//! contract. An extension must charge the weight of the work it performs through
+//! [`Environment::charge_weight`] before doing it.
+
+use crate::{Trait, gas::{Gas, GasMeter, Token}};
+use codec::Decode;
+use frame_support::weights::Weight;
+use sp_runtime::DispatchError;
+use sp_std::prelude::*;
+
+/// Result type of a chain extension call.
+pub type Result<T> = sp_std::result::Result<T, DispatchError>;
+
+/// A chain extension that can be called by contracts.
+pub trait ChainExtension<T: Trait> {
+	/// Returns whether contracts are allowed to call `func_id`.
+	///
+	/// Calls to a `func_id` that is not enabled trap the calling contract.
+	fn enabled(func_id: u32) -> bool;
+
+	/// Execute the function identified by `func_id`.
+	///
+	/// On success the returned bytes are placed in the scratch buffer of the calling contract.
+	/// The `env` must be charged for the work performed before performing it.
+	fn call(func_id: u32, env: &mut Environment<T>) -> Result<Vec<u8>>;
+}
+
+/// The default chain extension which exposes no functionality to contracts.
+impl<T: Trait> ChainExtension<T> for () {
+	fn enabled(_func_id: u32) -> bool {
+		false
+	}
+
+	fn call(_func_id: u32, _env: &mut Environment<T>) -> Result<Vec<u8>> {
+		Err(DispatchError::Other("chain extension is not available"))
+	}
+}
+
+/// The view of the calling contract that is handed to a chain extension.
+pub struct Environment<'a, T: Trait> {
+	address: &'a T::AccountId,
+	caller: &'a T::AccountId,
+	input: &'a [u8],
+	gas_meter: &'a mut GasMeter<T>,
+	out_of_gas: bool,
+}
+
+impl<'a, T: Trait> Environment<'a, T> {
+	pub(crate) fn new(
+		address: &'a T::AccountId,
+		caller: &'a T::AccountId,
+		input: &'a [u8],
+		gas_meter: &'a mut GasMeter<T>,
+	) -> Self {
+		Environment {
+			address,
+			caller,
+			input,
+			gas_meter,
+			out_of_gas: false,
+		}
+	}
+
+	/// The address of the contract calling the extension.
+	pub fn address(&self) -> &T::AccountId {
+		self.address
+	}
+
+	/// The caller of the contract calling the extension.
+	pub fn caller(&self) -> &T::AccountId {
+		self.caller
+	}
+
+	/// The raw input passed by the contract.
+	pub fn input(&self) -> &[u8] {
+		self.input
+	}
+
+	/// Decode the input passed by the contract as `D`.
+	pub fn read_as<D: Decode>(&self) -> Result<D> {
+		D::decode(&mut &self.input[..])
+			.map_err(|_| DispatchError::Other("chain extension input could not be decoded"))
+	}
+
+	/// Charge the given amount of weight from the gas left to the calling contract.
+	///
+	/// Once this fails the contract traps with an out of gas error regardless of what the
+	/// extension returns.
+	pub fn charge_weight(&mut self, weight: Weight) -> Result<()> {
+		if self.gas_meter.charge(&(), ChainExtensionToken(weight)).is_out_of_gas() {
+			self.out_of_gas = true;
+			return Err(DispatchError::Other("ran out of gas in chain extension"));
+		}
+		Ok(())
+	}
+
+	/// Whether the extension tried to charge more gas than was left.
+	pub(crate) fn is_out_of_gas(&self) -> bool {
+		self.out_of_gas
+	}
+}
+
+/// Weight charged by a chain extension.
+#[cfg_attr(test, derive(Debug, PartialEq, Eq))]
+#[derive(Copy, Clone)]
+pub struct ChainExtensionToken(pub Weight);
+
+impl<T: Trait> Token<T> for ChainExtensionToken {
+	type Metadata = ();
+
+	fn calculate_amount(&self, _metadata: &()) -> Gas {
+		self.0
+	}
+}
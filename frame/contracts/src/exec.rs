@@ -20,7 +20,7 @@ use crate::gas::{Gas, GasMeter, Token};
 use crate::rent;
 use crate::storage;
 
-use sp_std::prelude::*;
+use sp_std::{cell::RefCell, prelude::*};
 use sp_runtime::traits::{Bounded, Zero, Convert};
 use frame_support::{
 	storage::unhashed, dispatch::DispatchError,
@@ -165,6 +165,12 @@ pub trait Ext {
 		delta: Vec<StorageKey>,
 	) -> Result<(), &'static str>;
 
+	/// Replace the code of the current contract with the code stored under `hash`.
+	///
+	/// The currently executing code is unaffected; the new code is used from the next call into
+	/// the contract onwards.
+	fn set_code_hash(&mut self, hash: CodeHash<Self::T>) -> Result<(), DispatchError>;
+
 	/// Returns a reference to the account id of the caller.
 	fn caller(&self) -> &AccountIdOf<Self::T>;
 
@@ -215,6 +221,11 @@ pub trait Ext {
 
 	/// Returns the price for the specified amount of weight.
 	fn get_weight_price(&self, weight: Weight) -> BalanceOf<Self::T>;
+
+	/// Appends a message printed by the contract to the debug buffer of a dry run.
+	///
+	/// Returns `false` if the execution is not a dry run and the message was discarded.
+	fn append_debug_buffer(&mut self, msg: &str) -> bool;
 }
 
 /// Loader is a companion of the `Vm` trait. It loads an appropriate abstract
@@ -280,6 +291,8 @@ pub struct ExecutionContext<'a, T: Trait + 'a, V, L> {
 	pub loader: &'a L,
 	pub timestamp: MomentOf<T>,
 	pub block_number: T::BlockNumber,
+	/// Collects the messages printed by contracts when set, i.e. in dry runs.
+	pub debug_message: Option<&'a RefCell<Vec<u8>>>,
 }
 
 impl<'a, T, E, V, L> ExecutionContext<'a, T, V, L>
@@ -303,6 +316,7 @@ where
 			loader: &loader,
 			timestamp: T::Time::now(),
 			block_number: <frame_system::Module<T>>::block_number(),
+			debug_message: None,
 		}
 	}
 
@@ -319,6 +333,7 @@ where
 			loader: self.loader,
 			timestamp: self.timestamp.clone(),
 			block_number: self.block_number.clone(),
+			debug_message: self.debug_message,
 		}
 	}
 
@@ -778,6 +793,20 @@ where
 		result
 	}
 
+	fn set_code_hash(&mut self, hash: CodeHash<Self::T>) -> Result<(), DispatchError> {
+		if !crate::wasm::code_exists::<T>(&hash) {
+			return Err(DispatchError::Other("Code with the given hash is not stored"));
+		}
+		let self_id = self.ctx.self_account.clone();
+		let old_hash = storage::set_code_hash::<T>(&self_id, hash)
+			.map_err(|_| DispatchError::Other("Only alive contracts can replace their code"))?;
+		deposit_event::<Self::T>(
+			vec![],
+			RawEvent::ContractCodeUpdated(self_id, hash, old_hash),
+		);
+		Ok(())
+	}
+
 	fn address(&self) -> &T::AccountId {
 		&self.ctx.self_account
 	}
@@ -846,6 +875,18 @@ where
 	fn get_weight_price(&self, weight: Weight) -> BalanceOf<Self::T> {
 		T::WeightPrice::convert(weight)
 	}
+
+	fn append_debug_buffer(&mut self, msg: &str) -> bool {
+		match self.ctx.debug_message {
+			Some(buffer) => {
+				let mut buffer = buffer.borrow_mut();
+				buffer.extend_from_slice(msg.as_bytes());
+				buffer.push(b'\n');
+				true
+			},
+			None => false,
+		}
+	}
 }
 
 fn deposit_event<T: Trait>(
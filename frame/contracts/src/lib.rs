@@ -59,6 +59,8 @@
 //! ### Dispatchable functions
 //!
 //! * `put_code` - Stores the given binary Wasm code into the chain's storage and returns its `code_hash`.
+//! A deposit proportional to the size of the code is reserved from the sender.
+//! * `remove_code` - Removes code that no contract uses anymore and refunds its deposit.
 //! * `instantiate` - Deploys a new contract from the given `code_hash`, optionally transferring some balance.
 //! This instantiates a new smart contract account and calls its contract deploy handler to
 //! initialize the contract.
@@ -86,6 +88,8 @@ mod exec;
 mod wasm;
 mod rent;
 
+pub mod chain_extension;
+
 #[cfg(test)]
 mod tests;
 
@@ -98,7 +102,7 @@ pub use crate::exec::{ExecResult, ExecReturnValue, ExecError, StatusCode};
 #[cfg(feature = "std")]
 use serde::{Serialize, Deserialize};
 use sp_core::crypto::UncheckedFrom;
-use sp_std::{prelude::*, marker::PhantomData, fmt::Debug};
+use sp_std::{prelude::*, marker::PhantomData, fmt::Debug, cell::RefCell};
 use codec::{Codec, Encode, Decode};
 use sp_runtime::{
 	traits::{
@@ -109,11 +113,15 @@ use sp_runtime::{
 use frame_support::{
 	decl_module, decl_event, decl_storage, decl_error,
 	parameter_types, storage::child::ChildInfo,
-	dispatch::{DispatchResult, DispatchResultWithPostInfo},
-	traits::{OnUnbalanced, Currency, Get, Time, Randomness},
+	dispatch::{DispatchResult, DispatchResultWithPostInfo, DispatchError},
+	traits::{OnUnbalanced, Currency, ReservableCurrency, Get, Time, Randomness},
+	ensure, IterableStorageMap,
 };
 use frame_system::{self as system, ensure_signed, ensure_root};
-use pallet_contracts_primitives::{RentProjection, ContractAccessError};
+use pallet_contracts_primitives::{
+	RentProjection, ContractAccessError, ContractExecResult, Code, ContractInstantiateResult,
+	CodeUploadResult, CodeUploadReturnValue,
+};
 use frame_support::weights::Weight;
 
 pub type CodeHash<T> = <T as frame_system::Trait>::Hash;
@@ -287,6 +295,30 @@ where
 	}
 }
 
+/// The owner of stored code and the deposit reserved from them for storing it.
+#[derive(Encode, Decode, Clone, PartialEq, Eq, RuntimeDebug)]
+pub struct CodeDeposit<AccountId, Balance> {
+	/// The account that uploaded the code.
+	pub owner: AccountId,
+	/// The amount reserved from `owner`, refunded when the code is removed.
+	pub amount: Balance,
+}
+
+// A value placed in storage that represents the current version of the Contracts storage.
+// This value is used by the `on_runtime_upgrade` logic to determine whether we run
+// storage migration logic.
+#[derive(Encode, Decode, Clone, Copy, PartialEq, Eq, RuntimeDebug)]
+enum Releases {
+	V1,
+	V2,
+}
+
+impl Default for Releases {
+	fn default() -> Self {
+		Releases::V1
+	}
+}
+
 pub type BalanceOf<T> =
 	<<T as Trait>::Currency as Currency<<T as frame_system::Trait>::AccountId>>::Balance;
 pub type NegativeImbalanceOf<T> =
@@ -309,6 +341,8 @@ parameter_types! {
 	pub const DefaultMaxDepth: u32 = 32;
 	/// A reasonable default value for [`Trait::MaxValueSize`].
 	pub const DefaultMaxValueSize: u32 = 16_384;
+	/// A reasonable default value for [`Trait::MaxMigratedContracts`].
+	pub const DefaultMaxMigratedContracts: u32 = 256;
 }
 
 pub trait Trait: frame_system::Trait {
@@ -316,7 +350,7 @@ pub trait Trait: frame_system::Trait {
 	type Randomness: Randomness<Self::Hash>;
 
 	/// The currency in which fees are paid and contract balances are held.
-	type Currency: Currency<Self::AccountId>;
+	type Currency: ReservableCurrency<Self::AccountId>;
 
 	/// The overarching event type.
 	type Event: From<Event<Self>> + Into<<Self as frame_system::Trait>::Event>;
@@ -368,9 +402,21 @@ pub trait Trait: frame_system::Trait {
 	/// The maximum size of a storage value in bytes.
 	type MaxValueSize: Get<u32>;
 
+	/// The amount reserved from the uploader of code per byte of the code.
+	type CodeDepositPerByte: Get<BalanceOf<Self>>;
+
+	/// The maximum number of contracts whose code is counted per block while migrating to code
+	/// refcounting.
+	type MaxMigratedContracts: Get<u32>;
+
 	/// Used to answer contracts's queries regarding the current weight price. This is **not**
 	/// used to calculate the actual fee and is only for informational purposes.
 	type WeightPrice: Convert<Weight, BalanceOf<Self>>;
+
+	/// Functionality of the runtime exposed to contracts through `ext_call_chain_extension`.
+	///
+	/// Use `()` to expose nothing.
+	type ChainExtension: chain_extension::ChainExtension<Self>;
 }
 
 /// Simple contract address determiner.
@@ -410,7 +456,15 @@ decl_error! {
 		/// Tombstones don't match.
 		InvalidTombstone,
 		/// An origin TrieId written in the current block.
-		InvalidContractOrigin
+		InvalidContractOrigin,
+		/// No code with an owner is stored under the given hash.
+		CodeNotFound,
+		/// Only the account that uploaded the code can remove it.
+		NotCodeOwner,
+		/// The code is still used by a contract or a tombstone.
+		CodeInUse,
+		/// Code cannot be removed while the code of existing contracts is still being counted.
+		CodeRefcountMigrating,
 	}
 }
 
@@ -459,8 +513,19 @@ decl_module! {
 		/// The maximum size of a storage value in bytes. A reasonable default is 16 KiB.
 		const MaxValueSize: u32 = T::MaxValueSize::get();
 
+		/// The amount reserved from the uploader of code per byte of the code.
+		const CodeDepositPerByte: BalanceOf<T> = T::CodeDepositPerByte::get();
+
 		fn deposit_event() = default;
 
+		fn on_runtime_upgrade() -> Weight {
+			migrate_schedule::<T>() + migrate_to_code_refcount::<T>()
+		}
+
+		fn on_initialize() -> Weight {
+			count_migrated_code::<T>(T::MaxMigratedContracts::get())
+		}
+
 		/// Updates the schedule for metering contracts.
 		///
 		/// The schedule must have a greater version than the stored schedule.
@@ -479,18 +544,38 @@ decl_module! {
 
 		/// Stores the given binary Wasm code into the chain's storage and returns its `codehash`.
 		/// You can instantiate contracts only with stored code.
+		///
+		/// `CodeDepositPerByte` for every byte of the code is reserved from the sender. It is
+		/// refunded when the code is removed with `remove_code`.
 		#[weight = Module::<T>::calc_code_put_costs(&code)]
 		pub fn put_code(
 			origin,
 			code: Vec<u8>
 		) -> DispatchResult {
-			ensure_signed(origin)?;
-			let schedule = <Module<T>>::current_schedule();
-			let result = wasm::save_code::<T>(code, &schedule);
-			if let Ok(code_hash) = result {
-				Self::deposit_event(RawEvent::CodeStored(code_hash));
-			}
-			result.map(|_| ()).map_err(Into::into)
+			let origin = ensure_signed(origin)?;
+			let code_hash = Self::store_code(origin, code)?;
+			Self::deposit_event(RawEvent::CodeStored(code_hash));
+			Ok(())
+		}
+
+		/// Removes code that is no longer used by any contract or tombstone and refunds the
+		/// deposit reserved for it.
+		///
+		/// Only the account that uploaded the code can remove it. Code stored before deposits were
+		/// introduced has no owner and cannot be removed.
+		#[weight = T::DbWeight::get().reads_writes(3, 4)]
+		pub fn remove_code(origin, code_hash: CodeHash<T>) -> DispatchResult {
+			let origin = ensure_signed(origin)?;
+			let deposit = <CodeDeposits<T>>::get(&code_hash).ok_or(Error::<T>::CodeNotFound)?;
+			ensure!(deposit.owner == origin, Error::<T>::NotCodeOwner);
+			ensure!(!CodeRefcountCursor::exists(), Error::<T>::CodeRefcountMigrating);
+			ensure!(<CodeRefcount<T>>::get(&code_hash) == 0, Error::<T>::CodeInUse);
+
+			wasm::remove_code::<T>(&code_hash);
+			<CodeDeposits<T>>::remove(&code_hash);
+			T::Currency::unreserve(&origin, deposit.amount);
+			Self::deposit_event(RawEvent::CodeRemoved(code_hash));
+			Ok(())
 		}
 
 		/// Makes a call to an account, optionally transferring some balance.
@@ -512,7 +597,7 @@ decl_module! {
 			let dest = T::Lookup::lookup(dest)?;
 			let mut gas_meter = GasMeter::new(gas_limit);
 
-			let result = Self::execute_wasm(origin, &mut gas_meter, |ctx, gas_meter| {
+			let result = Self::execute_wasm(origin, &mut gas_meter, None, |ctx, gas_meter| {
 				ctx.call(dest, value, gas_meter, data)
 			});
 			gas_meter.into_dispatch_result(result.map_err(|e| e.reason))
@@ -539,7 +624,7 @@ decl_module! {
 			let origin = ensure_signed(origin)?;
 			let mut gas_meter = GasMeter::new(gas_limit);
 
-			let result = Self::execute_wasm(origin, &mut gas_meter, |ctx, gas_meter| {
+			let result = Self::execute_wasm(origin, &mut gas_meter, None, |ctx, gas_meter| {
 				ctx.instantiate(endowment, gas_meter, &code_hash, data)
 					.map(|(_address, output)| output)
 			});
@@ -595,7 +680,7 @@ impl<T: Trait> Module<T> {
 		input_data: Vec<u8>,
 	) -> ExecResult {
 		let mut gas_meter = GasMeter::new(gas_limit);
-		Self::execute_wasm(origin, &mut gas_meter, |ctx, gas_meter| {
+		Self::execute_wasm(origin, &mut gas_meter, None, |ctx, gas_meter| {
 			ctx.call(dest, value, gas_meter, input_data)
 		})
 	}
@@ -619,6 +704,71 @@ impl<T: Trait> Module<T> {
 	) -> sp_std::result::Result<RentProjection<T::BlockNumber>, ContractAccessError> {
 		rent::compute_rent_projection::<T>(&address)
 	}
+
+	/// Instantiate a contract and report the outcome, collecting the messages it prints.
+	///
+	/// This function is similar to `Self::instantiate`, but can upload the code first and is meant
+	/// to be called as a dry run from a runtime API, which discards all changes afterwards.
+	pub fn bare_instantiate(
+		origin: T::AccountId,
+		endowment: BalanceOf<T>,
+		gas_limit: Gas,
+		code: Code<CodeHash<T>>,
+		data: Vec<u8>,
+	) -> ContractInstantiateResult<T::AccountId, BalanceOf<T>, T::BlockNumber> {
+		let (code_hash, uploaded) = match code {
+			Code::Upload(code) => {
+				let code_hash = T::Hashing::hash(&code);
+				let uploaded = wasm::code_exists::<T>(&code_hash)
+					|| Self::store_code(origin.clone(), code).is_ok();
+				(code_hash, uploaded)
+			},
+			Code::Existing(code_hash) => (code_hash, true),
+		};
+		let address = T::DetermineContractAddress::contract_address_for(&code_hash, &data, &origin);
+		let mut gas_meter = GasMeter::new(gas_limit);
+		let debug_message = RefCell::new(Vec::new());
+
+		let result = if uploaded {
+			Self::execute_wasm(origin, &mut gas_meter, Some(&debug_message), |ctx, gas_meter| {
+				ctx.instantiate(endowment, gas_meter, &code_hash, data)
+					.map(|(_address, output)| output)
+			})
+		} else {
+			Err(ExecError { reason: "code could not be uploaded".into(), buffer: Vec::new() })
+		};
+
+		let instantiated = result.as_ref().map_or(false, |output| output.is_success());
+		let contract = <ContractInfoOf<T>>::get(&address).and_then(|c| c.get_alive());
+		let (required_endowment, rent_projection) = match contract {
+			Some(contract) if instantiated => (
+				rent::rent_exempt_balance::<T>(&contract),
+				rent::compute_rent_projection::<T>(&address).ok(),
+			),
+			_ => (Zero::zero(), None),
+		};
+
+		ContractInstantiateResult {
+			address,
+			gas_consumed: gas_limit - gas_meter.gas_left(),
+			required_endowment,
+			rent_projection,
+			debug_message: debug_message.into_inner(),
+			result: match result {
+				Ok(output) => ContractExecResult::Success { status: output.status, data: output.data },
+				Err(_) => ContractExecResult::Error,
+			},
+		}
+	}
+
+	/// Validate and instrument the given code as `Self::put_code` does, without storing it.
+	pub fn bare_upload_code(code: Vec<u8>) -> CodeUploadResult<CodeHash<T>> {
+		let schedule = <Module<T>>::current_schedule();
+		let gas_consumed = Self::calc_code_put_costs(&code);
+		wasm::validate_code::<T>(&code, &schedule)
+			.map(|code_hash| CodeUploadReturnValue { code_hash, gas_consumed })
+			.map_err(|reason| reason.as_bytes().to_vec())
+	}
 }
 
 impl<T: Trait> Module<T> {
@@ -626,15 +776,38 @@ impl<T: Trait> Module<T> {
 		<Module<T>>::current_schedule().put_code_per_byte_cost.saturating_mul(code.len() as Gas)
 	}
 
+	/// Store the given code, reserving the code deposit from `owner`.
+	///
+	/// Code that is stored already is kept as it is and no deposit is reserved for it.
+	fn store_code(owner: T::AccountId, code: Vec<u8>) -> Result<CodeHash<T>, DispatchError> {
+		let code_hash = T::Hashing::hash(&code);
+		if wasm::code_exists::<T>(&code_hash) {
+			return Ok(code_hash);
+		}
+
+		let amount = T::CodeDepositPerByte::get().saturating_mul((code.len() as u32).into());
+		T::Currency::reserve(&owner, amount)?;
+
+		let schedule = <Module<T>>::current_schedule();
+		if let Err(e) = wasm::save_code::<T>(code, &schedule) {
+			T::Currency::unreserve(&owner, amount);
+			return Err(e.into());
+		}
+		<CodeDeposits<T>>::insert(&code_hash, CodeDeposit { owner, amount });
+		Ok(code_hash)
+	}
+
 	fn execute_wasm(
 		origin: T::AccountId,
 		gas_meter: &mut GasMeter<T>,
+		debug_message: Option<&RefCell<Vec<u8>>>,
 		func: impl FnOnce(&mut ExecutionContext<T, WasmVm, WasmLoader>, &mut GasMeter<T>) -> ExecResult,
 	) -> ExecResult {
 		let cfg = Config::preload();
 		let vm = WasmVm::new(&cfg.schedule);
 		let loader = WasmLoader::new(&cfg.schedule);
 		let mut ctx = ExecutionContext::top_level(origin.clone(), &cfg, &vm, &loader);
+		ctx.debug_message = debug_message;
 		func(&mut ctx, gas_meter)
 	}
 }
@@ -679,6 +852,18 @@ decl_event! {
 
 		/// An event deposited upon execution of a contract from the account.
 		ContractExecution(AccountId, Vec<u8>),
+
+		/// Code with the specified hash has been removed and its deposit refunded.
+		CodeRemoved(Hash),
+
+		/// A contract replaced its code.
+		///
+		/// # Params
+		///
+		/// - `contract`: `AccountId`: Account ID of the contract
+		/// - `new_code_hash`: `Hash`: Code hash the contract now uses
+		/// - `old_code_hash`: `Hash`: Code hash the contract used before
+		ContractCodeUpdated(AccountId, Hash, Hash),
 	}
 }
 
@@ -690,12 +875,116 @@ decl_storage! {
 		pub PristineCode: map hasher(identity) CodeHash<T> => Option<Vec<u8>>;
 		/// A mapping between an original code hash and instrumented wasm code, ready for execution.
 		pub CodeStorage: map hasher(identity) CodeHash<T> => Option<wasm::PrefabWasmModule>;
+		/// The number of contracts and tombstones that use the code with the given hash.
+		///
+		/// Code that is not referenced by any contract or tombstone can be removed.
+		pub CodeRefcount get(fn code_refcount): map hasher(identity) CodeHash<T> => u32;
+		/// The owner of the code with the given hash and the deposit reserved for storing it.
+		pub CodeDeposits get(fn code_deposit):
+			map hasher(identity) CodeHash<T> => Option<CodeDeposit<T::AccountId, BalanceOf<T>>>;
+		/// Tombstones that existed before code was refcounted.
+		///
+		/// Their code is not counted in `CodeRefcount` until they are restored.
+		pub UncountedTombstones: map hasher(twox_64_concat) T::AccountId => bool;
+		/// The storage key of the last contract whose code was counted in `CodeRefcount`, while the
+		/// code of the contracts that existed before code was refcounted is being counted.
+		///
+		/// Contracts are counted in the order of their keys, so the code of contracts with a
+		/// greater key is not counted yet.
+		CodeRefcountCursor: Option<Vec<u8>>;
 		/// The subtrie counter.
 		pub AccountCounter: u64 = 0;
 		/// The code associated with a given account.
 		///
 		/// TWOX-NOTE: SAFE since `AccountId` is a secure hash.
 		pub ContractInfoOf: map hasher(twox_64_concat) T::AccountId => Option<ContractInfo<T>>;
+		/// Storage version of the pallet.
+		///
+		/// New networks start with the last version.
+		StorageVersion build(|_| Releases::V2): Releases;
+	}
+}
+
+/// Add `set_code_hash_cost` to the schedule stored before it was introduced.
+///
+/// The field is the last one of `Schedule`, so its default value is appended to the stored one.
+fn migrate_schedule<T: Trait>() -> Weight {
+	if StorageVersion::get() != Releases::V1 {
+		return 0;
+	}
+
+	let key = CurrentSchedule::hashed_key();
+	if let Some(mut schedule) = frame_support::storage::unhashed::get_raw(&key) {
+		Schedule::default().set_code_hash_cost.encode_to(&mut schedule);
+		frame_support::storage::unhashed::put_raw(&key, &schedule);
+	}
+
+	T::DbWeight::get().reads_writes(2, 1)
+}
+
+/// Start computing `CodeRefcount` for the contracts that existed before code was refcounted.
+///
+/// There may be any number of contracts, so they are counted over the following blocks by
+/// `count_migrated_code`.
+fn migrate_to_code_refcount<T: Trait>() -> Weight {
+	if StorageVersion::get() != Releases::V1 {
+		return 0;
+	}
+
+	CodeRefcountCursor::put(<ContractInfoOf<T>>::final_prefix().to_vec());
+	StorageVersion::put(Releases::V2);
+
+	T::DbWeight::get().reads_writes(1, 2)
+}
+
+/// Count the code of up to `limit` of the contracts that existed before code was refcounted,
+/// continuing after `CodeRefcountCursor`.
+///
+/// Tombstones do not record their code hash, so only alive contracts are counted. Tombstones are
+/// recorded in `UncountedTombstones` instead, and their code is counted when they are restored.
+fn count_migrated_code<T: Trait>(limit: u32) -> Weight {
+	let mut cursor = match CodeRefcountCursor::get() {
+		Some(cursor) => cursor,
+		None => return T::DbWeight::get().reads(1),
+	};
+
+	let prefix = <ContractInfoOf<T>>::final_prefix();
+	let mut contracts: Weight = 0;
+	loop {
+		if contracts == limit as Weight {
+			CodeRefcountCursor::put(cursor);
+			break;
+		}
+		let key = match sp_io::storage::next_key(&cursor).filter(|key| key.starts_with(&prefix)) {
+			Some(key) => key,
+			None => {
+				CodeRefcountCursor::kill();
+				break;
+			}
+		};
+		contracts += 1;
+		// `twox_64_concat` puts the encoded address after the 8 bytes of its hash.
+		let address = T::AccountId::decode(&mut &key[prefix.len() + 8..]);
+		match (address, frame_support::storage::unhashed::get::<ContractInfo<T>>(&key)) {
+			(_, Some(ContractInfo::Alive(info))) =>
+				wasm::increment_code_refcount::<T>(&info.code_hash),
+			(Ok(address), Some(ContractInfo::Tombstone(_))) =>
+				<UncountedTombstones<T>>::insert(&address, true),
+			_ => (),
+		}
+		cursor = key;
+	}
+
+	T::DbWeight::get().reads_writes(contracts + 1, contracts + 1)
+}
+
+/// Whether the code of the contract at `address` is counted in `CodeRefcount`.
+///
+/// This is not yet the case for the contracts that the code refcount migration has yet to reach.
+pub(crate) fn is_code_counted<T: Trait>(address: &T::AccountId) -> bool {
+	match CodeRefcountCursor::get() {
+		Some(cursor) => <ContractInfoOf<T>>::hashed_key_for(address) <= cursor,
+		None => true,
 	}
 }
 
@@ -793,6 +1082,10 @@ pub struct Schedule {
 
 	/// The maximum length of a subject used for PRNG generation.
 	pub max_subject_len: u32,
+
+	/// Cost of replacing the code of a contract: checking that the new code is stored and
+	/// updating the contract and the refcounts of the old and the new code.
+	pub set_code_hash_cost: Gas,
 }
 
 // 500 (2 instructions per nano second on 2GHZ) * 1000x slowdown through wasmi
@@ -824,6 +1117,7 @@ impl Default for Schedule {
 			max_table_size: 16 * 1024,
 			enable_println: false,
 			max_subject_len: 32,
+			set_code_hash_cost: 100 * WASM_INSTRUCTION_COST,
 		}
 	}
 }
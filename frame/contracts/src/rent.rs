@@ -18,7 +18,8 @@
 
 use crate::{
 	AliveContractInfo, BalanceOf, ContractInfo, ContractInfoOf, Module, RawEvent,
-	TombstoneContractInfo, Trait, CodeHash,
+	TombstoneContractInfo, Trait, CodeHash, UncountedTombstones, is_code_counted,
+	wasm::{code_exists, decrement_code_refcount, increment_code_refcount},
 };
 use sp_std::prelude::*;
 use sp_io::hashing::blake2_256;
@@ -229,6 +230,9 @@ fn enact_verdict<T: Trait>(
 		Verdict::Exempt => return Some(ContractInfo::Alive(alive_contract_info)),
 		Verdict::Kill => {
 			<ContractInfoOf<T>>::remove(account);
+			if is_code_counted::<T>(account) {
+				decrement_code_refcount::<T>(&alive_contract_info.code_hash);
+			}
 			child::kill_storage(
 				&alive_contract_info.child_trie_info(),
 			);
@@ -249,6 +253,7 @@ fn enact_verdict<T: Trait>(
 				&child_storage_root[..],
 				alive_contract_info.code_hash,
 			);
+			// The tombstone keeps referencing the code of the contract so that it can be restored.
 			let tombstone_info = ContractInfo::Tombstone(tombstone);
 			<ContractInfoOf<T>>::insert(account, &tombstone_info);
 
@@ -399,6 +404,17 @@ pub fn compute_rent_projection<T: Trait>(
 	))
 }
 
+/// Returns the smallest balance for which the rent deposit offset exempts the given contract from
+/// paying rent. It is never below the subsistence threshold.
+pub fn rent_exempt_balance<T: Trait>(contract: &AliveContractInfo<T>) -> BalanceOf<T> {
+	let storage_size = <BalanceOf<T>>::from(
+		contract.storage_size + T::StorageSizeOffset::get() + contract.empty_pair_count,
+	);
+	storage_size
+		.saturating_mul(T::RentDepositOffset::get())
+		.max(subsistence_threshold::<T>())
+}
+
 /// Restores the destination account using the origin as prototype.
 ///
 /// The restoration will be performed iff:
@@ -429,6 +445,10 @@ pub fn restore_to<T: Trait>(
 		return Err("Origin TrieId written in the current block");
 	}
 
+	if !code_exists::<T>(&code_hash) {
+		return Err("Cannot restore a contract with code that is not stored");
+	}
+
 	let dest_tombstone = <ContractInfoOf<T>>::get(&dest)
 		.and_then(|c| c.get_tombstone())
 		.ok_or("Cannot restore to inexisting or alive contract")?;
@@ -467,7 +487,15 @@ pub fn restore_to<T: Trait>(
 		.map(|(_, value)| value.len() as u32)
 		.sum::<u32>();
 
+	// The code of `dest` is already referenced by its tombstone, unless the tombstone predates
+	// code refcounting.
+	if <UncountedTombstones<T>>::take(&dest) {
+		increment_code_refcount::<T>(&code_hash);
+	}
 	<ContractInfoOf<T>>::remove(&origin);
+	if is_code_counted::<T>(&origin) {
+		decrement_code_refcount::<T>(&origin_contract.code_hash);
+	}
 	<ContractInfoOf<T>>::insert(&dest, ContractInfo::Alive(AliveContractInfo::<T> {
 		trie_id: origin_contract.trie_id,
 		storage_size: origin_contract.storage_size,
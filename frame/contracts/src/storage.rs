@@ -18,7 +18,9 @@
 
 use crate::{
 	exec::{AccountIdOf, StorageKey},
+	wasm::{increment_code_refcount, decrement_code_refcount},
 	AliveContractInfo, BalanceOf, CodeHash, ContractInfo, ContractInfoOf, Trait, TrieId,
+	is_code_counted,
 };
 use sp_std::prelude::*;
 use sp_io::hashing::blake2_256;
@@ -168,6 +170,9 @@ pub fn place_contract<T: Trait>(
 			return Err("Alive contract or tombstone already exists");
 		}
 
+		if is_code_counted::<T>(account) {
+			increment_code_refcount::<T>(&ch);
+		}
 		*maybe_contract_info = Some(
 			AliveContractInfo::<T> {
 				code_hash: ch,
@@ -186,10 +191,34 @@ pub fn place_contract<T: Trait>(
 	})
 }
 
+/// Replaces the code hash of the alive contract specified by `account` ID.
+///
+/// Returns the code hash the contract used before.
+pub fn set_code_hash<T: Trait>(
+	account: &AccountIdOf<T>,
+	new_code_hash: CodeHash<T>,
+) -> Result<CodeHash<T>, ContractAbsentError> {
+	<ContractInfoOf<T>>::mutate(account, |maybe_contract_info| match maybe_contract_info {
+		Some(ContractInfo::Alive(ref mut alive_info)) => {
+			let old_code_hash = sp_std::mem::replace(&mut alive_info.code_hash, new_code_hash);
+			if is_code_counted::<T>(account) {
+				increment_code_refcount::<T>(&new_code_hash);
+				decrement_code_refcount::<T>(&old_code_hash);
+			}
+			Ok(old_code_hash)
+		}
+		_ => Err(ContractAbsentError),
+	})
+}
+
 /// Removes the contract and all the storage associated with it.
 ///
 /// This function doesn't affect the account.
 pub fn destroy_contract<T: Trait>(address: &AccountIdOf<T>, trie_id: &TrieId) {
-	<ContractInfoOf<T>>::remove(address);
+	if let Some(ContractInfo::Alive(info)) = <ContractInfoOf<T>>::take(address) {
+		if is_code_counted::<T>(address) {
+			decrement_code_refcount::<T>(&info.code_hash);
+		}
+	}
 	child::kill_storage(&crate::child_trie_info(&trie_id));
 }
@@ -17,8 +17,12 @@
 use crate::{
 	BalanceOf, ContractAddressFor, ContractInfo, ContractInfoOf, GenesisConfig, Module,
 	RawAliveContractInfo, RawEvent, Trait, TrieId, Schedule, TrieIdGenerator, gas::Gas,
+	CodeRefcount, CodeDeposit, CodeDeposits, CodeStorage, PristineCode, Error, Releases,
+	TombstoneContractInfo, UncountedTombstones, CodeRefcountCursor,
+	StorageVersion, CurrentSchedule, chain_extension::{self, ChainExtension, Environment},
 };
 use assert_matches::assert_matches;
+use pallet_contracts_primitives::{Code, CodeUploadReturnValue, ContractExecResult};
 use hex_literal::*;
 use codec::Encode;
 use sp_runtime::{
@@ -27,7 +31,7 @@ use sp_runtime::{
 	testing::{Header, H256},
 };
 use frame_support::{
-	assert_ok, assert_err_ignore_postinfo, impl_outer_dispatch, impl_outer_event,
+	assert_ok, assert_noop, assert_err_ignore_postinfo, impl_outer_dispatch, impl_outer_event,
 	impl_outer_origin, parameter_types, StorageMap, StorageValue,
 	traits::{Currency, Get},
 	weights::{Weight, PostDispatchInfo},
@@ -92,6 +96,7 @@ pub mod test_utils {
 
 thread_local! {
 	static EXISTENTIAL_DEPOSIT: RefCell<u64> = RefCell::new(0);
+	static CODE_DEPOSIT_PER_BYTE: RefCell<u64> = RefCell::new(0);
 }
 
 pub struct ExistentialDeposit;
@@ -99,6 +104,11 @@ impl Get<u64> for ExistentialDeposit {
 	fn get() -> u64 { EXISTENTIAL_DEPOSIT.with(|v| *v.borrow()) }
 }
 
+pub struct CodeDepositPerByte;
+impl Get<u64> for CodeDepositPerByte {
+	fn get() -> u64 { CODE_DEPOSIT_PER_BYTE.with(|v| *v.borrow()) }
+}
+
 #[derive(Clone, Eq, PartialEq, Debug)]
 pub struct Test;
 parameter_types! {
@@ -157,6 +167,7 @@ parameter_types! {
 	pub const SurchargeReward: u64 = 150;
 	pub const MaxDepth: u32 = 100;
 	pub const MaxValueSize: u32 = 16_384;
+	pub const MaxMigratedContracts: u32 = 2;
 }
 
 parameter_types! {
@@ -185,7 +196,10 @@ impl Trait for Test {
 	type SurchargeReward = SurchargeReward;
 	type MaxDepth = MaxDepth;
 	type MaxValueSize = MaxValueSize;
+	type CodeDepositPerByte = CodeDepositPerByte;
+	type MaxMigratedContracts = MaxMigratedContracts;
 	type WeightPrice = Self;
+	type ChainExtension = TestExtension;
 }
 
 type Balances = pallet_balances::Module<Test>;
@@ -216,6 +230,38 @@ impl TrieIdGenerator<u64> for DummyTrieIdGenerator {
 	}
 }
 
+/// Weight charged by `TestExtension` for a balance query.
+const BALANCE_QUERY_WEIGHT: Weight = 1_000;
+
+/// Chain extension used to test `ext_call_chain_extension`.
+///
+/// - `0`: returns the free balance of the account passed as input.
+/// - `1`: charges more weight than any contract can pay.
+/// - `2`: always fails.
+///
+/// Any other function id is not enabled.
+pub struct TestExtension;
+impl ChainExtension<Test> for TestExtension {
+	fn enabled(func_id: u32) -> bool {
+		func_id <= 2
+	}
+
+	fn call(func_id: u32, env: &mut Environment<Test>) -> chain_extension::Result<Vec<u8>> {
+		match func_id {
+			0 => {
+				env.charge_weight(BALANCE_QUERY_WEIGHT)?;
+				let who: u64 = env.read_as()?;
+				Ok(Balances::free_balance(&who).encode())
+			},
+			1 => {
+				env.charge_weight(Weight::max_value())?;
+				Ok(Vec::new())
+			},
+			_ => Err("test extension failed".into()),
+		}
+	}
+}
+
 const ALICE: u64 = 1;
 const BOB: u64 = 2;
 const CHARLIE: u64 = 3;
@@ -225,11 +271,13 @@ const GAS_LIMIT: Gas = 10_000_000_000;
 
 pub struct ExtBuilder {
 	existential_deposit: u64,
+	code_deposit_per_byte: u64,
 }
 impl Default for ExtBuilder {
 	fn default() -> Self {
 		Self {
 			existential_deposit: 1,
+			code_deposit_per_byte: 0,
 		}
 	}
 }
@@ -238,8 +286,13 @@ impl ExtBuilder {
 		self.existential_deposit = existential_deposit;
 		self
 	}
+	pub fn code_deposit_per_byte(mut self, code_deposit_per_byte: u64) -> Self {
+		self.code_deposit_per_byte = code_deposit_per_byte;
+		self
+	}
 	pub fn set_associated_consts(&self) {
 		EXISTENTIAL_DEPOSIT.with(|v| *v.borrow_mut() = self.existential_deposit);
+		CODE_DEPOSIT_PER_BYTE.with(|v| *v.borrow_mut() = self.code_deposit_per_byte);
 	}
 	pub fn build(self) -> sp_io::TestExternalities {
 		self.set_associated_consts();
@@ -445,6 +498,63 @@ fn instantiate_and_call_and_deposit_event() {
 		});
 }
 
+#[test]
+fn dry_run_instantiate_reports_outcome() {
+	let (wasm, code_hash) = compile_module::<Test>("println_constructor").unwrap();
+
+	ExtBuilder::default()
+		.existential_deposit(50)
+		.build()
+		.execute_with(|| {
+			let _ = Balances::deposit_creating(&ALICE, 1_000_000);
+
+			let result = Contracts::bare_instantiate(
+				ALICE,
+				30_000,
+				GAS_LIMIT,
+				Code::Upload(wasm),
+				vec![],
+			);
+
+			assert_eq!(result.address, BOB);
+			assert!(result.gas_consumed > 0);
+			assert!(result.required_endowment >= 50);
+			assert!(result.rent_projection.is_some());
+			assert_eq!(result.debug_message, b"deploying\n".to_vec());
+			assert_matches!(result.result, ContractExecResult::Success { status: 0, .. });
+			assert!(ContractInfoOf::<Test>::get(BOB).is_some());
+
+			// Instantiating again collides with the existing contract.
+			let result = Contracts::bare_instantiate(
+				ALICE,
+				30_000,
+				GAS_LIMIT,
+				Code::Existing(code_hash.into()),
+				vec![],
+			);
+			assert_eq!(result.required_endowment, 0);
+			assert_eq!(result.rent_projection, None);
+			assert_eq!(result.result, ContractExecResult::Error);
+		});
+}
+
+#[test]
+fn dry_run_upload_code_does_not_store_code() {
+	let (wasm, code_hash) = compile_module::<Test>("return_from_start_fn").unwrap();
+
+	ExtBuilder::default().build().execute_with(|| {
+		assert_eq!(
+			Contracts::bare_upload_code(wasm.clone()),
+			Ok(CodeUploadReturnValue {
+				code_hash: code_hash.into(),
+				gas_consumed: Contracts::calc_code_put_costs(&wasm),
+			}),
+		);
+		assert!(crate::PristineCode::<Test>::get(&code_hash).is_none());
+		assert!(Contracts::bare_upload_code(vec![0, 1, 2, 3]).is_err());
+	});
+}
+
 #[test]
 fn run_out_of_gas() {
 	let (wasm, code_hash) = compile_module::<Test>("run_out_of_gas").unwrap();
@@ -1233,6 +1343,10 @@ fn restoration(test_different_storage: bool, test_restore_to_with_dirty_storage:
 					_ => unreachable!(),
 				}
 			} else {
+				// Pretend `BOB` was evicted before code was refcounted.
+				CodeRefcount::<Test>::remove(set_rent_code_hash);
+				UncountedTombstones::<Test>::insert(BOB, true);
+
 				assert_ok!(perform_the_restoration());
 
 				// Here we expect that the restoration is succeeded. Check that the restoration
@@ -1240,6 +1354,8 @@ fn restoration(test_different_storage: bool, test_restore_to_with_dirty_storage:
 				println!("{:?}", ContractInfoOf::<Test>::get(BOB));
 				let bob_contract = ContractInfoOf::<Test>::get(BOB).unwrap()
 					.get_alive().unwrap();
+				assert_eq!(CodeRefcount::<Test>::get(set_rent_code_hash), 1);
+				assert!(!UncountedTombstones::<Test>::contains_key(BOB));
 				assert_eq!(bob_contract.rent_allowance, 50);
 				assert_eq!(bob_contract.storage_size, 4);
 				assert_eq!(bob_contract.trie_id, django_trie_id);
@@ -1598,3 +1714,242 @@ fn crypto_hashes() {
 			}
 		})
 }
+
+#[test]
+fn chain_extension_works() {
+	let (wasm, code_hash) = compile_module::<Test>("chain_extension").unwrap();
+	let input = |func_id: u32, data: Vec<u8>| {
+		let mut input = func_id.to_le_bytes().to_vec();
+		input.extend(data);
+		input
+	};
+
+	ExtBuilder::default()
+		.existential_deposit(50)
+		.build()
+		.execute_with(|| {
+			let _ = Balances::deposit_creating(&ALICE, 1_000_000);
+			let _ = Balances::deposit_creating(&CHARLIE, 1_234);
+			assert_ok!(Contracts::put_code(Origin::signed(ALICE), wasm));
+			assert_ok!(Contracts::instantiate(
+				Origin::signed(ALICE),
+				100_000,
+				GAS_LIMIT,
+				code_hash.into(),
+				vec![],
+			));
+
+			// The balance query returns its output and charges its weight.
+			let result = Contracts::bare_call(ALICE, BOB, 0, GAS_LIMIT, input(0, CHARLIE.encode()))
+				.unwrap();
+			assert_eq!(result.status, 0);
+			assert_eq!(result.data, 1_234u64.encode());
+
+			// Malformed input is reported to the contract as a failure.
+			let result = Contracts::bare_call(ALICE, BOB, 0, GAS_LIMIT, input(0, vec![1]))
+				.unwrap();
+			assert_eq!(result.status, 1);
+			assert!(result.data.is_empty());
+
+			// Errors of the extension are reported to the contract.
+			let result = Contracts::bare_call(ALICE, BOB, 0, GAS_LIMIT, input(2, vec![]))
+				.unwrap();
+			assert_eq!(result.status, 1);
+
+			// Running out of gas in the extension traps the contract.
+			let result = Contracts::bare_call(ALICE, BOB, 0, GAS_LIMIT, input(1, vec![]));
+			assert_eq!(result.unwrap_err().reason, "ran out of gas during contract execution".into());
+
+			// Function ids that are not enabled trap the contract.
+			let result = Contracts::bare_call(ALICE, BOB, 0, GAS_LIMIT, input(3, vec![]));
+			assert_eq!(result.unwrap_err().reason, "contract trapped during execution".into());
+		});
+}
+
+#[test]
+fn set_code_hash_upgrades_contract() {
+	let (wasm, code_hash) = compile_module::<Test>("set_code_hash").unwrap();
+	let (new_wasm, new_code_hash) = compile_module::<Test>("return_with_data").unwrap();
+
+	ExtBuilder::default()
+		.existential_deposit(50)
+		.build()
+		.execute_with(|| {
+			let _ = Balances::deposit_creating(&ALICE, 1_000_000);
+			assert_ok!(Contracts::put_code(Origin::signed(ALICE), wasm));
+			assert_ok!(Contracts::put_code(Origin::signed(ALICE), new_wasm));
+			assert_ok!(Contracts::instantiate(
+				Origin::signed(ALICE),
+				100_000,
+				GAS_LIMIT,
+				code_hash.into(),
+				vec![],
+			));
+			assert_eq!(CodeRefcount::<Test>::get(code_hash), 1);
+			assert_eq!(CodeRefcount::<Test>::get(new_code_hash), 0);
+
+			// Code that is not stored is rejected.
+			let result = Contracts::bare_call(ALICE, BOB, 0, GAS_LIMIT, H256::repeat_byte(1).encode())
+				.unwrap();
+			assert_eq!(result.status, 1);
+			assert_eq!(crate::storage::code_hash::<Test>(&BOB), Ok(code_hash));
+
+			System::reset_events();
+			assert_ok!(Contracts::call(
+				Origin::signed(ALICE),
+				BOB,
+				0,
+				GAS_LIMIT,
+				new_code_hash.encode(),
+			));
+			assert_eq!(crate::storage::code_hash::<Test>(&BOB), Ok(new_code_hash));
+			assert_eq!(CodeRefcount::<Test>::get(code_hash), 0);
+			assert_eq!(CodeRefcount::<Test>::get(new_code_hash), 1);
+			assert!(System::events().contains(&EventRecord {
+				phase: Phase::Initialization,
+				event: MetaEvent::contracts(
+					RawEvent::ContractCodeUpdated(BOB, new_code_hash, code_hash),
+				),
+				topics: vec![],
+			}));
+
+			// The contract now runs the new code.
+			let result = Contracts::bare_call(ALICE, BOB, 0, GAS_LIMIT, vec![3, 0, 0, 0, 42])
+				.unwrap();
+			assert_eq!(result.status, 3);
+			assert_eq!(result.data, vec![42]);
+		});
+}
+
+#[test]
+fn code_deposit_is_refunded_when_unused_code_is_removed() {
+	let (wasm, code_hash) = compile_module::<Test>("self_destruct").unwrap();
+	let deposit = 2 * wasm.len() as u64;
+
+	ExtBuilder::default()
+		.existential_deposit(50)
+		.code_deposit_per_byte(2)
+		.build()
+		.execute_with(|| {
+			let _ = Balances::deposit_creating(&ALICE, 1_000_000);
+			let _ = Balances::deposit_creating(&CHARLIE, 1_000_000);
+			assert_ok!(Contracts::put_code(Origin::signed(ALICE), wasm.clone()));
+			assert_eq!(Balances::reserved_balance(ALICE), deposit);
+			assert_eq!(
+				CodeDeposits::<Test>::get(code_hash),
+				Some(CodeDeposit { owner: ALICE, amount: deposit }),
+			);
+
+			// Storing the same code again succeeds without changing its owner or deposit.
+			assert_ok!(Contracts::put_code(Origin::signed(CHARLIE), wasm));
+			assert_eq!(Balances::reserved_balance(CHARLIE), 0);
+			assert_eq!(
+				CodeDeposits::<Test>::get(code_hash),
+				Some(CodeDeposit { owner: ALICE, amount: deposit }),
+			);
+
+			assert_ok!(Contracts::instantiate(
+				Origin::signed(ALICE),
+				100_000,
+				GAS_LIMIT,
+				code_hash.into(),
+				vec![],
+			));
+
+			// Only the owner can remove the code and only once no contract uses it.
+			assert_noop!(
+				Contracts::remove_code(Origin::signed(CHARLIE), code_hash),
+				Error::<Test>::NotCodeOwner,
+			);
+			assert_noop!(
+				Contracts::remove_code(Origin::signed(ALICE), code_hash),
+				Error::<Test>::CodeInUse,
+			);
+
+			// Call BOB without input data which triggers termination.
+			assert_ok!(Contracts::call(Origin::signed(ALICE), BOB, 0, GAS_LIMIT, vec![]));
+			assert_eq!(CodeRefcount::<Test>::get(code_hash), 0);
+
+			assert_ok!(Contracts::remove_code(Origin::signed(ALICE), code_hash));
+			assert_eq!(Balances::reserved_balance(ALICE), 0);
+			assert!(!CodeStorage::<Test>::contains_key(code_hash));
+			assert!(!PristineCode::<Test>::contains_key(code_hash));
+			assert!(!CodeDeposits::<Test>::contains_key(code_hash));
+			assert!(System::events().contains(&EventRecord {
+				phase: Phase::Initialization,
+				event: MetaEvent::contracts(RawEvent::CodeRemoved(code_hash)),
+				topics: vec![],
+			}));
+
+			assert_noop!(
+				Contracts::remove_code(Origin::signed(ALICE), code_hash),
+				Error::<Test>::CodeNotFound,
+			);
+		});
+}
+
+#[test]
+fn migration_computes_code_refcount() {
+	let (wasm, code_hash) = compile_module::<Test>("return_from_start_fn").unwrap();
+
+	ExtBuilder::default().build().execute_with(|| {
+		let _ = Balances::deposit_creating(&ALICE, 1_000_000);
+		assert_ok!(Contracts::put_code(Origin::signed(ALICE), wasm));
+		test_utils::place_contract(&BOB, code_hash);
+		test_utils::place_contract(&CHARLIE, code_hash);
+		ContractInfoOf::<Test>::insert(
+			DJANGO,
+			ContractInfo::Tombstone(TombstoneContractInfo::new(&[0u8; 32], code_hash)),
+		);
+
+		// Pretend the contracts were created before code was refcounted.
+		CodeRefcount::<Test>::remove(code_hash);
+		StorageVersion::put(Releases::V1);
+
+		super::migrate_to_code_refcount::<Test>();
+		assert_eq!(StorageVersion::get(), Releases::V2);
+		assert_noop!(
+			Contracts::remove_code(Origin::signed(ALICE), code_hash),
+			Error::<Test>::CodeRefcountMigrating,
+		);
+
+		// A contract placed before the migration reaches it is only counted by the migration.
+		test_utils::place_contract(&5, code_hash);
+		assert_eq!(CodeRefcount::<Test>::get(code_hash), 0);
+
+		// The contracts are counted over the following blocks.
+		super::count_migrated_code::<Test>(2);
+		assert!(CodeRefcountCursor::exists());
+		super::count_migrated_code::<Test>(10);
+		assert!(!CodeRefcountCursor::exists());
+		assert_eq!(CodeRefcount::<Test>::get(code_hash), 3);
+		assert!(UncountedTombstones::<Test>::get(DJANGO));
+		assert!(!UncountedTombstones::<Test>::get(BOB));
+
+		// The migration only runs once.
+		CodeRefcount::<Test>::remove(code_hash);
+		super::migrate_to_code_refcount::<Test>();
+		super::count_migrated_code::<Test>(10);
+		assert_eq!(CodeRefcount::<Test>::get(code_hash), 0);
+	});
+}
+
+#[test]
+fn migration_adds_set_code_hash_cost_to_schedule() {
+	ExtBuilder::default().build().execute_with(|| {
+		let schedule = Schedule { version: 3, ..Default::default() };
+		// Pretend the schedule was stored before it had `set_code_hash_cost`.
+		let mut encoded = schedule.encode();
+		encoded.truncate(encoded.len() - schedule.set_code_hash_cost.encode().len());
+		frame_support::storage::unhashed::put_raw(&CurrentSchedule::hashed_key(), &encoded);
+		StorageVersion::put(Releases::V1);
+
+		super::migrate_schedule::<Test>();
+		assert_eq!(CurrentSchedule::get(), schedule);
+
+		// The migration only runs once.
+		StorageVersion::put(Releases::V2);
+		super::migrate_schedule::<Test>();
+		assert_eq!(CurrentSchedule::get(), schedule);
+	});
+}
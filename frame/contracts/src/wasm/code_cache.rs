@@ -27,7 +27,7 @@
 //! Thus, before executing a contract it should be reinstrument with new schedule.
 
 use crate::wasm::{prepare, runtime::Env, PrefabWasmModule};
-use crate::{CodeHash, CodeStorage, PristineCode, Schedule, Trait};
+use crate::{CodeHash, CodeRefcount, CodeStorage, PristineCode, Schedule, Trait};
 use sp_std::prelude::*;
 use sp_runtime::traits::Hash;
 use frame_support::StorageMap;
@@ -49,6 +49,15 @@ pub fn save<T: Trait>(
 	Ok(code_hash)
 }
 
+/// Instrument the given code without storing it and return the hash it would be stored under.
+pub fn validate<T: Trait>(
+	original_code: &[u8],
+	schedule: &Schedule,
+) -> Result<CodeHash<T>, &'static str> {
+	prepare::prepare_contract::<Env>(original_code, schedule)?;
+	Ok(T::Hashing::hash(original_code))
+}
+
 /// Load code with the given code hash.
 ///
 /// If the module was instrumented with a lower version of schedule than
@@ -73,3 +82,30 @@ pub fn load<T: Trait>(
 	}
 	Ok(prefab_module)
 }
+
+/// Returns whether code with the given code hash is stored.
+pub fn exists<T: Trait>(code_hash: &CodeHash<T>) -> bool {
+	<CodeStorage<T>>::contains_key(code_hash)
+}
+
+/// Remove the code with the given code hash from the storage.
+pub fn remove<T: Trait>(code_hash: &CodeHash<T>) {
+	<CodeStorage<T>>::remove(code_hash);
+	<PristineCode<T>>::remove(code_hash);
+}
+
+/// Record that one more contract uses the code with the given code hash.
+pub fn increment_refcount<T: Trait>(code_hash: &CodeHash<T>) {
+	<CodeRefcount<T>>::mutate(code_hash, |refcount| *refcount = refcount.saturating_add(1));
+}
+
+/// Record that one contract less uses the code with the given code hash.
+///
+/// Returns the number of contracts and tombstones that still use the code.
+pub fn decrement_refcount<T: Trait>(code_hash: &CodeHash<T>) -> u32 {
+	<CodeRefcount<T>>::mutate_exists(code_hash, |maybe_refcount| {
+		let refcount = maybe_refcount.unwrap_or(0).saturating_sub(1);
+		*maybe_refcount = if refcount == 0 { None } else { Some(refcount) };
+		refcount
+	})
+}
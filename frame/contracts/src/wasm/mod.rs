@@ -36,6 +36,11 @@ use self::runtime::{to_execution_result, Runtime};
 use self::code_cache::load as load_code;
 
 pub use self::code_cache::save as save_code;
+pub use self::code_cache::validate as validate_code;
+pub use self::code_cache::{
+	exists as code_exists, remove as remove_code, increment_refcount as increment_code_refcount,
+	decrement_refcount as decrement_code_refcount,
+};
 
 /// A prepared wasm module ready for execution.
 #[derive(Clone, Encode, Decode)]
@@ -207,8 +212,10 @@ mod tests {
 		terminations: Vec<TerminationEntry>,
 		transfers: Vec<TransferEntry>,
 		restores: Vec<RestoreEntry>,
+		code_hashes: Vec<H256>,
 		// (topics, data)
 		events: Vec<(Vec<H256>, Vec<u8>)>,
+		debug_buffer: Vec<u8>,
 		next_account_id: u64,
 
 		/// Runtime storage keys works the following way.
@@ -313,6 +320,10 @@ mod tests {
 			});
 			Ok(())
 		}
+		fn set_code_hash(&mut self, hash: H256) -> Result<(), DispatchError> {
+			self.code_hashes.push(hash);
+			Ok(())
+		}
 		fn caller(&self) -> &u64 {
 			&42
 		}
@@ -373,6 +384,11 @@ mod tests {
 		fn get_weight_price(&self, weight: Weight) -> BalanceOf<Self::T> {
 			BalanceOf::<Self::T>::from(1312_u32).saturating_mul(weight.into())
 		}
+		fn append_debug_buffer(&mut self, msg: &str) -> bool {
+			self.debug_buffer.extend_from_slice(msg.as_bytes());
+			self.debug_buffer.push(b'\n');
+			true
+		}
 	}
 
 	impl Ext for &mut MockExt {
@@ -431,6 +447,9 @@ mod tests {
 				delta,
 			)
 		}
+		fn set_code_hash(&mut self, hash: H256) -> Result<(), DispatchError> {
+			(**self).set_code_hash(hash)
+		}
 		fn caller(&self) -> &u64 {
 			(**self).caller()
 		}
@@ -476,6 +495,9 @@ mod tests {
 		fn get_weight_price(&self, weight: Weight) -> BalanceOf<Self::T> {
 			(**self).get_weight_price(weight)
 		}
+		fn append_debug_buffer(&mut self, msg: &str) -> bool {
+			(**self).append_debug_buffer(msg)
+		}
 	}
 
 	fn execute<E: Ext>(
@@ -719,6 +741,57 @@ mod tests {
 		);
 	}
 
+	const CODE_SET_CODE_HASH: &str = r#"
+(module
+	;; ext_set_code_hash(
+	;;     code_hash_ptr: u32,
+	;;     code_hash_len: u32,
+	;; ) -> u32
+	(import "env" "ext_set_code_hash" (func $ext_set_code_hash (param i32 i32) (result i32)))
+	(import "env" "memory" (memory 1 1))
+	(func $assert (param i32)
+		(block $ok
+			(br_if $ok
+				(get_local 0)
+			)
+			(unreachable)
+		)
+	)
+	(func (export "call")
+		(call $assert
+			(i32.eqz
+				(call $ext_set_code_hash
+					(i32.const 4)  ;; Pointer to the new code hash.
+					(i32.const 32) ;; Length of the new code hash.
+				)
+			)
+		)
+	)
+	(func (export "deploy"))
+
+	;; Hash of the new code.
+	(data (i32.const 4)
+		"\11\11\11\11\11\11\11\11\11\11\11\11\11\11\11\11"
+		"\11\11\11\11\11\11\11\11\11\11\11\11\11\11\11\11"
+	)
+)
+"#;
+
+	#[test]
+	fn contract_set_code_hash() {
+		let mut mock_ext = MockExt::default();
+		let mut gas_meter = GasMeter::new(GAS_LIMIT);
+		execute(
+			CODE_SET_CODE_HASH,
+			vec![],
+			&mut mock_ext,
+			&mut gas_meter,
+		).unwrap();
+
+		assert_eq!(&mock_ext.code_hashes, &[H256::repeat_byte(0x11)]);
+		assert!(GAS_LIMIT - gas_meter.gas_left() > crate::Schedule::default().set_code_hash_cost);
+	}
+
 	const CODE_TRANSFER_LIMITED_GAS: &str = r#"
 (module
 	;; ext_call(
@@ -1522,6 +1595,37 @@ mod tests {
 		);
 	}
 
+	const CODE_PRINTLN: &str = r#"
+(module
+	(import "env" "ext_println" (func $ext_println (param i32 i32)))
+	(import "env" "memory" (memory 1 1))
+
+	(func (export "call")
+		(call $ext_println
+			(i32.const 8) ;; Pointer to the string
+			(i32.const 5) ;; Length of the string
+		)
+	)
+	(func (export "deploy"))
+
+	(data (i32.const 8) "hello")
+)
+"#;
+
+	#[test]
+	fn println_appends_to_debug_buffer() {
+		let mut mock_ext = MockExt::default();
+		let mut gas_meter = GasMeter::new(GAS_LIMIT);
+		let _ = execute(
+			CODE_PRINTLN,
+			vec![],
+			&mut mock_ext,
+			&mut gas_meter
+		).unwrap();
+
+		assert_eq!(mock_ext.debug_buffer, b"hello\n".to_vec());
+	}
+
 	const CODE_DEPOSIT_EVENT: &str = r#"
 (module
 	(import "env" "ext_deposit_event" (func $ext_deposit_event (param i32 i32 i32 i32)))
@@ -17,6 +17,7 @@
 //! Environment definition of the wasm smart-contract runtime.
 
 use crate::{Schedule, Trait, CodeHash, BalanceOf};
+use crate::chain_extension::{ChainExtension, Environment};
 use crate::exec::{
 	Ext, ExecResult, ExecError, ExecReturnValue, StorageKey, TopicOf, STATUS_SUCCESS,
 };
@@ -164,6 +165,8 @@ pub enum RuntimeToken {
 	/// (topic_count, data_bytes): A buffer of the given size is posted as an event indexed with the
 	/// given number of topics.
 	DepositEvent(u32, u32),
+	/// The code of the calling contract is replaced.
+	SetCodeHash,
 }
 
 impl<T: Trait> Token<T> for RuntimeToken {
@@ -201,6 +204,7 @@ impl<T: Trait> Token<T> for RuntimeToken {
 						data_and_topics_cost.checked_add(metadata.event_base_cost)
 					)
 			},
+			SetCodeHash => Some(metadata.set_code_hash_cost),
 		};
 
 		value.unwrap_or_else(|| Bounded::max_value())
@@ -851,6 +855,32 @@ define_env!(Env, <E: Ext>,
 		Err(sp_sandbox::HostError)
 	},
 
+	// Replace the code of the calling contract with the code stored under the given code hash.
+	//
+	// The code that is currently executing keeps running until the call finishes. Every later
+	// call into the contract executes the new code. The address, balance and storage of the
+	// contract are kept.
+	//
+	// If the code was replaced zero is returned. If no code is stored under the given code hash
+	// one is returned and the contract keeps its code.
+	//
+	// `code_hash_ptr`, `code_hash_len` - the pointer and the length of a buffer that encodes
+	// the code hash of the new code.
+	ext_set_code_hash(ctx, code_hash_ptr: u32, code_hash_len: u32) -> u32 => {
+		let code_hash: CodeHash<<E as Ext>::T> =
+			read_sandbox_memory_as(ctx, code_hash_ptr, code_hash_len)?;
+		charge_gas(
+			ctx.gas_meter,
+			ctx.schedule,
+			&mut ctx.special_trap,
+			RuntimeToken::SetCodeHash,
+		)?;
+		match ctx.ext.set_code_hash(code_hash) {
+			Ok(_) => Ok(0),
+			Err(_) => Ok(1),
+		}
+	},
+
 	// Returns the size of the scratch buffer.
 	//
 	// For more details on the scratch buffer see `ext_scratch_read`.
@@ -960,12 +990,15 @@ define_env!(Env, <E: Ext>,
 	},
 
 	// Prints utf8 encoded string from the data buffer.
-	// Only available on `--dev` chains.
+	// Only available on `--dev` chains. In dry runs the string is returned to the caller as part of
+	// the debug message instead.
 	// This function may be removed at any time, superseded by a more general contract debugging feature.
 	ext_println(ctx, str_ptr: u32, str_len: u32) => {
 		let data = read_sandbox_memory(ctx, str_ptr, str_len)?;
 		if let Ok(utf8) = core::str::from_utf8(&data) {
-			sp_runtime::print(utf8);
+			if !ctx.ext.append_debug_buffer(utf8) {
+				sp_runtime::print(utf8);
+			}
 		}
 		Ok(())
 	},
@@ -977,6 +1010,57 @@ define_env!(Env, <E: Ext>,
 		Ok(())
 	},
 
+	// Call into the chain extension configured by the runtime.
+	//
+	// The chain extension decides what `func_id` means and how `input` is interpreted. Only
+	// function IDs enabled by the chain extension can be called. The extension charges the gas
+	// for the work it performs.
+	//
+	// If the call succeeds zero is returned and the scratch buffer contains the output of the
+	// extension. If the extension returns an error one is returned and the scratch buffer is
+	// cleared.
+	//
+	// # Parameters
+	//
+	// - `func_id`: the identifier of the function of the chain extension to call.
+	// - `input_ptr`: the pointer into the linear memory where the input data is placed.
+	// - `input_len`: the length of the input data in bytes.
+	//
+	// # Traps
+	//
+	// - If `func_id` is not enabled by the chain extension.
+	// - If the extension runs out of gas.
+	ext_call_chain_extension(ctx, func_id: u32, input_ptr: u32, input_len: u32) -> u32 => {
+		if !<<E as Ext>::T as Trait>::ChainExtension::enabled(func_id) {
+			return Err(sp_sandbox::HostError);
+		}
+		let input = read_sandbox_memory(ctx, input_ptr, input_len)?;
+		let (result, out_of_gas) = {
+			let mut env = Environment::new(
+				ctx.ext.address(),
+				ctx.ext.caller(),
+				&input,
+				ctx.gas_meter,
+			);
+			let result = <<E as Ext>::T as Trait>::ChainExtension::call(func_id, &mut env);
+			(result, env.is_out_of_gas())
+		};
+		if out_of_gas {
+			ctx.special_trap = Some(SpecialTrap::OutOfGas);
+			return Err(sp_sandbox::HostError);
+		}
+		match result {
+			Ok(output) => {
+				ctx.scratch_buf = output;
+				Ok(0)
+			},
+			Err(_) => {
+				ctx.scratch_buf.clear();
+				Ok(1)
+			},
+		}
+	},
+
 	// Computes the SHA2 256-bit hash on the given input buffer.
 	//
 	// Returns the result directly into the given output buffer.
@@ -0,0 +1,772 @@
+// This file is part of Substrate.
+
+// Copyright (C) 2020 Parity Technologies (UK) Ltd.
+// SPDX-License-Identifier: Apache-2.0
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// 	http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! # Multi-phase Election Module
+//!
+//! An NPoS election module that collects solutions over several phases before an election
+//! result is needed, instead of relying on validators alone.
+//!
+//! ## Overview
+//!
+//! The module implements [`ElectionProvider`] for a consumer such as the staking module, and
+//! reads the candidates and voters from an [`ElectionDataProvider`]. Each round goes through the
+//! following phases, timed backwards from
+//! [`ElectionDataProvider::next_election_prediction`]:
+//!
+//! ```ignore
+//!                           elect()
+//!  +   <--T::SignedPhase-->  +  <--T::UnsignedPhase-->  +
+//!  +-------------------------------------------------------------------+
+//!   Phase::Off        +      Phase::Signed       +     Phase::Unsigned     +
+//! ```
+//!
+//! ### Signed phase
+//!
+//! When the signed phase starts, a snapshot of the targets and voters is taken. Solutions refer to
+//! targets and voters by their index in the snapshot. Any account can then `submit` a
+//! [`CompactAssignments`] solution along with its claimed score, reserving a deposit of
+//! `SignedDepositBase` plus `SignedDepositByte` per encoded byte. Solutions may be at most
+//! `MaxSolutionSize` bytes long. Only the claimed score is looked at upon submission: the best
+//! `MaxSignedSubmissions` solutions are kept, and submitting a better solution to a full queue
+//! ejects, and refunds, the weakest one.
+//!
+//! At the end of the signed phase the queued solutions are checked, best first. The first
+//! feasible one is accepted and its submitter gets their deposit back along with
+//! `SignedRewardBase`, paid from the `RewardSource` account. The deposit of every infeasible
+//! solution checked before it is slashed. The solutions which were not checked are refunded.
+//!
+//! ### Unsigned phase
+//!
+//! During the unsigned phase, the offchain worker of every node mines a solution from the snapshot
+//! and submits it as an unsigned transaction, which is only accepted if it is feasible and better
+//! than the queued solution by at least `SolutionImprovementThreshold`.
+//!
+//! ### Feasibility
+//!
+//! A solution is feasible if it elects exactly `min(desired_targets, targets)` distinct targets,
+//! every voter only distributes its stake among the targets it voted for and all of them are
+//! winners, and its claimed score matches the score computed via
+//! [`sp_npos_elections::evaluate_support`]. Scores are compared with
+//! [`sp_npos_elections::is_score_better`].
+//!
+//! ### Election
+//!
+//! When the consumer calls [`ElectionProvider::elect`], the queued solution is handed out, the
+//! snapshot is removed and the module goes back to [`Phase::Off`]. If no solution was accepted,
+//! `None` is returned and the consumer has to compute a result itself.
+
+#![cfg_attr(not(feature = "std"), no_std)]
+
+#[cfg(test)]
+mod mock;
+#[cfg(test)]
+mod tests;
+
+pub mod unsigned;
+
+use codec::{Encode, Decode};
+use frame_support::{
+	decl_module, decl_event, decl_storage, decl_error, ensure,
+	weights::{Weight, DispatchClass, constants::{WEIGHT_PER_MICROS, WEIGHT_PER_NANOS}},
+	traits::{Currency, ReservableCurrency, OnUnbalanced, Get, ExistenceRequirement},
+	dispatch::DispatchResult,
+};
+use frame_system::{self as system, ensure_signed, ensure_none, offchain::SendTransactionTypes};
+use sp_npos_elections::{
+	generate_compact_solution_type, build_support_map, evaluate_support, is_score_better,
+	assignment_ratio_to_staked, Assignment, ElectionScore, ExtendedBalance, Support, SupportMap,
+	VoteWeight, ElectionDataProvider, ElectionProvider, ProvidedElection,
+};
+use sp_runtime::{
+	PerU16, Perbill, RuntimeDebug, SaturatedConversion,
+	traits::{Saturating, Zero},
+	transaction_validity::{
+		TransactionValidity, ValidTransaction, InvalidTransaction, TransactionSource,
+		TransactionPriority, TransactionValidityError,
+	},
+};
+use sp_std::{prelude::*, collections::{btree_map::BTreeMap, btree_set::BTreeSet}};
+
+pub(crate) const LOG_TARGET: &'static str = "election-multi-phase";
+
+// syntactic sugar for logging.
+macro_rules! log {
+	($level:tt, $patter:expr $(, $values:expr)* $(,)?) => {
+		frame_support::debug::$level!(
+			target: crate::LOG_TARGET,
+			$patter $(, $values)*
+		)
+	};
+}
+
+type BalanceOf<T> =
+	<<T as Trait>::Currency as Currency<<T as frame_system::Trait>::AccountId>>::Balance;
+type NegativeImbalanceOf<T> =
+	<<T as Trait>::Currency as Currency<<T as frame_system::Trait>::AccountId>>::NegativeImbalance;
+
+/// Data type used to index voters in the compact type.
+pub type VoterIndex = u32;
+
+/// Data type used to index targets in the compact type.
+pub type TargetIndex = u16;
+
+/// Accuracy used for the edge weights of submitted solutions.
+pub type SolutionAccuracy = PerU16;
+
+// Note: Maximum number of votes per voter is set here -- 16.
+generate_compact_solution_type!(pub GenericCompactAssignments, 16);
+
+/// The compact solution type used by this module.
+pub type CompactAssignments = GenericCompactAssignments<VoterIndex, TargetIndex, SolutionAccuracy>;
+
+/// A voter of the snapshot: its account, vote weight and the targets it votes for.
+pub type Voter<AccountId> = (AccountId, VoteWeight, Vec<AccountId>);
+
+/// The phase of the current election round.
+#[derive(PartialEq, Eq, Clone, Copy, Encode, Decode, RuntimeDebug)]
+pub enum Phase {
+	/// Nothing is going on.
+	Off,
+	/// Signed solutions are being collected.
+	Signed,
+	/// Unsigned solutions are being collected.
+	Unsigned,
+}
+
+impl Default for Phase {
+	fn default() -> Self {
+		Phase::Off
+	}
+}
+
+impl Phase {
+	/// Whether signed solutions are accepted.
+	pub fn is_signed(&self) -> bool {
+		*self == Phase::Signed
+	}
+
+	/// Whether unsigned solutions are accepted.
+	pub fn is_unsigned(&self) -> bool {
+		*self == Phase::Unsigned
+	}
+
+	/// Whether no election is going on.
+	pub fn is_off(&self) -> bool {
+		*self == Phase::Off
+	}
+}
+
+/// The way a queued solution was submitted.
+#[derive(PartialEq, Eq, Clone, Copy, Encode, Decode, RuntimeDebug)]
+pub enum ElectionCompute {
+	/// Submitted during the signed phase.
+	Signed,
+	/// Submitted during the unsigned phase.
+	Unsigned,
+}
+
+/// A solution as submitted to the chain.
+#[derive(PartialEq, Eq, Clone, Encode, Decode, RuntimeDebug, Default)]
+pub struct RawSolution {
+	/// The indices of the winners in the target snapshot.
+	pub winners: Vec<TargetIndex>,
+	/// The assignments of the voters, by their indices in the snapshots.
+	pub compact: CompactAssignments,
+	/// The claimed score of the solution.
+	pub score: ElectionScore,
+}
+
+/// A signed solution waiting to be checked at the end of the signed phase.
+#[derive(PartialEq, Eq, Clone, Encode, Decode, RuntimeDebug)]
+pub struct SignedSubmission<AccountId, Balance> {
+	/// The submitter.
+	pub who: AccountId,
+	/// The deposit reserved from the submitter.
+	pub deposit: Balance,
+	/// The submitted solution.
+	pub solution: RawSolution,
+}
+
+/// The support of a winner of a checked solution.
+#[derive(PartialEq, Eq, Clone, Encode, Decode, RuntimeDebug, Default)]
+pub struct ReadySupport<AccountId> {
+	/// Total support.
+	pub total: ExtendedBalance,
+	/// Support from voters.
+	pub voters: Vec<(AccountId, ExtendedBalance)>,
+}
+
+/// A solution which passed the feasibility check, ready to be handed out by [`Module::elect`].
+#[derive(PartialEq, Eq, Clone, Encode, Decode, RuntimeDebug)]
+pub struct ReadySolution<AccountId> {
+	/// The winners of the election.
+	pub winners: Vec<AccountId>,
+	/// The support of each winner.
+	pub supports: Vec<(AccountId, ReadySupport<AccountId>)>,
+	/// The score of the solution.
+	pub score: ElectionScore,
+	/// How the solution was submitted.
+	pub compute: ElectionCompute,
+}
+
+pub trait Trait: frame_system::Trait + SendTransactionTypes<Call<Self>> {
+	/// The overarching event type.
+	type Event: From<Event<Self>> + Into<<Self as frame_system::Trait>::Event>;
+
+	/// The currency used for deposits and rewards.
+	type Currency: ReservableCurrency<Self::AccountId>;
+
+	/// Where the candidates and voters of the election come from.
+	type DataProvider: ElectionDataProvider<Self::AccountId, Self::BlockNumber>;
+
+	/// Number of blocks of the signed phase.
+	type SignedPhase: Get<Self::BlockNumber>;
+
+	/// Number of blocks of the unsigned phase, which directly precedes the election.
+	type UnsignedPhase: Get<Self::BlockNumber>;
+
+	/// The maximum number of signed solutions kept at once.
+	type MaxSignedSubmissions: Get<u32>;
+
+	/// The base deposit of a signed solution.
+	type SignedDepositBase: Get<BalanceOf<Self>>;
+
+	/// The deposit of a signed solution per byte of its encoding.
+	type SignedDepositByte: Get<BalanceOf<Self>>;
+
+	/// The reward of the submitter of the accepted signed solution.
+	type SignedRewardBase: Get<BalanceOf<Self>>;
+
+	/// The maximum encoded size, in bytes, of a signed solution.
+	type MaxSolutionSize: Get<u32>;
+
+	/// The account the rewards are paid from, such as the treasury.
+	type RewardSource: Get<Self::AccountId>;
+
+	/// Handler for the slashed deposits of infeasible signed solutions.
+	type Slash: OnUnbalanced<NegativeImbalanceOf<Self>>;
+
+	/// The minimum improvement of the score an unsigned solution needs over the queued one.
+	type SolutionImprovementThreshold: Get<Perbill>;
+
+	/// The base priority of unsigned solutions.
+	type UnsignedPriority: Get<TransactionPriority>;
+}
+
+decl_storage! {
+	trait Store for Module<T: Trait> as ElectionMultiPhase {
+		/// The index of the current election round. Incremented every time a round ends.
+		pub Round get(fn round): u32 = 1;
+
+		/// The phase of the current round.
+		pub CurrentPhase get(fn current_phase): Phase;
+
+		/// Snapshot of the targets of the current round, taken when the round starts.
+		pub SnapshotTargets get(fn snapshot_targets): Option<Vec<T::AccountId>>;
+
+		/// Snapshot of the voters of the current round, taken when the round starts.
+		pub SnapshotVoters get(fn snapshot_voters): Option<Vec<Voter<T::AccountId>>>;
+
+		/// The number of targets to elect in the current round.
+		pub DesiredTargets get(fn desired_targets): u32;
+
+		/// The signed solutions of the current round, sorted by score with the best one last.
+		pub SignedSubmissions get(fn signed_submissions):
+			Vec<SignedSubmission<T::AccountId, BalanceOf<T>>>;
+
+		/// The best checked solution of the current round.
+		pub QueuedSolution get(fn queued_solution): Option<ReadySolution<T::AccountId>>;
+	}
+}
+
+decl_event!(
+	pub enum Event<T> where
+		AccountId = <T as frame_system::Trait>::AccountId,
+		Balance = BalanceOf<T>,
+	{
+		/// The signed phase of the given round has started.
+		SignedPhaseStarted(u32),
+		/// The unsigned phase of the given round has started.
+		UnsignedPhaseStarted(u32),
+		/// A signed solution has been submitted by the given account.
+		SolutionSubmitted(AccountId),
+		/// A solution has been checked and queued.
+		SolutionStored(ElectionCompute),
+		/// The submitter of the accepted signed solution has been rewarded.
+		Rewarded(AccountId, Balance),
+		/// The deposit of an infeasible signed solution has been slashed.
+		Slashed(AccountId, Balance),
+		/// The round has ended, handing out a solution if one was queued.
+		ElectionFinalized(Option<ElectionCompute>),
+	}
+);
+
+decl_error! {
+	pub enum Error for Module<T: Trait> {
+		/// The solution was submitted in the wrong phase.
+		EarlySubmission,
+		/// The signed queue is full and the solution is not better than any queued one.
+		QueueFull,
+		/// The unsigned solution is not sufficiently better than the queued one.
+		WeakSubmission,
+		/// The snapshot of the round is not available.
+		SnapshotUnavailable,
+		/// The number of winners is not the number of desired targets.
+		BogusWinnerCount,
+		/// One of the winners is not in the snapshot, or is listed twice.
+		BogusWinner,
+		/// The compact assignments could not be decoded against the snapshot.
+		BogusCompact,
+		/// A voter is assigned more than once.
+		BogusVoter,
+		/// A voter distributes its stake to a target it did not vote for.
+		BogusNomination,
+		/// A voter distributes its stake to a target which is not a winner.
+		BogusEdge,
+		/// The claimed score does not match the score of the solution.
+		BogusScore,
+		/// The encoded solution is bigger than `MaxSolutionSize`.
+		SolutionTooBig,
+	}
+}
+
+/// Weight functions of the module.
+pub mod weight {
+	use super::*;
+
+	/// The weight of checking the feasibility of `solution`.
+	pub fn feasibility_check<T: Trait>(solution: &RawSolution) -> Weight {
+		T::DbWeight::get().reads(3)
+			.saturating_add(50 * WEIGHT_PER_MICROS)
+			.saturating_add((solution.compact.edge_count() as Weight).saturating_mul(5 * WEIGHT_PER_MICROS))
+	}
+
+	/// The weight of submitting a signed `solution`.
+	///
+	/// The solution is encoded once to compute its deposit, and the whole signed queue of up to
+	/// `MaxSignedSubmissions` solutions of at most `MaxSolutionSize` bytes is decoded and
+	/// encoded again.
+	pub fn submit<T: Trait>(solution: &RawSolution) -> Weight {
+		let solution_items = solution.compact.len() + solution.compact.edge_count() + solution.winners.len();
+		let queue_bytes = (T::MaxSignedSubmissions::get() as Weight)
+			.saturating_mul(T::MaxSolutionSize::get() as Weight);
+		T::DbWeight::get().reads_writes(3, 3)
+			.saturating_add(50 * WEIGHT_PER_MICROS)
+			.saturating_add((solution_items as Weight).saturating_mul(WEIGHT_PER_MICROS))
+			.saturating_add(queue_bytes.saturating_mul(2 * WEIGHT_PER_NANOS))
+	}
+
+	/// The weight of taking a snapshot of `targets` targets and `voters` voters.
+	pub fn create_snapshot<T: Trait>(targets: u32, voters: u32) -> Weight {
+		T::DataProvider::weight_of_data(targets, voters)
+			.saturating_add(T::DbWeight::get().writes(3))
+			.saturating_add(((targets + voters) as Weight).saturating_mul(WEIGHT_PER_MICROS))
+	}
+
+	/// The weight of settling the deposit of a signed submission, slashing or returning it.
+	pub fn settle_submission<T: Trait>() -> Weight {
+		T::DbWeight::get().reads_writes(1, 1)
+	}
+}
+
+decl_module! {
+	pub struct Module<T: Trait> for enum Call where origin: T::Origin {
+		type Error = Error<T>;
+
+		/// Number of blocks of the signed phase.
+		const SignedPhase: T::BlockNumber = T::SignedPhase::get();
+
+		/// Number of blocks of the unsigned phase.
+		const UnsignedPhase: T::BlockNumber = T::UnsignedPhase::get();
+
+		/// The maximum number of signed solutions kept at once.
+		const MaxSignedSubmissions: u32 = T::MaxSignedSubmissions::get();
+
+		/// The base deposit of a signed solution.
+		const SignedDepositBase: BalanceOf<T> = T::SignedDepositBase::get();
+
+		/// The deposit of a signed solution per byte of its encoding.
+		const SignedDepositByte: BalanceOf<T> = T::SignedDepositByte::get();
+
+		/// The reward of the submitter of the accepted signed solution.
+		const SignedRewardBase: BalanceOf<T> = T::SignedRewardBase::get();
+
+		/// The maximum encoded size, in bytes, of a signed solution.
+		const MaxSolutionSize: u32 = T::MaxSolutionSize::get();
+
+		/// The minimum improvement of the score an unsigned solution needs over the queued one.
+		const SolutionImprovementThreshold: Perbill = T::SolutionImprovementThreshold::get();
+
+		fn deposit_event() = default;
+
+		/// Move through the phases of the round, based on the predicted block of the next
+		/// election.
+		///
+		/// Starting a phase takes the snapshot or checks the signed solutions, which is accounted
+		/// for in the returned weight.
+		fn on_initialize(now: T::BlockNumber) -> Weight {
+			let next_election = T::DataProvider::next_election_prediction(now);
+			let remaining = next_election.saturating_sub(now);
+			let unsigned_deadline = T::UnsignedPhase::get();
+			let signed_deadline = T::SignedPhase::get().saturating_add(unsigned_deadline);
+			// `CurrentPhase`, and `Round` for the event of a started phase.
+			let base_weight = T::DbWeight::get().reads(2);
+
+			match Self::current_phase() {
+				Phase::Off if remaining <= signed_deadline && remaining > unsigned_deadline => {
+					let (created, snapshot_weight) = Self::create_snapshot();
+					if created {
+						CurrentPhase::put(Phase::Signed);
+						Self::deposit_event(RawEvent::SignedPhaseStarted(Self::round()));
+					}
+					base_weight
+						.saturating_add(snapshot_weight)
+						.saturating_add(T::DbWeight::get().writes(1))
+				}
+				phase @ Phase::Off | phase @ Phase::Signed
+					if remaining <= unsigned_deadline && !remaining.is_zero() =>
+				{
+					let (started, phase_weight) = match phase {
+						Phase::Signed => (true, Self::finalize_signed_phase().1),
+						_ => Self::create_snapshot(),
+					};
+					if started {
+						CurrentPhase::put(Phase::Unsigned);
+						Self::deposit_event(RawEvent::UnsignedPhaseStarted(Self::round()));
+					}
+					base_weight
+						.saturating_add(phase_weight)
+						.saturating_add(T::DbWeight::get().writes(1))
+				}
+				_ => T::DbWeight::get().reads(1),
+			}
+		}
+
+		/// Mine and submit an unsigned solution during the unsigned phase.
+		fn offchain_worker(now: T::BlockNumber) {
+			if Self::current_phase().is_unsigned() {
+				match unsigned::set_check_offchain_execution_status::<T>(now) {
+					Ok(()) => if let Err(e) = unsigned::mine_and_submit::<T>() {
+						log!(debug, "🗳 Error in unsigned election offchain worker: {:?}", e);
+					},
+					Err(why) => log!(debug, "🗳 skipping unsigned election offchain worker due to [{}]", why),
+				}
+			}
+		}
+
+		/// Submit a solution during the signed phase, reserving a deposit.
+		///
+		/// Only the claimed score is looked at now; the solution is checked at the end of the
+		/// signed phase, where the deposit is returned with a reward if the solution is accepted,
+		/// and slashed if it is found infeasible.
+		///
+		/// The encoded solution must not be bigger than `MaxSolutionSize`.
+		///
+		/// # <weight>
+		/// - O(V + E + W) to encode the solution, where V, E and W are its number of voters, edges
+		///   and winners.
+		/// - O(S * L) to decode and encode the signed queue, where S is `MaxSignedSubmissions` and
+		///   L is `MaxSolutionSize`.
+		/// - DB: 2 reads, 1 to 2 balance writes, 1 write of the signed queue.
+		/// # </weight>
+		#[weight = weight::submit::<T>(&solution)]
+		fn submit(origin, solution: RawSolution) -> DispatchResult {
+			let who = ensure_signed(origin)?;
+			ensure!(
+				solution.encoded_size() as u32 <= T::MaxSolutionSize::get(),
+				Error::<T>::SolutionTooBig,
+			);
+			ensure!(Self::current_phase().is_signed(), Error::<T>::EarlySubmission);
+
+			let mut submissions = Self::signed_submissions();
+			// the index to insert at; all submissions before it are worse.
+			let mut index = submissions
+				.iter()
+				.take_while(|s| is_score_better(solution.score, s.solution.score, Perbill::zero()))
+				.count();
+			let full = submissions.len() as u32 >= T::MaxSignedSubmissions::get();
+			ensure!(!full || index > 0, Error::<T>::QueueFull);
+
+			let deposit = Self::deposit_for(&solution);
+			T::Currency::reserve(&who, deposit)?;
+
+			if full {
+				let weakest = submissions.remove(0);
+				let _ = T::Currency::unreserve(&weakest.who, weakest.deposit);
+				index -= 1;
+			}
+			submissions.insert(index, SignedSubmission { who: who.clone(), deposit, solution });
+			<SignedSubmissions<T>>::put(submissions);
+
+			Self::deposit_event(RawEvent::SolutionSubmitted(who));
+			Ok(())
+		}
+
+		/// Submit a solution during the unsigned phase. Only callable as an unsigned transaction
+		/// from the offchain worker.
+		///
+		/// The solution must be feasible and better than the queued one by
+		/// `SolutionImprovementThreshold`.
+		#[weight = weight::feasibility_check::<T>(&solution) + T::DbWeight::get().writes(1)]
+		fn submit_unsigned(origin, solution: RawSolution) -> DispatchResult {
+			ensure_none(origin)?;
+			Self::unsigned_pre_dispatch_checks(&solution)?;
+
+			let ready = Self::feasibility_check(solution, ElectionCompute::Unsigned)?;
+			<QueuedSolution<T>>::put(ready);
+
+			Self::deposit_event(RawEvent::SolutionStored(ElectionCompute::Unsigned));
+			Ok(())
+		}
+	}
+}
+
+impl<T: Trait> Module<T> {
+	/// The deposit to reserve for a signed `solution`.
+	pub fn deposit_for(solution: &RawSolution) -> BalanceOf<T> {
+		let encoded_len: BalanceOf<T> = (solution.encoded_size() as u32).into();
+		T::SignedDepositBase::get().saturating_add(T::SignedDepositByte::get().saturating_mul(encoded_len))
+	}
+
+	/// Take the snapshot of the round from the data provider, returning whether it was taken and
+	/// the weight of doing so.
+	///
+	/// No snapshot is taken if there are too many targets or voters to be indexed by a solution.
+	/// The round is then skipped, and the consumer computes the result itself.
+	fn create_snapshot() -> (bool, Weight) {
+		let targets = T::DataProvider::targets();
+		let voters = T::DataProvider::voters();
+		let weight = weight::create_snapshot::<T>(targets.len() as u32, voters.len() as u32);
+
+		if targets.len() > TargetIndex::max_value() as usize
+			|| voters.len() > VoterIndex::max_value() as usize
+		{
+			log!(
+				warn,
+				"🗳 Snapshot of {} targets and {} voters is too big; skipping round {}.",
+				targets.len(),
+				voters.len(),
+				Self::round(),
+			);
+			return (false, weight);
+		}
+
+		<SnapshotTargets<T>>::put(targets);
+		<SnapshotVoters<T>>::put(voters);
+		DesiredTargets::put(T::DataProvider::desired_targets());
+		(true, weight)
+	}
+
+	/// Check the signed solutions, best first, and queue the first feasible one.
+	///
+	/// The submitter of the accepted solution is rewarded from `RewardSource`, the deposits of
+	/// the infeasible solutions checked before it are slashed and the remaining deposits are
+	/// returned. Returns whether a solution was accepted, and the weight of doing all this.
+	fn finalize_signed_phase() -> (bool, Weight) {
+		let mut submissions = <SignedSubmissions<T>>::take();
+		let mut accepted = false;
+		let mut weight = T::DbWeight::get().reads_writes(1, 1);
+
+		while let Some(SignedSubmission { who, deposit, solution }) = submissions.pop() {
+			weight = weight
+				.saturating_add(weight::feasibility_check::<T>(&solution))
+				.saturating_add(weight::settle_submission::<T>());
+			match Self::feasibility_check(solution, ElectionCompute::Signed) {
+				Ok(ready) => {
+					<QueuedSolution<T>>::put(ready);
+					let _ = T::Currency::unreserve(&who, deposit);
+					Self::deposit_event(RawEvent::SolutionStored(ElectionCompute::Signed));
+
+					let reward = T::SignedRewardBase::get();
+					let source = T::RewardSource::get();
+					match T::Currency::transfer(&source, &who, reward, ExistenceRequirement::KeepAlive) {
+						Ok(()) => Self::deposit_event(RawEvent::Rewarded(who, reward)),
+						Err(error) => log!(warn, "🗳 Failed to reward {:?}: {:?}", who, error),
+					}
+					// the solution, the reward source and the submitter.
+					weight = weight.saturating_add(T::DbWeight::get().reads_writes(2, 3));
+					accepted = true;
+					break;
+				}
+				Err(error) => {
+					log!(debug, "🗳 Signed solution of {:?} is infeasible: {:?}", who, error);
+					let (imbalance, _) = T::Currency::slash_reserved(&who, deposit);
+					T::Slash::on_unbalanced(imbalance);
+					Self::deposit_event(RawEvent::Slashed(who, deposit));
+				}
+			}
+		}
+
+		// the remaining solutions were never checked.
+		for SignedSubmission { who, deposit, .. } in submissions {
+			let _ = T::Currency::unreserve(&who, deposit);
+			weight = weight.saturating_add(weight::settle_submission::<T>());
+		}
+
+		(accepted, weight)
+	}
+
+	/// The checks an unsigned solution must pass before being checked for feasibility.
+	fn unsigned_pre_dispatch_checks(solution: &RawSolution) -> DispatchResult {
+		ensure!(Self::current_phase().is_unsigned(), Error::<T>::EarlySubmission);
+		ensure!(
+			Self::queued_solution().map_or(true, |queued| is_score_better(
+				solution.score,
+				queued.score,
+				T::SolutionImprovementThreshold::get(),
+			)),
+			Error::<T>::WeakSubmission,
+		);
+		Ok(())
+	}
+
+	/// Check the feasibility of `solution` against the snapshot, see the module documentation.
+	pub fn feasibility_check(
+		solution: RawSolution,
+		compute: ElectionCompute,
+	) -> Result<ReadySolution<T::AccountId>, Error<T>> {
+		let RawSolution { winners, compact, score } = solution;
+		let targets = Self::snapshot_targets().ok_or(Error::<T>::SnapshotUnavailable)?;
+		let voters = Self::snapshot_voters().ok_or(Error::<T>::SnapshotUnavailable)?;
+
+		let desired_winners = Self::desired_targets().min(targets.len() as u32);
+		ensure!(winners.len() as u32 == desired_winners, Error::<T>::BogusWinnerCount);
+
+		let mut winner_indices = winners.clone();
+		winner_indices.sort_unstable();
+		winner_indices.dedup();
+		ensure!(winner_indices.len() == winners.len(), Error::<T>::BogusWinner);
+		let winners = winners.into_iter()
+			.map(|i| targets.get(i as usize).cloned().ok_or(Error::<T>::BogusWinner))
+			.collect::<Result<Vec<T::AccountId>, Error<T>>>()?;
+
+		let voter_at = |i: VoterIndex| voters.get(i as usize).map(|(who, _, _)| who.clone());
+		let target_at = |i: TargetIndex| targets.get(i as usize).cloned();
+		let assignments = compact.into_assignment(voter_at, target_at)
+			.map_err(|_| Error::<T>::BogusCompact)?;
+
+		let votes_of = voters.iter()
+			.map(|(who, weight, votes)| (who.clone(), (*weight, votes)))
+			.collect::<BTreeMap<_, _>>();
+		let mut assigned = BTreeSet::new();
+		for Assignment { who, distribution } in assignments.iter() {
+			ensure!(assigned.insert(who.clone()), Error::<T>::BogusVoter);
+			let (_, votes) = votes_of.get(who).ok_or(Error::<T>::BogusVoter)?;
+			ensure!(
+				distribution.iter().all(|(target, _)| votes.contains(target)),
+				Error::<T>::BogusNomination,
+			);
+		}
+
+		let stake_of = |who: &T::AccountId| -> VoteWeight {
+			votes_of.get(who).map(|(weight, _)| *weight).unwrap_or_default()
+		};
+		let staked = assignment_ratio_to_staked(assignments, stake_of);
+		let (supports, num_error) = build_support_map::<T::AccountId>(&winners, &staked);
+		ensure!(num_error == 0, Error::<T>::BogusEdge);
+		ensure!(evaluate_support(&supports) == score, Error::<T>::BogusScore);
+
+		let supports = supports.into_iter()
+			.map(|(who, Support { total, voters })| (who, ReadySupport { total, voters }))
+			.collect();
+		Ok(ReadySolution { winners, supports, score, compute })
+	}
+
+	/// Remove all data of the current round and start the next one, returning the weight of
+	/// doing so.
+	fn end_round() -> Weight {
+		let refund = <SignedSubmissions<T>>::take();
+		let refunds = refund.len() as Weight;
+		for SignedSubmission { who, deposit, .. } in refund {
+			let _ = T::Currency::unreserve(&who, deposit);
+		}
+		<SnapshotTargets<T>>::kill();
+		<SnapshotVoters<T>>::kill();
+		DesiredTargets::kill();
+		CurrentPhase::put(Phase::Off);
+		Round::mutate(|r| *r += 1);
+		T::DbWeight::get().reads_writes(2, 6)
+			.saturating_add(weight::settle_submission::<T>().saturating_mul(refunds))
+	}
+}
+
+impl<T: Trait> ElectionProvider<T::AccountId> for Module<T> {
+	/// The weight of ending the round, including checking the signed solutions of a signed phase
+	/// cut short, is registered with the system module since the consumer cannot account for it.
+	fn elect() -> Option<ProvidedElection<T::AccountId>> {
+		// `CurrentPhase` and `QueuedSolution`.
+		let mut weight = T::DbWeight::get().reads_writes(2, 1);
+		// a signed phase cut short by an early election still gets its solutions checked.
+		if Self::current_phase().is_signed() {
+			weight = weight.saturating_add(Self::finalize_signed_phase().1);
+		}
+
+		let queued = <QueuedSolution<T>>::take();
+		weight = weight.saturating_add(Self::end_round());
+		Self::deposit_event(RawEvent::ElectionFinalized(queued.as_ref().map(|q| q.compute)));
+		<frame_system::Module<T>>::register_extra_weight_unchecked(weight, DispatchClass::Mandatory);
+
+		queued.map(|ReadySolution { winners, supports, compute, .. }| ProvidedElection {
+			winners,
+			supports: supports.into_iter()
+				.map(|(who, ReadySupport { total, voters })| (who, Support { total, voters }))
+				.collect::<SupportMap<_>>(),
+			signed: compute == ElectionCompute::Signed,
+		})
+	}
+}
+
+#[allow(deprecated)]
+impl<T: Trait> frame_support::unsigned::ValidateUnsigned for Module<T> {
+	type Call = Call<T>;
+	fn validate_unsigned(source: TransactionSource, call: &Self::Call) -> TransactionValidity {
+		if let Call::submit_unsigned(solution) = call {
+			// discard solution not coming from the local OCW.
+			match source {
+				TransactionSource::Local | TransactionSource::InBlock => { /* allowed */ }
+				_ => return InvalidTransaction::Call.into(),
+			}
+
+			if let Err(error) = Self::unsigned_pre_dispatch_checks(solution) {
+				log!(debug, "🗳 validate unsigned pre dispatch checks failed due to {:?}.", error);
+				return InvalidTransaction::Custom(unsigned::INVALID_SOLUTION).into();
+			}
+
+			ValidTransaction::with_tag_prefix("ElectionMultiPhase")
+				// The higher the score[0], the better a solution is.
+				.priority(T::UnsignedPriority::get().saturating_add(solution.score[0].saturated_into()))
+				// One solution per round is enough; a better one replaces it in the pool.
+				.and_provides(Self::round())
+				.longevity(T::UnsignedPhase::get().saturated_into::<u64>())
+				// We don't propagate this. This can never the validated at a remote node.
+				.propagate(false)
+				.build()
+		} else {
+			InvalidTransaction::Call.into()
+		}
+	}
+
+	fn pre_dispatch(call: &Self::Call) -> Result<(), TransactionValidityError> {
+		if let Call::submit_unsigned(solution) = call {
+			// Re-checked here so that a solution that became stale in the pool is not included.
+			Self::unsigned_pre_dispatch_checks(solution)
+				.map_err(|_| InvalidTransaction::Custom(unsigned::INVALID_SOLUTION).into())
+		} else {
+			Err(InvalidTransaction::Call.into())
+		}
+	}
+}
@@ -0,0 +1,217 @@
+// This file is part of Substrate.
+
+// Copyright (C) 2020 Parity Technologies (UK) Ltd.
+// SPDX-License-Identifier: Apache-2.0
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// 	http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Test utilities
+
+use std::cell::RefCell;
+use sp_core::H256;
+use sp_runtime::{
+	Perbill,
+	testing::{Header, TestXt},
+	traits::{BlakeTwo256, IdentityLookup},
+};
+use frame_support::{
+	impl_outer_origin, impl_outer_dispatch, impl_outer_event, parameter_types,
+	traits::OnInitialize,
+	weights::Weight,
+};
+use sp_npos_elections::{ElectionDataProvider, VoteWeight};
+use crate::*;
+
+pub(crate) type AccountId = u64;
+pub(crate) type BlockNumber = u64;
+pub(crate) type Balance = u64;
+
+impl_outer_origin! {
+	pub enum Origin for Test where system = frame_system {}
+}
+
+impl_outer_dispatch! {
+	pub enum Call for Test where origin: Origin {
+		election_multi_phase::ElectionMultiPhase,
+	}
+}
+
+mod election_multi_phase {
+	// Re-export needed for `impl_outer_event!`.
+	pub use super::super::*;
+}
+
+use frame_system as system;
+use pallet_balances as balances;
+
+impl_outer_event! {
+	pub enum MetaEvent for Test {
+		system<T>,
+		balances<T>,
+		election_multi_phase<T>,
+	}
+}
+
+thread_local! {
+	static TARGETS: RefCell<Vec<AccountId>> = RefCell::new(vec![10, 20, 30, 40]);
+	static VOTERS: RefCell<Vec<Voter<AccountId>>> = RefCell::new(vec![
+		(1, 10, vec![10, 20]),
+		(2, 20, vec![30]),
+		(3, 30, vec![40]),
+		(10, 10, vec![10]),
+		(20, 20, vec![20]),
+		(30, 30, vec![30]),
+		(40, 40, vec![40]),
+	]);
+	static DESIRED_TARGETS: RefCell<u32> = RefCell::new(2);
+	static NEXT_ELECTION: RefCell<BlockNumber> = RefCell::new(30);
+}
+
+/// Provides the data of the thread-local election, with the next election at block 30.
+pub struct StakingMock;
+impl ElectionDataProvider<AccountId, BlockNumber> for StakingMock {
+	fn targets() -> Vec<AccountId> {
+		TARGETS.with(|v| v.borrow().clone())
+	}
+	fn voters() -> Vec<(AccountId, VoteWeight, Vec<AccountId>)> {
+		VOTERS.with(|v| v.borrow().clone())
+	}
+	fn desired_targets() -> u32 {
+		DESIRED_TARGETS.with(|v| *v.borrow())
+	}
+	fn next_election_prediction(_: BlockNumber) -> BlockNumber {
+		NEXT_ELECTION.with(|v| *v.borrow())
+	}
+	fn weight_of_data(targets: u32, voters: u32) -> Weight {
+		(targets + voters) as Weight
+	}
+}
+
+pub fn set_next_election(at: BlockNumber) {
+	NEXT_ELECTION.with(|v| *v.borrow_mut() = at);
+}
+
+// Workaround for https://github.com/rust-lang/rust/issues/26925 . Remove when sorted.
+#[derive(Clone, Eq, PartialEq, Debug)]
+pub struct Test;
+
+parameter_types! {
+	pub const BlockHashCount: u64 = 250;
+	pub const MaximumBlockWeight: Weight = 1024;
+	pub const MaximumBlockLength: u32 = 2 * 1024;
+	pub const AvailableBlockRatio: Perbill = Perbill::one();
+}
+impl frame_system::Trait for Test {
+	type BaseCallFilter = ();
+	type Origin = Origin;
+	type Index = u64;
+	type BlockNumber = BlockNumber;
+	type Call = Call;
+	type Hash = H256;
+	type Hashing = BlakeTwo256;
+	type AccountId = AccountId;
+	type Lookup = IdentityLookup<Self::AccountId>;
+	type Header = Header;
+	type Event = MetaEvent;
+	type BlockHashCount = BlockHashCount;
+	type MaximumBlockWeight = MaximumBlockWeight;
+	type DbWeight = ();
+	type BlockExecutionWeight = ();
+	type ExtrinsicBaseWeight = ();
+	type MaximumExtrinsicWeight = MaximumBlockWeight;
+	type AvailableBlockRatio = AvailableBlockRatio;
+	type MaximumBlockLength = MaximumBlockLength;
+	type Version = ();
+	type ModuleToIndex = ();
+	type AccountData = pallet_balances::AccountData<Balance>;
+	type OnNewAccount = ();
+	type OnKilledAccount = ();
+}
+
+parameter_types! {
+	pub const ExistentialDeposit: Balance = 1;
+}
+impl pallet_balances::Trait for Test {
+	type Balance = Balance;
+	type Event = MetaEvent;
+	type DustRemoval = ();
+	type ExistentialDeposit = ExistentialDeposit;
+	type AccountStore = System;
+}
+
+parameter_types! {
+	pub const SignedPhase: BlockNumber = 10;
+	pub const UnsignedPhase: BlockNumber = 5;
+	pub const MaxSignedSubmissions: u32 = 3;
+	pub const SignedDepositBase: Balance = 5;
+	pub const SignedDepositByte: Balance = 0;
+	pub const SignedRewardBase: Balance = 7;
+	pub const MaxSolutionSize: u32 = 256;
+	pub const RewardSource: AccountId = 90;
+	pub const SolutionImprovementThreshold: Perbill = Perbill::zero();
+	pub const UnsignedPriority: u64 = 1 << 20;
+}
+impl Trait for Test {
+	type Event = MetaEvent;
+	type Currency = Balances;
+	type DataProvider = StakingMock;
+	type SignedPhase = SignedPhase;
+	type UnsignedPhase = UnsignedPhase;
+	type MaxSignedSubmissions = MaxSignedSubmissions;
+	type SignedDepositBase = SignedDepositBase;
+	type SignedDepositByte = SignedDepositByte;
+	type SignedRewardBase = SignedRewardBase;
+	type MaxSolutionSize = MaxSolutionSize;
+	type RewardSource = RewardSource;
+	type Slash = ();
+	type SolutionImprovementThreshold = SolutionImprovementThreshold;
+	type UnsignedPriority = UnsignedPriority;
+}
+
+pub type Extrinsic = TestXt<Call, ()>;
+
+impl<LocalCall> frame_system::offchain::SendTransactionTypes<LocalCall> for Test where
+	Call: From<LocalCall>,
+{
+	type OverarchingCall = Call;
+	type Extrinsic = Extrinsic;
+}
+
+pub type System = frame_system::Module<Test>;
+pub type Balances = pallet_balances::Module<Test>;
+pub type ElectionMultiPhase = Module<Test>;
+
+/// Accounts 96 to 99 can submit signed solutions. Rewards are paid from account 90.
+pub fn new_test_ext() -> sp_io::TestExternalities {
+	let mut t = frame_system::GenesisConfig::default().build_storage::<Test>().unwrap();
+	pallet_balances::GenesisConfig::<Test> {
+		balances: vec![(90, 100), (96, 100), (97, 100), (98, 100), (99, 100)],
+	}.assimilate_storage(&mut t).unwrap();
+	let mut ext = sp_io::TestExternalities::new(t);
+	ext.execute_with(|| System::set_block_number(1));
+	ext
+}
+
+pub fn roll_to(n: BlockNumber) {
+	for b in System::block_number() + 1..=n {
+		System::set_block_number(b);
+		ElectionMultiPhase::on_initialize(b);
+	}
+}
+
+pub fn multi_phase_events() -> Vec<RawEvent<AccountId, Balance>> {
+	System::events().into_iter().filter_map(|r| match r.event {
+		MetaEvent::election_multi_phase(inner) => Some(inner),
+		_ => None,
+	}).collect()
+}
@@ -0,0 +1,310 @@
+// This file is part of Substrate.
+
+// Copyright (C) 2020 Parity Technologies (UK) Ltd.
+// SPDX-License-Identifier: Apache-2.0
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// 	http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Tests for the module.
+
+use super::*;
+use mock::*;
+use frame_support::{
+	assert_err, assert_noop, assert_ok, unsigned::ValidateUnsigned,
+	traits::{OffchainWorker, OnInitialize},
+};
+use sp_core::offchain::{
+	OffchainExt, TransactionPoolExt,
+	testing::{TestOffchainExt, TestTransactionPoolExt},
+};
+use sp_runtime::{DispatchError, traits::Dispatchable};
+use substrate_test_utils::assert_eq_uvec;
+
+fn signed_solution(score: ExtendedBalance) -> RawSolution {
+	RawSolution { score: [score, 0, 0], ..Default::default() }
+}
+
+fn queued_scores() -> Vec<ExtendedBalance> {
+	ElectionMultiPhase::signed_submissions().iter().map(|s| s.solution.score[0]).collect()
+}
+
+#[test]
+fn phases_follow_the_election_prediction() {
+	new_test_ext().execute_with(|| {
+		roll_to(14);
+		assert!(ElectionMultiPhase::current_phase().is_off());
+		assert!(ElectionMultiPhase::snapshot_targets().is_none());
+
+		roll_to(15);
+		assert!(ElectionMultiPhase::current_phase().is_signed());
+		assert_eq!(ElectionMultiPhase::snapshot_targets().unwrap(), vec![10, 20, 30, 40]);
+		assert_eq!(ElectionMultiPhase::snapshot_voters().unwrap().len(), 7);
+		assert_eq!(ElectionMultiPhase::desired_targets(), 2);
+
+		roll_to(25);
+		assert!(ElectionMultiPhase::current_phase().is_unsigned());
+
+		// nothing was submitted.
+		assert!(ElectionMultiPhase::elect().is_none());
+		assert!(ElectionMultiPhase::current_phase().is_off());
+		assert!(ElectionMultiPhase::snapshot_targets().is_none());
+		assert_eq!(ElectionMultiPhase::round(), 2);
+
+		assert_eq!(multi_phase_events(), vec![
+			RawEvent::SignedPhaseStarted(1),
+			RawEvent::UnsignedPhaseStarted(1),
+			RawEvent::ElectionFinalized(None),
+		]);
+	})
+}
+
+#[test]
+fn unsigned_phase_can_start_without_signed_phase() {
+	new_test_ext().execute_with(|| {
+		// the next election is predicted within the unsigned phase length.
+		set_next_election(5);
+		roll_to(2);
+		assert!(ElectionMultiPhase::current_phase().is_unsigned());
+		assert!(ElectionMultiPhase::snapshot_voters().is_some());
+	})
+}
+
+#[test]
+fn signed_submissions_are_sorted_and_bounded() {
+	new_test_ext().execute_with(|| {
+		assert_noop!(
+			ElectionMultiPhase::submit(Origin::signed(99), signed_solution(5)),
+			Error::<Test>::EarlySubmission,
+		);
+
+		roll_to(15);
+		assert_ok!(ElectionMultiPhase::submit(Origin::signed(99), signed_solution(5)));
+		assert_ok!(ElectionMultiPhase::submit(Origin::signed(98), signed_solution(10)));
+		assert_ok!(ElectionMultiPhase::submit(Origin::signed(97), signed_solution(1)));
+		assert_eq!(queued_scores(), vec![1, 5, 10]);
+		assert_eq!(Balances::reserved_balance(97), 5);
+
+		// the queue is full and this is not better than anything in it.
+		assert_noop!(
+			ElectionMultiPhase::submit(Origin::signed(96), signed_solution(1)),
+			Error::<Test>::QueueFull,
+		);
+
+		// a better one ejects the weakest.
+		assert_ok!(ElectionMultiPhase::submit(Origin::signed(96), signed_solution(7)));
+		assert_eq!(queued_scores(), vec![5, 7, 10]);
+		assert_eq!(Balances::reserved_balance(96), 5);
+		assert_eq!(Balances::reserved_balance(97), 0);
+		assert_eq!(Balances::free_balance(97), 100);
+	})
+}
+
+#[test]
+fn signed_phase_rewards_best_feasible_and_slashes_infeasible() {
+	new_test_ext().execute_with(|| {
+		roll_to(15);
+		let solution = unsigned::mine_solution::<Test>().unwrap();
+		let mut bogus = solution.clone();
+		bogus.score[0] += 1;
+
+		assert_ok!(ElectionMultiPhase::submit(Origin::signed(99), solution.clone()));
+		assert_ok!(ElectionMultiPhase::submit(Origin::signed(98), bogus));
+		assert_ok!(ElectionMultiPhase::submit(Origin::signed(97), signed_solution(1)));
+
+		roll_to(25);
+		// the best claimed score was infeasible.
+		assert_eq!(Balances::free_balance(98), 95);
+		assert_eq!(Balances::reserved_balance(98), 0);
+		// the next one is accepted, and rewarded from the reward source.
+		assert_eq!(Balances::free_balance(99), 107);
+		assert_eq!(Balances::reserved_balance(99), 0);
+		assert_eq!(Balances::free_balance(90), 93);
+		// the last one is never checked.
+		assert_eq!(Balances::free_balance(97), 100);
+		assert!(ElectionMultiPhase::signed_submissions().is_empty());
+
+		let queued = ElectionMultiPhase::queued_solution().unwrap();
+		assert_eq!(queued.compute, ElectionCompute::Signed);
+		assert_eq!(queued.score, solution.score);
+
+		let election = ElectionMultiPhase::elect().unwrap();
+		assert!(election.signed);
+		assert_eq_uvec!(election.winners, vec![30, 40]);
+		assert_eq!(election.supports.len(), 2);
+		assert!(ElectionMultiPhase::queued_solution().is_none());
+	})
+}
+
+#[test]
+fn signed_solutions_are_bounded_in_size() {
+	new_test_ext().execute_with(|| {
+		roll_to(15);
+		let mut solution = signed_solution(5);
+		solution.compact.votes1 = (0..200).map(|v| (v, 0)).collect();
+		assert!(solution.encoded_size() as u32 > MaxSolutionSize::get());
+		assert_noop!(
+			ElectionMultiPhase::submit(Origin::signed(99), solution),
+			Error::<Test>::SolutionTooBig,
+		);
+	})
+}
+
+#[test]
+fn phases_are_weighed_by_their_work() {
+	new_test_ext().execute_with(|| {
+		roll_to(14);
+		System::set_block_number(15);
+		assert_eq!(ElectionMultiPhase::on_initialize(15), weight::create_snapshot::<Test>(4, 7));
+
+		let solution = unsigned::mine_solution::<Test>().unwrap();
+		let mut bogus = solution.clone();
+		bogus.score[0] += 1;
+		assert_ok!(ElectionMultiPhase::submit(Origin::signed(99), solution.clone()));
+		assert_ok!(ElectionMultiPhase::submit(Origin::signed(98), bogus));
+
+		// both solutions are checked when the signed phase ends.
+		roll_to(24);
+		System::set_block_number(25);
+		assert_eq!(
+			ElectionMultiPhase::on_initialize(25),
+			2 * weight::feasibility_check::<Test>(&solution),
+		);
+	})
+}
+
+#[test]
+fn elect_registers_the_weight_of_checking_a_cut_short_signed_phase() {
+	new_test_ext().execute_with(|| {
+		roll_to(15);
+		let solution = unsigned::mine_solution::<Test>().unwrap();
+		assert_ok!(ElectionMultiPhase::submit(Origin::signed(99), solution.clone()));
+
+		let before = System::block_weight().total();
+		assert!(ElectionMultiPhase::elect().unwrap().signed);
+		assert_eq!(
+			System::block_weight().total(),
+			before + weight::feasibility_check::<Test>(&solution),
+		);
+	})
+}
+
+#[test]
+fn feasibility_check_rejects_bogus_solutions() {
+	new_test_ext().execute_with(|| {
+		roll_to(15);
+		let solution = unsigned::mine_solution::<Test>().unwrap();
+		let check = |s: RawSolution| ElectionMultiPhase::feasibility_check(s, ElectionCompute::Signed)
+			.map_err(DispatchError::from);
+		assert_ok!(check(solution.clone()));
+
+		let mut bogus = solution.clone();
+		bogus.winners.pop();
+		assert_err!(check(bogus), Error::<Test>::BogusWinnerCount);
+
+		let mut bogus = solution.clone();
+		bogus.winners[1] = bogus.winners[0];
+		assert_err!(check(bogus), Error::<Test>::BogusWinner);
+
+		let mut bogus = solution.clone();
+		bogus.winners[1] = 99;
+		assert_err!(check(bogus), Error::<Test>::BogusWinner);
+
+		// voter 2 (index 1) only votes for 30 (index 2); make it back 40 (index 3) instead.
+		let mut bogus = solution.clone();
+		bogus.compact.votes1.iter_mut().find(|(v, _)| *v == 1).unwrap().1 = 3;
+		assert_err!(check(bogus), Error::<Test>::BogusNomination);
+
+		let mut bogus = solution.clone();
+		bogus.score[1] += 1;
+		assert_err!(check(bogus), Error::<Test>::BogusScore);
+	})
+}
+
+#[test]
+fn unsigned_solution_must_be_feasible_and_better() {
+	new_test_ext().execute_with(|| {
+		roll_to(15);
+		let solution = unsigned::mine_solution::<Test>().unwrap();
+		assert_noop!(
+			ElectionMultiPhase::submit_unsigned(Origin::none(), solution.clone()),
+			Error::<Test>::EarlySubmission,
+		);
+
+		roll_to(25);
+		let mut bogus = solution.clone();
+		bogus.score[0] += 1;
+		assert_noop!(
+			ElectionMultiPhase::submit_unsigned(Origin::none(), bogus),
+			Error::<Test>::BogusScore,
+		);
+
+		assert_ok!(ElectionMultiPhase::submit_unsigned(Origin::none(), solution.clone()));
+		assert_eq!(ElectionMultiPhase::queued_solution().unwrap().compute, ElectionCompute::Unsigned);
+
+		// not better than the queued one.
+		assert_noop!(
+			ElectionMultiPhase::submit_unsigned(Origin::none(), solution.clone()),
+			Error::<Test>::WeakSubmission,
+		);
+
+		let election = ElectionMultiPhase::elect().unwrap();
+		assert!(!election.signed);
+		assert_eq!(multi_phase_events().last(), Some(&RawEvent::ElectionFinalized(Some(ElectionCompute::Unsigned))));
+	})
+}
+
+#[test]
+fn validate_unsigned_only_accepts_local_solutions() {
+	new_test_ext().execute_with(|| {
+		roll_to(25);
+		let solution = unsigned::mine_solution::<Test>().unwrap();
+		let call = crate::Call::submit_unsigned(solution);
+
+		assert!(<ElectionMultiPhase as ValidateUnsigned>::validate_unsigned(
+			TransactionSource::External,
+			&call,
+		).is_err());
+		assert!(<ElectionMultiPhase as ValidateUnsigned>::validate_unsigned(
+			TransactionSource::Local,
+			&call,
+		).is_ok());
+	})
+}
+
+#[test]
+fn offchain_worker_submits_mined_solution() {
+	let mut ext = new_test_ext();
+	let (offchain, _) = TestOffchainExt::new();
+	let (pool, pool_state) = TestTransactionPoolExt::new();
+	ext.register_extension(OffchainExt::new(offchain));
+	ext.register_extension(TransactionPoolExt::new(pool));
+
+	ext.execute_with(|| {
+		roll_to(15);
+		// not in the unsigned phase.
+		ElectionMultiPhase::offchain_worker(15);
+		assert!(pool_state.read().transactions.is_empty());
+
+		roll_to(25);
+		ElectionMultiPhase::offchain_worker(25);
+		assert_eq!(pool_state.read().transactions.len(), 1);
+		// throttled.
+		ElectionMultiPhase::offchain_worker(26);
+		assert_eq!(pool_state.read().transactions.len(), 1);
+
+		let encoded = pool_state.write().transactions.pop().unwrap();
+		let extrinsic: Extrinsic = Decode::decode(&mut &*encoded).unwrap();
+		assert_ok!(extrinsic.call.dispatch(Origin::none()));
+		assert!(ElectionMultiPhase::queued_solution().is_some());
+	})
+}
@@ -0,0 +1,153 @@
+// This file is part of Substrate.
+
+// Copyright (C) 2020 Parity Technologies (UK) Ltd.
+// SPDX-License-Identifier: Apache-2.0
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// 	http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Helpers for the offchain worker mining unsigned solutions.
+
+use crate::{
+	Call, CompactAssignments, Module, RawSolution, SolutionAccuracy, TargetIndex, Trait,
+	VoterIndex,
+};
+use frame_system::offchain::SubmitTransaction;
+use sp_npos_elections::{
+	seq_phragmen, build_support_map, evaluate_support, reduce, is_score_better,
+	assignment_ratio_to_staked, assignment_staked_to_ratio_normalized, to_without_backing,
+	ElectionResult, VoteWeight,
+};
+use sp_runtime::{RuntimeDebug, offchain::storage::StorageValueRef};
+use frame_support::traits::Get;
+use sp_std::{convert::TryInto, collections::btree_map::BTreeMap, prelude::*};
+
+/// Storage key used to store the persistent offchain worker status.
+pub(crate) const OFFCHAIN_HEAD_DB: &[u8] = b"parity/multi-phase-unsigned-election/";
+/// The repeat threshold of the offchain worker. This means we won't run the offchain worker twice
+/// within a window of 5 blocks.
+pub(crate) const OFFCHAIN_REPEAT: u32 = 5;
+/// The custom `InvalidTransaction` code of an unsigned solution failing the pre-dispatch checks.
+pub(crate) const INVALID_SOLUTION: u8 = 0;
+
+/// Error types related to mining unsigned solutions.
+#[derive(RuntimeDebug, PartialEq)]
+pub enum MinerError {
+	/// The snapshot is not available.
+	SnapshotUnavailable,
+	/// The election returned no result.
+	ElectionFailed,
+	/// Error from npos-election crate. This usually relates to compact operation.
+	InternalElectionError(sp_npos_elections::Error),
+	/// One of the winners could not be indexed.
+	InvalidWinner,
+	/// The mined solution is not better than the queued one.
+	WeakSolution,
+	/// Submission to the transaction pool failed.
+	PoolSubmissionFailed,
+}
+
+impl From<sp_npos_elections::Error> for MinerError {
+	fn from(e: sp_npos_elections::Error) -> Self {
+		Self::InternalElectionError(e)
+	}
+}
+
+/// Checks if an execution of the offchain worker is permitted at the given block number, or not.
+///
+/// This makes sure that we don't run on previous blocks in case of a re-org, and we don't run
+/// twice within a window of length [`OFFCHAIN_REPEAT`].
+pub(crate) fn set_check_offchain_execution_status<T: Trait>(
+	now: T::BlockNumber,
+) -> Result<(), &'static str> {
+	let storage = StorageValueRef::persistent(&OFFCHAIN_HEAD_DB);
+	let threshold = T::BlockNumber::from(OFFCHAIN_REPEAT);
+
+	let mutate_stat =
+		storage.mutate::<_, &'static str, _>(|maybe_head: Option<Option<T::BlockNumber>>| {
+			match maybe_head {
+				Some(Some(head)) if now < head => Err("fork."),
+				Some(Some(head)) if now <= head + threshold => Err("recently executed."),
+				// we can run (again) now. Write the new head.
+				_ => Ok(now),
+			}
+		});
+
+	match mutate_stat {
+		Ok(Ok(_)) => Ok(()),
+		Ok(Err(_)) => Err("failed to write to offchain db."),
+		Err(why) => Err(why),
+	}
+}
+
+/// Mine a solution and submit it as an unsigned transaction, if it beats the queued one.
+pub(crate) fn mine_and_submit<T: Trait>() -> Result<(), MinerError> {
+	let solution = mine_solution::<T>()?;
+
+	let improves = <Module<T>>::queued_solution().map_or(true, |queued| is_score_better(
+		solution.score,
+		queued.score,
+		T::SolutionImprovementThreshold::get(),
+	));
+	if !improves {
+		return Err(MinerError::WeakSolution);
+	}
+
+	let call = Call::submit_unsigned(solution).into();
+	SubmitTransaction::<T, Call<T>>::submit_unsigned_transaction(call)
+		.map_err(|_| MinerError::PoolSubmissionFailed)
+}
+
+/// Run the phragmen election over the snapshot and turn the result into a reduced, compact
+/// solution with its score.
+pub fn mine_solution<T: Trait>() -> Result<RawSolution, MinerError> {
+	let targets = <Module<T>>::snapshot_targets().ok_or(MinerError::SnapshotUnavailable)?;
+	let voters = <Module<T>>::snapshot_voters().ok_or(MinerError::SnapshotUnavailable)?;
+	let desired_targets = <Module<T>>::desired_targets() as usize;
+
+	let ElectionResult { winners, assignments } = seq_phragmen::<_, SolutionAccuracy>(
+		desired_targets,
+		desired_targets.min(targets.len()).max(1),
+		targets.clone(),
+		voters.clone(),
+	).ok_or(MinerError::ElectionFailed)?;
+	let winners = to_without_backing(winners);
+
+	// helper closures.
+	let stakes = voters.iter().map(|(who, stake, _)| (who.clone(), *stake)).collect::<BTreeMap<_, _>>();
+	let stake_of = |who: &T::AccountId| -> VoteWeight { stakes.get(who).cloned().unwrap_or_default() };
+	let voter_index = |who: &T::AccountId| -> Option<VoterIndex> {
+		voters.iter().position(|(v, _, _)| v == who).and_then(|i| i.try_into().ok())
+	};
+	let target_index = |who: &T::AccountId| -> Option<TargetIndex> {
+		targets.iter().position(|t| t == who).and_then(|i| i.try_into().ok())
+	};
+
+	// reduce the solution, and convert it back to ratios, which take less space.
+	let mut staked = assignment_ratio_to_staked(assignments, &stake_of);
+	reduce(&mut staked);
+	let assignments = assignment_staked_to_ratio_normalized::<_, SolutionAccuracy>(staked)?;
+
+	// the score must be computed the same way the chain does, from the final ratios.
+	let score = {
+		let staked = assignment_ratio_to_staked(assignments.clone(), &stake_of);
+		let (supports, _) = build_support_map::<T::AccountId>(&winners, &staked);
+		evaluate_support::<T::AccountId>(&supports)
+	};
+
+	let compact = CompactAssignments::from_assignment(assignments, voter_index, target_index)?;
+	let winners = winners.iter()
+		.map(|w| target_index(w).ok_or(MinerError::InvalidWinner))
+		.collect::<Result<Vec<TargetIndex>, MinerError>>()?;
+
+	Ok(RawSolution { winners, compact, score })
+}
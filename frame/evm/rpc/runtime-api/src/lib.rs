@@ -0,0 +1,57 @@
+// This file is part of Substrate.
+
+// Copyright (C) 2020 Parity Technologies (UK) Ltd.
+// SPDX-License-Identifier: Apache-2.0
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// 	http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Runtime API definition required by EVM RPC extensions.
+//!
+//! This API should be imported and implemented by the runtime,
+//! of a node that wants to use the custom RPC extension
+//! adding EVM tracing methods.
+
+#![cfg_attr(not(feature = "std"), no_std)]
+
+use sp_core::{H160, U256};
+use sp_runtime::{DispatchError, traits::Block as BlockT};
+use sp_std::vec::Vec;
+
+pub use pallet_evm::{TraceAction, TraceResult};
+
+sp_api::decl_runtime_apis! {
+	/// The API to replay EVM operations with a step tracer.
+	pub trait EvmApi {
+		/// Replay `action` from `source` against the state of this block without applying it.
+		///
+		/// See the EVM module's `trace` function for more details.
+		fn trace(
+			source: H160,
+			action: TraceAction,
+			value: U256,
+			gas_limit: u32,
+			gas_price: U256,
+		) -> Result<TraceResult, DispatchError>;
+
+		/// Replay the EVM extrinsic at `index` of a block with the given `header` and
+		/// `extrinsics`.
+		///
+		/// Must be called at the parent of that block. The extrinsics before `index` are applied
+		/// first so that the traced one sees the state it was originally executed against.
+		fn trace_extrinsic(
+			header: &<Block as BlockT>::Header,
+			extrinsics: Vec<<Block as BlockT>::Extrinsic>,
+			index: u32,
+		) -> Result<TraceResult, DispatchError>;
+	}
+}
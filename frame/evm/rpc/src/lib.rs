@@ -0,0 +1,420 @@
+// This file is part of Substrate.
+
+// Copyright (C) 2020 Parity Technologies (UK) Ltd.
+// SPDX-License-Identifier: Apache-2.0
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// 	http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Node-specific RPC methods for tracing EVM executions.
+
+use std::sync::Arc;
+
+use jsonrpc_core::{Error, ErrorCode, Result};
+use jsonrpc_derive::rpc;
+use pallet_evm::{FailureReason, StorageWrite, TraceStep, decode_revert_message};
+use sc_client_api::BlockBackend;
+use serde::{Deserialize, Serialize};
+use sp_api::ProvideRuntimeApi;
+use sp_blockchain::HeaderBackend;
+use sp_core::{Bytes, H160, H256, U256};
+use sp_runtime::{
+	generic::BlockId,
+	traits::{Block as BlockT, Header as HeaderT},
+};
+
+pub use self::gen_client::Client as EvmClient;
+pub use pallet_evm_rpc_runtime_api::{
+	self as runtime_api, EvmApi as EvmRuntimeApi, TraceAction, TraceResult,
+};
+
+const RUNTIME_ERROR: i64 = 1;
+const TRACE_ERROR: i64 = 2;
+const BLOCK_NOT_FOUND: i64 = 3;
+
+/// A struct that encodes RPC parameters required to trace an EVM call or create.
+#[derive(Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+#[serde(deny_unknown_fields)]
+pub struct TraceCallRequest {
+	from: H160,
+	/// Address to call. A contract is created when it is omitted.
+	to: Option<H160>,
+	/// Call data, or init code for creates.
+	data: Option<Bytes>,
+	value: Option<U256>,
+	gas: u32,
+	gas_price: Option<U256>,
+	/// Salt for a `CREATE2` contract creation.
+	salt: Option<H256>,
+}
+
+/// Memory changed by a traced step.
+#[derive(Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RpcMemoryDiff {
+	offset: u32,
+	data: Bytes,
+}
+
+/// A traced step, in the shape of `debug_traceTransaction` struct logs.
+#[derive(Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RpcTraceStep {
+	pc: u32,
+	op: String,
+	gas: U256,
+	gas_cost: U256,
+	depth: u32,
+	stack: Vec<H256>,
+	memory: Vec<RpcMemoryDiff>,
+	storage: Option<StorageWrite>,
+}
+
+impl From<TraceStep> for RpcTraceStep {
+	fn from(step: TraceStep) -> Self {
+		RpcTraceStep {
+			pc: step.pc,
+			op: opcode_name(step.op),
+			gas: step.gas,
+			gas_cost: step.gas_cost,
+			depth: step.depth,
+			stack: step.stack,
+			memory: step.memory.into_iter()
+				.map(|diff| RpcMemoryDiff { offset: diff.offset, data: diff.data.into() })
+				.collect(),
+			storage: step.storage,
+		}
+	}
+}
+
+/// An RPC serializable result of a traced EVM execution.
+#[derive(Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RpcTraceResult {
+	/// Address of the contract, for creates.
+	created: Option<H160>,
+	failed: bool,
+	/// Name of the EVM error, if the execution failed without reverting.
+	error: Option<String>,
+	/// Decoded `Error(string)` message, if the execution reverted with one.
+	revert_reason: Option<String>,
+	return_value: Bytes,
+	gas: U256,
+	struct_logs: Vec<RpcTraceStep>,
+}
+
+impl From<TraceResult> for RpcTraceResult {
+	fn from(r: TraceResult) -> Self {
+		let (error, revert_reason) = match &r.failure {
+			None => (None, None),
+			Some(FailureReason::Revert(data)) => (
+				None,
+				decode_revert_message(data).map(|m| String::from_utf8_lossy(&m).into_owned()),
+			),
+			Some(FailureReason::Error(name)) | Some(FailureReason::Fatal(name)) =>
+				(Some(String::from_utf8_lossy(name).into_owned()), None),
+		};
+
+		RpcTraceResult {
+			created: r.created,
+			failed: r.failure.is_some(),
+			error,
+			revert_reason,
+			return_value: r.output.into(),
+			gas: r.gas_used,
+			struct_logs: r.steps.into_iter().map(Into::into).collect(),
+		}
+	}
+}
+
+/// EVM RPC methods.
+#[rpc]
+pub trait EvmApi<BlockHash> {
+	/// Executes a call or create locally with an opcode-level tracer.
+	///
+	/// Nothing is submitted and no state is changed. The steps of nested calls and creates are
+	/// recorded along with those of the outermost frame.
+	#[rpc(name = "debug_traceCall")]
+	fn trace_call(
+		&self,
+		request: TraceCallRequest,
+		at: Option<BlockHash>,
+	) -> Result<RpcTraceResult>;
+
+	/// Re-executes the EVM extrinsic at `index` of `block` with an opcode-level tracer.
+	#[rpc(name = "debug_traceTransaction")]
+	fn trace_transaction(
+		&self,
+		block: BlockHash,
+		index: u32,
+	) -> Result<RpcTraceResult>;
+}
+
+/// An implementation of EVM specific RPC methods.
+pub struct Evm<C, B> {
+	client: Arc<C>,
+	_marker: std::marker::PhantomData<B>,
+}
+
+impl<C, B> Evm<C, B> {
+	/// Create new `Evm` with the given reference to the client.
+	pub fn new(client: Arc<C>) -> Self {
+		Evm {
+			client,
+			_marker: Default::default(),
+		}
+	}
+}
+
+impl<C, Block> EvmApi<<Block as BlockT>::Hash> for Evm<C, Block>
+where
+	Block: BlockT,
+	C: Send + Sync + 'static + ProvideRuntimeApi<Block> + HeaderBackend<Block> + BlockBackend<Block>,
+	C::Api: EvmRuntimeApi<Block>,
+{
+	fn trace_call(
+		&self,
+		request: TraceCallRequest,
+		at: Option<<Block as BlockT>::Hash>,
+	) -> Result<RpcTraceResult> {
+		let api = self.client.runtime_api();
+		let at = BlockId::hash(at.unwrap_or_else(||
+			// If the block hash is not supplied assume the best block.
+			self.client.info().best_hash));
+
+		let TraceCallRequest {
+			from,
+			to,
+			data,
+			value,
+			gas,
+			gas_price,
+			salt,
+		} = request;
+		let data = data.map(|d| d.to_vec()).unwrap_or_default();
+
+		let action = match (to, salt) {
+			(Some(target), None) => TraceAction::Call { target, input: data },
+			(None, None) => TraceAction::Create { init: data },
+			(None, Some(salt)) => TraceAction::Create2 { init: data, salt },
+			(Some(_), Some(_)) => return Err(Error {
+				code: ErrorCode::InvalidParams,
+				message: "`salt` is only allowed for contract creation".into(),
+				data: None,
+			}),
+		};
+
+		let result = api
+			.trace(
+				&at,
+				from,
+				action,
+				value.unwrap_or_default(),
+				gas,
+				gas_price.unwrap_or_default(),
+			)
+			.map_err(|e| runtime_error_into_rpc_err(e))?
+			.map_err(|e| trace_error_into_rpc_err(e))?;
+
+		Ok(result.into())
+	}
+
+	fn trace_transaction(
+		&self,
+		block: <Block as BlockT>::Hash,
+		index: u32,
+	) -> Result<RpcTraceResult> {
+		let api = self.client.runtime_api();
+		let id = BlockId::hash(block);
+
+		let header = self.client.header(id)
+			.map_err(|e| runtime_error_into_rpc_err(e))?
+			.ok_or_else(|| block_not_found(block))?;
+		let extrinsics = self.client.block_body(&id)
+			.map_err(|e| runtime_error_into_rpc_err(e))?
+			.ok_or_else(|| block_not_found(block))?;
+		let parent = BlockId::hash(*header.parent_hash());
+
+		let result = api
+			.trace_extrinsic(&parent, &header, extrinsics, index)
+			.map_err(|e| runtime_error_into_rpc_err(e))?
+			.map_err(|e| trace_error_into_rpc_err(e))?;
+
+		Ok(result.into())
+	}
+}
+
+/// Converts a runtime trap into an RPC error.
+fn runtime_error_into_rpc_err(err: impl std::fmt::Debug) -> Error {
+	Error {
+		code: ErrorCode::ServerError(RUNTIME_ERROR),
+		message: "Runtime trapped".into(),
+		data: Some(format!("{:?}", err).into()),
+	}
+}
+
+/// Converts an error refusing to trace into an RPC error.
+fn trace_error_into_rpc_err(err: impl std::fmt::Debug) -> Error {
+	Error {
+		code: ErrorCode::ServerError(TRACE_ERROR),
+		message: "Unable to trace the execution".into(),
+		data: Some(format!("{:?}", err).into()),
+	}
+}
+
+fn block_not_found(hash: impl std::fmt::Debug) -> Error {
+	Error {
+		code: ErrorCode::ServerError(BLOCK_NOT_FOUND),
+		message: "Block not found".into(),
+		data: Some(format!("{:?}", hash).into()),
+	}
+}
+
+/// Mnemonic of an opcode as used by Ethereum tracers.
+fn opcode_name(op: u8) -> String {
+	let name = match op {
+		0x00 => "STOP",
+		0x01 => "ADD",
+		0x02 => "MUL",
+		0x03 => "SUB",
+		0x04 => "DIV",
+		0x05 => "SDIV",
+		0x06 => "MOD",
+		0x07 => "SMOD",
+		0x08 => "ADDMOD",
+		0x09 => "MULMOD",
+		0x0a => "EXP",
+		0x0b => "SIGNEXTEND",
+		0x10 => "LT",
+		0x11 => "GT",
+		0x12 => "SLT",
+		0x13 => "SGT",
+		0x14 => "EQ",
+		0x15 => "ISZERO",
+		0x16 => "AND",
+		0x17 => "OR",
+		0x18 => "XOR",
+		0x19 => "NOT",
+		0x1a => "BYTE",
+		0x1b => "SHL",
+		0x1c => "SHR",
+		0x1d => "SAR",
+		0x20 => "SHA3",
+		0x30 => "ADDRESS",
+		0x31 => "BALANCE",
+		0x32 => "ORIGIN",
+		0x33 => "CALLER",
+		0x34 => "CALLVALUE",
+		0x35 => "CALLDATALOAD",
+		0x36 => "CALLDATASIZE",
+		0x37 => "CALLDATACOPY",
+		0x38 => "CODESIZE",
+		0x39 => "CODECOPY",
+		0x3a => "GASPRICE",
+		0x3b => "EXTCODESIZE",
+		0x3c => "EXTCODECOPY",
+		0x3d => "RETURNDATASIZE",
+		0x3e => "RETURNDATACOPY",
+		0x3f => "EXTCODEHASH",
+		0x40 => "BLOCKHASH",
+		0x41 => "COINBASE",
+		0x42 => "TIMESTAMP",
+		0x43 => "NUMBER",
+		0x44 => "DIFFICULTY",
+		0x45 => "GASLIMIT",
+		0x46 => "CHAINID",
+		0x47 => "SELFBALANCE",
+		0x50 => "POP",
+		0x51 => "MLOAD",
+		0x52 => "MSTORE",
+		0x53 => "MSTORE8",
+		0x54 => "SLOAD",
+		0x55 => "SSTORE",
+		0x56 => "JUMP",
+		0x57 => "JUMPI",
+		0x58 => "PC",
+		0x59 => "MSIZE",
+		0x5a => "GAS",
+		0x5b => "JUMPDEST",
+		0x60..=0x7f => return format!("PUSH{}", op - 0x5f),
+		0x80..=0x8f => return format!("DUP{}", op - 0x7f),
+		0x90..=0x9f => return format!("SWAP{}", op - 0x8f),
+		0xa0..=0xa4 => return format!("LOG{}", op - 0xa0),
+		0xf0 => "CREATE",
+		0xf1 => "CALL",
+		0xf2 => "CALLCODE",
+		0xf3 => "RETURN",
+		0xf4 => "DELEGATECALL",
+		0xf5 => "CREATE2",
+		0xfa => "STATICCALL",
+		0xfd => "REVERT",
+		0xfe => "INVALID",
+		0xff => "SELFDESTRUCT",
+		_ => return format!("0x{:02x}", op),
+	};
+	name.into()
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn trace_call_request_should_deserialize_properly() {
+		let req: TraceCallRequest = serde_json::from_str(r#"
+		{
+			"from": "0x1000000000000000000000000000000000000001",
+			"to": "0x1000000000000000000000000000000000000002",
+			"data": "0x8c97db39",
+			"gas": 1000000
+		}
+		"#).unwrap();
+		assert_eq!(req.gas, 1000000);
+		assert_eq!(req.value, None);
+		assert_eq!(req.data.unwrap().to_vec(), vec![0x8c, 0x97, 0xdb, 0x39]);
+	}
+
+	#[test]
+	fn trace_result_should_serialize_properly() {
+		let result: RpcTraceResult = TraceResult {
+			created: None,
+			failure: Some(FailureReason::Error(b"OutOfGas".to_vec())),
+			output: vec![],
+			gas_used: 21000.into(),
+			steps: vec![TraceStep {
+				pc: 2,
+				op: 0x60,
+				depth: 1,
+				gas: 100.into(),
+				gas_cost: 3.into(),
+				stack: vec![H256::zero()],
+				memory: vec![],
+				storage: None,
+			}],
+		}.into();
+
+		assert_eq!(
+			serde_json::to_string(&result).unwrap(),
+			r#"{"created":null,"failed":true,"error":"OutOfGas","revertReason":null,"returnValue":"0x","gas":"0x5208","structLogs":[{"pc":2,"op":"PUSH1","gas":"0x64","gasCost":"0x3","depth":1,"stack":["0x0000000000000000000000000000000000000000000000000000000000000000"],"memory":[],"storage":null}]}"#,
+		);
+	}
+
+	#[test]
+	fn opcode_names() {
+		assert_eq!(opcode_name(0x7f), "PUSH32");
+		assert_eq!(opcode_name(0x80), "DUP1");
+		assert_eq!(opcode_name(0x9f), "SWAP16");
+		assert_eq!(opcode_name(0xa2), "LOG2");
+		assert_eq!(opcode_name(0x0c), "0x0c");
+	}
+}
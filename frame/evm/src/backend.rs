@@ -109,19 +109,23 @@ impl<'vicinity, T: Trait> BackendT for Backend<'vicinity, T> {
 	}
 
 	fn code_size(&self, address: H160) -> usize {
-		AccountCodes::decode_len(&address).unwrap_or(0)
+		Module::<T>::account_code_len(&address)
 	}
 
 	fn code_hash(&self, address: H160) -> H256 {
-		H256::from_slice(Keccak256::digest(&AccountCodes::get(&address)).as_slice())
+		H256::from_slice(Keccak256::digest(&Module::<T>::account_code(&address)).as_slice())
 	}
 
 	fn code(&self, address: H160) -> Vec<u8> {
-		AccountCodes::get(&address)
+		Module::<T>::account_code(&address)
 	}
 
 	fn storage(&self, address: H160, index: H256) -> H256 {
-		AccountStorages::get(address, index)
+		if Module::<T>::is_pending_deletion(address) {
+			H256::default()
+		} else {
+			AccountStorages::get(address, index)
+		}
 	}
 }
 
@@ -138,8 +142,10 @@ impl<'vicinity, T: Trait> ApplyBackend for Backend<'vicinity, T> {
 	{
 		for apply in values {
 			match apply {
+				// `reset_storage` is only set by creates, which collide with addresses whose storage
+				// is still being cleared, so there is never anything to clear here.
 				Apply::Modify {
-					address, basic, code, storage, reset_storage,
+					address, basic, code, storage, reset_storage: _,
 				} => {
 					Accounts::mutate(&address, |account| {
 						account.balance = basic.balance;
@@ -150,10 +156,6 @@ impl<'vicinity, T: Trait> ApplyBackend for Backend<'vicinity, T> {
 						AccountCodes::insert(address, code);
 					}
 
-					if reset_storage {
-						AccountStorages::remove_prefix(address);
-					}
-
 					for (index, value) in storage {
 						if value == H256::default() {
 							AccountStorages::remove(address, index);
@@ -0,0 +1,222 @@
+// This file is part of Substrate.
+
+// Copyright (C) 2017-2020 Parity Technologies (UK) Ltd.
+// SPDX-License-Identifier: Apache-2.0
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// 	http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! ERC-20 precompile for substrate-side assets.
+//!
+//! Every asset is served by a precompile at its own address in a reserved range. Balances and the
+//! total supply are those of the asset itself, read and moved through [`Assets`]: the asset held
+//! by an EVM address is held by the account `Trait::AddressMapping` maps it to. Allowances are
+//! ERC-20 state and kept in the `Allowances` storage of this module.
+//!
+//! Precompiles of the EVM engine are not told their caller. `transfer`, `approve` and
+//! `transferFrom` are therefore only served where this module knows it: when the token is called
+//! by the transaction itself. They fail when made by contracts or through a static call. The
+//! writes do not go through the journal of the EVM executor, so operations run in a storage
+//! transaction that is rolled back unless they succeed.
+
+use sp_std::prelude::*;
+use sp_core::{U256, H160};
+use evm::{ExitError, ExitSucceed};
+use crate::{Trait, Module, Event, Log, Assets, AddressMapping, Allowances};
+
+environmental::environmental!(token_caller: H160);
+
+/// The first bytes of every token address. The last four bytes are the big-endian asset id.
+pub const TOKEN_ADDRESS_PREFIX: [u8; 16] = [0xff, 0xff, 0xff, 0xff, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0];
+
+/// The code reported for every token address, so that contracts checking `EXTCODESIZE` before
+/// calling it succeed. It is never run: `PUSH1 0x00 DUP1 REVERT`.
+pub const CODE: &[u8] = &[0x60, 0x00, 0x80, 0xfd];
+
+/// `keccak256("Transfer(address,address,uint256)")`.
+pub const TRANSFER_TOPIC: [u8; 32] = [
+	0xdd, 0xf2, 0x52, 0xad, 0x1b, 0xe2, 0xc8, 0x9b, 0x69, 0xc2, 0xb0, 0x68, 0xfc, 0x37, 0x8d, 0xaa,
+	0x95, 0x2b, 0xa7, 0xf1, 0x63, 0xc4, 0xa1, 0x16, 0x28, 0xf5, 0x5a, 0x4d, 0xf5, 0x23, 0xb3, 0xef,
+];
+
+/// `keccak256("Approval(address,address,uint256)")`.
+pub const APPROVAL_TOPIC: [u8; 32] = [
+	0x8c, 0x5b, 0xe1, 0xe5, 0xeb, 0xec, 0x7d, 0x5b, 0xd1, 0x4f, 0x71, 0x42, 0x7d, 0x1e, 0x84, 0xf3,
+	0xdd, 0x03, 0x14, 0xc0, 0xf7, 0xb2, 0x29, 0x1e, 0x5b, 0x20, 0x0a, 0xc8, 0xc7, 0xc3, 0xb9, 0x25,
+];
+
+const TOTAL_SUPPLY: [u8; 4] = [0x18, 0x16, 0x0d, 0xdd];
+const BALANCE_OF: [u8; 4] = [0x70, 0xa0, 0x82, 0x31];
+const ALLOWANCE: [u8; 4] = [0xdd, 0x62, 0xed, 0x3e];
+const TRANSFER: [u8; 4] = [0xa9, 0x05, 0x9c, 0xbb];
+const APPROVE: [u8; 4] = [0x09, 0x5e, 0xa7, 0xb3];
+const TRANSFER_FROM: [u8; 4] = [0x23, 0xb8, 0x72, 0xdd];
+
+/// Gas charged for reading the total supply, a balance or an allowance.
+pub const READ_GAS: usize = 1_000;
+/// Gas charged for `approve`.
+pub const APPROVE_GAS: usize = 22_000;
+/// Gas charged for `transfer` and `transferFrom`.
+pub const TRANSFER_GAS: usize = 30_000;
+
+/// Run `f`, telling token precompiles called by it that they are called by `caller`.
+///
+/// `f` must not call anything but a single token, or the precompile could be told the wrong
+/// caller.
+pub(crate) fn with_caller<R>(mut caller: H160, f: impl FnOnce() -> R) -> R {
+	token_caller::using(&mut caller, f)
+}
+
+/// The address of the token of `asset`.
+pub fn token_address(asset: u32) -> H160 {
+	let mut address = [0u8; 20];
+	address[..16].copy_from_slice(&TOKEN_ADDRESS_PREFIX);
+	address[16..].copy_from_slice(&asset.to_be_bytes());
+	H160::from(address)
+}
+
+/// The asset whose token lives at `address`, if `address` is in the reserved range.
+pub fn token_asset(address: &H160) -> Option<u32> {
+	let address = address.as_bytes();
+	if address[..16] != TOKEN_ADDRESS_PREFIX {
+		return None
+	}
+	let mut asset = [0u8; 4];
+	asset.copy_from_slice(&address[16..]);
+	Some(u32::from_be_bytes(asset))
+}
+
+/// Execute the token precompile at `address`, if `address` is the token of an existing asset.
+pub(crate) fn execute<T: Trait>(
+	address: H160,
+	input: &[u8],
+	target_gas: Option<usize>,
+) -> Option<Result<(ExitSucceed, Vec<u8>, usize), ExitError>> {
+	let asset = token_asset(&address).filter(|asset| T::Assets::exists(*asset))?;
+	Some(call::<T>(asset, address, input, target_gas))
+}
+
+fn call<T: Trait>(
+	asset: u32,
+	token: H160,
+	input: &[u8],
+	target_gas: Option<usize>,
+) -> Result<(ExitSucceed, Vec<u8>, usize), ExitError> {
+	if input.len() < 4 {
+		return Err(ExitError::Other("Unknown ERC-20 function"))
+	}
+	let mut selector = [0u8; 4];
+	selector.copy_from_slice(&input[..4]);
+	let args = &input[4..];
+
+	let cost = match selector {
+		TOTAL_SUPPLY | BALANCE_OF | ALLOWANCE => READ_GAS,
+		APPROVE => APPROVE_GAS,
+		TRANSFER | TRANSFER_FROM => TRANSFER_GAS,
+		_ => return Err(ExitError::Other("Unknown ERC-20 function")),
+	};
+	if target_gas.map_or(false, |gas| gas < cost) {
+		return Err(ExitError::OutOfGas)
+	}
+
+	let output = match selector {
+		TOTAL_SUPPLY => word(T::Assets::total_supply(asset)),
+		BALANCE_OF => word(T::Assets::balance(asset, &holder::<T>(address_arg(args, 0)?))),
+		ALLOWANCE => word(Allowances::get(asset, (address_arg(args, 0)?, address_arg(args, 1)?))),
+		TRANSFER => {
+			let caller = writer()?;
+			transfer::<T>(asset, token, caller, address_arg(args, 0)?, uint_arg(args, 1)?)?;
+			word(U256::one())
+		},
+		APPROVE => {
+			let caller = writer()?;
+			let (spender, amount) = (address_arg(args, 0)?, uint_arg(args, 1)?);
+			if amount.is_zero() {
+				Allowances::remove(asset, (caller, spender));
+			} else {
+				Allowances::insert(asset, (caller, spender), amount);
+			}
+			deposit_log::<T>(token, APPROVAL_TOPIC, caller, spender, amount);
+			word(U256::one())
+		},
+		_ => {
+			let caller = writer()?;
+			let (owner, dest, amount) = (address_arg(args, 0)?, address_arg(args, 1)?, uint_arg(args, 2)?);
+			let allowance = Allowances::get(asset, (owner, caller));
+			let remaining = allowance.checked_sub(amount)
+				.ok_or(ExitError::Other("ERC-20 allowance too low"))?;
+			transfer::<T>(asset, token, owner, dest, amount)?;
+			// An allowance of `2^256 - 1` is never spent.
+			if allowance != U256::max_value() {
+				if remaining.is_zero() {
+					Allowances::remove(asset, (owner, caller));
+				} else {
+					Allowances::insert(asset, (owner, caller), remaining);
+				}
+			}
+			word(U256::one())
+		},
+	};
+
+	Ok((ExitSucceed::Returned, output, cost))
+}
+
+/// The caller of a state-changing function, if it is the transaction itself.
+fn writer() -> Result<H160, ExitError> {
+	token_caller::with(|caller| *caller)
+		.ok_or(ExitError::Other("ERC-20 state change from a contract"))
+}
+
+fn holder<T: Trait>(address: H160) -> T::AccountId {
+	T::AddressMapping::into_account_id(address)
+}
+
+fn transfer<T: Trait>(
+	asset: u32,
+	token: H160,
+	source: H160,
+	dest: H160,
+	amount: U256,
+) -> Result<(), ExitError> {
+	if !amount.is_zero() && source != dest {
+		T::Assets::transfer(asset, &holder::<T>(source), &holder::<T>(dest), amount)
+			.map_err(|_| ExitError::Other("ERC-20 transfer failed"))?;
+	}
+	deposit_log::<T>(token, TRANSFER_TOPIC, source, dest, amount);
+	Ok(())
+}
+
+fn deposit_log<T: Trait>(token: H160, topic: [u8; 32], from: H160, to: H160, value: U256) {
+	Module::<T>::deposit_event(Event::<T>::Log(Log {
+		address: token,
+		topics: vec![topic.into(), from.into(), to.into()],
+		data: word(value),
+	}));
+}
+
+fn address_arg(args: &[u8], index: usize) -> Result<H160, ExitError> {
+	args.get(index * 32..(index + 1) * 32)
+		.map(|arg| H160::from_slice(&arg[12..]))
+		.ok_or(ExitError::Other("Invalid ERC-20 arguments"))
+}
+
+fn uint_arg(args: &[u8], index: usize) -> Result<U256, ExitError> {
+	args.get(index * 32..(index + 1) * 32)
+		.map(U256::from_big_endian)
+		.ok_or(ExitError::Other("Invalid ERC-20 arguments"))
+}
+
+fn word(value: U256) -> Vec<u8> {
+	let mut data = [0u8; 32];
+	value.to_big_endian(&mut data);
+	data.to_vec()
+}
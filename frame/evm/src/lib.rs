@@ -21,28 +21,32 @@
 #![cfg_attr(not(feature = "std"), no_std)]
 
 mod backend;
+pub mod erc20;
+mod tracer;
 mod tests;
 
 pub use crate::backend::{Account, Log, Vicinity, Backend};
+pub use crate::tracer::{TraceAction, TraceResult, TraceStep, MemoryDiff, StorageWrite};
 
 use sp_std::{vec::Vec, marker::PhantomData};
-#[cfg(feature = "std")]
 use codec::{Encode, Decode};
 #[cfg(feature = "std")]
 use serde::{Serialize, Deserialize};
 use frame_support::{ensure, decl_module, decl_storage, decl_event, decl_error};
 use frame_support::weights::Weight;
-use frame_support::traits::{Currency, WithdrawReason, ExistenceRequirement, Get};
+use frame_support::traits::{Currency, ReservableCurrency, WithdrawReason, ExistenceRequirement, Get};
+use frame_support::storage::{IterableStorageDoubleMap, StorageDoubleMap, with_transaction};
 use frame_system::{self as system, ensure_signed};
-use sp_runtime::ModuleId;
+use sp_runtime::{DispatchError, TransactionOutcome};
+use sp_runtime::{ModuleId, RuntimeDebug};
 use sp_core::{U256, H256, H160, Hasher};
 use sp_runtime::{
-	DispatchResult, traits::{UniqueSaturatedInto, AccountIdConversion, SaturatedConversion},
+	DispatchResult, traits::{UniqueSaturatedInto, AccountIdConversion, SaturatedConversion, Zero},
 };
 use sha3::{Digest, Keccak256};
-use evm::{ExitReason, ExitSucceed, ExitError, Config};
+use evm::{ExitReason, ExitSucceed, ExitError, ExitFatal, Config};
 use evm::executor::StackExecutor;
-use evm::backend::ApplyBackend;
+use evm::backend::{Backend as BackendT, ApplyBackend, Apply};
 
 /// Type alias for currency balance.
 pub type BalanceOf<T> = <<T as Trait>::Currency as Currency<<T as frame_system::Trait>::AccountId>>::Balance;
@@ -119,6 +123,68 @@ impl Precompiles for () {
 	}
 }
 
+/// Substrate-side assets that can be bridged into the EVM as ERC-20 tokens.
+///
+/// See the [`erc20`] module for how the tokens are represented.
+pub trait Assets<AccountId> {
+	/// Whether `asset` exists and can be bridged.
+	fn exists(asset: u32) -> bool;
+	/// The total supply of `asset`.
+	fn total_supply(asset: u32) -> U256;
+	/// The balance of `asset` held by `who`.
+	fn balance(asset: u32, who: &AccountId) -> U256;
+	/// Move exactly `amount` of `asset` from `source` to `dest`, failing rather than reaping
+	/// `source` or moving more than `amount`.
+	fn transfer(
+		asset: u32,
+		source: &AccountId,
+		dest: &AccountId,
+		amount: U256,
+	) -> Result<(), DispatchError>;
+}
+
+impl<AccountId> Assets<AccountId> for () {
+	fn exists(_asset: u32) -> bool {
+		false
+	}
+
+	fn total_supply(_asset: u32) -> U256 {
+		U256::zero()
+	}
+
+	fn balance(_asset: u32, _who: &AccountId) -> U256 {
+		U256::zero()
+	}
+
+	fn transfer(
+		_asset: u32,
+		_source: &AccountId,
+		_dest: &AccountId,
+		_amount: U256,
+	) -> Result<(), DispatchError> {
+		Err(DispatchError::Other("No assets can be bridged"))
+	}
+}
+
+/// Trait for finding the Substrate account that holds the assets of an EVM address.
+pub trait AddressMapping<A> {
+	/// Given an Ethereum address, return the corresponding Substrate account.
+	fn into_account_id(address: H160) -> A;
+}
+
+/// Hash the address prefixed with `evm:`, taking the 256-bit hash as the account id.
+pub struct HashedAddressMapping<H>(PhantomData<H>);
+
+impl<H: Hasher<Out = H256>, A: From<[u8; 32]>> AddressMapping<A> for HashedAddressMapping<H> {
+	fn into_account_id(address: H160) -> A {
+		let mut data = [0u8; 24];
+		data[0..4].copy_from_slice(b"evm:");
+		data[4..24].copy_from_slice(address.as_bytes());
+		let hash: [u8; 32] = H::hash(&data).into();
+		A::from(hash)
+	}
+}
+
 /// Substrate system chain ID.
 pub struct SystemChainId;
 
@@ -128,8 +194,94 @@ impl Get<u64> for SystemChainId {
 	}
 }
 
+/// Reason an EVM execution did not succeed.
+#[derive(Clone, Eq, PartialEq, Encode, Decode, RuntimeDebug)]
+#[cfg_attr(feature = "std", derive(Serialize, Deserialize))]
+pub enum FailureReason {
+	/// Execution hit `REVERT`. Carries the revert data returned by the contract, which for
+	/// Solidity is usually an ABI encoded `Error(string)`. The executor does not hand it out, so
+	/// it is only known to replays, see [`Module::trace`], and empty in events.
+	Revert(Vec<u8>),
+	/// Execution stopped on an EVM error, such as running out of gas. Carries the error name.
+	Error(Vec<u8>),
+	/// Execution stopped on an error of the EVM engine itself. Carries the error name.
+	Fatal(Vec<u8>),
+}
+
+impl FailureReason {
+	/// Build the failure reason for an execution that exited with `reason` and returned
+	/// `output`. Returns `None` if the execution succeeded.
+	pub fn from_exit(reason: ExitReason, output: Vec<u8>) -> Option<Self> {
+		match reason {
+			ExitReason::Succeed(_) => None,
+			ExitReason::Revert(_) => Some(FailureReason::Revert(output)),
+			ExitReason::Error(e) => Some(FailureReason::Error(exit_error_name(e).as_bytes().to_vec())),
+			ExitReason::Fatal(e) => Some(FailureReason::Fatal(match e {
+				ExitFatal::NotSupported => "NotSupported",
+				ExitFatal::UnhandledInterrupt => "UnhandledInterrupt",
+				ExitFatal::CallErrorAsFatal(e) => exit_error_name(e),
+				ExitFatal::Other(name) => name,
+			}.as_bytes().to_vec())),
+		}
+	}
+
+	/// The revert message, if this is a revert carrying a Solidity `Error(string)` payload.
+	pub fn revert_message(&self) -> Option<Vec<u8>> {
+		match self {
+			FailureReason::Revert(data) => decode_revert_message(data),
+			_ => None,
+		}
+	}
+}
+
+fn exit_error_name(error: ExitError) -> &'static str {
+	match error {
+		ExitError::StackUnderflow => "StackUnderflow",
+		ExitError::StackOverflow => "StackOverflow",
+		ExitError::InvalidJump => "InvalidJump",
+		ExitError::InvalidRange => "InvalidRange",
+		ExitError::DesignatedInvalid => "DesignatedInvalid",
+		ExitError::CallTooDeep => "CallTooDeep",
+		ExitError::CreateCollision => "CreateCollision",
+		ExitError::CreateContractLimit => "CreateContractLimit",
+		ExitError::OutOfOffset => "OutOfOffset",
+		ExitError::OutOfGas => "OutOfGas",
+		ExitError::OutOfFund => "OutOfFund",
+		ExitError::PCUnderflow => "PCUnderflow",
+		ExitError::CreateEmpty => "CreateEmpty",
+		ExitError::Other(name) => name,
+	}
+}
+
+/// Decode the message of a Solidity `Error(string)` revert payload.
+///
+/// The payload is the `0x08c379a0` selector followed by the ABI encoding of a single string:
+/// a 32-byte offset, a 32-byte length and the padded UTF-8 bytes.
+pub fn decode_revert_message(data: &[u8]) -> Option<Vec<u8>> {
+	const ERROR_SELECTOR: [u8; 4] = [0x08, 0xc3, 0x79, 0xa0];
+
+	if data.len() < 4 + 64 || data[..4] != ERROR_SELECTOR {
+		return None
+	}
+	let body = &data[4..];
+	let offset = U256::from_big_endian(&body[..32]);
+	if offset > U256::from(body.len()) {
+		return None
+	}
+	let offset = offset.as_usize();
+	let len = U256::from_big_endian(body.get(offset..offset.checked_add(32)?)?);
+	if len > U256::from(body.len()) {
+		return None
+	}
+	let start = offset + 32;
+	body.get(start..start.checked_add(len.as_usize())?).map(|message| message.to_vec())
+}
+
 static ISTANBUL_CONFIG: Config = Config::istanbul();
 
+/// Number of bytes a non-zero storage slot is accounted for: a 32-byte key and a 32-byte value.
+const SLOT_BYTES: usize = 64;
+
 /// EVM module trait
 pub trait Trait: frame_system::Trait + pallet_timestamp::Trait {
 	/// The EVM's module id
@@ -139,13 +291,22 @@ pub trait Trait: frame_system::Trait + pallet_timestamp::Trait {
 	/// Convert account ID to H160;
 	type ConvertAccountId: ConvertAccountId<Self::AccountId>;
 	/// Currency type for deposit and withdraw.
-	type Currency: Currency<Self::AccountId>;
+	type Currency: ReservableCurrency<Self::AccountId>;
 	/// The overarching event type.
 	type Event: From<Event<Self>> + Into<<Self as frame_system::Trait>::Event>;
 	/// Precompiles associated with this EVM engine.
 	type Precompiles: Precompiles;
 	/// Chain ID of EVM.
 	type ChainId: Get<u64>;
+	/// The amount reserved from the substrate account of a caller for every byte of contract
+	/// code and storage its transactions add to the state. Zero disables storage deposits.
+	type StorageDepositPerByte: Get<BalanceOf<Self>>;
+	/// The maximum weight spent per block on clearing the storage of destroyed contracts.
+	type DeletionWeightLimit: Get<Weight>;
+	/// Assets exposed to the EVM as ERC-20 tokens.
+	type Assets: Assets<Self::AccountId>;
+	/// Maps EVM addresses to the accounts holding their assets.
+	type AddressMapping: AddressMapping<Self::AccountId>;
 
 	/// EVM config used in the module.
 	fn config() -> &'static Config {
@@ -173,6 +334,23 @@ decl_storage! {
 		AccountCodes get(fn account_codes): map hasher(blake2_128_concat) H160 => Vec<u8>;
 		AccountStorages get(fn account_storages):
 			double_map hasher(blake2_128_concat) H160, hasher(blake2_128_concat) H256 => H256;
+
+		/// Destroyed contracts whose storage is still to be cleared, by position in the queue.
+		DeletionQueue get(fn deletion_queue): map hasher(twox_64_concat) u64 => Option<H160>;
+		/// Position of the oldest entry of the deletion queue.
+		DeletionQueueHead get(fn deletion_queue_head): u64;
+		/// Position the next entry of the deletion queue is inserted at.
+		DeletionQueueTail get(fn deletion_queue_tail): u64;
+		/// Addresses in the deletion queue. Their leftover storage reads as empty and they cannot
+		/// be created again until it is cleared.
+		PendingDeletion get(fn is_pending_deletion): map hasher(blake2_128_concat) H160 => bool;
+		/// Storage deposit reserved from an account for the code and storage of a contract.
+		StorageDeposits get(fn storage_deposits):
+			double_map hasher(blake2_128_concat) H160, hasher(blake2_128_concat) T::AccountId
+			=> BalanceOf<T>;
+		/// The amount of an asset's ERC-20 token a spender may transfer on behalf of an owner.
+		Allowances get(fn allowance):
+			double_map hasher(twox_64_concat) u32, hasher(blake2_128_concat) (H160, H160) => U256;
 	}
 
 	add_extra_genesis {
@@ -197,21 +375,34 @@ decl_event! {
 	/// EVM events
 	pub enum Event<T> where
 		<T as frame_system::Trait>::AccountId,
+		Balance = BalanceOf<T>,
 	{
 		/// Ethereum events from contracts.
 		Log(Log),
 		/// A contract has been created at given address.
 		Created(H160),
-		/// A contract was attempted to be created, but the execution failed.
-		CreatedFailed(H160),
+		/// A contract was attempted to be created, but the execution failed for the given reason.
+		/// Replay the operation for any revert data.
+		CreatedFailed(H160, FailureReason),
 		/// A contract has been executed successfully with states applied.
 		Executed(H160),
 		/// A contract has been executed with errors. States are reverted with only gas fees applied.
-		ExecutedFailed(H160),
+		/// Replay the operation for any revert data.
+		ExecutedFailed(H160, FailureReason),
 		/// A deposit has been made at a given address.
 		BalanceDeposit(AccountId, H160, U256),
 		/// A withdrawal has been made from a given address.
 		BalanceWithdraw(AccountId, H160, U256),
+		/// A storage deposit has been reserved from an account for the state of a contract.
+		StorageDepositReserved(AccountId, H160, Balance),
+		/// A storage deposit held for the state of a contract has been released.
+		StorageDepositReleased(AccountId, H160, Balance),
+		/// The storage of a destroyed contract has been fully cleared.
+		StorageCleared(H160),
+		/// An asset has been moved from an account to the holder of the given address.
+		AssetDeposit(AccountId, H160, u32, U256),
+		/// An asset has been moved from the holder of the given address back to its account.
+		AssetWithdraw(AccountId, H160, u32, U256),
 	}
 }
 
@@ -229,6 +420,10 @@ decl_error! {
 		GasPriceTooLow,
 		/// Nonce is invalid
 		InvalidNonce,
+		/// Not enough free balance to reserve the storage deposit
+		StorageDepositFailed,
+		/// The asset does not exist or cannot be bridged
+		UnknownAsset,
 	}
 }
 
@@ -240,8 +435,18 @@ decl_module! {
 
 		const ModuleId: ModuleId = T::ModuleId::get();
 
+		/// The amount reserved for every byte of contract code and storage added to the state.
+		const StorageDepositPerByte: BalanceOf<T> = T::StorageDepositPerByte::get();
+
+		/// The maximum weight spent per block on clearing the storage of destroyed contracts.
+		const DeletionWeightLimit: Weight = T::DeletionWeightLimit::get();
+
+		fn on_initialize() -> Weight {
+			Self::process_deletion_queue(T::DeletionWeightLimit::get())
+		}
+
 		/// Deposit balance from currency/balances module into EVM.
-		#[weight = 0]
+		#[weight = T::DbWeight::get().reads_writes(3, 3) + 70_000_000]
 		fn deposit_balance(origin, value: BalanceOf<T>) {
 			let sender = ensure_signed(origin)?;
 
@@ -262,7 +467,7 @@ decl_module! {
 		}
 
 		/// Withdraw balance from EVM into currency/balances module.
-		#[weight = 0]
+		#[weight = T::DbWeight::get().reads_writes(3, 3) + 70_000_000]
 		fn withdraw_balance(origin, value: BalanceOf<T>) {
 			let sender = ensure_signed(origin)?;
 			let address = T::ConvertAccountId::convert_account_id(&sender);
@@ -285,6 +490,34 @@ decl_module! {
 			Module::<T>::deposit_event(Event::<T>::BalanceWithdraw(sender, address, bvalue));
 		}
 
+		/// Move an asset of the sender to the account holding the assets of the sender's EVM
+		/// address, where its ERC-20 token can spend it.
+		#[weight = T::DbWeight::get().reads_writes(4, 2) + 50_000_000]
+		fn deposit_asset(origin, asset: u32, value: U256) {
+			let sender = ensure_signed(origin)?;
+			ensure!(T::Assets::exists(asset), Error::<T>::UnknownAsset);
+
+			let address = T::ConvertAccountId::convert_account_id(&sender);
+			let holder = T::AddressMapping::into_account_id(address);
+			T::Assets::transfer(asset, &sender, &holder, value)?;
+
+			Module::<T>::deposit_event(Event::<T>::AssetDeposit(sender, address, asset, value));
+		}
+
+		/// Move an asset held for the sender's EVM address back to the sender.
+		#[weight = T::DbWeight::get().reads_writes(4, 2) + 50_000_000]
+		fn withdraw_asset(origin, asset: u32, value: U256) {
+			let sender = ensure_signed(origin)?;
+			ensure!(T::Assets::exists(asset), Error::<T>::UnknownAsset);
+
+			let address = T::ConvertAccountId::convert_account_id(&sender);
+			let holder = T::AddressMapping::into_account_id(address);
+			ensure!(T::Assets::balance(asset, &holder) >= value, Error::<T>::BalanceLow);
+			T::Assets::transfer(asset, &holder, &sender, value)?;
+
+			Module::<T>::deposit_event(Event::<T>::AssetWithdraw(sender, address, asset, value));
+		}
+
 		/// Issue an EVM call operation. This is similar to a message call transaction in Ethereum.
 		#[weight = (*gas_price).saturated_into::<Weight>().saturating_mul(*gas_limit as Weight)]
 		fn call(
@@ -301,7 +534,7 @@ decl_module! {
 			let sender = ensure_signed(origin)?;
 			let source = T::ConvertAccountId::convert_account_id(&sender);
 
-			match Self::execute_call(
+			let reason = Self::execute_call(
 				source,
 				target,
 				input,
@@ -309,12 +542,14 @@ decl_module! {
 				gas_limit,
 				gas_price,
 				nonce,
-			)? {
-				ExitReason::Succeed(_) => {
+				Some(&sender),
+			)?;
+			match FailureReason::from_exit(reason, Vec::new()) {
+				None => {
 					Module::<T>::deposit_event(Event::<T>::Executed(target));
 				},
-				ExitReason::Error(_) | ExitReason::Revert(_) | ExitReason::Fatal(_) => {
-					Module::<T>::deposit_event(Event::<T>::ExecutedFailed(target));
+				Some(failure) => {
+					Module::<T>::deposit_event(Event::<T>::ExecutedFailed(target, failure));
 				},
 			}
 
@@ -337,19 +572,21 @@ decl_module! {
 			let sender = ensure_signed(origin)?;
 			let source = T::ConvertAccountId::convert_account_id(&sender);
 
-			match Self::execute_create(
+			let (create_address, reason) = Self::execute_create(
 				source,
 				init,
 				value,
 				gas_limit,
 				gas_price,
-				nonce
-			)? {
-				(create_address, ExitReason::Succeed(_)) => {
+				nonce,
+				Some(&sender),
+			)?;
+			match FailureReason::from_exit(reason, Vec::new()) {
+				None => {
 					Module::<T>::deposit_event(Event::<T>::Created(create_address));
 				},
-				(create_address, _) => {
-					Module::<T>::deposit_event(Event::<T>::CreatedFailed(create_address));
+				Some(failure) => {
+					Module::<T>::deposit_event(Event::<T>::CreatedFailed(create_address, failure));
 				},
 			}
 
@@ -372,20 +609,22 @@ decl_module! {
 			let sender = ensure_signed(origin)?;
 			let source = T::ConvertAccountId::convert_account_id(&sender);
 
-			match Self::execute_create2(
+			let (create_address, reason) = Self::execute_create2(
 				source,
 				init,
 				salt,
 				value,
 				gas_limit,
 				gas_price,
-				nonce
-			)? {
-				(create_address, ExitReason::Succeed(_)) => {
+				nonce,
+				Some(&sender),
+			)?;
+			match FailureReason::from_exit(reason, Vec::new()) {
+				None => {
 					Module::<T>::deposit_event(Event::<T>::Created(create_address));
 				},
-				(create_address, _) => {
-					Module::<T>::deposit_event(Event::<T>::CreatedFailed(create_address));
+				Some(failure) => {
+					Module::<T>::deposit_event(Event::<T>::CreatedFailed(create_address, failure));
 				},
 			}
 
@@ -403,10 +642,38 @@ impl<T: Trait> Module<T> {
 		T::ModuleId::get().into_account()
 	}
 
+	/// Execute the precompile at `address`: the ERC-20 token of an asset, or one of
+	/// `T::Precompiles`.
+	pub fn execute_precompile(
+		address: H160,
+		input: &[u8],
+		target_gas: Option<usize>,
+	) -> Option<core::result::Result<(ExitSucceed, Vec<u8>, usize), ExitError>> {
+		erc20::execute::<T>(address, input, target_gas)
+			.or_else(|| T::Precompiles::execute(address, input, target_gas))
+	}
+
+	/// The code of an account. Token addresses of bridged assets report a stub, see
+	/// [`erc20::CODE`].
+	pub fn account_code(address: &H160) -> Vec<u8> {
+		match erc20::token_asset(address) {
+			Some(asset) if T::Assets::exists(asset) => erc20::CODE.to_vec(),
+			_ => AccountCodes::get(address),
+		}
+	}
+
+	/// The length of the code of an account.
+	pub fn account_code_len(address: &H160) -> usize {
+		match erc20::token_asset(address) {
+			Some(asset) if T::Assets::exists(asset) => erc20::CODE.len(),
+			_ => AccountCodes::decode_len(address).unwrap_or(0),
+		}
+	}
+
 	/// Check whether an account is empty.
 	pub fn is_account_empty(address: &H160) -> bool {
 		let account = Accounts::get(address);
-		let code_len = AccountCodes::decode_len(address).unwrap_or(0);
+		let code_len = Self::account_code_len(address);
 
 		account.nonce == U256::zero() &&
 			account.balance == U256::zero() &&
@@ -421,41 +688,191 @@ impl<T: Trait> Module<T> {
 	}
 
 	/// Remove an account from state.
+	///
+	/// Its storage, which may be arbitrarily large, is not removed right away. Instead the account
+	/// is queued and its storage cleared over the following blocks in `on_initialize`.
 	fn remove_account(address: &H160) {
 		Accounts::remove(address);
 		AccountCodes::remove(address);
-		AccountStorages::remove_prefix(address);
+
+		if !PendingDeletion::get(address) && (
+			AccountStorages::iter_prefix(address).next().is_some() ||
+			<StorageDeposits<T>>::iter_prefix(address).next().is_some()
+		) {
+			let tail = DeletionQueueTail::get();
+			PendingDeletion::insert(address, true);
+			DeletionQueue::insert(tail, address);
+			DeletionQueueTail::put(tail + 1);
+		}
+	}
+
+	/// Clear the storage of destroyed contracts in the deletion queue, oldest first, without
+	/// exceeding `weight_limit`. Once all storage of a contract is gone, the storage deposits held
+	/// for it are released.
+	///
+	/// Returns the weight consumed, which grows with the number of slots touched.
+	pub fn process_deletion_queue(weight_limit: Weight) -> Weight {
+		let db_weight = T::DbWeight::get();
+		let (mut head, tail) = (DeletionQueueHead::get(), DeletionQueueTail::get());
+		if head == tail {
+			return db_weight.reads(2)
+		}
+
+		let entry_weight = db_weight.reads(1);
+		let slot_weight = db_weight.writes(1).max(1);
+		let deposit_weight = db_weight.reads_writes(1, 2).max(1);
+		let mut weight = db_weight.reads_writes(2, 1);
+
+		while head < tail {
+			if weight.saturating_add(entry_weight) > weight_limit {
+				break
+			}
+			weight = weight.saturating_add(entry_weight);
+			let address = match DeletionQueue::get(head) {
+				Some(address) => address,
+				None => {
+					head += 1;
+					continue
+				},
+			};
+
+			let slots = weight_limit.saturating_sub(weight) / slot_weight;
+			if slots == 0 {
+				break
+			}
+			let removed = AccountStorages::drain_prefix(address)
+				.take(slots as usize)
+				.count() as Weight;
+			weight = weight.saturating_add(removed.saturating_mul(slot_weight));
+			if removed == slots {
+				break
+			}
+
+			let deposits = weight_limit.saturating_sub(weight) / deposit_weight;
+			if deposits == 0 {
+				break
+			}
+			let released = <StorageDeposits<T>>::drain_prefix(address)
+				.take(deposits as usize)
+				.map(|(who, amount)| {
+					T::Currency::unreserve(&who, amount);
+					Self::deposit_event(Event::<T>::StorageDepositReleased(who, address, amount));
+				})
+				.count() as Weight;
+			weight = weight.saturating_add(released.saturating_mul(deposit_weight));
+			if released == deposits {
+				break
+			}
+
+			DeletionQueue::remove(head);
+			PendingDeletion::remove(address);
+			head += 1;
+			weight = weight.saturating_add(slot_weight);
+			Self::deposit_event(Event::<T>::StorageCleared(address));
+		}
+
+		DeletionQueueHead::put(head);
+		weight
+	}
+
+	/// Reserve from `depositor` the storage deposit for the code and storage that `values` add to
+	/// the state, and release its deposit for the code and storage they remove.
+	///
+	/// Must be called before `values` are applied. A contract's storage shrinking only releases
+	/// deposit that `depositor` itself reserved for that contract.
+	fn settle_storage_deposit(
+		depositor: &T::AccountId,
+		values: &[Apply<Vec<(H256, H256)>>],
+	) -> Result<(), Error<T>> {
+		let per_byte = T::StorageDepositPerByte::get();
+		if per_byte.is_zero() {
+			return Ok(())
+		}
+		let deposit_for = |bytes: usize| per_byte.saturating_mul(bytes.saturated_into());
+
+		let mut changes = Vec::new();
+		for apply in values {
+			if let Apply::Modify { address, code, storage, reset_storage, .. } = apply {
+				let (mut added, mut freed) = (0, 0);
+				if let Some(code) = code {
+					let old_len = AccountCodes::decode_len(address).unwrap_or(0);
+					if code.len() > old_len {
+						added += code.len() - old_len;
+					} else {
+						freed += old_len - code.len();
+					}
+				}
+				for (index, value) in storage {
+					let existed = !reset_storage &&
+						AccountStorages::get(address, index) != H256::default();
+					match (existed, *value != H256::default()) {
+						(false, true) => added += SLOT_BYTES,
+						(true, false) => freed += SLOT_BYTES,
+						_ => (),
+					}
+				}
+				if added != freed {
+					changes.push((*address, added, freed));
+				}
+			}
+		}
+
+		let total = changes.iter()
+			.filter(|(_, added, freed)| added > freed)
+			.fold(Zero::zero(), |total: BalanceOf<T>, (_, added, freed)| {
+				total.saturating_add(deposit_for(added - freed))
+			});
+		ensure!(T::Currency::can_reserve(depositor, total), Error::<T>::StorageDepositFailed);
+
+		for (address, added, freed) in changes {
+			let held = <StorageDeposits<T>>::get(address, depositor);
+			if added > freed {
+				let amount = deposit_for(added - freed);
+				T::Currency::reserve(depositor, amount)
+					.map_err(|_| Error::<T>::StorageDepositFailed)?;
+				<StorageDeposits<T>>::insert(address, depositor, held.saturating_add(amount));
+				Self::deposit_event(Event::<T>::StorageDepositReserved(depositor.clone(), address, amount));
+			} else if !held.is_zero() {
+				let amount = deposit_for(freed - added).min(held);
+				T::Currency::unreserve(depositor, amount);
+				if amount == held {
+					<StorageDeposits<T>>::remove(address, depositor);
+				} else {
+					<StorageDeposits<T>>::insert(address, depositor, held - amount);
+				}
+				Self::deposit_event(Event::<T>::StorageDepositReleased(depositor.clone(), address, amount));
+			}
+		}
+
+		Ok(())
 	}
 
 	/// Execute a create transaction on behalf of given sender.
+	///
+	/// If a `depositor` is given, the storage deposit for the state growth is reserved from it.
 	pub fn execute_create(
 		source: H160,
 		init: Vec<u8>,
 		value: U256,
 		gas_limit: u32,
 		gas_price: U256,
-		nonce: Option<U256>
+		nonce: Option<U256>,
+		depositor: Option<&T::AccountId>,
 	) -> Result<(H160, ExitReason), Error<T>> {
 		Self::execute_evm(
 			source,
+			TraceAction::Create { init },
 			value,
 			gas_limit,
 			gas_price,
 			nonce,
-			|executor| {
-				(executor.create_address(
-					evm::CreateScheme::Legacy { caller: source },
-				), executor.transact_create(
-					source,
-					value,
-					init,
-					gas_limit as usize,
-				))
-			},
+			depositor,
 		)
 	}
 
 	/// Execute a create2 transaction on behalf of a given sender.
+	///
+	/// If a `depositor` is given, the storage deposit for the state growth is reserved from it.
 	pub fn execute_create2(
 		source: H160,
 		init: Vec<u8>,
@@ -463,30 +880,23 @@ impl<T: Trait> Module<T> {
 		value: U256,
 		gas_limit: u32,
 		gas_price: U256,
-		nonce: Option<U256>
+		nonce: Option<U256>,
+		depositor: Option<&T::AccountId>,
 	) -> Result<(H160, ExitReason), Error<T>> {
-		let code_hash = H256::from_slice(Keccak256::digest(&init).as_slice());
 		Self::execute_evm(
 			source,
+			TraceAction::Create2 { init, salt },
 			value,
 			gas_limit,
 			gas_price,
 			nonce,
-			|executor| {
-				(executor.create_address(
-					evm::CreateScheme::Create2 { caller: source, code_hash, salt },
-				), executor.transact_create2(
-					source,
-					value,
-					init,
-					salt,
-					gas_limit as usize,
-				))
-			},
+			depositor,
 		)
 	}
 
 	/// Execute a call transaction on behalf of a given sender.
+	///
+	/// If a `depositor` is given, the storage deposit for the state growth is reserved from it.
 	pub fn execute_call(
 		source: H160,
 		target: H160,
@@ -495,34 +905,131 @@ impl<T: Trait> Module<T> {
 		gas_limit: u32,
 		gas_price: U256,
 		nonce: Option<U256>,
+		depositor: Option<&T::AccountId>,
 	) -> Result<ExitReason, Error<T>> {
 		Ok(Self::execute_evm(
 			source,
+			TraceAction::Call { target, input },
 			value,
 			gas_limit,
 			gas_price,
 			nonce,
-			|executor| ((), executor.transact_call(
-				source,
-				target,
-				value,
-				input,
-				gas_limit as usize,
-			)),
+			depositor,
 		)?.1)
 	}
 
+	/// Replay an EVM operation on behalf of a given sender against the current state, recording
+	/// every step of every frame it enters. Nothing is written to storage.
+	pub fn trace(
+		source: H160,
+		action: TraceAction,
+		value: U256,
+		gas_limit: u32,
+		gas_price: U256,
+	) -> Result<TraceResult, Error<T>> {
+		let vicinity = Vicinity {
+			gas_price,
+			origin: source,
+		};
+		let backend = Backend::<T>::new(&vicinity);
+
+		tracer::replay(&backend, source, action, value, gas_limit, gas_price)
+	}
+
+	/// Run `action` on `executor` with its `transact_*` function, returning the address called or
+	/// created and the exit reason.
+	///
+	/// A token called by the transaction itself is told the transaction is its caller. Creating a
+	/// contract where the storage of a destroyed one is still being cleared collides.
+	fn transact<B: BackendT>(
+		executor: &mut StackExecutor<B>,
+		source: H160,
+		action: TraceAction,
+		value: U256,
+		gas_limit: u32,
+	) -> (H160, ExitReason) {
+		match action {
+			TraceAction::Call { target, input } => {
+				let transact_call = || executor.transact_call(
+					source,
+					target,
+					value,
+					input,
+					gas_limit as usize,
+				);
+				// A call to a token runs nothing but its precompile, so no other frame is told
+				// the caller.
+				let reason = if erc20::token_asset(&target).is_some() {
+					erc20::with_caller(source, transact_call)
+				} else {
+					transact_call()
+				};
+				(target, reason)
+			},
+			TraceAction::Create { init } => {
+				let address = executor.create_address(
+					evm::CreateScheme::Legacy { caller: source },
+				);
+				if Self::is_pending_deletion(address) {
+					return (address, ExitError::CreateCollision.into());
+				}
+				(address, executor.transact_create(
+					source,
+					value,
+					init,
+					gas_limit as usize,
+				))
+			},
+			TraceAction::Create2 { init, salt } => {
+				let code_hash = H256::from_slice(Keccak256::digest(&init).as_slice());
+				let address = executor.create_address(
+					evm::CreateScheme::Create2 { caller: source, code_hash, salt },
+				);
+				if Self::is_pending_deletion(address) {
+					return (address, ExitError::CreateCollision.into());
+				}
+				(address, executor.transact_create2(
+					source,
+					value,
+					init,
+					salt,
+					gas_limit as usize,
+				))
+			},
+		}
+	}
+
 	/// Execute an EVM operation.
-	fn execute_evm<F, R>(
+	///
+	/// Nothing is kept, token writes included, if the operation fails to be applied, such as when
+	/// its storage deposit cannot be reserved.
+	fn execute_evm(
 		source: H160,
+		action: TraceAction,
 		value: U256,
 		gas_limit: u32,
 		gas_price: U256,
 		nonce: Option<U256>,
-		f: F,
-	) -> Result<(R, ExitReason), Error<T>> where
-		F: FnOnce(&mut StackExecutor<Backend<T>>) -> (R, ExitReason),
-	{
+		depositor: Option<&T::AccountId>,
+	) -> Result<(H160, ExitReason), Error<T>> {
+		with_transaction(|| {
+			match Self::apply_evm(source, action, value, gas_limit, gas_price, nonce, depositor) {
+				Ok(result) => TransactionOutcome::Commit(Ok(result)),
+				Err(error) => TransactionOutcome::Rollback(Err(error)),
+			}
+		})
+	}
+
+	/// Execute an EVM operation and apply its changes to state.
+	fn apply_evm(
+		source: H160,
+		action: TraceAction,
+		value: U256,
+		gas_limit: u32,
+		gas_price: U256,
+		nonce: Option<U256>,
+		depositor: Option<&T::AccountId>,
+	) -> Result<(H160, ExitReason), Error<T>> {
 		let vicinity = Vicinity {
 			gas_price,
 			origin: source,
@@ -533,7 +1040,7 @@ impl<T: Trait> Module<T> {
 			&backend,
 			gas_limit as usize,
 			T::config(),
-			T::Precompiles::execute,
+			Module::<T>::execute_precompile,
 		);
 
 		let total_fee = gas_price.checked_mul(U256::from(gas_limit))
@@ -547,14 +1054,35 @@ impl<T: Trait> Module<T> {
 			ensure!(source_account.nonce == nonce, Error::<T>::InvalidNonce);
 		}
 
-		let (retv, reason) = f(&mut executor);
+		// Token precompiles write to storage directly, so keep their writes only if the operation
+		// succeeds. See the `erc20` module.
+		let (address, reason) = with_transaction(|| {
+			let (address, reason) = Self::transact(&mut executor, source, action, value, gas_limit);
+			match reason {
+				ExitReason::Succeed(_) => TransactionOutcome::Commit((address, reason)),
+				_ => TransactionOutcome::Rollback((address, reason)),
+			}
+		});
 
 		let actual_fee = executor.fee(gas_price);
 		executor.deposit(source, total_fee.saturating_sub(actual_fee));
 
 		let (values, logs) = executor.deconstruct();
+		let values = values.into_iter().map(|apply| match apply {
+			Apply::Modify { address, basic, code, storage, reset_storage } => Apply::Modify {
+				address,
+				basic,
+				code,
+				storage: storage.into_iter().collect::<Vec<_>>(),
+				reset_storage,
+			},
+			Apply::Delete { address } => Apply::Delete { address },
+		}).collect::<Vec<_>>();
+		if let Some(depositor) = depositor {
+			Self::settle_storage_deposit(depositor, &values)?;
+		}
 		backend.apply(values, logs, true);
 
-		Ok((retv, reason))
+		Ok((address, reason))
 	}
 }
@@ -4,7 +4,7 @@ use super::*;
 
 use std::{str::FromStr, collections::BTreeMap};
 use frame_support::{
-	assert_ok, impl_outer_origin, parameter_types, impl_outer_dispatch,
+	assert_ok, assert_noop, impl_outer_origin, parameter_types, impl_outer_dispatch,
 };
 use sp_core::H256;
 // The testing primitives are very useful for avoiding having to work with signatures
@@ -91,8 +91,60 @@ impl FeeCalculator for FixedGasPrice {
 		0.into()
 	}
 }
+/// Bridges the native balance as asset `0`.
+pub struct NativeAsset;
+impl Assets<H256> for NativeAsset {
+	fn exists(asset: u32) -> bool {
+		asset == 0
+	}
+
+	fn total_supply(_asset: u32) -> U256 {
+		U256::from(Balances::total_issuance())
+	}
+
+	fn balance(_asset: u32, who: &H256) -> U256 {
+		U256::from(Balances::free_balance(who))
+	}
+
+	fn transfer(
+		_asset: u32,
+		source: &H256,
+		dest: &H256,
+		amount: U256,
+	) -> Result<(), DispatchError> {
+		<Balances as Currency<_>>::transfer(
+			source,
+			dest,
+			amount.low_u64(),
+			ExistenceRequirement::KeepAlive,
+		)
+	}
+}
+
+/// Storage key written by the precompile at `0x…09`.
+const PRECOMPILE_KEY: &[u8] = b":test:precompile";
+
+/// A precompile at `0x…09` that writes to runtime storage directly, as token precompiles do.
+pub struct StoringPrecompile;
+impl Precompiles for StoringPrecompile {
+	fn execute(
+		address: H160,
+		_input: &[u8],
+		_target_gas: Option<usize>
+	) -> Option<core::result::Result<(ExitSucceed, Vec<u8>, usize), ExitError>> {
+		if address == H160::from_low_u64_be(9) {
+			frame_support::storage::unhashed::put(PRECOMPILE_KEY, &true);
+			Some(Ok((ExitSucceed::Stopped, Vec::new(), 0)))
+		} else {
+			None
+		}
+	}
+}
+
 parameter_types! {
 	pub const EVMModuleId: ModuleId = ModuleId(*b"py/evmpa");
+	pub const StorageDepositPerByte: u64 = 1;
+	pub const DeletionWeightLimit: Weight = 3;
 }
 impl Trait for Test {
 	type ChainId = SystemChainId;
@@ -101,7 +153,11 @@ impl Trait for Test {
 	type ConvertAccountId = HashTruncateConvertAccountId<BlakeTwo256>;
 	type Currency = Balances;
 	type Event = Event<Test>;
-	type Precompiles = ();
+	type Precompiles = StoringPrecompile;
+	type StorageDepositPerByte = StorageDepositPerByte;
+	type DeletionWeightLimit = DeletionWeightLimit;
+	type Assets = NativeAsset;
+	type AddressMapping = HashedAddressMapping<BlakeTwo256>;
 }
 
 type System = frame_system::Module<Test>;
@@ -137,8 +193,41 @@ pub fn new_test_ext() -> sp_io::TestExternalities {
 		}
 	);
 
+	accounts.insert(
+		H160::from_str("1000000000000000000000000000000000000003").unwrap(),
+		GenesisAccount {
+			nonce: U256::from(1),
+			balance: U256::from(1000000),
+			storage: Default::default(),
+			code: vec![
+				0x60, 0x2a, // PUSH1 0x2a
+				0x60, 0x00, // PUSH1 0x00
+				0x52,       // MSTORE
+				0x60, 0x20, // PUSH1 0x20
+				0x60, 0x00, // PUSH1 0x00
+				0xfd,       // REVERT
+			],
+		}
+	);
+	accounts.insert(
+		H160::from_str("1000000000000000000000000000000000000004").unwrap(),
+		GenesisAccount {
+			nonce: U256::from(1),
+			balance: U256::from(1000000),
+			storage: Default::default(),
+			code: vec![
+				0x60, 0x01, // PUSH1 0x01
+				0x60, 0x00, // PUSH1 0x00
+				0x55,       // SSTORE
+				0x00,       // STOP
+			],
+		}
+	);
+
 	// We use default for brevity, but you can configure as desired if needed.
-	pallet_balances::GenesisConfig::<Test>::default().assimilate_storage(&mut t).unwrap();
+	pallet_balances::GenesisConfig::<Test> {
+		balances: vec![(H256::repeat_byte(1), 1000)],
+	}.assimilate_storage(&mut t).unwrap();
 	GenesisConfig { accounts }.assimilate_storage(&mut t).unwrap();
 	t.into()
 }
@@ -167,3 +256,490 @@ fn fail_call_return_ok() {
 		));
 	});
 }
+
+#[test]
+fn reverted_call_reports_revert_data_when_traced() {
+	new_test_ext().execute_with(|| {
+		let target = H160::from_str("1000000000000000000000000000000000000003").unwrap();
+		let traced = EVM::trace(
+			H160::default(),
+			TraceAction::Call { target, input: Vec::new() },
+			U256::default(),
+			1000000,
+			U256::default(),
+		).unwrap();
+		let reason = EVM::execute_call(
+			H160::default(),
+			target,
+			Vec::new(),
+			U256::default(),
+			1000000,
+			U256::default(),
+			None,
+			None,
+		).unwrap();
+
+		assert_eq!(reason, ExitReason::Revert(evm::ExitRevert::Reverted));
+		assert_eq!(U256::from_big_endian(&traced.output), U256::from(0x2a));
+		assert_eq!(traced.failure, Some(FailureReason::Revert(traced.output.clone())));
+	});
+}
+
+#[test]
+fn executed_call_applies_frame_state() {
+	new_test_ext().execute_with(|| {
+		let target = H160::from_str("1000000000000000000000000000000000000004").unwrap();
+		let reason = EVM::execute_call(
+			H160::default(),
+			target,
+			Vec::new(),
+			U256::default(),
+			1000000,
+			U256::default(),
+			None,
+			None,
+		).unwrap();
+
+		assert_eq!(reason, ExitReason::Succeed(ExitSucceed::Stopped));
+		assert_eq!(AccountStorages::get(target, H256::zero()), H256::from_low_u64_be(1));
+		assert_eq!(Accounts::get(H160::default()).nonce, U256::one());
+	});
+}
+
+#[test]
+fn trace_records_steps_without_applying_state() {
+	new_test_ext().execute_with(|| {
+		let target = H160::from_str("1000000000000000000000000000000000000004").unwrap();
+		let result = EVM::trace(
+			H160::default(),
+			TraceAction::Call { target, input: Vec::new() },
+			U256::default(),
+			1000000,
+			U256::default(),
+		).unwrap();
+
+		assert_eq!(result.failure, None);
+		assert_eq!(result.steps.iter().map(|step| step.pc).collect::<Vec<_>>(), vec![0, 2, 4, 5]);
+		assert_eq!(result.steps[2].stack.len(), 2);
+		assert_eq!(result.steps[2].storage, Some(StorageWrite {
+			key: H256::zero(),
+			value: H256::from_low_u64_be(1),
+		}));
+		assert!(result.steps.iter().all(|step| step.gas_cost <= step.gas && step.depth == 1));
+		assert_eq!(AccountStorages::get(target, H256::zero()), H256::zero());
+	});
+}
+
+#[test]
+fn trace_records_steps_of_nested_frames() {
+	new_test_ext().execute_with(|| {
+		let inner = H160::from_str("1000000000000000000000000000000000000004").unwrap();
+		let outer = H160::repeat_byte(5);
+		let mut code = vec![
+			0x60, 0x00, // PUSH1 0x00
+			0x60, 0x00, // PUSH1 0x00
+			0x60, 0x00, // PUSH1 0x00
+			0x60, 0x00, // PUSH1 0x00
+			0x60, 0x00, // PUSH1 0x00
+			0x73,       // PUSH20 inner
+		];
+		code.extend_from_slice(inner.as_bytes());
+		code.extend_from_slice(&[
+			0x5a,       // GAS
+			0xf1,       // CALL
+			0x00,       // STOP
+		]);
+		AccountCodes::insert(outer, code);
+
+		let result = EVM::trace(
+			H160::default(),
+			TraceAction::Call { target: outer, input: Vec::new() },
+			U256::default(),
+			1000000,
+			U256::default(),
+		).unwrap();
+
+		assert_eq!(result.failure, None);
+		assert_eq!(
+			result.steps.iter().map(|step| (step.depth, step.pc)).collect::<Vec<_>>(),
+			vec![
+				(1, 0), (1, 2), (1, 4), (1, 6), (1, 8), (1, 10), (1, 31), (1, 32),
+				(2, 0), (2, 2), (2, 4), (2, 5),
+				(1, 33),
+			],
+		);
+		assert_eq!(result.steps[10].storage, Some(StorageWrite {
+			key: H256::zero(),
+			value: H256::from_low_u64_be(1),
+		}));
+		// The call charges at least the gas its frame used.
+		assert!(result.steps[7].gas_cost >= result.steps[8].gas - result.steps[11].gas);
+		assert_eq!(AccountStorages::get(inner, H256::zero()), H256::zero());
+	});
+}
+
+#[test]
+fn trace_records_memory_writes_and_revert() {
+	new_test_ext().execute_with(|| {
+		let result = EVM::trace(
+			H160::default(),
+			TraceAction::Call {
+				target: H160::from_str("1000000000000000000000000000000000000003").unwrap(),
+				input: Vec::new(),
+			},
+			U256::default(),
+			1000000,
+			U256::default(),
+		).unwrap();
+
+		let mut word = [0u8; 32];
+		word[31] = 0x2a;
+		assert_eq!(result.steps[2].memory, vec![MemoryDiff { offset: 0, data: word.to_vec() }]);
+		assert_eq!(result.failure, Some(FailureReason::Revert(word.to_vec())));
+	});
+}
+
+#[test]
+fn decode_revert_message_works() {
+	let mut data = vec![0x08, 0xc3, 0x79, 0xa0];
+	data.extend_from_slice(H256::from_low_u64_be(32).as_bytes());
+	data.extend_from_slice(H256::from_low_u64_be(4).as_bytes());
+	data.extend_from_slice(b"oops");
+	data.extend_from_slice(&[0u8; 28]);
+
+	assert_eq!(decode_revert_message(&data), Some(b"oops".to_vec()));
+	assert_eq!(decode_revert_message(&data[..40]), None);
+	assert_eq!(decode_revert_message(&[0u8; 100]), None);
+}
+
+#[test]
+fn storage_deposit_is_reserved_from_caller() {
+	new_test_ext().execute_with(|| {
+		let caller = H256::repeat_byte(1);
+		let target = H160::from_str("1000000000000000000000000000000000000004").unwrap();
+		let call = || EVM::call(
+			Origin::signed(caller),
+			target,
+			Vec::new(),
+			U256::default(),
+			1000000,
+			U256::default(),
+			None,
+		);
+
+		assert_ok!(call());
+		assert_eq!(Balances::reserved_balance(caller), 64);
+		assert_eq!(EVM::storage_deposits(target, caller), 64);
+
+		// Overwriting an existing slot does not grow the state.
+		assert_ok!(call());
+		assert_eq!(Balances::reserved_balance(caller), 64);
+	});
+}
+
+#[test]
+fn storage_deposit_fails_without_free_balance() {
+	new_test_ext().execute_with(|| {
+		let target = H160::from_str("1000000000000000000000000000000000000004").unwrap();
+
+		assert_noop!(
+			EVM::call(
+				Origin::signed(H256::default()),
+				target,
+				Vec::new(),
+				U256::default(),
+				1000000,
+				U256::default(),
+				None,
+			),
+			Error::<Test>::StorageDepositFailed,
+		);
+		assert_eq!(AccountStorages::get(target, H256::zero()), H256::zero());
+	});
+}
+
+#[test]
+fn failed_storage_deposit_reverts_precompile_writes() {
+	new_test_ext().execute_with(|| {
+		let target = H160::repeat_byte(5);
+		AccountCodes::insert(target, vec![
+			0x60, 0x00, // PUSH1 0x00
+			0x60, 0x00, // PUSH1 0x00
+			0x60, 0x00, // PUSH1 0x00
+			0x60, 0x00, // PUSH1 0x00
+			0x60, 0x00, // PUSH1 0x00
+			0x60, 0x09, // PUSH1 0x09
+			0x5a,       // GAS
+			0xf1,       // CALL
+			0x50,       // POP
+			0x60, 0x01, // PUSH1 0x01
+			0x60, 0x00, // PUSH1 0x00
+			0x55,       // SSTORE
+			0x00,       // STOP
+		]);
+		let call = |origin: H256| EVM::call(
+			Origin::signed(origin),
+			target,
+			Vec::new(),
+			U256::default(),
+			1000000,
+			U256::default(),
+			None,
+		);
+
+		assert_noop!(call(H256::default()), Error::<Test>::StorageDepositFailed);
+		assert_eq!(frame_support::storage::unhashed::get::<bool>(PRECOMPILE_KEY), None);
+
+		assert_ok!(call(H256::repeat_byte(1)));
+		assert_eq!(frame_support::storage::unhashed::get::<bool>(PRECOMPILE_KEY), Some(true));
+		assert_eq!(AccountStorages::get(target, H256::zero()), H256::from_low_u64_be(1));
+	});
+}
+
+#[test]
+fn destroyed_contract_storage_is_cleared_lazily() {
+	new_test_ext().execute_with(|| {
+		let caller = H256::repeat_byte(1);
+		let target = H160::from_str("1000000000000000000000000000000000000004").unwrap();
+		for i in 1..5 {
+			AccountStorages::insert(target, H256::from_low_u64_be(i), H256::from_low_u64_be(i));
+		}
+		assert_ok!(EVM::call(
+			Origin::signed(caller),
+			target,
+			Vec::new(),
+			U256::default(),
+			1000000,
+			U256::default(),
+			None,
+		));
+		assert_eq!(Balances::reserved_balance(caller), 64);
+
+		EVM::remove_account(&target);
+		assert!(EVM::is_pending_deletion(target));
+		assert_eq!(EVM::deletion_queue(0), Some(target));
+		assert_eq!((EVM::deletion_queue_head(), EVM::deletion_queue_tail()), (0, 1));
+		let vicinity = Vicinity::default();
+		let backend = Backend::<Test>::new(&vicinity);
+		assert_eq!(evm::backend::Backend::storage(&backend, target, H256::zero()), H256::zero());
+		assert_eq!(evm::backend::Backend::basic(&backend, target).nonce, U256::one());
+
+		// Five slots are cleared three per block; the deposit is released once they are gone.
+		assert_eq!(EVM::process_deletion_queue(DeletionWeightLimit::get()), 3);
+		assert_eq!(AccountStorages::iter_prefix(target).count(), 2);
+		assert_eq!(Balances::reserved_balance(caller), 64);
+
+		EVM::process_deletion_queue(DeletionWeightLimit::get());
+		assert_eq!(AccountStorages::iter_prefix(target).count(), 0);
+		assert_eq!(Balances::reserved_balance(caller), 0);
+		assert!(EVM::is_pending_deletion(target));
+
+		EVM::process_deletion_queue(DeletionWeightLimit::get());
+		assert!(!EVM::is_pending_deletion(target));
+		assert_eq!(EVM::deletion_queue(0), None);
+		assert_eq!((EVM::deletion_queue_head(), EVM::deletion_queue_tail()), (1, 1));
+	});
+}
+
+#[test]
+fn create_over_storage_pending_deletion_collides() {
+	new_test_ext().execute_with(|| {
+		let init = vec![0x00]; // STOP
+		let created = EVM::trace(
+			H160::default(),
+			TraceAction::Create { init: init.clone() },
+			U256::default(),
+			1000000,
+			U256::default(),
+		).unwrap().created.unwrap();
+		AccountStorages::insert(created, H256::zero(), H256::from_low_u64_be(1));
+		PendingDeletion::insert(created, true);
+
+		let (address, reason) = EVM::execute_create(
+			H160::default(),
+			init,
+			U256::default(),
+			1000000,
+			U256::default(),
+			None,
+			None,
+		).unwrap();
+
+		assert_eq!(address, created);
+		assert_eq!(reason, ExitReason::Error(ExitError::CreateCollision));
+		assert_eq!(AccountStorages::get(created, H256::zero()), H256::from_low_u64_be(1));
+		assert_eq!(EVM::accounts(created).nonce, U256::zero());
+	});
+}
+
+fn token_call(selector: [u8; 4], args: &[H256]) -> Vec<u8> {
+	let mut input = selector.to_vec();
+	for arg in args {
+		input.extend_from_slice(arg.as_bytes());
+	}
+	input
+}
+
+#[test]
+fn assets_are_bridged_as_erc20_tokens() {
+	new_test_ext().execute_with(|| {
+		let caller = H256::repeat_byte(1);
+		let address = HashTruncateConvertAccountId::<BlakeTwo256>::convert_account_id(&caller);
+		let holder: H256 = HashedAddressMapping::<BlakeTwo256>::into_account_id(address);
+		let token = erc20::token_address(0);
+		assert_eq!(erc20::token_asset(&token), Some(0));
+		assert_eq!(EVM::account_code(&token), erc20::CODE.to_vec());
+
+		assert_noop!(
+			EVM::deposit_asset(Origin::signed(caller), 1, U256::from(100)),
+			Error::<Test>::UnknownAsset,
+		);
+		assert_ok!(EVM::deposit_asset(Origin::signed(caller), 0, U256::from(100)));
+		assert_eq!(Balances::free_balance(caller), 900);
+		assert_eq!(Balances::free_balance(holder), 100);
+
+		assert_noop!(
+			EVM::withdraw_asset(Origin::signed(caller), 0, U256::from(101)),
+			Error::<Test>::BalanceLow,
+		);
+		assert_ok!(EVM::withdraw_asset(Origin::signed(caller), 0, U256::from(40)));
+		assert_eq!(Balances::free_balance(caller), 940);
+		assert_eq!(Balances::free_balance(holder), 60);
+	});
+}
+
+#[test]
+fn erc20_token_transfers_and_allowances_work() {
+	new_test_ext().execute_with(|| {
+		let caller = H256::repeat_byte(1);
+		let address = HashTruncateConvertAccountId::<BlakeTwo256>::convert_account_id(&caller);
+		let token = erc20::token_address(0);
+		let recipient = H160::repeat_byte(2);
+		let spender = H160::from_str("1000000000000000000000000000000000000001").unwrap();
+		let balance = |address: H160| Balances::free_balance(
+			HashedAddressMapping::<BlakeTwo256>::into_account_id(address),
+		);
+		assert_ok!(EVM::deposit_asset(Origin::signed(caller), 0, U256::from(100)));
+
+		let call = |source: H160, input: Vec<u8>| EVM::execute_call(
+			source,
+			token,
+			input,
+			U256::default(),
+			1000000,
+			U256::default(),
+			None,
+			None,
+		).unwrap();
+
+		// totalSupply() is that of the asset.
+		let result = EVM::trace(
+			address,
+			TraceAction::Call { target: token, input: token_call([0x18, 0x16, 0x0d, 0xdd], &[]) },
+			U256::default(),
+			1000000,
+			U256::default(),
+		).unwrap();
+		assert_eq!(result.failure, None);
+		assert_eq!(U256::from_big_endian(&result.output), U256::from(Balances::total_issuance()));
+
+		// transfer(recipient, 30)
+		assert_eq!(
+			call(address, token_call([0xa9, 0x05, 0x9c, 0xbb], &[recipient.into(), H256::from_low_u64_be(30)])),
+			ExitReason::Succeed(ExitSucceed::Returned),
+		);
+		assert_eq!(balance(address), 70);
+		assert_eq!(balance(recipient), 30);
+
+		// transfer(recipient, 71) exceeds the balance.
+		assert!(matches!(
+			call(address, token_call([0xa9, 0x05, 0x9c, 0xbb], &[recipient.into(), H256::from_low_u64_be(71)])),
+			ExitReason::Error(_),
+		));
+		assert_eq!(balance(address), 70);
+
+		// approve(spender, 50), then transferFrom(address, recipient, 20) by the spender.
+		assert_eq!(
+			call(address, token_call([0x09, 0x5e, 0xa7, 0xb3], &[spender.into(), H256::from_low_u64_be(50)])),
+			ExitReason::Succeed(ExitSucceed::Returned),
+		);
+		assert_eq!(EVM::allowance(0, (address, spender)), U256::from(50));
+		assert_eq!(
+			call(spender, token_call(
+				[0x23, 0xb8, 0x72, 0xdd],
+				&[address.into(), recipient.into(), H256::from_low_u64_be(20)],
+			)),
+			ExitReason::Succeed(ExitSucceed::Returned),
+		);
+		assert_eq!(balance(address), 50);
+		assert_eq!(balance(recipient), 50);
+		assert_eq!(EVM::allowance(0, (address, spender)), U256::from(30));
+
+		// The remaining allowance of 30 does not cover another 40.
+		assert!(matches!(
+			call(spender, token_call(
+				[0x23, 0xb8, 0x72, 0xdd],
+				&[address.into(), recipient.into(), H256::from_low_u64_be(40)],
+			)),
+			ExitReason::Error(_),
+		));
+		assert_eq!(balance(address), 50);
+	});
+}
+
+#[test]
+fn erc20_token_refuses_state_changes_from_contracts() {
+	new_test_ext().execute_with(|| {
+		let token = erc20::token_address(0);
+		let recipient = H160::repeat_byte(2);
+		let forwarder = H160::repeat_byte(3);
+		let holder: H256 = HashedAddressMapping::<BlakeTwo256>::into_account_id(forwarder);
+		let mut code = vec![
+			0x36,       // CALLDATASIZE
+			0x60, 0x00, // PUSH1 0x00
+			0x60, 0x00, // PUSH1 0x00
+			0x37,       // CALLDATACOPY
+			0x60, 0x20, // PUSH1 0x20
+			0x60, 0x00, // PUSH1 0x00
+			0x36,       // CALLDATASIZE
+			0x60, 0x00, // PUSH1 0x00
+			0x60, 0x00, // PUSH1 0x00
+			0x73,       // PUSH20 token
+		];
+		code.extend_from_slice(token.as_bytes());
+		code.extend_from_slice(&[
+			0x5a,       // GAS
+			0xf1,       // CALL
+			0x15,       // ISZERO
+			0x60, 0x2b, // PUSH1 0x2b
+			0x57,       // JUMPI
+			0x00,       // STOP
+			0x5b,       // JUMPDEST
+			0x60, 0x00, // PUSH1 0x00
+			0x80,       // DUP1
+			0xfd,       // REVERT
+		]);
+		AccountCodes::insert(forwarder, code);
+		assert_ok!(Balances::transfer(Origin::signed(H256::repeat_byte(1)), holder, 100));
+
+		let transfer = token_call([0xa9, 0x05, 0x9c, 0xbb], &[recipient.into(), H256::from_low_u64_be(30)]);
+		let call = |source: H160, target: H160| EVM::execute_call(
+			source,
+			target,
+			transfer.clone(),
+			U256::default(),
+			1000000,
+			U256::default(),
+			None,
+			None,
+		).unwrap();
+
+		// The token does not know the forwarder called it, so it refuses to move its funds.
+		assert_eq!(call(H160::default(), forwarder), ExitReason::Revert(evm::ExitRevert::Reverted));
+		assert_eq!(Balances::free_balance(holder), 100);
+
+		// Called by a transaction of the forwarder's address, it does.
+		assert_eq!(call(forwarder, token), ExitReason::Succeed(ExitSucceed::Returned));
+		assert_eq!(Balances::free_balance(holder), 70);
+	});
+}
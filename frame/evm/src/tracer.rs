@@ -0,0 +1,511 @@
+//! Step-by-step replay of EVM operations.
+//!
+//! Replays never apply anything to storage. A replayed operation is run by the executor's own
+//! `transact_*`, exactly as `execute_evm` runs it, which decides its outcome and the gas it uses.
+//! Steps are recorded on the side: before the operation, and before every message call or create
+//! it makes, the frame about to be entered is stepped through on a copy of the state it is going
+//! to see. Frames run the same on that copy, so their steps, at any depth, are those of the real
+//! run.
+
+use sp_std::{vec::Vec, rc::Rc, convert::Infallible, marker::PhantomData};
+#[cfg(feature = "std")]
+use serde::{Serialize, Deserialize};
+use codec::{Encode, Decode};
+use sp_core::{U256, H256, H160};
+use sp_runtime::RuntimeDebug;
+use sha3::{Digest, Keccak256};
+use evm::{
+	Capture, Context, CreateScheme, ExitError, ExitReason, ExternalOpcode, Handler, Opcode,
+	Runtime, Stack, Transfer,
+};
+use evm::gasometer::{self, Gasometer};
+use evm::executor::StackExecutor;
+use evm::backend::Backend as BackendT;
+use frame_support::storage::with_transaction;
+use sp_runtime::TransactionOutcome;
+use crate::{Trait, Error, Accounts, Backend, Module, FailureReason, erc20};
+
+const STOP: u8 = 0x00;
+const SSTORE: u8 = 0x55;
+
+/// Top-level EVM operation, as issued by the `call`, `create` and `create2` dispatchables.
+#[derive(Clone, Eq, PartialEq, Encode, Decode, RuntimeDebug)]
+#[cfg_attr(feature = "std", derive(Serialize, Deserialize))]
+pub enum TraceAction {
+	/// Message call to `target` with `input`.
+	Call {
+		/// Address being called.
+		target: H160,
+		/// Call data.
+		input: Vec<u8>,
+	},
+	/// Contract creation running `init`.
+	Create {
+		/// Init code of the contract.
+		init: Vec<u8>,
+	},
+	/// Contract creation running `init`, at an address derived from `salt`.
+	Create2 {
+		/// Init code of the contract.
+		init: Vec<u8>,
+		/// Salt of the contract address.
+		salt: H256,
+	},
+}
+
+/// Storage slot written by a step.
+#[derive(Clone, Eq, PartialEq, Encode, Decode, RuntimeDebug)]
+#[cfg_attr(feature = "std", derive(Serialize, Deserialize))]
+pub struct StorageWrite {
+	/// Storage key.
+	pub key: H256,
+	/// Value written.
+	pub value: H256,
+}
+
+/// Contiguous memory region changed by a step, in whole 32-byte words.
+#[derive(Clone, Eq, PartialEq, Encode, Decode, RuntimeDebug)]
+#[cfg_attr(feature = "std", derive(Serialize, Deserialize))]
+pub struct MemoryDiff {
+	/// Offset of the region.
+	pub offset: u32,
+	/// New content of the region.
+	pub data: Vec<u8>,
+}
+
+/// One executed opcode.
+#[derive(Clone, Eq, PartialEq, Encode, Decode, RuntimeDebug)]
+#[cfg_attr(feature = "std", derive(Serialize, Deserialize))]
+pub struct TraceStep {
+	/// Program counter of the opcode.
+	pub pc: u32,
+	/// The opcode.
+	pub op: u8,
+	/// Depth of the frame running the opcode, starting at 1 for the outermost frame.
+	pub depth: u32,
+	/// Gas left in the frame before the opcode ran.
+	pub gas: U256,
+	/// Gas charged for the opcode, including any nested call or create it made.
+	pub gas_cost: U256,
+	/// Stack before the opcode ran, bottom first.
+	pub stack: Vec<H256>,
+	/// Memory changed by the opcode.
+	pub memory: Vec<MemoryDiff>,
+	/// Storage written by the opcode, for `SSTORE`.
+	pub storage: Option<StorageWrite>,
+}
+
+/// Outcome of a replayed EVM operation.
+#[derive(Clone, Eq, PartialEq, Encode, Decode, RuntimeDebug)]
+#[cfg_attr(feature = "std", derive(Serialize, Deserialize))]
+pub struct TraceResult {
+	/// Address of the contract, for creates.
+	pub created: Option<H160>,
+	/// Why the operation failed, or `None` if it succeeded.
+	pub failure: Option<FailureReason>,
+	/// Data returned by the outermost frame.
+	pub output: Vec<u8>,
+	/// Gas used, including the intrinsic transaction cost and any refund.
+	pub gas_used: U256,
+	/// Steps of every frame, in the order they ran.
+	pub steps: Vec<TraceStep>,
+}
+
+/// Replay `action` against `backend` without applying anything, recording the steps of every
+/// frame it enters.
+pub(crate) fn replay<T: Trait>(
+	backend: &Backend<T>,
+	source: H160,
+	action: TraceAction,
+	value: U256,
+	gas_limit: u32,
+	gas_price: U256,
+) -> Result<TraceResult, Error<T>> {
+	let mut executor = StackExecutor::new_with_precompile(
+		backend,
+		gas_limit as usize,
+		T::config(),
+		Module::<T>::execute_precompile,
+	);
+
+	let total_fee = gas_price.checked_mul(U256::from(gas_limit))
+		.ok_or(Error::<T>::FeeOverflow)?;
+	let total_payment = value.checked_add(total_fee).ok_or(Error::<T>::PaymentOverflow)?;
+	if Accounts::get(&source).balance < total_payment {
+		return Err(Error::<T>::BalanceLow)
+	}
+	executor.withdraw(source, total_fee).map_err(|_| Error::<T>::WithdrawFailed)?;
+
+	let is_create = match action {
+		TraceAction::Call { .. } => false,
+		TraceAction::Create { .. } | TraceAction::Create2 { .. } => true,
+	};
+	let mut steps = Vec::new();
+	let (address, reason, output, gas_used) = with_transaction(|| {
+		let output = discard(|| trace_operation::<T, _>(
+			&executor,
+			source,
+			&action,
+			value,
+			gas_limit,
+			&mut steps,
+		));
+		let (address, reason) = Module::<T>::transact(&mut executor, source, action, value, gas_limit);
+		TransactionOutcome::Rollback((address, reason, output, executor.used_gas()))
+	});
+	let output = match reason {
+		ExitReason::Succeed(_) | ExitReason::Revert(_) => output,
+		ExitReason::Error(_) | ExitReason::Fatal(_) => Vec::new(),
+	};
+	// A colliding create never runs its init code.
+	if reason == ExitReason::Error(ExitError::CreateCollision) {
+		steps.clear();
+	}
+
+	Ok(TraceResult {
+		created: if is_create { Some(address) } else { None },
+		failure: FailureReason::from_exit(reason, output.clone()),
+		output,
+		gas_used: U256::from(gas_used),
+		steps,
+	})
+}
+
+/// Run `f` in a storage transaction that is always rolled back.
+fn discard<R>(f: impl FnOnce() -> R) -> R {
+	with_transaction(|| TransactionOutcome::Rollback(f()))
+}
+
+/// A frame about to be entered by the executor.
+struct Frame {
+	/// Code run by the frame.
+	code: Vec<u8>,
+	/// Input of the frame.
+	input: Vec<u8>,
+	/// Context the code runs in.
+	context: Context,
+	/// Value moved into the frame.
+	transfer: Option<Transfer>,
+	/// Gas given to the frame.
+	gas_limit: usize,
+	/// Whether the frame must not change state.
+	is_static: bool,
+	/// Whether the frame runs the init code of a contract created at `context.address`.
+	is_create: bool,
+}
+
+/// Step through the outermost frame of `action` on a copy of the state of `executor`, which must
+/// be that of the transaction before it ran. Returns the data returned by the frame.
+fn trace_operation<T: Trait, B: BackendT>(
+	executor: &StackExecutor<'_, '_, B>,
+	source: H160,
+	action: &TraceAction,
+	value: U256,
+	gas_limit: u32,
+	steps: &mut Vec<TraceStep>,
+) -> Vec<u8> {
+	let (cost, address, code, input, is_create) = match action {
+		TraceAction::Call { target, input } => (
+			gasometer::call_transaction_cost(input),
+			*target,
+			executor.code(*target),
+			input.clone(),
+			false,
+		),
+		TraceAction::Create { init } => (
+			gasometer::create_transaction_cost(init),
+			executor.create_address(CreateScheme::Legacy { caller: source }),
+			init.clone(),
+			Vec::new(),
+			true,
+		),
+		TraceAction::Create2 { init, salt } => {
+			let code_hash = H256::from_slice(Keccak256::digest(init).as_slice());
+			(
+				gasometer::create_transaction_cost(init),
+				executor.create_address(CreateScheme::Create2 { caller: source, code_hash, salt: *salt }),
+				init.clone(),
+				Vec::new(),
+				true,
+			)
+		},
+	};
+
+	let mut gasometer = Gasometer::new(gas_limit as usize, T::config());
+	if gasometer.record_transaction(cost).is_err() {
+		return Vec::new()
+	}
+	let gas_limit = gasometer.gas();
+
+	if is_create && (
+		executor.code_size(address) != U256::zero() || executor.nonce(address) > U256::zero()
+	) {
+		return Vec::new()
+	}
+	if !is_create {
+		let precompile = erc20::with_caller(source, || {
+			Module::<T>::execute_precompile(address, &input, Some(gas_limit))
+		});
+		if let Some(result) = precompile {
+			return result.map(|(_, output, _)| output).unwrap_or_default()
+		}
+	}
+
+	trace_frame::<T, B>(executor, Frame {
+		code,
+		input,
+		context: Context { caller: source, address, apparent_value: value },
+		transfer: Some(Transfer { source, target: address, value }),
+		gas_limit,
+		is_static: false,
+		is_create,
+	}, 1, steps)
+}
+
+/// Step through `frame` at `depth` on a copy of the state of `executor`, recording its steps and
+/// those of the frames it enters in turn. Returns the data returned by the frame.
+fn trace_frame<T: Trait, B: BackendT>(
+	executor: &StackExecutor<'_, '_, B>,
+	frame: Frame,
+	depth: u32,
+	steps: &mut Vec<TraceStep>,
+) -> Vec<u8> {
+	let config = T::config();
+	let address = frame.context.address;
+	let mut state = executor.substate(frame.gas_limit, frame.is_static);
+	if frame.is_create {
+		let account = state.account_mut(address);
+		account.reset_storage = true;
+		account.storage = Default::default();
+	}
+	if let Some(transfer) = frame.transfer {
+		if state.transfer(transfer).is_err() {
+			return Vec::new()
+		}
+	}
+	if frame.is_create && config.create_increase_nonce {
+		state.account_mut(address).basic.nonce += U256::one();
+	}
+
+	let code = frame.code;
+	let mut runtime = Runtime::new(Rc::new(code.clone()), Rc::new(frame.input), frame.context, config);
+	let mut tracer = Tracer::<T, B> { executor: &mut state, depth, steps, _marker: PhantomData };
+
+	loop {
+		let pc = match runtime.machine().position() {
+			Ok(pc) => *pc,
+			Err(_) => break,
+		};
+		// Running past the end of the code is an implicit `STOP`.
+		let op = code.get(pc).cloned().unwrap_or(STOP);
+		let gas = tracer.executor.gas();
+		let stack = runtime.machine().stack();
+		let index = tracer.steps.len();
+		tracer.steps.push(TraceStep {
+			pc: pc as u32,
+			op,
+			depth,
+			gas: U256::from(gas),
+			gas_cost: U256::zero(),
+			stack: stack_items(stack),
+			memory: Vec::new(),
+			storage: match (op, stack.peek(0), stack.peek(1)) {
+				(SSTORE, Ok(key), Ok(value)) => Some(StorageWrite { key, value }),
+				_ => None,
+			},
+		});
+		let memory_before = if writes_memory(op) { Some(memory(&runtime)) } else { None };
+
+		let exited = runtime.step(&mut tracer).is_err();
+
+		let step = &mut tracer.steps[index];
+		step.gas_cost = U256::from(gas.saturating_sub(tracer.executor.gas()));
+		if let Some(before) = memory_before {
+			step.memory = memory_diff(&before, &memory(&runtime));
+		}
+		if exited {
+			break
+		}
+	}
+
+	runtime.machine().return_value()
+}
+
+/// Handler of a frame being stepped through, which records the frames it enters before letting
+/// its executor run them.
+struct Tracer<'a, 'backend, 'config, T, B: BackendT> {
+	executor: &'a mut StackExecutor<'backend, 'config, B>,
+	depth: u32,
+	steps: &'a mut Vec<TraceStep>,
+	_marker: PhantomData<T>,
+}
+
+impl<'a, 'backend, 'config, T: Trait, B: BackendT> Tracer<'a, 'backend, 'config, T, B> {
+	/// Gas the executor gives a frame entered now with `target_gas`, before any stipend.
+	fn nested_gas_limit(&self, target_gas: Option<usize>) -> usize {
+		let gas = self.executor.gas();
+		let after_gas = if T::config().call_l64_after_gas { gas - gas / 64 } else { gas };
+		target_gas.map_or(after_gas, |target_gas| target_gas.min(after_gas))
+	}
+
+	/// Record the steps of `frame`, unless the executor refuses to enter it for being too deep.
+	fn trace_nested(&mut self, frame: Frame) {
+		if self.depth as usize > T::config().call_stack_limit {
+			return
+		}
+		discard(|| trace_frame::<T, B>(&*self.executor, frame, self.depth + 1, &mut *self.steps));
+	}
+}
+
+impl<'a, 'backend, 'config, T: Trait, B: BackendT> Handler for Tracer<'a, 'backend, 'config, T, B> {
+	type CreateInterrupt = Infallible;
+	type CreateFeedback = Infallible;
+	type CallInterrupt = Infallible;
+	type CallFeedback = Infallible;
+
+	fn balance(&self, address: H160) -> U256 { self.executor.balance(address) }
+	fn code_size(&self, address: H160) -> U256 { self.executor.code_size(address) }
+	fn code_hash(&self, address: H160) -> H256 { self.executor.code_hash(address) }
+	fn code(&self, address: H160) -> Vec<u8> { self.executor.code(address) }
+	fn storage(&self, address: H160, index: H256) -> H256 { self.executor.storage(address, index) }
+	fn original_storage(&self, address: H160, index: H256) -> H256 {
+		self.executor.original_storage(address, index)
+	}
+	fn gas_left(&self) -> U256 { self.executor.gas_left() }
+	fn gas_price(&self) -> U256 { self.executor.gas_price() }
+	fn origin(&self) -> H160 { self.executor.origin() }
+	fn block_hash(&self, number: U256) -> H256 { self.executor.block_hash(number) }
+	fn block_number(&self) -> U256 { self.executor.block_number() }
+	fn block_coinbase(&self) -> H160 { self.executor.block_coinbase() }
+	fn block_timestamp(&self) -> U256 { self.executor.block_timestamp() }
+	fn block_difficulty(&self) -> U256 { self.executor.block_difficulty() }
+	fn block_gas_limit(&self) -> U256 { self.executor.block_gas_limit() }
+	fn chain_id(&self) -> U256 { self.executor.chain_id() }
+	fn exists(&self, address: H160) -> bool { self.executor.exists(address) }
+	fn deleted(&self, address: H160) -> bool { self.executor.deleted(address) }
+
+	fn set_storage(&mut self, address: H160, index: H256, value: H256) -> Result<(), ExitError> {
+		self.executor.set_storage(address, index, value)
+	}
+
+	fn log(&mut self, address: H160, topics: Vec<H256>, data: Vec<u8>) -> Result<(), ExitError> {
+		self.executor.log(address, topics, data)
+	}
+
+	fn mark_delete(&mut self, address: H160, target: H160) -> Result<(), ExitError> {
+		self.executor.mark_delete(address, target)
+	}
+
+	fn create(
+		&mut self,
+		caller: H160,
+		scheme: CreateScheme,
+		value: U256,
+		init_code: Vec<u8>,
+		target_gas: Option<usize>,
+	) -> Capture<(ExitReason, Option<H160>, Vec<u8>), Self::CreateInterrupt> {
+		let address = self.executor.create_address(scheme);
+		let collides = self.executor.code_size(address) != U256::zero() ||
+			self.executor.nonce(address) > U256::zero();
+		if !collides {
+			let gas_limit = self.nested_gas_limit(target_gas);
+			self.trace_nested(Frame {
+				code: init_code.clone(),
+				input: Vec::new(),
+				context: Context { caller, address, apparent_value: value },
+				transfer: Some(Transfer { source: caller, target: address, value }),
+				gas_limit,
+				is_static: false,
+				is_create: true,
+			});
+		}
+
+		self.executor.create(caller, scheme, value, init_code, target_gas)
+	}
+
+	fn call(
+		&mut self,
+		code_address: H160,
+		transfer: Option<Transfer>,
+		input: Vec<u8>,
+		target_gas: Option<usize>,
+		is_static: bool,
+		context: Context,
+	) -> Capture<(ExitReason, Vec<u8>), Self::CallInterrupt> {
+		let mut gas_limit = self.nested_gas_limit(target_gas);
+		if transfer.as_ref().map_or(false, |transfer| !transfer.value.is_zero()) {
+			gas_limit = gas_limit.saturating_add(T::config().call_stipend);
+		}
+		let is_precompile = discard(|| {
+			Module::<T>::execute_precompile(code_address, &input, Some(gas_limit)).is_some()
+		});
+		if !is_precompile {
+			self.trace_nested(Frame {
+				code: self.executor.code(code_address),
+				input: input.clone(),
+				context: context.clone(),
+				transfer: transfer.clone(),
+				gas_limit,
+				is_static,
+				is_create: false,
+			});
+		}
+
+		self.executor.call(code_address, transfer, input, target_gas, is_static, context)
+	}
+
+	fn pre_validate(
+		&mut self,
+		context: &Context,
+		opcode: Result<Opcode, ExternalOpcode>,
+		stack: &Stack,
+	) -> Result<(), ExitError> {
+		self.executor.pre_validate(context, opcode, stack)
+	}
+}
+
+/// Whether `op` may write to memory.
+fn writes_memory(op: u8) -> bool {
+	match op {
+		// CALLDATACOPY, CODECOPY, EXTCODECOPY, RETURNDATACOPY
+		0x37 | 0x39 | 0x3c | 0x3e => true,
+		// MSTORE, MSTORE8
+		0x52 | 0x53 => true,
+		// CALL, CALLCODE, DELEGATECALL, STATICCALL
+		0xf1 | 0xf2 | 0xf4 | 0xfa => true,
+		_ => false,
+	}
+}
+
+fn stack_items(stack: &Stack) -> Vec<H256> {
+	let mut items = Vec::new();
+	while let Ok(item) = stack.peek(items.len()) {
+		items.push(item);
+	}
+	items.reverse();
+	items
+}
+
+fn memory(runtime: &Runtime) -> Vec<u8> {
+	let memory = runtime.machine().memory();
+	memory.get(0, memory.len())
+}
+
+/// Word-aligned regions of `after` that differ from `before`, merging adjacent words.
+fn memory_diff(before: &[u8], after: &[u8]) -> Vec<MemoryDiff> {
+	let mut diffs: Vec<MemoryDiff> = Vec::new();
+
+	for (index, word) in after.chunks(32).enumerate() {
+		let offset = index * 32;
+		if before.get(offset..offset + word.len()) == Some(word) {
+			continue
+		}
+		match diffs.last_mut() {
+			Some(last) if last.offset as usize + last.data.len() == offset =>
+				last.data.extend_from_slice(word),
+			_ => diffs.push(MemoryDiff { offset: offset as u32, data: word.to_vec() }),
+		}
+	}
+
+	diffs
+}
@@ -0,0 +1,155 @@
+// This file is part of Substrate.
+
+// Copyright (C) 2020 Parity Technologies (UK) Ltd.
+// SPDX-License-Identifier: Apache-2.0
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// 	http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Runtime API definition required by generic asset RPC extensions.
+//!
+//! This API should be imported and implemented by the runtime,
+//! of a node that wants to use the custom RPC extension
+//! adding generic asset access methods.
+
+#![cfg_attr(not(feature = "std"), no_std)]
+
+use sp_std::prelude::*;
+use codec::{Codec, Encode, Decode};
+#[cfg(feature = "std")]
+use serde::{Serialize, Deserialize, Serializer, Deserializer};
+use sp_runtime::traits::{MaybeDisplay, MaybeFromStr};
+use pallet_generic_asset::{Owner, PermissionLatest};
+
+/// An asset with its total issuance and the accounts holding its permissions.
+#[derive(Eq, PartialEq, Encode, Decode, Default)]
+#[cfg_attr(feature = "std", derive(Debug, Serialize, Deserialize))]
+#[cfg_attr(feature = "std", serde(rename_all = "camelCase"))]
+pub struct AssetInfo<AssetId, Balance, AccountId> {
+	/// The id of the asset.
+	pub asset_id: AssetId,
+	/// The total issuance of the asset.
+	#[cfg_attr(feature = "std", serde(bound(serialize = "Balance: std::fmt::Display")))]
+	#[cfg_attr(feature = "std", serde(serialize_with = "serialize_as_string"))]
+	#[cfg_attr(feature = "std", serde(bound(deserialize = "Balance: std::str::FromStr")))]
+	#[cfg_attr(feature = "std", serde(deserialize_with = "deserialize_from_string"))]
+	pub total_issuance: Balance,
+	/// The account allowed to update the permissions, if any.
+	pub update: Option<AccountId>,
+	/// The account allowed to mint the asset, if any.
+	pub mint: Option<AccountId>,
+	/// The account allowed to burn the asset, if any.
+	pub burn: Option<AccountId>,
+}
+
+impl<AssetId, Balance, AccountId> From<(AssetId, Balance, PermissionLatest<AccountId>)>
+	for AssetInfo<AssetId, Balance, AccountId>
+{
+	fn from((asset_id, total_issuance, permissions): (AssetId, Balance, PermissionLatest<AccountId>)) -> Self {
+		fn account<AccountId>(owner: Owner<AccountId>) -> Option<AccountId> {
+			match owner {
+				Owner::None => None,
+				Owner::Address(who) => Some(who),
+			}
+		}
+
+		AssetInfo {
+			asset_id,
+			total_issuance,
+			update: account(permissions.update),
+			mint: account(permissions.mint),
+			burn: account(permissions.burn),
+		}
+	}
+}
+
+/// The balances of an account in an asset.
+#[derive(Eq, PartialEq, Encode, Decode, Default)]
+#[cfg_attr(feature = "std", derive(Debug, Serialize, Deserialize))]
+#[cfg_attr(feature = "std", serde(rename_all = "camelCase"))]
+pub struct AssetBalance<AssetId, Balance> {
+	/// The id of the asset.
+	pub asset_id: AssetId,
+	/// The free balance of the account.
+	#[cfg_attr(feature = "std", serde(bound(serialize = "Balance: std::fmt::Display")))]
+	#[cfg_attr(feature = "std", serde(serialize_with = "serialize_as_string"))]
+	#[cfg_attr(feature = "std", serde(bound(deserialize = "Balance: std::str::FromStr")))]
+	#[cfg_attr(feature = "std", serde(deserialize_with = "deserialize_from_string"))]
+	pub free: Balance,
+	/// The reserved balance of the account.
+	#[cfg_attr(feature = "std", serde(bound(serialize = "Balance: std::fmt::Display")))]
+	#[cfg_attr(feature = "std", serde(serialize_with = "serialize_as_string"))]
+	#[cfg_attr(feature = "std", serde(bound(deserialize = "Balance: std::str::FromStr")))]
+	#[cfg_attr(feature = "std", serde(deserialize_with = "deserialize_from_string"))]
+	pub reserved: Balance,
+}
+
+impl<AssetId, Balance> From<(AssetId, Balance, Balance)> for AssetBalance<AssetId, Balance> {
+	fn from((asset_id, free, reserved): (AssetId, Balance, Balance)) -> Self {
+		AssetBalance { asset_id, free, reserved }
+	}
+}
+
+#[cfg(feature = "std")]
+fn serialize_as_string<S: Serializer, T: std::fmt::Display>(t: &T, serializer: S) -> Result<S::Ok, S::Error> {
+	serializer.serialize_str(&t.to_string())
+}
+
+#[cfg(feature = "std")]
+fn deserialize_from_string<'de, D: Deserializer<'de>, T: std::str::FromStr>(deserializer: D) -> Result<T, D::Error> {
+	let s = String::deserialize(deserializer)?;
+	s.parse::<T>().map_err(|_| serde::de::Error::custom("Parse from string failed"))
+}
+
+sp_api::decl_runtime_apis! {
+	/// The API to query the assets of the generic asset module and the balances of accounts.
+	pub trait GenericAssetApi<AccountId, AssetId, Balance> where
+		AccountId: Codec,
+		AssetId: Codec,
+		Balance: Codec + MaybeDisplay + MaybeFromStr,
+	{
+		/// Get a page of assets ordered by asset id.
+		///
+		/// See the generic asset module's `assets` function for more details.
+		fn assets(offset: u32, limit: u32) -> Vec<AssetInfo<AssetId, Balance, AccountId>>;
+
+		/// Get a page of the balances of `who` in every asset, ordered by asset id.
+		///
+		/// See the generic asset module's `balances_of` function for more details.
+		fn balances(who: AccountId, offset: u32, limit: u32) -> Vec<AssetBalance<AssetId, Balance>>;
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn should_serialize_balances_as_strings() {
+		let info = AssetInfo::from((16000u32, u128::max_value(), PermissionLatest {
+			update: Owner::Address(1u64),
+			mint: Owner::None,
+			burn: Owner::Address(2u64),
+		}));
+
+		let json_str = r#"{"assetId":16000,"totalIssuance":"340282366920938463463374607431768211455","update":1,"mint":null,"burn":2}"#;
+
+		assert_eq!(serde_json::to_string(&info).unwrap(), json_str);
+		assert_eq!(serde_json::from_str::<AssetInfo<u32, u128, u64>>(json_str).unwrap(), info);
+
+		let balance = AssetBalance::from((16000u32, 70u64, 30u64));
+		let json_str = r#"{"assetId":16000,"free":"70","reserved":"30"}"#;
+
+		assert_eq!(serde_json::to_string(&balance).unwrap(), json_str);
+		assert_eq!(serde_json::from_str::<AssetBalance<u32, u64>>(json_str).unwrap(), balance);
+	}
+}
@@ -0,0 +1,147 @@
+// This file is part of Substrate.
+
+// Copyright (C) 2020 Parity Technologies (UK) Ltd.
+// SPDX-License-Identifier: Apache-2.0
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// 	http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Node-specific RPC methods for querying generic assets.
+
+use std::sync::Arc;
+
+use codec::Codec;
+use jsonrpc_core::{Error, ErrorCode, Result};
+use jsonrpc_derive::rpc;
+use sp_api::ProvideRuntimeApi;
+use sp_blockchain::HeaderBackend;
+use sp_runtime::{generic::BlockId, traits::{Block as BlockT, MaybeDisplay, MaybeFromStr}};
+
+pub use self::gen_client::Client as GenericAssetClient;
+pub use pallet_generic_asset_rpc_runtime_api::{
+	self as runtime_api, AssetBalance, AssetInfo, GenericAssetApi as GenericAssetRuntimeApi,
+};
+
+const RUNTIME_ERROR: i64 = 1;
+
+/// The number of entries returned when no limit is given, and the largest accepted limit.
+const MAX_PAGE_SIZE: u32 = 100;
+
+/// Generic asset RPC methods.
+#[rpc]
+pub trait GenericAssetApi<BlockHash, AccountId, AssetId, Balance> {
+	/// Returns a page of assets with their total issuance and permissions, ordered by asset id.
+	///
+	/// At most 100 assets are returned per call.
+	#[rpc(name = "genericAsset_assets")]
+	fn assets(
+		&self,
+		offset: Option<u32>,
+		limit: Option<u32>,
+		at: Option<BlockHash>,
+	) -> Result<Vec<AssetInfo<AssetId, Balance, AccountId>>>;
+
+	/// Returns a page of the free and reserved balances of `who` in every asset, ordered by
+	/// asset id.
+	///
+	/// At most 100 balances are returned per call.
+	#[rpc(name = "genericAsset_balances")]
+	fn balances(
+		&self,
+		who: AccountId,
+		offset: Option<u32>,
+		limit: Option<u32>,
+		at: Option<BlockHash>,
+	) -> Result<Vec<AssetBalance<AssetId, Balance>>>;
+}
+
+/// An implementation of generic asset specific RPC methods.
+pub struct GenericAsset<C, B> {
+	client: Arc<C>,
+	_marker: std::marker::PhantomData<B>,
+}
+
+impl<C, B> GenericAsset<C, B> {
+	/// Create new `GenericAsset` with the given reference to the client.
+	pub fn new(client: Arc<C>) -> Self {
+		GenericAsset { client, _marker: Default::default() }
+	}
+}
+
+impl<C, Block, AccountId, AssetId, Balance> GenericAssetApi<<Block as BlockT>::Hash, AccountId, AssetId, Balance>
+	for GenericAsset<C, Block>
+where
+	Block: BlockT,
+	C: Send + Sync + 'static + ProvideRuntimeApi<Block> + HeaderBackend<Block>,
+	C::Api: GenericAssetRuntimeApi<Block, AccountId, AssetId, Balance>,
+	AccountId: Codec,
+	AssetId: Codec,
+	Balance: Codec + MaybeDisplay + MaybeFromStr,
+{
+	fn assets(
+		&self,
+		offset: Option<u32>,
+		limit: Option<u32>,
+		at: Option<<Block as BlockT>::Hash>,
+	) -> Result<Vec<AssetInfo<AssetId, Balance, AccountId>>> {
+		let api = self.client.runtime_api();
+		let at = BlockId::hash(at.unwrap_or_else(||
+			// If the block hash is not supplied assume the best block.
+			self.client.info().best_hash
+		));
+
+		api.assets(&at, offset.unwrap_or(0), page_size(limit)).map_err(runtime_error_into_rpc_err)
+	}
+
+	fn balances(
+		&self,
+		who: AccountId,
+		offset: Option<u32>,
+		limit: Option<u32>,
+		at: Option<<Block as BlockT>::Hash>,
+	) -> Result<Vec<AssetBalance<AssetId, Balance>>> {
+		let api = self.client.runtime_api();
+		let at = BlockId::hash(at.unwrap_or_else(||
+			// If the block hash is not supplied assume the best block.
+			self.client.info().best_hash
+		));
+
+		api.balances(&at, who, offset.unwrap_or(0), page_size(limit))
+			.map_err(runtime_error_into_rpc_err)
+	}
+}
+
+/// The number of entries to return for the requested `limit`.
+fn page_size(limit: Option<u32>) -> u32 {
+	limit.unwrap_or(MAX_PAGE_SIZE).min(MAX_PAGE_SIZE)
+}
+
+/// Converts a runtime trap into an RPC error.
+fn runtime_error_into_rpc_err(err: impl std::fmt::Debug) -> Error {
+	Error {
+		code: ErrorCode::ServerError(RUNTIME_ERROR),
+		message: "Runtime trapped".into(),
+		data: Some(format!("{:?}", err).into()),
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn page_size_is_capped() {
+		assert_eq!(page_size(None), MAX_PAGE_SIZE);
+		assert_eq!(page_size(Some(10)), 10);
+		assert_eq!(page_size(Some(1_000)), MAX_PAGE_SIZE);
+	}
+}
@@ -88,6 +88,10 @@
 //! - `total_balance`: Get an account's total balance of an asset kind.
 //! - `free_balance`: Get an account's free balance of an asset kind.
 //! - `reserved_balance`: Get an account's reserved balance of an asset kind.
+//! - `assets`: Get a page of assets with their total issuance and permissions. Served by the
+//! `genericAsset_assets` RPC.
+//! - `balances_of`: Get a page of an account's free and reserved balances in every asset. Served by
+//! the `genericAsset_balances` RPC.
 //! - `create_asset`: Creates an asset.
 //! - `make_transfer`: Transfer some liquid free balance from one account to another.
 //! This will not emit the `Transferred` event.
@@ -169,7 +173,7 @@ use frame_support::{
 		ReservableCurrency, SignedImbalance, WithdrawReason, WithdrawReasons, TryDrop,
 		BalanceStatus,
 	},
-	Parameter, StorageMap,
+	Parameter, StorageMap, IterableStorageMap,
 };
 use frame_system::{self as system, ensure_signed, ensure_root};
 
@@ -524,6 +528,37 @@ impl<T: Trait> Module<T> {
 		<ReservedBalance<T>>::get(asset_id, who)
 	}
 
+	/// Get the ids of all assets in ascending order, skipping `offset` and returning at most
+	/// `limit` of them.
+	fn asset_ids(offset: u32, limit: u32) -> Vec<T::AssetId> {
+		let mut ids = <TotalIssuance<T>>::iter().map(|(id, _)| id).collect::<Vec<_>>();
+		ids.sort();
+		ids.into_iter().skip(offset as usize).take(limit as usize).collect()
+	}
+
+	/// Get a page of assets with their total issuance and permissions, ordered by asset id.
+	///
+	/// Meant to be called by runtime APIs; it iterates over all assets.
+	pub fn assets(offset: u32, limit: u32) -> Vec<(T::AssetId, T::Balance, PermissionLatest<T::AccountId>)> {
+		Self::asset_ids(offset, limit).into_iter()
+			.map(|id| (id, Self::total_issuance(id), Self::get_permission(id).into()))
+			.collect()
+	}
+
+	/// Get a page of the free and reserved balances of `who` in every asset, ordered by asset
+	/// id.
+	///
+	/// Meant to be called by runtime APIs; it iterates over all assets.
+	pub fn balances_of(
+		who: &T::AccountId,
+		offset: u32,
+		limit: u32,
+	) -> Vec<(T::AssetId, T::Balance, T::Balance)> {
+		Self::asset_ids(offset, limit).into_iter()
+			.map(|id| (id, Self::free_balance(&id, who), Self::reserved_balance(&id, who)))
+			.collect()
+	}
+
 	/// Mint to an account's free balance, without event
 	pub fn mint_free(
 		asset_id: &T::AssetId,
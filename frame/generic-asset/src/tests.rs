@@ -1213,3 +1213,51 @@ fn burn_should_raise_event() {
 		},
 	);
 }
+
+#[test]
+fn assets_are_listed_in_pages() {
+	ExtBuilder::default().next_asset_id(1000).free_balance((16000, 1, 100)).build().execute_with(|| {
+		let permissions = PermissionLatest {
+			update: Owner::Address(1),
+			mint: Owner::Address(1),
+			burn: Owner::None,
+		};
+		for issuance in &[10, 20] {
+			assert_ok!(GenericAsset::create(
+				Origin::signed(1),
+				AssetOptions { initial_issuance: *issuance, permissions: permissions.clone() },
+			));
+		}
+
+		assert_eq!(
+			GenericAsset::assets(0, 10),
+			vec![
+				(1000, 10, permissions.clone()),
+				(1001, 20, permissions.clone()),
+				(16000, 100, Default::default()),
+			],
+		);
+		assert_eq!(GenericAsset::assets(1, 1), vec![(1001, 20, permissions)]);
+		assert_eq!(GenericAsset::assets(3, 10), vec![]);
+	});
+}
+
+#[test]
+fn balances_of_account_are_listed_in_pages() {
+	ExtBuilder::default().next_asset_id(1000).free_balance((16000, 1, 100)).build().execute_with(|| {
+		let permissions = PermissionLatest {
+			update: Owner::Address(1),
+			mint: Owner::Address(1),
+			burn: Owner::Address(1),
+		};
+		assert_ok!(GenericAsset::create(
+			Origin::signed(1),
+			AssetOptions { initial_issuance: 50, permissions },
+		));
+		assert_ok!(GenericAsset::reserve(&16000, &1, 30));
+
+		assert_eq!(GenericAsset::balances_of(&1, 0, 10), vec![(1000, 50, 0), (16000, 70, 30)]);
+		assert_eq!(GenericAsset::balances_of(&1, 1, 10), vec![(16000, 70, 30)]);
+		assert_eq!(GenericAsset::balances_of(&2, 0, 1), vec![(1000, 0, 0)]);
+	});
+}
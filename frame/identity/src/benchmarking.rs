@@ -149,7 +149,9 @@ benchmarks! {
 					RawOrigin::Signed(account::<T>("registrar", i)).into(),
 					i,
 					caller_lookup.clone(),
-					Judgement::Reasonable
+					Judgement::Reasonable,
+					Default::default(),
+					None,
 				)?;
 			}
 			caller
@@ -189,7 +191,9 @@ benchmarks! {
 				RawOrigin::Signed(account::<T>("registrar", i)).into(),
 				i,
 				caller_lookup.clone(),
-				Judgement::Reasonable
+				Judgement::Reasonable,
+				Default::default(),
+				None,
 			)?;
 		}
 	}: _(RawOrigin::Signed(caller))
@@ -211,6 +215,9 @@ benchmarks! {
 		let x in ...;
 
 		Identity::<T>::request_judgement(caller_origin, r - 1, 10.into())?;
+		// Let the request time out.
+		let deadline = Identity::<T>::request_deadline(&caller, r - 1).ok_or("request has no deadline")?;
+		frame_system::Module::<T>::set_block_number(deadline);
 	}: _(RawOrigin::Signed(caller), r - 1)
 
 	set_fee {
@@ -262,7 +269,7 @@ benchmarks! {
 
 		Identity::<T>::add_registrar(RawOrigin::Root.into(), caller.clone())?;
 		Identity::<T>::request_judgement(user_origin.clone(), r, 10.into())?;
-	}: _(RawOrigin::Signed(caller), r, user_lookup, Judgement::Reasonable)
+	}: _(RawOrigin::Signed(caller), r, user_lookup, Judgement::Reasonable, Default::default(), None)
 
 	kill_identity {
 		let caller = account::<T>("caller", 0);
@@ -281,7 +288,9 @@ benchmarks! {
 				RawOrigin::Signed(account::<T>("registrar", i)).into(),
 				i,
 				caller_lookup.clone(),
-				Judgement::Reasonable
+				Judgement::Reasonable,
+				Default::default(),
+				None,
 			)?;
 		}
 	}: _(RawOrigin::Root, caller_lookup)
@@ -32,6 +32,14 @@
 //! complete removal of the identity, or by the registrar. Judgements are allowed to represent a
 //! portion of funds that have been reserved for the registrar.
 //!
+//! The fee of a requested judgement is held in escrow: it is paid to the registrar when they
+//! provide their judgement, and can only be reclaimed by cancelling the request once the
+//! registrar failed to do so within `JudgementTimeout` blocks. Registrars may only be asked for
+//! a judgement on identities providing all the fields they set with `set_fields`.
+//!
+//! Each judgement is given along with the hash of the evidence the registrar checked, which the
+//! registrar keeps off-chain, and optionally a block number at which the judgement lapses.
+//!
 //! A super-user can remove accounts and in doing so, slash the deposit.
 //!
 //! All accounts may also have a limited number of sub-accounts which may be specified by the owner;
@@ -50,7 +58,7 @@
 //! * `set_subs` - Set the sub-accounts of an identity.
 //! * `clear_identity` - Remove an account's associated identity; the deposit is returned.
 //! * `request_judgement` - Request a judgement from a registrar, paying a fee.
-//! * `cancel_request` - Cancel the previous request for a judgement.
+//! * `cancel_request` - Cancel the previous request for a judgement once it timed out.
 //!
 //! #### For registrars
 //! * `set_fee` - Set the fee required to be paid for a judgement to be given by the registrar.
@@ -71,7 +79,7 @@ use sp_std::{fmt::Debug, ops::Add, iter::once};
 use enumflags2::BitFlags;
 use codec::{Encode, Decode};
 use sp_runtime::{DispatchError, RuntimeDebug};
-use sp_runtime::traits::{StaticLookup, Zero, AppendZerosInput};
+use sp_runtime::traits::{StaticLookup, Zero, AppendZerosInput, Saturating};
 use frame_support::{
 	decl_module, decl_event, decl_storage, ensure, decl_error,
 	dispatch::DispatchResultWithPostInfo,
@@ -122,6 +130,10 @@ pub trait Trait: frame_system::Trait {
 
 	/// The origin which may add or remove registrars. Root can always do this.
 	type RegistrarOrigin: EnsureOrigin<Self::Origin>;
+
+	/// The number of blocks a registrar has to provide a requested judgement before the
+	/// request can be cancelled and its fee refunded.
+	type JudgementTimeout: Get<Self::BlockNumber>;
 }
 
 /// Either underlying data blob if it is at most 32 bytes, or a hash of it. If the data is greater
@@ -236,6 +248,14 @@ impl<
 		}
 	}
 
+	/// Returns `true` if this judgement attests to the accuracy of the data.
+	fn is_positive(&self) -> bool {
+		match self {
+			Judgement::Reasonable | Judgement::KnownGood => true,
+			_ => false,
+		}
+	}
+
 	/// Returns `true` if this judgement is one that should not be generally be replaced outside
 	/// of specialized handlers. Examples include "malicious" judgements and deposit-holding
 	/// judgements.
@@ -331,6 +351,35 @@ pub struct IdentityInfo {
 	pub twitter: Data,
 }
 
+impl IdentityInfo {
+	/// The fields of this identity which are set.
+	pub fn fields(&self) -> IdentityFields {
+		let mut fields = BitFlags::empty();
+		for (field, data) in &[
+			(IdentityField::Display, &self.display),
+			(IdentityField::Legal, &self.legal),
+			(IdentityField::Web, &self.web),
+			(IdentityField::Riot, &self.riot),
+			(IdentityField::Email, &self.email),
+			(IdentityField::Image, &self.image),
+			(IdentityField::Twitter, &self.twitter),
+		] {
+			if **data != Data::None {
+				fields.insert(*field);
+			}
+		}
+		if self.pgp_fingerprint.is_some() {
+			fields.insert(IdentityField::PgpFingerprint);
+		}
+		IdentityFields(fields)
+	}
+
+	/// Returns `true` if all of the given `fields` are set on this identity.
+	pub fn has_fields(&self, fields: IdentityFields) -> bool {
+		self.fields().0.contains(fields.0)
+	}
+}
+
 /// Information concerning the identity of the controller of an account.
 ///
 /// NOTE: This is stored separately primarily to facilitate the addition of extra fields in a
@@ -386,6 +435,24 @@ pub struct RegistrarInfo<
 	pub fields: IdentityFields,
 }
 
+/// The evidence behind a registrar's judgement.
+#[derive(Clone, Encode, Decode, Eq, PartialEq, RuntimeDebug)]
+pub struct JudgementEvidence<Hash, BlockNumber> {
+	/// The hash of the evidence the registrar checked. The evidence itself is kept off-chain by
+	/// the registrar.
+	pub evidence: Hash,
+
+	/// The block number from which the judgement is no longer valid, if any.
+	pub expiry: Option<BlockNumber>,
+}
+
+impl<Hash, BlockNumber: PartialOrd> JudgementEvidence<Hash, BlockNumber> {
+	/// Returns `true` if the judgement has lapsed at block `now`.
+	fn is_expired(&self, now: &BlockNumber) -> bool {
+		self.expiry.as_ref().map_or(false, |expiry| expiry <= now)
+	}
+}
+
 decl_storage! {
 	trait Store for Module<T: Trait> as Identity {
 		/// Information that is pertinent to identify the entity behind an account.
@@ -412,6 +479,21 @@ decl_storage! {
 		///
 		/// The index into this can be cast to `RegistrarIndex` to get a valid value.
 		pub Registrars get(fn registrars): Vec<Option<RegistrarInfo<BalanceOf<T>, T::AccountId>>>;
+
+		/// The block number from which a pending judgement request can be cancelled, if the
+		/// registrar has not provided their judgement by then.
+		///
+		/// TWOX-NOTE: OK ― `AccountId` is a secure hash.
+		pub RequestDeadline get(fn request_deadline):
+			double_map hasher(twox_64_concat) T::AccountId, hasher(twox_64_concat) RegistrarIndex
+			=> Option<T::BlockNumber>;
+
+		/// The evidence behind the judgements given on an identity by each registrar.
+		///
+		/// TWOX-NOTE: OK ― `AccountId` is a secure hash.
+		pub EvidenceOf get(fn evidence_of):
+			double_map hasher(twox_64_concat) T::AccountId, hasher(twox_64_concat) RegistrarIndex
+			=> Option<JudgementEvidence<T::Hash, T::BlockNumber>>;
 	}
 }
 
@@ -463,6 +545,12 @@ decl_error! {
 		TooManyFields,
 		/// Maximum amount of registrars reached. Cannot add any more.
 		TooManyRegistrars,
+		/// The identity lacks fields the registrar requires.
+		MissingFields,
+		/// The judgement request has not timed out yet.
+		RequestNotExpired,
+		/// The judgement expiry is not in the future.
+		InvalidExpiry,
 }
 }
 
@@ -492,6 +580,7 @@ mod weight_for {
 		extra_fields: Weight
 	) -> Weight {
 		T::DbWeight::get().reads_writes(1, 1)
+			+ T::DbWeight::get().reads_writes(judgements, judgements) // R `EvidenceOf` reads and removals
 			+ 51_000_000 // constant
 			+ 220_000 * judgements // R
 			+ 1_500_000 * extra_fields // X
@@ -524,6 +613,7 @@ mod weight_for {
 		extra_fields: Weight
 	) -> Weight {
 		T::DbWeight::get().reads_writes(2, subs + 2) // S + 2 deletions
+			+ T::DbWeight::get().writes(2 * judgements) // `RequestDeadline` and `EvidenceOf` prefix removals
 			+ 44_000_000 // constant
 			+ 100_000 * judgements // R
 			+ 2_600_000 * subs // S
@@ -538,7 +628,8 @@ mod weight_for {
 		judgements: Weight,
 		extra_fields: Weight
 	) -> Weight {
-		T::DbWeight::get().reads_writes(2, 1)
+		T::DbWeight::get().reads_writes(2, 3) // `RequestDeadline` insert and `EvidenceOf` removal
+			+ T::DbWeight::get().reads_writes(judgements, judgements) // R lapsed `EvidenceOf` removals
 			+ 52_000_000 // constant
 			+ 400_000 * judgements // R
 			+ 1_900_000 * extra_fields // X
@@ -552,7 +643,7 @@ mod weight_for {
 		judgements: Weight,
 		extra_fields: Weight
 	) -> Weight {
-		T::DbWeight::get().reads_writes(1, 1)
+		T::DbWeight::get().reads_writes(2, 2) // `RequestDeadline` read and removal
 			+ 41_000_000 // constant
 			+ 300_000 * judgements // R
 			+ 1_700_000 * extra_fields // X
@@ -566,7 +657,7 @@ mod weight_for {
 		judgements: Weight,
 		extra_fields: Weight
 	) -> Weight {
-		T::DbWeight::get().reads_writes(2, 1)
+		T::DbWeight::get().reads_writes(2, 3) // `RequestDeadline` removal and `EvidenceOf` insert
 			+ 41_000_000 // constant
 			+ 300_000 * judgements // R
 			+ 1_700_000 * extra_fields// X
@@ -583,6 +674,7 @@ mod weight_for {
 	) -> Weight {
 		let db = T::DbWeight::get();
 		db.reads_writes(2, subs + 2) // 2 `take`s + S deletions
+			+ db.writes(2 * judgements) // `RequestDeadline` and `EvidenceOf` prefix removals
 			+ db.reads_writes(1, 1) // balance ops
 			+ 84_000_000 // constant
 			+ 130_000 * judgements // R
@@ -616,6 +708,10 @@ decl_module! {
 		/// of, e.g., updating judgements.
 		const MaxRegistrars: u32 = T::MaxRegistrars::get();
 
+		/// The number of blocks a registrar has to provide a requested judgement before the
+		/// request can be cancelled and its fee refunded.
+		const JudgementTimeout: T::BlockNumber = T::JudgementTimeout::get();
+
 		type Error = Error<T>;
 
 		fn deposit_event() = default;
@@ -655,7 +751,8 @@ decl_module! {
 		/// Set an account's identity information and reserve the appropriate deposit.
 		///
 		/// If the account already has identity information, the deposit is taken as part payment
-		/// for the new deposit.
+		/// for the new deposit. Judgements which are not sticky, including those which have lapsed,
+		/// are dropped along with their evidence.
 		///
 		/// The dispatch origin for this call must be _Signed_.
 		///
@@ -669,6 +766,7 @@ decl_module! {
 		///   - where `R` judgements-count (registrar-count-bounded)
 		/// - One balance reserve operation.
 		/// - One storage mutation (codec-read `O(X' + R)`, codec-write `O(X + R)`).
+		/// - Up to `R` evidence reads and removals `O(1)`.
 		/// - One event.
 		/// # </weight>
 		#[weight =  weight_for::set_identity::<T>(
@@ -681,9 +779,12 @@ decl_module! {
 			ensure!(extra_fields <= T::MaxAdditionalFields::get(), Error::<T>::TooManyFields);
 			let fd = <BalanceOf<T>>::from(extra_fields) * T::FieldDeposit::get();
 
+			let mut dropped = Vec::new();
 			let mut id = match <IdentityOf<T>>::get(&sender) {
 				Some(mut id) => {
+					Self::lapse_judgements(&sender, &mut id);
 					// Only keep non-positive judgements.
+					dropped = id.judgements.iter().filter(|j| !j.1.is_sticky()).map(|j| j.0).collect();
 					id.judgements.retain(|j| j.1.is_sticky());
 					id.info = info;
 					id
@@ -699,6 +800,9 @@ decl_module! {
 			if old_deposit > id.deposit {
 				let _ = T::Currency::unreserve(&sender, old_deposit - id.deposit);
 			}
+			for reg_index in dropped {
+				<EvidenceOf<T>>::remove(&sender, reg_index);
+			}
 
 			let judgements = id.judgements.len() as Weight;
 			<IdentityOf<T>>::insert(&sender, id);
@@ -788,6 +892,7 @@ decl_module! {
 		///   - where `X` additional-field-count (deposit-bounded and code-bounded).
 		/// - One balance-unreserve operation.
 		/// - `2` storage reads and `S + 2` storage deletions.
+		/// - Up to `2R` request deadline and evidence deletions.
 		/// - One event.
 		/// # </weight>
 		#[weight = weight_for::clear_identity::<T>(
@@ -805,6 +910,8 @@ decl_module! {
 			for sub in sub_ids.iter() {
 				<SuperOf<T>>::remove(sub);
 			}
+			<RequestDeadline<T>>::remove_prefix(&sender);
+			<EvidenceOf<T>>::remove_prefix(&sender);
 
 			let _ = T::Currency::unreserve(&sender, deposit.clone());
 
@@ -820,10 +927,12 @@ decl_module! {
 		/// Request a judgement from a registrar.
 		///
 		/// Payment: At most `max_fee` will be reserved for payment to the registrar if judgement
-		/// given.
+		/// given. It can only be reclaimed with `cancel_request` once `JudgementTimeout` blocks
+		/// have passed without the registrar providing their judgement.
 		///
 		/// The dispatch origin for this call must be _Signed_ and the sender must have a
-		/// registered identity.
+		/// registered identity, providing all the fields the registrar requires. Judgements which
+		/// have lapsed are downgraded to `OutOfDate` and may be requested again.
 		///
 		/// - `reg_index`: The index of the registrar whose judgement is requested.
 		/// - `max_fee`: The maximum fee that may be paid. This should just be auto-populated as:
@@ -837,7 +946,8 @@ decl_module! {
 		/// # <weight>
 		/// - `O(R + X)`.
 		/// - One balance-reserve operation.
-		/// - Storage: 1 read `O(R)`, 1 mutate `O(X + R)`.
+		/// - Storage: 1 read `O(R)`, 1 mutate `O(X + R)`, 1 insert and up to 1 removal `O(1)`.
+		/// - Up to `R` evidence reads and removals of lapsed judgements `O(1)`.
 		/// - One event.
 		/// # </weight>
 		#[weight = weight_for::request_judgement::<T>(
@@ -854,6 +964,8 @@ decl_module! {
 				.ok_or(Error::<T>::EmptyIndex)?;
 			ensure!(max_fee >= registrar.fee, Error::<T>::FeeChanged);
 			let mut id = <IdentityOf<T>>::get(&sender).ok_or(Error::<T>::NoIdentity)?;
+			ensure!(id.info.has_fields(registrar.fields), Error::<T>::MissingFields);
+			let lapsed = Self::lapse_judgements(&sender, &mut id);
 
 			let item = (reg_index, Judgement::FeePaid(registrar.fee));
 			match id.judgements.binary_search_by_key(&reg_index, |x| x.0) {
@@ -867,6 +979,14 @@ decl_module! {
 
 			T::Currency::reserve(&sender, registrar.fee)?;
 
+			let deadline = <frame_system::Module<T>>::block_number()
+				.saturating_add(T::JudgementTimeout::get());
+			<RequestDeadline<T>>::insert(&sender, reg_index, deadline);
+			<EvidenceOf<T>>::remove(&sender, reg_index);
+			for lapsed_index in lapsed {
+				<EvidenceOf<T>>::remove(&sender, lapsed_index);
+			}
+
 			let judgements = id.judgements.len() as Weight;
 			let extra_fields = id.info.additional.len() as Weight;
 			<IdentityOf<T>>::insert(&sender, id);
@@ -876,12 +996,12 @@ decl_module! {
 			Ok(Some(weight_for::request_judgement::<T>(judgements, extra_fields)).into())
 		}
 
-		/// Cancel a previous request.
+		/// Cancel a previous request which the registrar did not respond to in time.
 		///
 		/// Payment: A previously reserved deposit is returned on success.
 		///
 		/// The dispatch origin for this call must be _Signed_ and the sender must have a
-		/// registered identity.
+		/// registered identity. `JudgementTimeout` blocks must have passed since the request.
 		///
 		/// - `reg_index`: The index of the registrar whose judgement is no longer requested.
 		///
@@ -891,6 +1011,7 @@ decl_module! {
 		/// - `O(R + X)`.
 		/// - One balance-reserve operation.
 		/// - One storage mutation `O(R + X)`.
+		/// - One storage read and removal `O(1)`.
 		/// - One event
 		/// # </weight>
 		#[weight = weight_for::cancel_request::<T>(
@@ -908,15 +1029,22 @@ decl_module! {
 			} else {
 				Err(Error::<T>::JudgementGiven)?
 			};
+			// Requests made before deadlines were recorded can be cancelled at any time.
+			let now = <frame_system::Module<T>>::block_number();
+			ensure!(
+				Self::request_deadline(&sender, reg_index).map_or(true, |deadline| deadline <= now),
+				Error::<T>::RequestNotExpired,
+			);
 
 			let _ = T::Currency::unreserve(&sender, fee);
+			<RequestDeadline<T>>::remove(&sender, reg_index);
 			let judgements = id.judgements.len() as Weight;
 			let extra_fields = id.info.additional.len() as Weight;
 			<IdentityOf<T>>::insert(&sender, id);
 
 			Self::deposit_event(RawEvent::JudgementUnrequested(sender, reg_index));
 
-			Ok(Some(weight_for::request_judgement::<T>(judgements, extra_fields)).into())
+			Ok(Some(weight_for::cancel_request::<T>(judgements, extra_fields)).into())
 		}
 
 		/// Set the fee required for a judgement to be requested from a registrar.
@@ -1032,7 +1160,12 @@ decl_module! {
 		/// - `reg_index`: the index of the registrar whose judgement is being made.
 		/// - `target`: the account whose identity the judgement is upon. This must be an account
 		///   with a registered identity.
-		/// - `judgement`: the judgement of the registrar of index `reg_index` about `target`.
+		/// - `judgement`: the judgement of the registrar of index `reg_index` about `target`. A
+		///   positive judgement can only be given on an identity providing all the fields the
+		///   registrar requires.
+		/// - `evidence`: the hash of the evidence the registrar checked to make the judgement.
+		/// - `expiry`: the block number from which the judgement is no longer valid, if any. This
+		///   must be in the future.
 		///
 		/// Emits `JudgementGiven` if successful.
 		///
@@ -1040,7 +1173,7 @@ decl_module! {
 		/// - `O(R + X)`.
 		/// - One balance-transfer operation.
 		/// - Up to one account-lookup operation.
-		/// - Storage: 1 read `O(R)`, 1 mutate `O(R + X)`.
+		/// - Storage: 1 read `O(R)`, 1 mutate `O(R + X)`, 1 insert and 1 removal `O(1)`.
 		/// - One event.
 		/// # </weight>
 		#[weight = weight_for::provide_judgement::<T>(
@@ -1051,27 +1184,38 @@ decl_module! {
 			#[compact] reg_index: RegistrarIndex,
 			target: <T::Lookup as StaticLookup>::Source,
 			judgement: Judgement<BalanceOf<T>>,
+			evidence: T::Hash,
+			expiry: Option<T::BlockNumber>,
 		) -> DispatchResultWithPostInfo {
 			let sender = ensure_signed(origin)?;
 			let target = T::Lookup::lookup(target)?;
 			ensure!(!judgement.has_deposit(), Error::<T>::InvalidJudgement);
-			<Registrars<T>>::get()
+			let now = <frame_system::Module<T>>::block_number();
+			ensure!(expiry.map_or(true, |expiry| expiry > now), Error::<T>::InvalidExpiry);
+			let registrars = <Registrars<T>>::get();
+			let registrar = registrars
 				.get(reg_index as usize)
 				.and_then(Option::as_ref)
 				.and_then(|r| if r.account == sender { Some(r) } else { None })
 				.ok_or(Error::<T>::InvalidIndex)?;
 			let mut id = <IdentityOf<T>>::get(&target).ok_or(Error::<T>::InvalidTarget)?;
+			ensure!(
+				!judgement.is_positive() || id.info.has_fields(registrar.fields),
+				Error::<T>::MissingFields,
+			);
 
 			let item = (reg_index, judgement);
 			match id.judgements.binary_search_by_key(&reg_index, |x| x.0) {
 				Ok(position) => {
 					if let Judgement::FeePaid(fee) = id.judgements[position].1 {
 						let _ = T::Currency::repatriate_reserved(&target, &sender, fee, BalanceStatus::Free);
+						<RequestDeadline<T>>::remove(&target, reg_index);
 					}
 					id.judgements[position] = item
 				}
 				Err(position) => id.judgements.insert(position, item),
 			}
+			<EvidenceOf<T>>::insert(&target, reg_index, JudgementEvidence { evidence, expiry });
 
 			let judgements = id.judgements.len() as Weight;
 			let extra_fields = id.info.additional.len() as Weight;
@@ -1098,6 +1242,7 @@ decl_module! {
 		/// - `O(R + S + X)`.
 		/// - One balance-reserve operation.
 		/// - `S + 2` storage mutations.
+		/// - Up to `2R` request deadline and evidence deletions.
 		/// - One event.
 		/// # </weight>
 		#[weight = weight_for::kill_identity::<T>(
@@ -1117,6 +1262,8 @@ decl_module! {
 			for sub in sub_ids.iter() {
 				<SuperOf<T>>::remove(sub);
 			}
+			<RequestDeadline<T>>::remove_prefix(&target);
+			<EvidenceOf<T>>::remove_prefix(&target);
 			// Slash their deposit from them.
 			T::Slashed::on_unbalanced(T::Currency::slash_reserved(&target, deposit).0);
 
@@ -1139,6 +1286,33 @@ impl<T: Trait> Module<T> {
 			.filter_map(|a| SuperOf::<T>::get(&a).map(|x| (a, x.1)))
 			.collect()
 	}
+
+	/// Get the judgements on the identity of an account, with those which have lapsed reported as
+	/// `OutOfDate`.
+	pub fn current_judgements(who: &T::AccountId) -> Vec<(RegistrarIndex, Judgement<BalanceOf<T>>)> {
+		<IdentityOf<T>>::get(who)
+			.map(|mut id| {
+				Self::lapse_judgements(who, &mut id);
+				id.judgements
+			})
+			.unwrap_or_default()
+	}
+
+	/// Downgrade the judgements on `id`, the identity of `who`, which have lapsed to `OutOfDate`.
+	///
+	/// Only `id` is changed. Returns the registrars whose evidence is to be dropped along with
+	/// their judgement.
+	fn lapse_judgements(who: &T::AccountId, id: &mut Registration<BalanceOf<T>>) -> Vec<RegistrarIndex> {
+		let now = <frame_system::Module<T>>::block_number();
+		let mut lapsed = Vec::new();
+		for (reg_index, judgement) in id.judgements.iter_mut() {
+			if Self::evidence_of(who, *reg_index).map_or(false, |e| e.is_expired(&now)) {
+				*judgement = Judgement::OutOfDate;
+				lapsed.push(*reg_index);
+			}
+		}
+		lapsed
+	}
 }
 
 #[cfg(test)]
@@ -1216,6 +1390,7 @@ mod tests {
 		pub const MaxSubAccounts: u32 = 2;
 		pub const MaxAdditionalFields: u32 = 2;
 		pub const MaxRegistrars: u32 = 20;
+		pub const JudgementTimeout: u64 = 10;
 	}
 	ord_parameter_types! {
 		pub const One: u64 = 1;
@@ -1243,6 +1418,7 @@ mod tests {
 		type MaxRegistrars = MaxRegistrars;
 		type RegistrarOrigin = EnsureOneOrRoot;
 		type ForceOrigin = EnsureTwoOrRoot;
+		type JudgementTimeout = JudgementTimeout;
 	}
 	type System = frame_system::Module<Test>;
 	type Balances = pallet_balances::Module<Test>;
@@ -1337,27 +1513,27 @@ mod tests {
 	fn uninvited_judgement_should_work() {
 		new_test_ext().execute_with(|| {
 			assert_noop!(
-				Identity::provide_judgement(Origin::signed(3), 0, 10, Judgement::Reasonable),
+				Identity::provide_judgement(Origin::signed(3), 0, 10, Judgement::Reasonable, H256::zero(), None),
 				Error::<Test>::InvalidIndex
 			);
 
 			assert_ok!(Identity::add_registrar(Origin::signed(1), 3));
 			assert_noop!(
-				Identity::provide_judgement(Origin::signed(3), 0, 10, Judgement::Reasonable),
+				Identity::provide_judgement(Origin::signed(3), 0, 10, Judgement::Reasonable, H256::zero(), None),
 				Error::<Test>::InvalidTarget
 			);
 
 			assert_ok!(Identity::set_identity(Origin::signed(10), ten()));
 			assert_noop!(
-				Identity::provide_judgement(Origin::signed(10), 0, 10, Judgement::Reasonable),
+				Identity::provide_judgement(Origin::signed(10), 0, 10, Judgement::Reasonable, H256::zero(), None),
 				Error::<Test>::InvalidIndex
 			);
 			assert_noop!(
-				Identity::provide_judgement(Origin::signed(3), 0, 10, Judgement::FeePaid(1)),
+				Identity::provide_judgement(Origin::signed(3), 0, 10, Judgement::FeePaid(1), H256::zero(), None),
 				Error::<Test>::InvalidJudgement
 			);
 
-			assert_ok!(Identity::provide_judgement(Origin::signed(3), 0, 10, Judgement::Reasonable));
+			assert_ok!(Identity::provide_judgement(Origin::signed(3), 0, 10, Judgement::Reasonable, H256::zero(), None));
 			assert_eq!(Identity::identity(10).unwrap().judgements, vec![(0, Judgement::Reasonable)]);
 		});
 	}
@@ -1367,7 +1543,7 @@ mod tests {
 		new_test_ext().execute_with(|| {
 			assert_ok!(Identity::add_registrar(Origin::signed(1), 3));
 			assert_ok!(Identity::set_identity(Origin::signed(10), ten()));
-			assert_ok!(Identity::provide_judgement(Origin::signed(3), 0, 10, Judgement::Reasonable));
+			assert_ok!(Identity::provide_judgement(Origin::signed(3), 0, 10, Judgement::Reasonable, H256::zero(), None));
 			assert_ok!(Identity::clear_identity(Origin::signed(10)));
 			assert_eq!(Identity::identity(10), None);
 		});
@@ -1456,11 +1632,17 @@ mod tests {
 			assert_noop!(Identity::cancel_request(Origin::signed(10), 0), Error::<Test>::NoIdentity);
 			assert_ok!(Identity::set_identity(Origin::signed(10), ten()));
 			assert_ok!(Identity::request_judgement(Origin::signed(10), 0, 10));
+			assert_eq!(Identity::request_deadline(10, 0), Some(10));
+			// The fee is held in escrow until the request times out.
+			System::set_block_number(9);
+			assert_noop!(Identity::cancel_request(Origin::signed(10), 0), Error::<Test>::RequestNotExpired);
+			System::set_block_number(10);
 			assert_ok!(Identity::cancel_request(Origin::signed(10), 0));
+			assert_eq!(Identity::request_deadline(10, 0), None);
 			assert_eq!(Balances::free_balance(10), 90);
 			assert_noop!(Identity::cancel_request(Origin::signed(10), 0), Error::<Test>::NotFound);
 
-			assert_ok!(Identity::provide_judgement(Origin::signed(3), 0, 10, Judgement::Reasonable));
+			assert_ok!(Identity::provide_judgement(Origin::signed(3), 0, 10, Judgement::Reasonable, H256::zero(), None));
 			assert_noop!(Identity::cancel_request(Origin::signed(10), 0), Error::<Test>::JudgementGiven);
 		});
 	}
@@ -1478,7 +1660,7 @@ mod tests {
 
 			// Re-requesting won't work as we already paid.
 			assert_noop!(Identity::request_judgement(Origin::signed(10), 0, 10), Error::<Test>::StickyJudgement);
-			assert_ok!(Identity::provide_judgement(Origin::signed(3), 0, 10, Judgement::Erroneous));
+			assert_ok!(Identity::provide_judgement(Origin::signed(3), 0, 10, Judgement::Erroneous, H256::zero(), None));
 			// Registrar got their payment now.
 			assert_eq!(Balances::free_balance(3), 20);
 
@@ -1490,7 +1672,7 @@ mod tests {
 			assert_ok!(Identity::request_judgement(Origin::signed(10), 1, 10));
 
 			// Re-requesting after the judgement has been reduced works.
-			assert_ok!(Identity::provide_judgement(Origin::signed(3), 0, 10, Judgement::OutOfDate));
+			assert_ok!(Identity::provide_judgement(Origin::signed(3), 0, 10, Judgement::OutOfDate, H256::zero(), None));
 			assert_ok!(Identity::request_judgement(Origin::signed(10), 0, 10));
 		});
 	}
@@ -1522,4 +1704,94 @@ mod tests {
 			assert_ok!(Identity::set_account_id(Origin::signed(4), 0, 3));
 		});
 	}
+
+	#[test]
+	fn registrar_fields_should_be_enforced() {
+		new_test_ext().execute_with(|| {
+			assert_ok!(Identity::add_registrar(Origin::signed(1), 3));
+			let fields = IdentityFields(IdentityField::Display | IdentityField::Email);
+			assert_ok!(Identity::set_fields(Origin::signed(3), 0, fields));
+			assert_ok!(Identity::set_identity(Origin::signed(10), ten()));
+			assert_eq!(ten().fields(), IdentityFields(IdentityField::Display | IdentityField::Legal));
+
+			// The identity has no email.
+			assert_noop!(Identity::request_judgement(Origin::signed(10), 0, 0), Error::<Test>::MissingFields);
+			assert_noop!(
+				Identity::provide_judgement(Origin::signed(3), 0, 10, Judgement::KnownGood, H256::zero(), None),
+				Error::<Test>::MissingFields
+			);
+			// Negative judgements can still be given.
+			assert_ok!(Identity::provide_judgement(Origin::signed(3), 0, 10, Judgement::LowQuality, H256::zero(), None));
+
+			let info = IdentityInfo { email: Data::Raw(b"ten@ten.io".to_vec()), .. ten() };
+			assert_ok!(Identity::set_identity(Origin::signed(10), info));
+			assert_ok!(Identity::request_judgement(Origin::signed(10), 0, 0));
+			assert_ok!(Identity::provide_judgement(Origin::signed(3), 0, 10, Judgement::KnownGood, H256::zero(), None));
+		});
+	}
+
+	#[test]
+	fn judgements_should_record_evidence_and_expire() {
+		new_test_ext().execute_with(|| {
+			assert_ok!(Identity::add_registrar(Origin::signed(1), 3));
+			assert_ok!(Identity::add_registrar(Origin::signed(1), 4));
+			assert_ok!(Identity::set_fee(Origin::signed(3), 0, 10));
+			assert_ok!(Identity::set_identity(Origin::signed(10), ten()));
+			assert_ok!(Identity::request_judgement(Origin::signed(10), 0, 10));
+
+			System::set_block_number(5);
+			let evidence = H256::repeat_byte(1);
+			assert_noop!(
+				Identity::provide_judgement(Origin::signed(3), 0, 10, Judgement::Reasonable, evidence, Some(5)),
+				Error::<Test>::InvalidExpiry
+			);
+			assert_ok!(Identity::provide_judgement(Origin::signed(3), 0, 10, Judgement::Reasonable, evidence, Some(20)));
+			assert_ok!(Identity::provide_judgement(Origin::signed(4), 1, 10, Judgement::KnownGood, evidence, None));
+			// The registrar was paid and the request is settled.
+			assert_eq!(Balances::free_balance(3), 20);
+			assert_eq!(Identity::request_deadline(10, 0), None);
+			assert_eq!(Identity::evidence_of(10, 0), Some(JudgementEvidence { evidence, expiry: Some(20) }));
+			assert_eq!(
+				Identity::current_judgements(&10),
+				vec![(0, Judgement::Reasonable), (1, Judgement::KnownGood)]
+			);
+
+			// The first judgement lapses.
+			System::set_block_number(20);
+			assert_eq!(
+				Identity::current_judgements(&10),
+				vec![(0, Judgement::OutOfDate), (1, Judgement::KnownGood)]
+			);
+
+			// Resetting the identity drops the judgements along with their evidence.
+			assert_ok!(Identity::set_identity(Origin::signed(10), ten()));
+			assert_eq!(Identity::evidence_of(10, 0), None);
+			assert_eq!(Identity::evidence_of(10, 1), None);
+			assert!(Identity::current_judgements(&10).is_empty());
+		});
+	}
+
+	#[test]
+	fn lapsed_judgements_are_downgraded_when_read() {
+		new_test_ext().execute_with(|| {
+			assert_ok!(Identity::add_registrar(Origin::signed(1), 3));
+			assert_ok!(Identity::add_registrar(Origin::signed(1), 4));
+			assert_ok!(Identity::set_identity(Origin::signed(10), ten()));
+			let evidence = H256::repeat_byte(1);
+			assert_ok!(Identity::provide_judgement(Origin::signed(3), 0, 10, Judgement::Erroneous, evidence, Some(10)));
+			assert_ok!(Identity::provide_judgement(Origin::signed(4), 1, 10, Judgement::Reasonable, evidence, Some(10)));
+			// An erroneous judgement cannot be replaced by a request while it holds.
+			assert_noop!(Identity::request_judgement(Origin::signed(10), 0, 10), Error::<Test>::StickyJudgement);
+
+			// Once lapsed, it is out of date and can be requested again.
+			System::set_block_number(10);
+			assert_ok!(Identity::request_judgement(Origin::signed(10), 0, 10));
+			assert_eq!(
+				Identity::identity(10).unwrap().judgements,
+				vec![(0, Judgement::FeePaid(0)), (1, Judgement::OutOfDate)]
+			);
+			assert_eq!(Identity::evidence_of(10, 0), None);
+			assert_eq!(Identity::evidence_of(10, 1), None);
+		});
+	}
 }
@@ -24,10 +24,27 @@
 //!
 //! ## Overview
 //!
+//! A proxy may be registered with a `delay`. Such a proxy cannot dispatch calls directly: it
+//! must first `announce` the hash of the call, and the call can only be dispatched through
+//! `proxy_announced` once `delay` blocks have passed. Until then the account that delegated to
+//! the proxy can `reject_announcement`, which gives it time to react to a compromised proxy key.
+//! Announcements are held on deposit.
+//!
 //! ## Interface
 //!
 //! ### Dispatchable Functions
 //!
+//! * `proxy` - dispatch a call on behalf of an account, as a proxy without a delay.
+//! * `add_proxy` - register a proxy, with an optional delay.
+//! * `remove_proxy` - unregister a proxy.
+//! * `remove_proxies` - unregister all proxies.
+//! * `anonymous` - spawn an account only accessible through a proxy.
+//! * `kill_anonymous` - remove an account spawned by `anonymous`.
+//! * `announce` - announce the hash of a call to be dispatched by a delayed proxy.
+//! * `remove_announcement` - remove an announcement, by the proxy that made it.
+//! * `reject_announcement` - remove an announcement, by the account it was made for.
+//! * `proxy_announced` - dispatch an announced call once the delay of its proxy has passed.
+//!
 //! [`Call`]: ./enum.Call.html
 //! [`Trait`]: ./trait.Trait.html
 
@@ -37,14 +54,15 @@
 use sp_std::prelude::*;
 use codec::{Encode, Decode};
 use sp_io::hashing::blake2_256;
-use sp_runtime::{DispatchResult, traits::{Dispatchable, Zero}};
+use sp_runtime::{DispatchResult, DispatchError, RuntimeDebug, traits::{Dispatchable, Zero, Hash, Saturating}};
 use sp_runtime::traits::Member;
 use frame_support::{
 	decl_module, decl_event, decl_error, decl_storage, Parameter, ensure, traits::{
 		Get, ReservableCurrency, Currency, InstanceFilter,
 		OriginTrait, IsType,
-	}, weights::{GetDispatchInfo, constants::{WEIGHT_PER_MICROS, WEIGHT_PER_NANOS}},
+	}, weights::{Weight, GetDispatchInfo, constants::{WEIGHT_PER_MICROS, WEIGHT_PER_NANOS}},
 	dispatch::{PostDispatchInfo, IsSubType},
+	storage::IterableStorageMap,
 };
 use frame_system::{self as system, ensure_signed};
 
@@ -53,6 +71,47 @@ mod benchmarking;
 
 type BalanceOf<T> = <<T as Trait>::Currency as Currency<<T as frame_system::Trait>::AccountId>>::Balance;
 
+type CallHashOf<T> = <<T as Trait>::CallHasher as Hash>::Output;
+
+/// The parameters under which a particular account has a proxy relationship with some other
+/// account.
+#[derive(Encode, Decode, Clone, Copy, Eq, PartialEq, Ord, PartialOrd, RuntimeDebug)]
+pub struct ProxyDefinition<AccountId, ProxyType, BlockNumber> {
+	/// The account which may act on behalf of another.
+	pub delegate: AccountId,
+	/// A value defining the subset of calls that it is allowed to make.
+	pub proxy_type: ProxyType,
+	/// The number of blocks that an announcement must be in place for before the corresponding
+	/// call may be dispatched. If zero, then no announcement is needed.
+	pub delay: BlockNumber,
+}
+
+/// Details surrounding a specific instance of an announcement to make a call.
+#[derive(Encode, Decode, Clone, Copy, Eq, PartialEq, RuntimeDebug)]
+pub struct Announcement<AccountId, Hash, BlockNumber> {
+	/// The account which the proxy intends to make a call on behalf of.
+	pub real: AccountId,
+	/// The hash of the call to be made.
+	pub call_hash: Hash,
+	/// The height at which the announcement was made.
+	pub height: BlockNumber,
+}
+
+// A value placed in storage that represents the current version of the Proxy storage.
+// This value is used by the `on_runtime_upgrade` logic to determine whether we run
+// storage migration logic.
+#[derive(Encode, Decode, Clone, Copy, PartialEq, Eq, RuntimeDebug)]
+enum Releases {
+	V1,
+	V2,
+}
+
+impl Default for Releases {
+	fn default() -> Self {
+		Releases::V1
+	}
+}
+
 /// Configuration trait.
 pub trait Trait: frame_system::Trait {
 	/// The overarching event type.
@@ -87,13 +146,40 @@ pub trait Trait: frame_system::Trait {
 
 	/// The maximum amount of proxies allowed for a single account.
 	type MaxProxies: Get<u16>;
+
+	/// The maximum amount of time-delayed announcements that are allowed to be pending.
+	type MaxPending: Get<u32>;
+
+	/// The type of hash used for hashing the call.
+	type CallHasher: Hash;
+
+	/// The base amount of currency needed to reserve for creating an announcement.
+	///
+	/// This is held when a new storage item holding a `Balance` is created (typically 16 bytes).
+	type AnnouncementDepositBase: Get<BalanceOf<Self>>;
+
+	/// The amount of currency needed per announcement made.
+	///
+	/// This is held for adding an `AccountId`, `Hash` and `BlockNumber` (typically 68 bytes)
+	/// into a pre-existing storage value.
+	type AnnouncementDepositFactor: Get<BalanceOf<Self>>;
 }
 
 decl_storage! {
 	trait Store for Module<T: Trait> as Proxy {
 		/// The set of account proxies. Maps the account which has delegated to the accounts
 		/// which are being delegated to, together with the amount held on deposit.
-		pub Proxies: map hasher(twox_64_concat) T::AccountId => (Vec<(T::AccountId, T::ProxyType)>, BalanceOf<T>);
+		pub Proxies get(fn proxies): map hasher(twox_64_concat) T::AccountId
+			=> (Vec<ProxyDefinition<T::AccountId, T::ProxyType, T::BlockNumber>>, BalanceOf<T>);
+
+		/// The announcements made by the proxy (key).
+		pub Announcements get(fn announcements): map hasher(twox_64_concat) T::AccountId
+			=> (Vec<Announcement<T::AccountId, CallHashOf<T>, T::BlockNumber>>, BalanceOf<T>);
+
+		/// Storage version of the pallet.
+		///
+		/// New networks start with the last version.
+		StorageVersion build(|_| Releases::V2): Releases;
 	}
 }
 
@@ -111,6 +197,8 @@ decl_error! {
 		Duplicate,
 		/// Call may not be made by proxy because it may escalate its privileges.
 		NoPermission,
+		/// Announcement, if made at all, was made too recently.
+		Unannounced,
 	}
 }
 
@@ -118,13 +206,16 @@ decl_event! {
 	/// Events type.
 	pub enum Event<T> where
 		AccountId = <T as frame_system::Trait>::AccountId,
-		ProxyType = <T as Trait>::ProxyType
+		ProxyType = <T as Trait>::ProxyType,
+		Hash = CallHashOf<T>,
 	{
 		/// A proxy was executed correctly, with the given result.
 		ProxyExecuted(DispatchResult),
 		/// Anonymous account (first parameter) has been created by new proxy (second) with given
 		/// disambiguation index and proxy type.
 		AnonymousCreated(AccountId, AccountId, ProxyType, u16),
+		/// An announcement was placed to make a call in the future. \[real, proxy, call_hash\]
+		Announced(AccountId, AccountId, Hash),
 	}
 }
 
@@ -144,8 +235,21 @@ decl_module! {
 		/// The maximum amount of proxies allowed for a single account.
 		const MaxProxies: u16 = T::MaxProxies::get();
 
+		/// The maximum amount of time-delayed announcements that are allowed to be pending.
+		const MaxPending: u32 = T::MaxPending::get();
+
+		/// The base amount of currency needed to reserve for creating an announcement.
+		const AnnouncementDepositBase: BalanceOf<T> = T::AnnouncementDepositBase::get();
+
+		/// The amount of currency needed per announcement made.
+		const AnnouncementDepositFactor: BalanceOf<T> = T::AnnouncementDepositFactor::get();
+
+		fn on_runtime_upgrade() -> Weight {
+			migrate_to_proxy_definitions::<T>()
+		}
+
 		/// Dispatch the given `call` from an account that the sender is authorised for through
-		/// `add_proxy`.
+		/// `add_proxy`. The proxy must not have a delay; delayed proxies use `proxy_announced`.
 		///
 		/// The dispatch origin for this call must be _Signed_.
 		///
@@ -174,24 +278,10 @@ decl_module! {
 			call: Box<<T as Trait>::Call>
 		) {
 			let who = ensure_signed(origin)?;
-			let (_, proxy_type) = Proxies::<T>::get(&real).0.into_iter()
-				.find(|x| &x.0 == &who && force_proxy_type.as_ref().map_or(true, |y| &x.1 == y))
-				.ok_or(Error::<T>::NotProxy)?;
+			let def = Self::find_proxy(&real, &who, force_proxy_type)?;
+			ensure!(def.delay.is_zero(), Error::<T>::Unannounced);
 
-			// This is a freshly authenticated new account, the origin restrictions doesn't apply.
-			let mut origin: T::Origin = frame_system::RawOrigin::Signed(real).into();
-			origin.add_filter(move |c: &<T as frame_system::Trait>::Call| {
-				let c = <T as Trait>::Call::from_ref(c);
-				match c.is_sub_type() {
-					Some(Call::add_proxy(_, ref pt)) | Some(Call::remove_proxy(_, ref pt))
-						if !proxy_type.is_superset(&pt) => false,
-					Some(Call::remove_proxies(..)) | Some(Call::kill_anonymous(..))
-					    if proxy_type != T::ProxyType::default() => false,
-					_ => proxy_type.filter(c)
-				}
-			});
-			let e = call.dispatch(origin);
-			Self::deposit_event(RawEvent::ProxyExecuted(e.map(|_| ()).map_err(|e| e.error)));
+			Self::do_proxy(def, real, *call);
 		}
 
 		/// Register a proxy account for the sender that is able to make calls on its behalf.
@@ -201,6 +291,8 @@ decl_module! {
 		/// Parameters:
 		/// - `proxy`: The account that the `caller` would like to make a proxy.
 		/// - `proxy_type`: The permissions allowed for this proxy account.
+		/// - `delay`: The announcement period required of the initial proxy. Will generally be
+		/// zero.
 		///
 		/// # <weight>
 		/// P is the number of proxies the user has
@@ -211,13 +303,17 @@ decl_module! {
 			.saturating_add(18 * WEIGHT_PER_MICROS)
 			.saturating_add((200 * WEIGHT_PER_NANOS).saturating_mul(T::MaxProxies::get().into()))
 		]
-		fn add_proxy(origin, proxy: T::AccountId, proxy_type: T::ProxyType) -> DispatchResult {
+		fn add_proxy(origin,
+			proxy: T::AccountId,
+			proxy_type: T::ProxyType,
+			delay: T::BlockNumber,
+		) -> DispatchResult {
 			let who = ensure_signed(origin)?;
 			Proxies::<T>::try_mutate(&who, |(ref mut proxies, ref mut deposit)| {
 				ensure!(proxies.len() < T::MaxProxies::get() as usize, Error::<T>::TooMany);
-				let typed_proxy = (proxy, proxy_type);
-				let i = proxies.binary_search(&typed_proxy).err().ok_or(Error::<T>::Duplicate)?;
-				proxies.insert(i, typed_proxy);
+				let proxy_def = ProxyDefinition { delegate: proxy, proxy_type, delay };
+				let i = proxies.binary_search(&proxy_def).err().ok_or(Error::<T>::Duplicate)?;
+				proxies.insert(i, proxy_def);
 				let new_deposit = T::ProxyDepositBase::get()
 					+ T::ProxyDepositFactor::get() * (proxies.len() as u32).into();
 				if new_deposit > *deposit {
@@ -237,6 +333,7 @@ decl_module! {
 		/// Parameters:
 		/// - `proxy`: The account that the `caller` would like to remove as a proxy.
 		/// - `proxy_type`: The permissions currently enabled for the removed proxy account.
+		/// - `delay`: The announcement period of the removed proxy account.
 		///
 		/// # <weight>
 		/// P is the number of proxies the user has
@@ -247,12 +344,16 @@ decl_module! {
 			.saturating_add(14 * WEIGHT_PER_MICROS)
 			.saturating_add((160 * WEIGHT_PER_NANOS).saturating_mul(T::MaxProxies::get().into()))
 		]
-		fn remove_proxy(origin, proxy: T::AccountId, proxy_type: T::ProxyType) -> DispatchResult {
+		fn remove_proxy(origin,
+			proxy: T::AccountId,
+			proxy_type: T::ProxyType,
+			delay: T::BlockNumber,
+		) -> DispatchResult {
 			let who = ensure_signed(origin)?;
 			Proxies::<T>::try_mutate_exists(&who, |x| {
 				let (mut proxies, old_deposit) = x.take().ok_or(Error::<T>::NotFound)?;
-				let typed_proxy = (proxy, proxy_type);
-				let i = proxies.binary_search(&typed_proxy).ok().ok_or(Error::<T>::NotFound)?;
+				let proxy_def = ProxyDefinition { delegate: proxy, proxy_type, delay };
+				let i = proxies.binary_search(&proxy_def).ok().ok_or(Error::<T>::NotFound)?;
 				proxies.remove(i);
 				let new_deposit = if proxies.is_empty() {
 					BalanceOf::<T>::zero()
@@ -326,7 +427,12 @@ decl_module! {
 			ensure!(!Proxies::<T>::contains_key(&anonymous), Error::<T>::Duplicate);
 			let deposit = T::ProxyDepositBase::get() + T::ProxyDepositFactor::get();
 			T::Currency::reserve(&who, deposit)?;
-			Proxies::<T>::insert(&anonymous, (vec![(who.clone(), proxy_type.clone())], deposit));
+			let proxy_def = ProxyDefinition {
+				delegate: who.clone(),
+				proxy_type: proxy_type.clone(),
+				delay: T::BlockNumber::zero(),
+			};
+			Proxies::<T>::insert(&anonymous, (vec![proxy_def], deposit));
 			Self::deposit_event(RawEvent::AnonymousCreated(anonymous, who, proxy_type, index));
 		}
 
@@ -372,10 +478,156 @@ decl_module! {
 			let (_, deposit) = Proxies::<T>::take(&who);
 			T::Currency::unreserve(&spawner, deposit);
 		}
+
+		/// Publish the hash of a proxy-call that will be made in the future.
+		///
+		/// This must be called some number of blocks before the corresponding `proxy_announced`
+		/// is attempted if the delay associated with the proxy relationship is greater than zero.
+		///
+		/// No more than `MaxPending` announcements may be made at any one time.
+		///
+		/// This will take a deposit of `AnnouncementDepositFactor` as well as
+		/// `AnnouncementDepositBase` if there are no other pending announcements.
+		///
+		/// The dispatch origin for this call must be _Signed_ and a proxy of `real`.
+		///
+		/// Parameters:
+		/// - `real`: The account that the proxy will make a call on behalf of.
+		/// - `call_hash`: The hash of the call to be made by the `real` account.
+		///
+		/// # <weight>
+		/// A is the number of announcements, P is the number of proxies of `real`
+		/// - DB weight: 2 storage reads and 1 write.
+		/// # </weight>
+		#[weight = T::DbWeight::get().reads_writes(2, 1)
+			.saturating_add(25 * WEIGHT_PER_MICROS)
+			.saturating_add((140 * WEIGHT_PER_NANOS).saturating_mul(T::MaxProxies::get().into()))
+			.saturating_add((200 * WEIGHT_PER_NANOS).saturating_mul(T::MaxPending::get().into()))
+		]
+		fn announce(origin, real: T::AccountId, call_hash: CallHashOf<T>) {
+			let who = ensure_signed(origin)?;
+			Proxies::<T>::get(&real).0.into_iter()
+				.find(|x| &x.delegate == &who)
+				.ok_or(Error::<T>::NotProxy)?;
+
+			let announcement = Announcement {
+				real: real.clone(),
+				call_hash: call_hash.clone(),
+				height: system::Module::<T>::block_number(),
+			};
+
+			Announcements::<T>::try_mutate(&who, |(ref mut pending, ref mut deposit)| {
+				ensure!(pending.len() < T::MaxPending::get() as usize, Error::<T>::TooMany);
+				pending.push(announcement);
+				Self::rejig_deposit(
+					&who,
+					*deposit,
+					T::AnnouncementDepositBase::get(),
+					T::AnnouncementDepositFactor::get(),
+					pending.len(),
+				).map(|d| d.expect("Just pushed; pending.len() > 0; rejig_deposit returns Some; qed"))
+					.map(|d| *deposit = d)
+			})?;
+			Self::deposit_event(RawEvent::Announced(real, who, call_hash));
+		}
+
+		/// Remove a given announcement.
+		///
+		/// May be called by a proxy account to remove a call they previously announced and return
+		/// the deposit.
+		///
+		/// The dispatch origin for this call must be _Signed_.
+		///
+		/// Parameters:
+		/// - `real`: The account that the proxy will make a call on behalf of.
+		/// - `call_hash`: The hash of the call to be made by the `real` account.
+		///
+		/// # <weight>
+		/// A is the number of announcements
+		/// - DB weight: 1 storage read and write.
+		/// # </weight>
+		#[weight = T::DbWeight::get().reads_writes(1, 1)
+			.saturating_add(20 * WEIGHT_PER_MICROS)
+			.saturating_add((200 * WEIGHT_PER_NANOS).saturating_mul(T::MaxPending::get().into()))
+		]
+		fn remove_announcement(origin, real: T::AccountId, call_hash: CallHashOf<T>) {
+			let who = ensure_signed(origin)?;
+			Self::edit_announcements(&who, |ann| ann.real != real || ann.call_hash != call_hash)?;
+		}
+
+		/// Remove the given announcement of a delegate.
+		///
+		/// May be called by a target (proxied) account to remove a call that one of their delegates
+		/// (`delegate`) has announced they want to execute. The deposit is returned.
+		///
+		/// The dispatch origin for this call must be _Signed_.
+		///
+		/// Parameters:
+		/// - `delegate`: The account that previously announced the call.
+		/// - `call_hash`: The hash of the call to be made.
+		///
+		/// # <weight>
+		/// A is the number of announcements
+		/// - DB weight: 1 storage read and write.
+		/// # </weight>
+		#[weight = T::DbWeight::get().reads_writes(1, 1)
+			.saturating_add(20 * WEIGHT_PER_MICROS)
+			.saturating_add((200 * WEIGHT_PER_NANOS).saturating_mul(T::MaxPending::get().into()))
+		]
+		fn reject_announcement(origin, delegate: T::AccountId, call_hash: CallHashOf<T>) {
+			let who = ensure_signed(origin)?;
+			Self::edit_announcements(&delegate, |ann| ann.real != who || ann.call_hash != call_hash)?;
+		}
+
+		/// Dispatch the given `call` from an account that the sender is authorized for through
+		/// `add_proxy`.
+		///
+		/// Removes any corresponding announcement(s).
+		///
+		/// The dispatch origin for this call must be _Signed_.
+		///
+		/// Parameters:
+		/// - `delegate`: The account that announced the call.
+		/// - `real`: The account that the proxy will make a call on behalf of.
+		/// - `force_proxy_type`: Specify the exact proxy type to be used and checked for this call.
+		/// - `call`: The call to be made by the `real` account.
+		///
+		/// # <weight>
+		/// A is the number of announcements, P is the number of proxies of `real`
+		/// - DB weight: 2 storage reads and 1 write.
+		/// - Plus the weight of the `call`
+		/// # </weight>
+		#[weight = {
+			let di = call.get_dispatch_info();
+			(T::DbWeight::get().reads_writes(2, 1)
+				.saturating_add(di.weight)
+				.saturating_add(25 * WEIGHT_PER_MICROS)
+				.saturating_add((140 * WEIGHT_PER_NANOS).saturating_mul(T::MaxProxies::get().into()))
+				.saturating_add((200 * WEIGHT_PER_NANOS).saturating_mul(T::MaxPending::get().into())),
+			di.class)
+		}]
+		fn proxy_announced(origin,
+			delegate: T::AccountId,
+			real: T::AccountId,
+			force_proxy_type: Option<T::ProxyType>,
+			call: Box<<T as Trait>::Call>,
+		) {
+			ensure_signed(origin)?;
+			let def = Self::find_proxy(&real, &delegate, force_proxy_type)?;
+
+			let call_hash = T::CallHasher::hash_of(&call);
+			let now = system::Module::<T>::block_number();
+			Self::edit_announcements(&delegate, |ann|
+				ann.real != real || ann.call_hash != call_hash || now.saturating_sub(ann.height) < def.delay
+			).map_err(|_| Error::<T>::Unannounced)?;
+
+			Self::do_proxy(def, real, *call);
+		}
 	}
 }
 
 impl<T: Trait> Module<T> {
+	/// Calculate the address of an anonymous account.
 	pub fn anonymous_account(
 		who: &T::AccountId,
 		proxy_type: &T::ProxyType,
@@ -390,4 +642,112 @@ impl<T: Trait> Module<T> {
 			.using_encoded(blake2_256);
 		T::AccountId::decode(&mut &entropy[..]).unwrap_or_default()
 	}
+	/// The deposit to hold for `len` items, given the current deposit `old_deposit`, reserving
+	/// or unreserving the difference. Returns `None` if nothing is held anymore.
+	fn rejig_deposit(
+		who: &T::AccountId,
+		old_deposit: BalanceOf<T>,
+		base: BalanceOf<T>,
+		factor: BalanceOf<T>,
+		len: usize,
+	) -> Result<Option<BalanceOf<T>>, DispatchError> {
+		let new_deposit = if len == 0 {
+			BalanceOf::<T>::zero()
+		} else {
+			base + factor * (len as u32).into()
+		};
+		if new_deposit > old_deposit {
+			T::Currency::reserve(&who, new_deposit - old_deposit)?;
+		} else if new_deposit < old_deposit {
+			T::Currency::unreserve(&who, old_deposit - new_deposit);
+		}
+		Ok(if len == 0 {
+			None
+		} else {
+			Some(new_deposit)
+		})
+	}
+
+	/// Keep the announcements of `delegate` that satisfy `f`, releasing the deposit of the
+	/// others. Fails with `NotFound` if no announcement is removed.
+	fn edit_announcements<
+		F: FnMut(&Announcement<T::AccountId, CallHashOf<T>, T::BlockNumber>) -> bool
+	>(delegate: &T::AccountId, f: F) -> DispatchResult {
+		Announcements::<T>::try_mutate_exists(delegate, |x| {
+			let (mut pending, old_deposit) = x.take().ok_or(Error::<T>::NotFound)?;
+			let orig_pending_len = pending.len();
+			pending.retain(f);
+			ensure!(orig_pending_len > pending.len(), Error::<T>::NotFound);
+			*x = Self::rejig_deposit(
+				delegate,
+				old_deposit,
+				T::AnnouncementDepositBase::get(),
+				T::AnnouncementDepositFactor::get(),
+				pending.len(),
+			)?.map(|deposit| (pending, deposit));
+			Ok(())
+		})
+	}
+
+	/// The proxy definition of `delegate` for `real`, of type `force_proxy_type` if given.
+	fn find_proxy(
+		real: &T::AccountId,
+		delegate: &T::AccountId,
+		force_proxy_type: Option<T::ProxyType>,
+	) -> Result<ProxyDefinition<T::AccountId, T::ProxyType, T::BlockNumber>, DispatchError> {
+		let f = |x: &ProxyDefinition<T::AccountId, T::ProxyType, T::BlockNumber>| -> bool {
+			&x.delegate == delegate && force_proxy_type.as_ref().map_or(true, |y| &x.proxy_type == y)
+		};
+		Ok(Proxies::<T>::get(real).0.into_iter().find(f).ok_or(Error::<T>::NotProxy)?)
+	}
+
+	/// Dispatch `call` from `real`, restricted to what the proxy type of `def` allows.
+	fn do_proxy(
+		def: ProxyDefinition<T::AccountId, T::ProxyType, T::BlockNumber>,
+		real: T::AccountId,
+		call: <T as Trait>::Call,
+	) {
+		// This is a freshly authenticated new account, the origin restrictions doesn't apply.
+		let mut origin: T::Origin = frame_system::RawOrigin::Signed(real).into();
+		origin.add_filter(move |c: &<T as frame_system::Trait>::Call| {
+			let c = <T as Trait>::Call::from_ref(c);
+			// We make sure the proxy call does access this pallet to change modify proxies.
+			match c.is_sub_type() {
+				// Proxy call cannot add or remove a proxy with more permissions than it already has.
+				Some(Call::add_proxy(_, ref pt, _)) | Some(Call::remove_proxy(_, ref pt, _))
+					if !def.proxy_type.is_superset(&pt) => false,
+				// Proxy call cannot remove all proxies or kill anonymous proxies unless it has full permissions.
+				Some(Call::remove_proxies(..)) | Some(Call::kill_anonymous(..))
+					if def.proxy_type != T::ProxyType::default() => false,
+				_ => def.proxy_type.filter(c)
+			}
+		});
+		let e = call.dispatch(origin);
+		Self::deposit_event(RawEvent::ProxyExecuted(e.map(|_| ()).map_err(|e| e.error)));
+	}
+}
+
+/// Migrate the proxies registered as `(proxy, proxy_type)` pairs to proxy definitions without a
+/// delay.
+fn migrate_to_proxy_definitions<T: Trait>() -> Weight {
+	if StorageVersion::get() != Releases::V1 {
+		return 0;
+	}
+
+	Proxies::<T>::translate::<(Vec<(T::AccountId, T::ProxyType)>, BalanceOf<T>), _>(
+		|_, (proxies, deposit)| {
+			let proxies = proxies.into_iter()
+				.map(|(delegate, proxy_type)| ProxyDefinition {
+					delegate,
+					proxy_type,
+					delay: Zero::zero(),
+				})
+				.collect::<Vec<_>>();
+			Some((proxies, deposit))
+		}
+	);
+	StorageVersion::put(Releases::V2);
+
+	let accounts = Proxies::<T>::iter().count() as Weight;
+	T::DbWeight::get().reads_writes(2 * accounts + 1, accounts + 1)
 }
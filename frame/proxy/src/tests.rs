@@ -27,7 +27,7 @@ use frame_support::{
 };
 use codec::{Encode, Decode};
 use sp_core::H256;
-use sp_runtime::{Perbill, traits::{BlakeTwo256, IdentityLookup}, testing::Header};
+use sp_runtime::{Perbill, traits::{BlakeTwo256, IdentityLookup, Hash}, testing::Header};
 use crate as proxy;
 
 impl_outer_origin! {
@@ -100,11 +100,15 @@ impl pallet_balances::Trait for Test {
 impl pallet_utility::Trait for Test {
 	type Event = TestEvent;
 	type Call = Call;
+	type PalletsOrigin = OriginCaller;
 }
 parameter_types! {
 	pub const ProxyDepositBase: u64 = 1;
 	pub const ProxyDepositFactor: u64 = 1;
 	pub const MaxProxies: u16 = 4;
+	pub const MaxPending: u32 = 2;
+	pub const AnnouncementDepositBase: u64 = 1;
+	pub const AnnouncementDepositFactor: u64 = 1;
 }
 #[derive(Copy, Clone, Eq, PartialEq, Ord, PartialOrd, Encode, Decode, RuntimeDebug)]
 pub enum ProxyType {
@@ -144,6 +148,10 @@ impl Trait for Test {
 	type ProxyDepositBase = ProxyDepositBase;
 	type ProxyDepositFactor = ProxyDepositFactor;
 	type MaxProxies = MaxProxies;
+	type MaxPending = MaxPending;
+	type CallHasher = BlakeTwo256;
+	type AnnouncementDepositBase = AnnouncementDepositBase;
+	type AnnouncementDepositFactor = AnnouncementDepositFactor;
 }
 
 type System = frame_system::Module<Test>;
@@ -185,13 +193,176 @@ fn expect_events(e: Vec<TestEvent>) {
 	assert_eq!(last_events(e.len()), e);
 }
 
+#[test]
+fn announcement_works() {
+	new_test_ext().execute_with(|| {
+		assert_ok!(Proxy::add_proxy(Origin::signed(1), 3, ProxyType::Any, 1));
+		assert_ok!(Proxy::add_proxy(Origin::signed(2), 3, ProxyType::Any, 1));
+		assert_eq!(Balances::reserved_balance(3), 0);
+
+		assert_ok!(Proxy::announce(Origin::signed(3), 1, [1; 32].into()));
+		expect_event(RawEvent::Announced(1, 3, [1; 32].into()));
+		assert_eq!(Announcements::<Test>::get(3), (vec![Announcement {
+			real: 1,
+			call_hash: [1; 32].into(),
+			height: 1,
+		}], 2));
+		assert_eq!(Balances::reserved_balance(3), 2);
+
+		assert_ok!(Proxy::announce(Origin::signed(3), 2, [2; 32].into()));
+		assert_eq!(Announcements::<Test>::get(3), (vec![
+			Announcement {
+				real: 1,
+				call_hash: [1; 32].into(),
+				height: 1,
+			},
+			Announcement {
+				real: 2,
+				call_hash: [2; 32].into(),
+				height: 1,
+			},
+		], 3));
+		assert_eq!(Balances::reserved_balance(3), 3);
+
+		assert_noop!(Proxy::announce(Origin::signed(3), 2, [3; 32].into()), Error::<Test>::TooMany);
+		assert_noop!(Proxy::announce(Origin::signed(4), 1, [1; 32].into()), Error::<Test>::NotProxy);
+	});
+}
+
+#[test]
+fn remove_announcement_works() {
+	new_test_ext().execute_with(|| {
+		assert_ok!(Proxy::add_proxy(Origin::signed(1), 3, ProxyType::Any, 1));
+		assert_ok!(Proxy::add_proxy(Origin::signed(2), 3, ProxyType::Any, 1));
+		assert_ok!(Proxy::announce(Origin::signed(3), 1, [1; 32].into()));
+		assert_ok!(Proxy::announce(Origin::signed(3), 2, [2; 32].into()));
+		let e = Error::<Test>::NotFound;
+		assert_noop!(Proxy::remove_announcement(Origin::signed(3), 1, [0; 32].into()), e);
+		assert_ok!(Proxy::remove_announcement(Origin::signed(3), 1, [1; 32].into()));
+		assert_eq!(Announcements::<Test>::get(3), (vec![Announcement {
+			real: 2,
+			call_hash: [2; 32].into(),
+			height: 1,
+		}], 2));
+		assert_eq!(Balances::reserved_balance(3), 2);
+
+		assert_ok!(Proxy::remove_announcement(Origin::signed(3), 2, [2; 32].into()));
+		assert!(!Announcements::<Test>::contains_key(3));
+		assert_eq!(Balances::reserved_balance(3), 0);
+	});
+}
+
+#[test]
+fn reject_announcement_works() {
+	new_test_ext().execute_with(|| {
+		assert_ok!(Proxy::add_proxy(Origin::signed(1), 3, ProxyType::Any, 1));
+		assert_ok!(Proxy::add_proxy(Origin::signed(2), 3, ProxyType::Any, 1));
+		assert_ok!(Proxy::announce(Origin::signed(3), 1, [1; 32].into()));
+		assert_ok!(Proxy::announce(Origin::signed(3), 2, [2; 32].into()));
+		let e = Error::<Test>::NotFound;
+		assert_noop!(Proxy::reject_announcement(Origin::signed(1), 3, [0; 32].into()), e);
+		let e = Error::<Test>::NotFound;
+		assert_noop!(Proxy::reject_announcement(Origin::signed(4), 3, [1; 32].into()), e);
+		assert_ok!(Proxy::reject_announcement(Origin::signed(1), 3, [1; 32].into()));
+		assert_eq!(Announcements::<Test>::get(3), (vec![Announcement {
+			real: 2,
+			call_hash: [2; 32].into(),
+			height: 1,
+		}], 2));
+		assert_eq!(Balances::reserved_balance(3), 2);
+	});
+}
+
+#[test]
+fn announcer_must_be_proxy() {
+	new_test_ext().execute_with(|| {
+		assert_noop!(Proxy::announce(Origin::signed(2), 1, H256::zero()), Error::<Test>::NotProxy);
+	});
+}
+
+#[test]
+fn delayed_requires_pre_announcement() {
+	new_test_ext().execute_with(|| {
+		assert_ok!(Proxy::add_proxy(Origin::signed(1), 2, ProxyType::Any, 1));
+		let call = Box::new(Call::Balances(BalancesCall::transfer(6, 1)));
+		let e = Error::<Test>::Unannounced;
+		assert_noop!(Proxy::proxy(Origin::signed(2), 1, None, call.clone()), e);
+		let e = Error::<Test>::Unannounced;
+		assert_noop!(Proxy::proxy_announced(Origin::signed(0), 2, 1, None, call.clone()), e);
+		let call_hash = BlakeTwo256::hash_of(&call);
+		assert_ok!(Proxy::announce(Origin::signed(2), 1, call_hash));
+		system::Module::<Test>::set_block_number(2);
+		assert_ok!(Proxy::proxy_announced(Origin::signed(0), 2, 1, None, call.clone()));
+	});
+}
+
+#[test]
+fn proxy_announced_removes_announcement_and_returns_deposit() {
+	new_test_ext().execute_with(|| {
+		assert_ok!(Proxy::add_proxy(Origin::signed(1), 3, ProxyType::Any, 1));
+		assert_ok!(Proxy::add_proxy(Origin::signed(2), 3, ProxyType::Any, 1));
+		let call = Box::new(Call::Balances(BalancesCall::transfer(6, 1)));
+		let call_hash = BlakeTwo256::hash_of(&call);
+		assert_ok!(Proxy::announce(Origin::signed(3), 1, call_hash));
+		assert_ok!(Proxy::announce(Origin::signed(3), 2, call_hash));
+		// Too early to execute announced call
+		let e = Error::<Test>::Unannounced;
+		assert_noop!(Proxy::proxy_announced(Origin::signed(0), 3, 1, None, call.clone()), e);
+
+		system::Module::<Test>::set_block_number(2);
+		assert_ok!(Proxy::proxy_announced(Origin::signed(0), 3, 1, None, call.clone()));
+		expect_event(RawEvent::ProxyExecuted(Ok(())));
+		assert_eq!(Balances::free_balance(6), 1);
+		assert_eq!(Announcements::<Test>::get(3), (vec![Announcement {
+			real: 2,
+			call_hash,
+			height: 1,
+		}], 2));
+		assert_eq!(Balances::reserved_balance(3), 2);
+	});
+}
+
+#[test]
+fn rejected_announcement_cannot_be_executed() {
+	new_test_ext().execute_with(|| {
+		assert_ok!(Proxy::add_proxy(Origin::signed(1), 2, ProxyType::Any, 1));
+		let call = Box::new(Call::Balances(BalancesCall::transfer(6, 1)));
+		let call_hash = BlakeTwo256::hash_of(&call);
+		assert_ok!(Proxy::announce(Origin::signed(2), 1, call_hash));
+		assert_ok!(Proxy::reject_announcement(Origin::signed(1), 2, call_hash));
+		assert_eq!(Balances::reserved_balance(2), 0);
+
+		system::Module::<Test>::set_block_number(2);
+		let e = Error::<Test>::Unannounced;
+		assert_noop!(Proxy::proxy_announced(Origin::signed(0), 2, 1, None, call.clone()), e);
+		assert_eq!(Balances::free_balance(6), 0);
+	});
+}
+
+#[test]
+fn migration_to_proxy_definitions_works() {
+	new_test_ext().execute_with(|| {
+		StorageVersion::put(Releases::V1);
+		let old: (Vec<(u64, ProxyType)>, u64) = (vec![(2, ProxyType::Any), (3, ProxyType::JustTransfer)], 3);
+		frame_support::storage::unhashed::put(&Proxies::<Test>::hashed_key_for(1), &old);
+
+		migrate_to_proxy_definitions::<Test>();
+
+		assert_eq!(StorageVersion::get(), Releases::V2);
+		assert_eq!(Proxies::<Test>::get(1), (vec![
+			ProxyDefinition { delegate: 2, proxy_type: ProxyType::Any, delay: 0 },
+			ProxyDefinition { delegate: 3, proxy_type: ProxyType::JustTransfer, delay: 0 },
+		], 3));
+	});
+}
+
 #[test]
 fn filtering_works() {
 	new_test_ext().execute_with(|| {
 		Balances::mutate_account(&1, |a| a.free = 1000);
-		assert_ok!(Proxy::add_proxy(Origin::signed(1), 2, ProxyType::Any));
-		assert_ok!(Proxy::add_proxy(Origin::signed(1), 3, ProxyType::JustTransfer));
-		assert_ok!(Proxy::add_proxy(Origin::signed(1), 4, ProxyType::JustUtility));
+		assert_ok!(Proxy::add_proxy(Origin::signed(1), 2, ProxyType::Any, 0));
+		assert_ok!(Proxy::add_proxy(Origin::signed(1), 3, ProxyType::JustTransfer, 0));
+		assert_ok!(Proxy::add_proxy(Origin::signed(1), 4, ProxyType::JustUtility, 0));
 
 		let call = Box::new(Call::Balances(BalancesCall::transfer(6, 1)));
 		assert_ok!(Proxy::proxy(Origin::signed(2), 1, None, call.clone()));
@@ -224,7 +395,7 @@ fn filtering_works() {
 			RawEvent::ProxyExecuted(Ok(())).into(),
 		]);
 
-		let inner = Box::new(Call::Proxy(ProxyCall::add_proxy(5, ProxyType::Any)));
+		let inner = Box::new(Call::Proxy(ProxyCall::add_proxy(5, ProxyType::Any, 0)));
 		let call = Box::new(Call::Utility(UtilityCall::batch(vec![*inner])));
 		assert_ok!(Proxy::proxy(Origin::signed(2), 1, None, call.clone()));
 		expect_events(vec![UtilityEvent::BatchCompleted.into(), RawEvent::ProxyExecuted(Ok(())).into()]);
@@ -249,24 +420,24 @@ fn filtering_works() {
 #[test]
 fn add_remove_proxies_works() {
 	new_test_ext().execute_with(|| {
-		assert_ok!(Proxy::add_proxy(Origin::signed(1), 2, ProxyType::Any));
-		assert_noop!(Proxy::add_proxy(Origin::signed(1), 2, ProxyType::Any), Error::<Test>::Duplicate);
+		assert_ok!(Proxy::add_proxy(Origin::signed(1), 2, ProxyType::Any, 0));
+		assert_noop!(Proxy::add_proxy(Origin::signed(1), 2, ProxyType::Any, 0), Error::<Test>::Duplicate);
 		assert_eq!(Balances::reserved_balance(1), 2);
-		assert_ok!(Proxy::add_proxy(Origin::signed(1), 2, ProxyType::JustTransfer));
+		assert_ok!(Proxy::add_proxy(Origin::signed(1), 2, ProxyType::JustTransfer, 0));
 		assert_eq!(Balances::reserved_balance(1), 3);
-		assert_ok!(Proxy::add_proxy(Origin::signed(1), 3, ProxyType::Any));
+		assert_ok!(Proxy::add_proxy(Origin::signed(1), 3, ProxyType::Any, 0));
 		assert_eq!(Balances::reserved_balance(1), 4);
-		assert_ok!(Proxy::add_proxy(Origin::signed(1), 4, ProxyType::JustUtility));
+		assert_ok!(Proxy::add_proxy(Origin::signed(1), 4, ProxyType::JustUtility, 0));
 		assert_eq!(Balances::reserved_balance(1), 5);
-		assert_noop!(Proxy::add_proxy(Origin::signed(1), 4, ProxyType::Any), Error::<Test>::TooMany);
-		assert_noop!(Proxy::remove_proxy(Origin::signed(1), 3, ProxyType::JustTransfer), Error::<Test>::NotFound);
-		assert_ok!(Proxy::remove_proxy(Origin::signed(1), 4, ProxyType::JustUtility));
+		assert_noop!(Proxy::add_proxy(Origin::signed(1), 4, ProxyType::Any, 0), Error::<Test>::TooMany);
+		assert_noop!(Proxy::remove_proxy(Origin::signed(1), 3, ProxyType::JustTransfer, 0), Error::<Test>::NotFound);
+		assert_ok!(Proxy::remove_proxy(Origin::signed(1), 4, ProxyType::JustUtility, 0));
 		assert_eq!(Balances::reserved_balance(1), 4);
-		assert_ok!(Proxy::remove_proxy(Origin::signed(1), 3, ProxyType::Any));
+		assert_ok!(Proxy::remove_proxy(Origin::signed(1), 3, ProxyType::Any, 0));
 		assert_eq!(Balances::reserved_balance(1), 3);
-		assert_ok!(Proxy::remove_proxy(Origin::signed(1), 2, ProxyType::Any));
+		assert_ok!(Proxy::remove_proxy(Origin::signed(1), 2, ProxyType::Any, 0));
 		assert_eq!(Balances::reserved_balance(1), 2);
-		assert_ok!(Proxy::remove_proxy(Origin::signed(1), 2, ProxyType::JustTransfer));
+		assert_ok!(Proxy::remove_proxy(Origin::signed(1), 2, ProxyType::JustTransfer, 0));
 		assert_eq!(Balances::reserved_balance(1), 0);
 	});
 }
@@ -274,10 +445,10 @@ fn add_remove_proxies_works() {
 #[test]
 fn cannot_add_proxy_without_balance() {
 	new_test_ext().execute_with(|| {
-		assert_ok!(Proxy::add_proxy(Origin::signed(5), 3, ProxyType::Any));
+		assert_ok!(Proxy::add_proxy(Origin::signed(5), 3, ProxyType::Any, 0));
 		assert_eq!(Balances::reserved_balance(5), 2);
 		assert_noop!(
-			Proxy::add_proxy(Origin::signed(5), 4, ProxyType::Any),
+			Proxy::add_proxy(Origin::signed(5), 4, ProxyType::Any, 0),
 			BalancesError::<Test, _>::InsufficientBalance
 		);
 	});
@@ -286,8 +457,8 @@ fn cannot_add_proxy_without_balance() {
 #[test]
 fn proxying_works() {
 	new_test_ext().execute_with(|| {
-		assert_ok!(Proxy::add_proxy(Origin::signed(1), 2, ProxyType::JustTransfer));
-		assert_ok!(Proxy::add_proxy(Origin::signed(1), 3, ProxyType::Any));
+		assert_ok!(Proxy::add_proxy(Origin::signed(1), 2, ProxyType::JustTransfer, 0));
+		assert_ok!(Proxy::add_proxy(Origin::signed(1), 3, ProxyType::Any, 0));
 
 		let call = Box::new(Call::Balances(BalancesCall::transfer(6, 1)));
 		assert_noop!(Proxy::proxy(Origin::signed(4), 1, None, call.clone()), Error::<Test>::NotProxy);
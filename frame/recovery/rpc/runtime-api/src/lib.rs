@@ -0,0 +1,81 @@
+// This file is part of Substrate.
+
+// Copyright (C) 2020 Parity Technologies (UK) Ltd.
+// SPDX-License-Identifier: Apache-2.0
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// 	http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Runtime API definition required by recovery RPC extensions.
+//!
+//! This API should be imported and implemented by the runtime,
+//! of a node that wants to use the custom RPC extension
+//! adding account recovery access methods.
+
+#![cfg_attr(not(feature = "std"), no_std)]
+
+use sp_std::prelude::*;
+use codec::{Codec, Encode, Decode};
+#[cfg(feature = "std")]
+use serde::{Serialize, Deserialize};
+
+/// A recovery attempt active against a recoverable account.
+#[derive(Eq, PartialEq, Encode, Decode, Default)]
+#[cfg_attr(feature = "std", derive(Debug, Serialize, Deserialize))]
+#[cfg_attr(feature = "std", serde(rename_all = "camelCase"))]
+pub struct RecoveryAttempt<AccountId, BlockNumber, Balance> {
+	/// The account trying to recover the lost account.
+	pub rescuer: AccountId,
+	/// The block number when the recovery attempt started.
+	pub created: BlockNumber,
+	/// The block number from which the recovery can be claimed, given enough vouches.
+	pub claimable_at: BlockNumber,
+	/// The deposit reserved by the rescuer.
+	pub deposit: Balance,
+	/// The friends which have vouched for the attempt so far.
+	pub vouched: Vec<AccountId>,
+}
+
+sp_api::decl_runtime_apis! {
+	/// The API to query the active recovery attempts of the recovery module.
+	pub trait RecoveryApi<AccountId, BlockNumber, Balance> where
+		AccountId: Codec,
+		BlockNumber: Codec,
+		Balance: Codec,
+	{
+		/// Get the recovery attempts active against `account`.
+		fn active_recoveries(account: AccountId) -> Vec<RecoveryAttempt<AccountId, BlockNumber, Balance>>;
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn should_serialize_recovery_attempts() {
+		let attempt = RecoveryAttempt {
+			rescuer: 1u64,
+			created: 5u64,
+			claimable_at: 15,
+			deposit: 10u128,
+			vouched: vec![2, 3],
+		};
+		let json_str = r#"{"rescuer":1,"created":5,"claimableAt":15,"deposit":10,"vouched":[2,3]}"#;
+
+		assert_eq!(serde_json::to_string(&attempt).unwrap(), json_str);
+		assert_eq!(
+			serde_json::from_str::<RecoveryAttempt<u64, u64, u128>>(json_str).unwrap(),
+			attempt,
+		);
+	}
+}
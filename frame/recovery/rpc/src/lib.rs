@@ -0,0 +1,94 @@
+// This file is part of Substrate.
+
+// Copyright (C) 2020 Parity Technologies (UK) Ltd.
+// SPDX-License-Identifier: Apache-2.0
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// 	http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Node-specific RPC methods for querying account recoveries.
+
+use std::sync::Arc;
+
+use codec::Codec;
+use jsonrpc_core::{Error, ErrorCode, Result};
+use jsonrpc_derive::rpc;
+use sp_api::ProvideRuntimeApi;
+use sp_blockchain::HeaderBackend;
+use sp_runtime::{generic::BlockId, traits::Block as BlockT};
+
+pub use self::gen_client::Client as RecoveryClient;
+pub use pallet_recovery_rpc_runtime_api::{
+	self as runtime_api, RecoveryApi as RecoveryRuntimeApi, RecoveryAttempt,
+};
+
+const RUNTIME_ERROR: i64 = 1;
+
+/// Recovery RPC methods.
+#[rpc]
+pub trait RecoveryApi<BlockHash, AccountId, BlockNumber, Balance> {
+	/// Returns the recovery attempts currently active against `account`, with the friends who
+	/// vouched for each of them and the block from which they can be claimed.
+	#[rpc(name = "recovery_activeRecoveries")]
+	fn active_recoveries(
+		&self,
+		account: AccountId,
+		at: Option<BlockHash>,
+	) -> Result<Vec<RecoveryAttempt<AccountId, BlockNumber, Balance>>>;
+}
+
+/// An implementation of recovery specific RPC methods.
+pub struct Recovery<C, B> {
+	client: Arc<C>,
+	_marker: std::marker::PhantomData<B>,
+}
+
+impl<C, B> Recovery<C, B> {
+	/// Create new `Recovery` with the given reference to the client.
+	pub fn new(client: Arc<C>) -> Self {
+		Recovery { client, _marker: Default::default() }
+	}
+}
+
+impl<C, Block, AccountId, BlockNumber, Balance>
+	RecoveryApi<<Block as BlockT>::Hash, AccountId, BlockNumber, Balance> for Recovery<C, Block>
+where
+	Block: BlockT,
+	C: Send + Sync + 'static + ProvideRuntimeApi<Block> + HeaderBackend<Block>,
+	C::Api: RecoveryRuntimeApi<Block, AccountId, BlockNumber, Balance>,
+	AccountId: Codec,
+	BlockNumber: Codec,
+	Balance: Codec,
+{
+	fn active_recoveries(
+		&self,
+		account: AccountId,
+		at: Option<<Block as BlockT>::Hash>,
+	) -> Result<Vec<RecoveryAttempt<AccountId, BlockNumber, Balance>>> {
+		let api = self.client.runtime_api();
+		let at = BlockId::hash(at.unwrap_or_else(||
+			// If the block hash is not supplied assume the best block.
+			self.client.info().best_hash
+		));
+
+		api.active_recoveries(&at, account).map_err(runtime_error_into_rpc_err)
+	}
+}
+
+/// Converts a runtime trap into an RPC error.
+fn runtime_error_into_rpc_err(err: impl std::fmt::Debug) -> Error {
+	Error {
+		code: ErrorCode::ServerError(RUNTIME_ERROR),
+		message: "Runtime trapped".into(),
+		data: Some(format!("{:?}", err).into()),
+	}
+}
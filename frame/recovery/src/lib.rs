@@ -40,6 +40,11 @@
 //! * `delay_period` - The minimum number of blocks after the beginning of the recovery
 //!   process that need to pass before the account can be successfully recovered.
 //!
+//! Friends can be swapped one at a time with `modify_recovery`. The change only takes
+//! effect `FriendChangeDelay` blocks after it was announced, when the account owner calls
+//! `enact_friend_change`, giving the affected friends notice of it. Vouches the removed
+//! friend made for active recovery attempts are dropped when the change is enacted.
+//!
 //! There is a configurable deposit that all users need to pay to create a recovery
 //! configuration. This deposit is composed of a base deposit plus a multiplier for
 //! the number of friends chosen. This deposit is returned in full when the account
@@ -89,6 +94,10 @@
 //! owner who still has access to their account can claim the deposit and
 //! essentially punish the malicious user.
 //!
+//! At most `MaxActiveRecoveries` recovery attempts can be active against an account
+//! at the same time, so that the account owner is never overwhelmed by attempts to
+//! close.
+//!
 //! Furthermore, the malicious recovery attempt can only be successful if the
 //! attacker is also able to get enough friends to vouch for the recovery attempt.
 //! In the case where the account owner prevents a malicious recovery process,
@@ -104,7 +113,9 @@
 //! * Configure a significant `delay_period` for your recovery process: As long as you
 //!   have access to your recoverable account, you need only check the blockchain once
 //!   every `delay_period` blocks to ensure that no recovery attempt is successful
-//!   against your account. Using off-chain notification systems can help with this,
+//!   against your account. Using off-chain notification systems can help with this:
+//!   all events concerning a recoverable account are indexed under the topic returned
+//!   by `notification_topic`, so they can be watched for without decoding every event,
 //!   but ultimately, setting a large `delay_period` means that even the most skilled
 //!   attacker will need to wait this long before they can access your account.
 //! * Use a high threshold of approvals: Setting a value of 1 for the threshold means
@@ -142,6 +153,8 @@
 //!
 //! * `close_recovery` - Close an active recovery process for your account and reclaim the recovery deposit.
 //! * `remove_recovery` - Remove the recovery configuration from the account, making it un-recoverable.
+//! * `modify_recovery` - Announce that one friend of the recovery configuration will be replaced.
+//! * `enact_friend_change` - Replace the friend once the announced change is due.
 //!
 //! #### For Super Users
 //!
@@ -151,16 +164,16 @@
 // Ensure we're `no_std` when compiling for Wasm.
 #![cfg_attr(not(feature = "std"), no_std)]
 
-use sp_std::prelude::*;
+use sp_std::{prelude::*, collections::btree_map::BTreeMap};
 use sp_runtime::{
-	traits::{Dispatchable, SaturatedConversion, CheckedAdd, CheckedMul},
+	traits::{Dispatchable, SaturatedConversion, CheckedAdd, CheckedMul, Hash, Saturating},
 	DispatchResult
 };
 use codec::{Encode, Decode};
 
 use frame_support::{
 	decl_module, decl_event, decl_storage, decl_error, ensure,
-	Parameter, RuntimeDebug, weights::GetDispatchInfo,
+	Parameter, RuntimeDebug, IterableStorageDoubleMap, weights::{GetDispatchInfo, Weight},
 	traits::{Currency, ReservableCurrency, Get, BalanceStatus},
 	dispatch::PostDispatchInfo,
 };
@@ -207,18 +220,26 @@ pub trait Trait: frame_system::Trait {
 	/// `sizeof(BlockNumber, Balance + T * AccountId)` bytes. Where T is a configurable
 	/// threshold.
 	type RecoveryDeposit: Get<BalanceOf<Self>>;
+
+	/// The number of blocks between announcing a change of friends with `modify_recovery`
+	/// and being able to enact it.
+	type FriendChangeDelay: Get<Self::BlockNumber>;
+
+	/// The maximum number of recovery attempts which can be active against an account at the
+	/// same time.
+	type MaxActiveRecoveries: Get<u32>;
 }
 
 /// An active recovery process.
 #[derive(Clone, Eq, PartialEq, Encode, Decode, Default, RuntimeDebug)]
 pub struct ActiveRecovery<BlockNumber, Balance, AccountId> {
 	/// The block number when the recovery process started.
-	created: BlockNumber,
+	pub created: BlockNumber,
 	/// The amount held in reserve of the `depositor`,
 	/// To be returned once this recovery process is closed.
-	deposit: Balance,
+	pub deposit: Balance,
 	/// The friends which have vouched so far. Always sorted.
-	friends: Vec<AccountId>,
+	pub friends: Vec<AccountId>,
 }
 
 /// Configuration for recovering an account.
@@ -236,6 +257,32 @@ pub struct RecoveryConfig<BlockNumber, Balance, AccountId> {
 	threshold: u16,
 }
 
+/// A change of friends announced for a recovery configuration.
+#[derive(Clone, Eq, PartialEq, Encode, Decode, Default, RuntimeDebug)]
+pub struct FriendChange<BlockNumber, AccountId> {
+	/// The friend to be removed.
+	pub old: AccountId,
+	/// The account replacing them.
+	pub new: AccountId,
+	/// The block number from which the change can be enacted.
+	pub enactment: BlockNumber,
+}
+
+// The storage version of the module, used by `on_runtime_upgrade` to decide whether to migrate.
+#[derive(Encode, Decode, Clone, Copy, PartialEq, Eq, RuntimeDebug)]
+enum Releases {
+	/// `ActiveRecoveryCount` does not exist yet.
+	V1,
+	/// `ActiveRecoveryCount` counts the entries of `ActiveRecoveries`.
+	V2,
+}
+
+impl Default for Releases {
+	fn default() -> Self {
+		Releases::V1
+	}
+}
+
 decl_storage! {
 	trait Store for Module<T: Trait> as Recovery {
 		/// The set of recoverable accounts and their recovery configuration.
@@ -251,11 +298,23 @@ decl_storage! {
 			double_map hasher(twox_64_concat) T::AccountId, hasher(twox_64_concat) T::AccountId =>
 			Option<ActiveRecovery<T::BlockNumber, BalanceOf<T>, T::AccountId>>;
 
+		/// The number of active recovery attempts against an account.
+		pub ActiveRecoveryCount get(fn active_recovery_count):
+			map hasher(twox_64_concat) T::AccountId => u32;
+
+		/// The friend changes announced for recoverable accounts which have not been enacted yet.
+		pub PendingFriendChanges get(fn pending_friend_change):
+			map hasher(twox_64_concat) T::AccountId
+			=> Option<FriendChange<T::BlockNumber, T::AccountId>>;
+
 		/// The list of allowed proxy accounts.
 		///
 		/// Map from the user who can access it to the recovered account.
 		pub Proxy get(fn proxy):
 			map hasher(blake2_128_concat) T::AccountId => Option<T::AccountId>;
+
+		/// Storage version of the module.
+		StorageVersion: Releases;
 	}
 }
 
@@ -263,6 +322,7 @@ decl_event! {
 	/// Events type.
 	pub enum Event<T> where
 		AccountId = <T as system::Trait>::AccountId,
+		BlockNumber = <T as system::Trait>::BlockNumber,
 	{
 		/// A recovery process has been set up for an account
 		RecoveryCreated(AccountId),
@@ -276,6 +336,10 @@ decl_event! {
 		AccountRecovered(AccountId, AccountId),
 		/// A recovery process has been removed for an account
 		RecoveryRemoved(AccountId),
+		/// Account_1 announced replacing friend account_2 by account_3, enactable from the block number
+		FriendChangeAnnounced(AccountId, AccountId, AccountId, BlockNumber),
+		/// Account_1 replaced friend account_2 by account_3
+		FriendChanged(AccountId, AccountId, AccountId),
 	}
 }
 
@@ -313,6 +377,14 @@ decl_error! {
 		Overflow,
 		/// This account is already set up for recovery
 		AlreadyProxy,
+		/// This account is already a friend
+		AlreadyFriend,
+		/// There are too many active recovery attempts for this account
+		TooManyRecoveries,
+		/// There is no friend change announced for this account
+		NoFriendChange,
+		/// The friend change cannot be enacted before its notice period is over
+		FriendChangeDelay,
 	}
 }
 
@@ -332,9 +404,19 @@ decl_module! {
 		/// The base amount of currency needed to reserve for starting a recovery.
 		const RecoveryDeposit: BalanceOf<T> = T::RecoveryDeposit::get();
 
+		/// The number of blocks between announcing a change of friends and being able to enact it.
+		const FriendChangeDelay: T::BlockNumber = T::FriendChangeDelay::get();
+
+		/// The maximum number of recovery attempts which can be active against an account.
+		const MaxActiveRecoveries: u32 = T::MaxActiveRecoveries::get();
+
 		/// Deposit one of this module's events by using the default implementation.
 		fn deposit_event() = default;
 
+		fn on_runtime_upgrade() -> Weight {
+			migrate_to_active_recovery_count::<T>()
+		}
+
 		/// Send a call through a recovered account.
 		///
 		/// The dispatch origin for this call must be _Signed_ and registered to
@@ -462,10 +544,11 @@ decl_module! {
 		/// # <weight>
 		/// - One storage read to check that account is recoverable. O(F)
 		/// - One storage read to check that this recovery process hasn't already started. O(1)
+		/// - One storage mutation to count the active recoveries. O(1)
 		/// - One currency reserve operation. O(X)
 		/// - One storage read to get the current block number. O(1)
 		/// - One storage write. O(1).
-		/// - One event.
+		/// - One indexed event.
 		///
 		/// Total Complexity: O(F + X)
 		/// # </weight>
@@ -476,6 +559,9 @@ decl_module! {
 			ensure!(<Recoverable<T>>::contains_key(&account), Error::<T>::NotRecoverable);
 			// Check that the recovery process has not already been started
 			ensure!(!<ActiveRecoveries<T>>::contains_key(&account, &who), Error::<T>::AlreadyStarted);
+			// Check that the account is not already flooded with recovery attempts
+			let active_count = Self::active_recovery_count(&account);
+			ensure!(active_count < T::MaxActiveRecoveries::get(), Error::<T>::TooManyRecoveries);
 			// Take recovery deposit
 			let recovery_deposit = T::RecoveryDeposit::get();
 			T::Currency::reserve(&who, recovery_deposit)?;
@@ -487,7 +573,8 @@ decl_module! {
 			};
 			// Create the active recovery storage item
 			<ActiveRecoveries<T>>::insert(&account, &who, recovery_status);
-			Self::deposit_event(RawEvent::RecoveryInitiated(account, who));
+			<ActiveRecoveryCount<T>>::insert(&account, active_count + 1);
+			Self::deposit_event_for(&account, RawEvent::RecoveryInitiated(account.clone(), who));
 		}
 
 		/// Allow a "friend" of a recoverable account to vouch for an active recovery
@@ -531,7 +618,7 @@ decl_module! {
 			}
 			// Update storage with the latest details
 			<ActiveRecoveries<T>>::insert(&lost, &rescuer, active_recovery);
-			Self::deposit_event(RawEvent::RecoveryVouched(lost, rescuer, who));
+			Self::deposit_event_for(&lost, RawEvent::RecoveryVouched(lost.clone(), rescuer, who));
 		}
 
 		/// Allow a successful rescuer to claim their recovered account.
@@ -576,7 +663,7 @@ decl_module! {
 			// Create the recovery storage item
 			Proxy::<T>::insert(&who, &account);
 			system::Module::<T>::inc_ref(&who);
-			Self::deposit_event(RawEvent::AccountRecovered(account, who));
+			Self::deposit_event_for(&account, RawEvent::AccountRecovered(account.clone(), who));
 		}
 
 		/// As the controller of a recoverable account, close an active recovery
@@ -595,7 +682,8 @@ decl_module! {
 		/// Key: V (len of vouching friends)
 		/// - One storage read/remove to get the active recovery process. O(1), Codec O(V)
 		/// - One balance call to repatriate reserved. O(X)
-		/// - One event.
+		/// - One storage mutation to count the active recoveries. O(1)
+		/// - One indexed event.
 		///
 		/// Total Complexity: O(V + X)
 		/// # </weight>
@@ -607,7 +695,8 @@ decl_module! {
 			// Move the reserved funds from the rescuer to the rescued account.
 			// Acts like a slashing mechanism for those who try to maliciously recover accounts.
 			let _ = T::Currency::repatriate_reserved(&rescuer, &who, active_recovery.deposit, BalanceStatus::Free);
-			Self::deposit_event(RawEvent::RecoveryClosed(who, rescuer));
+			<ActiveRecoveryCount<T>>::mutate(&who, |count| *count = count.saturating_sub(1));
+			Self::deposit_event_for(&who, RawEvent::RecoveryClosed(who.clone(), rescuer));
 		}
 
 		/// Remove the recovery process for your account. Recovered accounts are still accessible.
@@ -626,6 +715,7 @@ decl_module! {
 		/// Key: F (len of friends)
 		/// - One storage read to get the prefix iterator for active recoveries. O(1)
 		/// - One storage read/remove to get the recovery configuration. O(1), Codec O(F)
+		/// - Two storage removals of the recovery count and pending friend change. O(1)
 		/// - One balance call to unreserved. O(X)
 		/// - One event.
 		///
@@ -639,6 +729,8 @@ decl_module! {
 			ensure!(active_recoveries.next().is_none(), Error::<T>::StillActive);
 			// Take the recovery configuration for this account.
 			let recovery_config = <Recoverable<T>>::take(&who).ok_or(Error::<T>::NotRecoverable)?;
+			<ActiveRecoveryCount<T>>::remove(&who);
+			<PendingFriendChanges<T>>::remove(&who);
 
 			// Unreserve the initial deposit for the recovery configuration.
 			T::Currency::unreserve(&who, recovery_config.deposit);
@@ -664,9 +756,119 @@ decl_module! {
 			Proxy::<T>::remove(&who);
 			system::Module::<T>::dec_ref(&who);
 		}
+
+		/// Announce that one friend of your recovery configuration will be replaced by another
+		/// account.
+		///
+		/// The change can be enacted with `enact_friend_change` once `FriendChangeDelay` blocks
+		/// have passed. Announcing a change replaces any change announced before.
+		///
+		/// The dispatch origin for this call must be _Signed_ and must be a
+		/// recoverable account (i.e. has a recovery configuration).
+		///
+		/// Parameters:
+		/// - `old`: The friend to remove from the recovery configuration.
+		/// - `new`: The account to add to the recovery configuration in place of `old`.
+		///
+		/// # <weight>
+		/// Key: F (len of friends)
+		/// - One storage read to get the recovery configuration. O(1), Codec O(F)
+		/// - Two binary searches to check `old` is a friend and `new` is not. O(logF)
+		/// - One storage read to get the current block number. O(1)
+		/// - One storage write. O(1).
+		/// - One indexed event.
+		///
+		/// Total Complexity: O(F + logF)
+		/// # </weight>
+		#[weight = 50_000_000]
+		fn modify_recovery(origin, old: T::AccountId, new: T::AccountId) {
+			let who = ensure_signed(origin)?;
+			let recovery_config = Self::recovery_config(&who).ok_or(Error::<T>::NotRecoverable)?;
+			ensure!(Self::is_friend(&recovery_config.friends, &old), Error::<T>::NotFriend);
+			ensure!(!Self::is_friend(&recovery_config.friends, &new), Error::<T>::AlreadyFriend);
+			let enactment = <system::Module<T>>::block_number()
+				.checked_add(&T::FriendChangeDelay::get())
+				.ok_or(Error::<T>::Overflow)?;
+			let change = FriendChange { old: old.clone(), new: new.clone(), enactment };
+			<PendingFriendChanges<T>>::insert(&who, change);
+			Self::deposit_event_for(&who, RawEvent::FriendChangeAnnounced(who.clone(), old, new, enactment));
+		}
+
+		/// Enact the friend change announced with `modify_recovery` for your account.
+		///
+		/// Any vouch the removed friend made for an active recovery attempt against your
+		/// account is dropped.
+		///
+		/// The dispatch origin for this call must be _Signed_ and must be a
+		/// recoverable account with a friend change due.
+		///
+		/// # <weight>
+		/// Key: F (len of friends), A (number of active recoveries), V (len of vouching friends)
+		/// - One storage read/remove to get the pending friend change. O(1)
+		/// - One storage read to get the current block number. O(1)
+		/// - One storage mutation of the recovery configuration. O(1), Codec O(F)
+		/// - One storage iteration and at most A writes of the active recoveries. O(A), Codec O(A * V)
+		/// - One indexed event.
+		///
+		/// Total Complexity: O(F + A * V), where A is at most `MaxActiveRecoveries`.
+		/// # </weight>
+		#[weight = T::DbWeight::get().reads_writes(1, 1)
+			.saturating_add(10_000_000)
+			.saturating_mul(T::MaxActiveRecoveries::get() as Weight)
+			.saturating_add(100_000_000)
+		]
+		fn enact_friend_change(origin) {
+			let who = ensure_signed(origin)?;
+			let change = Self::pending_friend_change(&who).ok_or(Error::<T>::NoFriendChange)?;
+			ensure!(change.enactment <= <system::Module<T>>::block_number(), Error::<T>::FriendChangeDelay);
+			<Recoverable<T>>::try_mutate(&who, |maybe_config| -> DispatchResult {
+				let config = maybe_config.as_mut().ok_or(Error::<T>::NotRecoverable)?;
+				let old_pos = config.friends.binary_search(&change.old).map_err(|_| Error::<T>::NotFriend)?;
+				config.friends.remove(old_pos);
+				match config.friends.binary_search(&change.new) {
+					Ok(_pos) => Err(Error::<T>::AlreadyFriend)?,
+					Err(pos) => config.friends.insert(pos, change.new.clone()),
+				}
+				Ok(())
+			})?;
+			<PendingFriendChanges<T>>::remove(&who);
+
+			// Drop the vouches of the removed friend.
+			let vouched = <ActiveRecoveries<T>>::iter_prefix(&who)
+				.filter(|(_, active_recovery)| Self::is_friend(&active_recovery.friends, &change.old))
+				.collect::<Vec<_>>();
+			for (rescuer, mut active_recovery) in vouched {
+				active_recovery.friends.retain(|friend| friend != &change.old);
+				<ActiveRecoveries<T>>::insert(&who, &rescuer, active_recovery);
+			}
+
+			Self::deposit_event_for(&who, RawEvent::FriendChanged(who.clone(), change.old, change.new));
+		}
 	}
 }
 
+/// Count the active recovery attempts against each account into `ActiveRecoveryCount`.
+fn migrate_to_active_recovery_count<T: Trait>() -> Weight {
+	if StorageVersion::get() != Releases::V1 {
+		return T::DbWeight::get().reads(1);
+	}
+
+	// counted from scratch, so that counts kept since the module was added are not doubled.
+	let mut counts = BTreeMap::<T::AccountId, u32>::new();
+	let mut attempts = 0;
+	for (account, _, _) in <ActiveRecoveries<T>>::iter() {
+		*counts.entry(account).or_default() += 1;
+		attempts += 1;
+	}
+	let accounts = counts.len() as Weight;
+	for (account, count) in counts {
+		<ActiveRecoveryCount<T>>::insert(&account, count);
+	}
+	StorageVersion::put(Releases::V2);
+
+	T::DbWeight::get().reads_writes(attempts + 1, accounts + 1)
+}
+
 impl<T: Trait> Module<T> {
 	/// Check that friends list is sorted and has no duplicates.
 	fn is_sorted_and_unique(friends: &Vec<T::AccountId>) -> bool {
@@ -677,4 +879,33 @@ impl<T: Trait> Module<T> {
 	fn is_friend(friends: &Vec<T::AccountId>, friend: &T::AccountId) -> bool {
 		friends.binary_search(&friend).is_ok()
 	}
+
+	/// The topic under which all events concerning the recoverable `account` are indexed.
+	pub fn notification_topic(account: &T::AccountId) -> T::Hash {
+		T::Hashing::hash_of(&(b"recovery", account))
+	}
+
+	/// Deposit an event concerning the recoverable `account`, indexed by its notification topic.
+	fn deposit_event_for(account: &T::AccountId, event: Event<T>) {
+		let event = <T as Trait>::Event::from(event).into();
+		<system::Module<T>>::deposit_event_indexed(&[Self::notification_topic(account)], event);
+	}
+
+	/// The recovery attempts active against `lost`, along with the block number from which each
+	/// of them can be claimed.
+	pub fn active_recoveries_of(lost: &T::AccountId) -> Vec<(
+		T::AccountId,
+		ActiveRecovery<T::BlockNumber, BalanceOf<T>, T::AccountId>,
+		T::BlockNumber,
+	)> {
+		let delay_period = Self::recovery_config(lost)
+			.map(|config| config.delay_period)
+			.unwrap_or_default();
+		<ActiveRecoveries<T>>::iter_prefix(lost)
+			.map(|(rescuer, active_recovery)| {
+				let claimable_at = active_recovery.created.saturating_add(delay_period);
+				(rescuer, active_recovery, claimable_at)
+			})
+			.collect()
+	}
 }
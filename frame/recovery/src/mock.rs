@@ -107,6 +107,8 @@ parameter_types! {
 	pub const FriendDepositFactor: u64 = 1;
 	pub const MaxFriends: u16 = 3;
 	pub const RecoveryDeposit: u64 = 10;
+	pub const FriendChangeDelay: u64 = 10;
+	pub const MaxActiveRecoveries: u32 = 2;
 }
 
 impl Trait for Test {
@@ -117,6 +119,8 @@ impl Trait for Test {
 	type FriendDepositFactor = FriendDepositFactor;
 	type MaxFriends = MaxFriends;
 	type RecoveryDeposit = RecoveryDeposit;
+	type FriendChangeDelay = FriendChangeDelay;
+	type MaxActiveRecoveries = MaxActiveRecoveries;
 }
 
 pub type Recovery = Module<Test>;
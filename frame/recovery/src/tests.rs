@@ -19,7 +19,7 @@
 
 use super::*;
 use mock::{
-	Recovery, Balances, Test, Origin, Call, BalancesCall, RecoveryCall,
+	Recovery, Balances, System, Test, TestEvent, Origin, Call, BalancesCall, RecoveryCall,
 	new_test_ext, run_to_block
 };
 use sp_runtime::traits::{BadOrigin};
@@ -385,3 +385,140 @@ fn remove_recovery_works() {
 		assert_ok!(Recovery::remove_recovery(Origin::signed(5)));
 	});
 }
+
+#[test]
+fn initiate_recovery_is_capped() {
+	new_test_ext().execute_with(|| {
+		let friends = vec![2, 3, 4];
+		assert_ok!(Recovery::create_recovery(Origin::signed(5), friends, 3, 10));
+		assert_ok!(Recovery::initiate_recovery(Origin::signed(1), 5));
+		assert_ok!(Recovery::initiate_recovery(Origin::signed(2), 5));
+		assert_eq!(Recovery::active_recovery_count(5), 2);
+		// Only `MaxActiveRecoveries` attempts can be active at once
+		assert_noop!(Recovery::initiate_recovery(Origin::signed(3), 5), Error::<Test>::TooManyRecoveries);
+		// Closing one makes room for another
+		assert_ok!(Recovery::close_recovery(Origin::signed(5), 1));
+		assert_eq!(Recovery::active_recovery_count(5), 1);
+		assert_ok!(Recovery::initiate_recovery(Origin::signed(3), 5));
+		assert_eq!(Recovery::active_recovery_count(5), 2);
+	});
+}
+
+#[test]
+fn recovery_events_are_indexed_by_lost_account() {
+	new_test_ext().execute_with(|| {
+		run_to_block(1);
+		let friends = vec![2, 3, 4];
+		assert_ok!(Recovery::create_recovery(Origin::signed(5), friends, 3, 10));
+		assert_ok!(Recovery::initiate_recovery(Origin::signed(1), 5));
+		assert_ok!(Recovery::vouch_recovery(Origin::signed(2), 5, 1));
+
+		let topic = Recovery::notification_topic(&5);
+		let notified = System::events().into_iter()
+			.filter(|record| record.topics == vec![topic])
+			.map(|record| record.event)
+			.collect::<Vec<_>>();
+		assert_eq!(notified, vec![
+			TestEvent::recovery(RawEvent::RecoveryInitiated(5, 1)),
+			TestEvent::recovery(RawEvent::RecoveryVouched(5, 1, 2)),
+		]);
+	});
+}
+
+#[test]
+fn modify_recovery_handles_basic_errors() {
+	new_test_ext().execute_with(|| {
+		assert_noop!(Recovery::modify_recovery(Origin::signed(5), 2, 1), Error::<Test>::NotRecoverable);
+		assert_noop!(Recovery::enact_friend_change(Origin::signed(5)), Error::<Test>::NoFriendChange);
+		let friends = vec![2, 3, 4];
+		assert_ok!(Recovery::create_recovery(Origin::signed(5), friends, 3, 10));
+		// Can only replace an existing friend
+		assert_noop!(Recovery::modify_recovery(Origin::signed(5), 1, 6), Error::<Test>::NotFriend);
+		// Cannot add an existing friend
+		assert_noop!(Recovery::modify_recovery(Origin::signed(5), 2, 3), Error::<Test>::AlreadyFriend);
+		// The notice period must pass
+		assert_ok!(Recovery::modify_recovery(Origin::signed(5), 2, 1));
+		run_to_block(9);
+		assert_noop!(Recovery::enact_friend_change(Origin::signed(5)), Error::<Test>::FriendChangeDelay);
+	});
+}
+
+#[test]
+fn modify_recovery_works() {
+	new_test_ext().execute_with(|| {
+		let friends = vec![2, 3, 4];
+		assert_ok!(Recovery::create_recovery(Origin::signed(5), friends, 2, 10));
+		assert_ok!(Recovery::initiate_recovery(Origin::signed(1), 5));
+		assert_ok!(Recovery::vouch_recovery(Origin::signed(2), 5, 1));
+		assert_ok!(Recovery::vouch_recovery(Origin::signed(3), 5, 1));
+		// Account 5 announces replacing friend 3 by account 6
+		assert_ok!(Recovery::modify_recovery(Origin::signed(5), 3, 6));
+		let change = FriendChange { old: 3, new: 6, enactment: 10 };
+		assert_eq!(Recovery::pending_friend_change(5), Some(change));
+		// Until the change is enacted, friends are unchanged
+		assert_noop!(Recovery::vouch_recovery(Origin::signed(6), 5, 1), Error::<Test>::NotFriend);
+		run_to_block(10);
+		assert_ok!(Recovery::enact_friend_change(Origin::signed(5)));
+		assert_eq!(Recovery::pending_friend_change(5), None);
+		// The configuration is updated, keeping the deposit
+		let recovery_config = RecoveryConfig {
+			delay_period: 10,
+			deposit: 13,
+			friends: vec![2, 4, 6],
+			threshold: 2,
+		};
+		assert_eq!(Recovery::recovery_config(5), Some(recovery_config));
+		// The vouch of the removed friend was dropped
+		assert_eq!(Recovery::active_recovery(&5, &1).unwrap().friends, vec![2]);
+		assert_noop!(Recovery::claim_recovery(Origin::signed(1), 5), Error::<Test>::Threshold);
+		assert_noop!(Recovery::vouch_recovery(Origin::signed(3), 5, 1), Error::<Test>::NotFriend);
+		assert_ok!(Recovery::vouch_recovery(Origin::signed(6), 5, 1));
+		assert_ok!(Recovery::claim_recovery(Origin::signed(1), 5));
+	});
+}
+
+#[test]
+fn active_recoveries_of_works() {
+	new_test_ext().execute_with(|| {
+		let friends = vec![2, 3, 4];
+		assert_ok!(Recovery::create_recovery(Origin::signed(5), friends, 3, 10));
+		assert_ok!(Recovery::initiate_recovery(Origin::signed(1), 5));
+		run_to_block(5);
+		assert_ok!(Recovery::initiate_recovery(Origin::signed(2), 5));
+		assert_ok!(Recovery::vouch_recovery(Origin::signed(3), 5, 2));
+
+		let mut active = Recovery::active_recoveries_of(&5);
+		active.sort_by_key(|(rescuer, _, _)| *rescuer);
+		assert_eq!(active, vec![
+			(1, ActiveRecovery { created: 0, deposit: 10, friends: vec![] }, 10),
+			(2, ActiveRecovery { created: 5, deposit: 10, friends: vec![3] }, 15),
+		]);
+		assert!(Recovery::active_recoveries_of(&4).is_empty());
+	});
+}
+
+#[test]
+fn migration_counts_active_recoveries() {
+	new_test_ext().execute_with(|| {
+		let friends = vec![2, 3, 4];
+		assert_ok!(Recovery::create_recovery(Origin::signed(5), friends.clone(), 3, 10));
+		assert_ok!(Recovery::create_recovery(Origin::signed(1), friends, 3, 10));
+		assert_ok!(Recovery::initiate_recovery(Origin::signed(1), 5));
+		assert_ok!(Recovery::initiate_recovery(Origin::signed(2), 5));
+		assert_ok!(Recovery::initiate_recovery(Origin::signed(3), 1));
+		// Counts did not exist before the migration
+		<ActiveRecoveryCount<Test>>::remove(5);
+		<ActiveRecoveryCount<Test>>::remove(1);
+
+		migrate_to_active_recovery_count::<Test>();
+		assert_eq!(Recovery::active_recovery_count(5), 2);
+		assert_eq!(Recovery::active_recovery_count(1), 1);
+		assert_eq!(Recovery::active_recovery_count(4), 0);
+		assert_eq!(StorageVersion::get(), Releases::V2);
+		assert_noop!(Recovery::initiate_recovery(Origin::signed(4), 5), Error::<Test>::TooManyRecoveries);
+
+		// Migrating again changes nothing
+		migrate_to_active_recovery_count::<Test>();
+		assert_eq!(Recovery::active_recovery_count(5), 2);
+	});
+}
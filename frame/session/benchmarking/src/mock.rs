@@ -152,6 +152,8 @@ pallet_staking_reward_curve::build! {
 parameter_types! {
 	pub const RewardCurve: &'static sp_runtime::curve::PiecewiseLinear<'static> = &I_NPOS;
 	pub const MaxNominatorRewardedPerValidator: u32 = 64;
+	pub const MaxSnapshotNominators: u32 = 10_000;
+	pub const MaxNominators: u32 = 20_000;
 	pub const UnsignedPriority: u64 = 1 << 20;
 }
 
@@ -182,6 +184,10 @@ impl pallet_staking::Trait for Test {
 	type ElectionLookahead = ();
 	type Call = Call;
 	type MaxNominatorRewardedPerValidator = MaxNominatorRewardedPerValidator;
+	type MaxSnapshotNominators = MaxSnapshotNominators;
+	type MaxNominators = MaxNominators;
+	type PayoutSubmitter = ();
+	type ElectionProvider = ();
 	type UnsignedPriority = UnsignedPriority;
 	type MaxIterations = ();
 	type MinSolutionScoreBump = ();
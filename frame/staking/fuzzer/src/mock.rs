@@ -156,6 +156,8 @@ pallet_staking_reward_curve::build! {
 parameter_types! {
 	pub const RewardCurve: &'static sp_runtime::curve::PiecewiseLinear<'static> = &I_NPOS;
 	pub const MaxNominatorRewardedPerValidator: u32 = 64;
+	pub const MaxSnapshotNominators: u32 = 10_000;
+	pub const MaxNominators: u32 = 20_000;
 	pub const MaxIterations: u32 = 20;
 }
 
@@ -188,5 +190,9 @@ impl pallet_staking::Trait for Test {
 	type MaxIterations = MaxIterations;
 	type MinSolutionScoreBump = ();
 	type MaxNominatorRewardedPerValidator = MaxNominatorRewardedPerValidator;
+	type MaxSnapshotNominators = MaxSnapshotNominators;
+	type MaxNominators = MaxNominators;
+	type PayoutSubmitter = ();
+	type ElectionProvider = ();
 	type UnsignedPriority = ();
 }
@@ -0,0 +1,73 @@
+// This file is part of Substrate.
+
+// Copyright (C) 2020 Parity Technologies (UK) Ltd.
+// SPDX-License-Identifier: Apache-2.0
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// 	http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Runtime API definition required by staking RPC extensions.
+//!
+//! This API should be imported and implemented by the runtime,
+//! of a node that wants to use the custom RPC extension
+//! adding staking reward access methods.
+
+#![cfg_attr(not(feature = "std"), no_std)]
+
+use sp_std::prelude::*;
+use codec::{Codec, Encode, Decode};
+#[cfg(feature = "std")]
+use serde::{Serialize, Deserialize};
+use pallet_staking::EraIndex;
+
+/// A validator and era whose reward has not been paid out yet.
+#[derive(Eq, PartialEq, Encode, Decode, Default)]
+#[cfg_attr(feature = "std", derive(Debug, Serialize, Deserialize))]
+#[cfg_attr(feature = "std", serde(rename_all = "camelCase"))]
+pub struct UnclaimedPayout<AccountId> {
+	/// The stash of the validator to call `payout_stakers` for.
+	pub validator: AccountId,
+	/// The era to call `payout_stakers` for.
+	pub era: EraIndex,
+}
+
+impl<AccountId> From<(AccountId, EraIndex)> for UnclaimedPayout<AccountId> {
+	fn from((validator, era): (AccountId, EraIndex)) -> Self {
+		UnclaimedPayout { validator, era }
+	}
+}
+
+sp_api::decl_runtime_apis! {
+	/// The API to query the unclaimed rewards of the staking module.
+	pub trait StakingApi<AccountId> where
+		AccountId: Codec,
+	{
+		/// Get the payouts which would reward `stash` and have not been claimed yet, oldest first.
+		///
+		/// See the staking module's `unclaimed_payouts` function for more details.
+		fn unclaimed_payouts(stash: AccountId) -> Vec<UnclaimedPayout<AccountId>>;
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn should_serialize_unclaimed_payouts() {
+		let payout = UnclaimedPayout::from((11u64, 42));
+		let json_str = r#"{"validator":11,"era":42}"#;
+
+		assert_eq!(serde_json::to_string(&payout).unwrap(), json_str);
+		assert_eq!(serde_json::from_str::<UnclaimedPayout<u64>>(json_str).unwrap(), payout);
+	}
+}
@@ -0,0 +1,93 @@
+// This file is part of Substrate.
+
+// Copyright (C) 2020 Parity Technologies (UK) Ltd.
+// SPDX-License-Identifier: Apache-2.0
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// 	http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Node-specific RPC methods for querying staking rewards.
+
+use std::sync::Arc;
+
+use codec::Codec;
+use jsonrpc_core::{Error, ErrorCode, Result};
+use jsonrpc_derive::rpc;
+use sp_api::ProvideRuntimeApi;
+use sp_blockchain::HeaderBackend;
+use sp_runtime::{generic::BlockId, traits::Block as BlockT};
+
+pub use self::gen_client::Client as StakingClient;
+pub use pallet_staking_rpc_runtime_api::{
+	self as runtime_api, StakingApi as StakingRuntimeApi, UnclaimedPayout,
+};
+
+const RUNTIME_ERROR: i64 = 1;
+
+/// Staking RPC methods.
+#[rpc]
+pub trait StakingApi<BlockHash, AccountId> {
+	/// Returns the `(validator, era)` pairs whose payout would reward `stash`, either as a
+	/// validator or as a nominator, and which have not been claimed yet.
+	///
+	/// Only eras which can still be paid out are returned, oldest first.
+	#[rpc(name = "staking_unclaimedPayouts")]
+	fn unclaimed_payouts(
+		&self,
+		stash: AccountId,
+		at: Option<BlockHash>,
+	) -> Result<Vec<UnclaimedPayout<AccountId>>>;
+}
+
+/// An implementation of staking specific RPC methods.
+pub struct Staking<C, B> {
+	client: Arc<C>,
+	_marker: std::marker::PhantomData<B>,
+}
+
+impl<C, B> Staking<C, B> {
+	/// Create new `Staking` with the given reference to the client.
+	pub fn new(client: Arc<C>) -> Self {
+		Staking { client, _marker: Default::default() }
+	}
+}
+
+impl<C, Block, AccountId> StakingApi<<Block as BlockT>::Hash, AccountId> for Staking<C, Block>
+where
+	Block: BlockT,
+	C: Send + Sync + 'static + ProvideRuntimeApi<Block> + HeaderBackend<Block>,
+	C::Api: StakingRuntimeApi<Block, AccountId>,
+	AccountId: Codec,
+{
+	fn unclaimed_payouts(
+		&self,
+		stash: AccountId,
+		at: Option<<Block as BlockT>::Hash>,
+	) -> Result<Vec<UnclaimedPayout<AccountId>>> {
+		let api = self.client.runtime_api();
+		let at = BlockId::hash(at.unwrap_or_else(||
+			// If the block hash is not supplied assume the best block.
+			self.client.info().best_hash
+		));
+
+		api.unclaimed_payouts(&at, stash).map_err(runtime_error_into_rpc_err)
+	}
+}
+
+/// Converts a runtime trap into an RPC error.
+fn runtime_error_into_rpc_err(err: impl std::fmt::Debug) -> Error {
+	Error {
+		code: ErrorCode::ServerError(RUNTIME_ERROR),
+		message: "Runtime trapped".into(),
+		data: Some(format!("{:?}", err).into()),
+	}
+}
@@ -205,6 +205,27 @@ benchmarks! {
 		let (_, controller) = create_stash_controller::<T>(u, 100)?;
 	}: _(RawOrigin::Signed(controller))
 
+	chill_other {
+		let u in ...;
+		// Offset by one so the nominator does not collide with the validator.
+		let (stash, controller) = create_stash_controller::<T>(u + 1, 100)?;
+		let validators = create_validators::<T>(1, 100)?;
+		Staking::<T>::nominate(RawOrigin::Signed(controller.clone()).into(), validators)?;
+		MinNominatorBond::<T>::put(T::Currency::minimum_balance() * 1000.into());
+		let caller: T::AccountId = account("caller", 0, SEED);
+	}: _(RawOrigin::Signed(caller), controller)
+	verify {
+		assert!(!Nominators::<T>::contains_key(stash));
+	}
+
+	set_min_nominator_bond {
+		let i in 0 .. 1;
+		let bond = T::Currency::minimum_balance() * 10.into();
+	}: _(RawOrigin::Root, bond)
+	verify {
+		assert_eq!(MinNominatorBond::<T>::get(), bond);
+	}
+
 	set_payee {
 		let u in ...;
 		let (stash, controller) = create_stash_controller::<T>(u, 100)?;
@@ -364,6 +385,34 @@ benchmarks! {
 		assert!(validators.len() == v as usize);
 	}
 
+	// The snapshot never holds more than `MaxSnapshotNominators` nominators, however many there are.
+	// Up to `MaxNominators` are read and weighed to pick them, so this goes beyond the snapshot cap.
+	create_stakers_snapshot {
+		let v in 1 .. 10;
+		let n in 1 .. T::MaxNominators::get();
+		MinimumValidatorCount::put(0);
+		create_validators_with_nominators_for_era::<T>(v, n, MAX_NOMINATIONS, true, None)?;
+	}: {
+		let (did_snapshot, _) = Staking::<T>::create_stakers_snapshot();
+		assert!(did_snapshot);
+	}
+	verify {
+		let nominators = Staking::<T>::snapshot_nominators().ok_or("snapshot missing")?;
+		// validators are also added as self-voting nominators.
+		assert!(nominators.len() <= (T::MaxSnapshotNominators::get().min(n) + v) as usize);
+	}
+
+	// The on-chain fallback election is bounded by `MaxSnapshotNominators` in the same way.
+	on_chain_election {
+		let v in 1 .. 10;
+		let n in 1 .. T::MaxNominators::get();
+		MinimumValidatorCount::put(0);
+		create_validators_with_nominators_for_era::<T>(v, n, MAX_NOMINATIONS, true, None)?;
+	}: {
+		let result = Staking::<T>::do_phragmen::<ChainAccuracy>().ok_or("election failed")?;
+		assert!(result.assignments.len() <= (T::MaxSnapshotNominators::get().min(n) + v) as usize);
+	}
+
 	do_slash {
 		let l in 1 .. MAX_UNLOCKING_CHUNKS as u32;
 		let (stash, controller) = create_stash_controller::<T>(0, 100)?;
@@ -725,6 +774,8 @@ mod tests {
 			assert_ok!(test_benchmark_validate::<Test>());
 			assert_ok!(test_benchmark_nominate::<Test>());
 			assert_ok!(test_benchmark_chill::<Test>());
+			assert_ok!(test_benchmark_chill_other::<Test>());
+			assert_ok!(test_benchmark_set_min_nominator_bond::<Test>());
 			assert_ok!(test_benchmark_set_payee::<Test>());
 			assert_ok!(test_benchmark_set_controller::<Test>());
 			assert_ok!(test_benchmark_set_validator_count::<Test>());
@@ -740,6 +791,8 @@ mod tests {
 			assert_ok!(test_benchmark_set_history_depth::<Test>());
 			assert_ok!(test_benchmark_reap_stash::<Test>());
 			assert_ok!(test_benchmark_new_era::<Test>());
+			assert_ok!(test_benchmark_create_stakers_snapshot::<Test>());
+			assert_ok!(test_benchmark_on_chain_election::<Test>());
 			assert_ok!(test_benchmark_do_slash::<Test>());
 			assert_ok!(test_benchmark_payout_all::<Test>());
 			// only run one of them to same time on the CI. ignore the other two.
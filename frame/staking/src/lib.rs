@@ -98,7 +98,11 @@
 //! the misbehaving/offline validators as much as possible, simply because the nominators will also
 //! lose funds if they vote poorly.
 //!
-//! An account can become a nominator via the [`nominate`](enum.Call.html#variant.nominate) call.
+//! An account can become a nominator via the [`nominate`](enum.Call.html#variant.nominate) call,
+//! provided it has at least [`MinNominatorBond`](./struct.MinNominatorBond.html) actively bonded.
+//! Only the [`Trait::MaxSnapshotNominators`] nominators with the most stake are considered in an
+//! election. There are at most [`Trait::MaxNominators`] nominators; once there are that
+//! many, new nominators are rejected.
 //!
 //! #### Rewards and Slash
 //!
@@ -111,6 +115,11 @@
 //! biggest stakers can claim their reward. This is to limit the i/o cost to mutate storage for each
 //! nominator's account.
 //!
+//! Payouts can be automated by a node: with [`Trait::PayoutSubmitter`] configured, the offchain
+//! worker submits `payout_stakers` for the unclaimed eras of the validators listed in its local
+//! storage. The unclaimed payouts of any stash are returned by [`Module::unclaimed_payouts`]. See
+//! the [`offchain_payout`] module for details.
+//!
 //! Slashing can occur at any point in time, once misbehavior is reported. Once slashing is
 //! determined, a value is deducted from the balance of the validator and all the nominators who
 //! voted for this validator (values are deducted from the _stash_ account of the slashed entity).
@@ -127,7 +136,9 @@
 //! This means that if they are a nominator, they will not be considered as voters anymore and if
 //! they are validators, they will no longer be a candidate for the next election.
 //!
-//! An account can step back via the [`chill`](enum.Call.html#variant.chill) call.
+//! An account can step back via the [`chill`](enum.Call.html#variant.chill) call. A nominator whose
+//! active bond has fallen below the minimum can be chilled by anyone via the
+//! [`chill_other`](enum.Call.html#variant.chill_other) call.
 //!
 //! ### Session managing
 //!
@@ -278,6 +289,7 @@ pub mod benchmarking;
 
 pub mod slashing;
 pub mod offchain_election;
+pub mod offchain_payout;
 pub mod inflation;
 
 use sp_std::{
@@ -291,7 +303,7 @@ use codec::{HasCompact, Encode, Decode};
 use frame_support::{
 	decl_module, decl_event, decl_storage, ensure, decl_error,
 	weights::{Weight, constants::{WEIGHT_PER_MICROS, WEIGHT_PER_NANOS}},
-	storage::IterableStorageMap,
+	storage::{IterableStorageMap, IterableStorageDoubleMap},
 	dispatch::{
 		IsSubType, DispatchResult, DispatchResultWithPostInfo, DispatchErrorWithPostInfo,
 		WithPostDispatchInfo,
@@ -307,7 +319,7 @@ use sp_runtime::{
 	curve::PiecewiseLinear,
 	traits::{
 		Convert, Zero, StaticLookup, CheckedSub, Saturating, SaturatedConversion,
-		AtLeast32BitUnsigned, Dispatchable,
+		AtLeast32BitUnsigned, Dispatchable, Bounded,
 	},
 	transaction_validity::{
 		TransactionValidityError, TransactionValidity, ValidTransaction, InvalidTransaction,
@@ -327,7 +339,8 @@ use frame_system::{
 use sp_npos_elections::{
 	ExtendedBalance, Assignment, ElectionScore, ElectionResult as PrimitiveElectionResult,
 	build_support_map, evaluate_support, seq_phragmen, generate_compact_solution_type,
-	is_score_better, VotingLimit, SupportMap, VoteWeight,
+	is_score_better, VotingLimit, SupportMap, VoteWeight, ElectionDataProvider, ElectionProvider,
+	ProvidedElection,
 };
 
 const DEFAULT_MINIMUM_VALIDATOR_COUNT: u32 = 4;
@@ -910,6 +923,29 @@ pub trait Trait: frame_system::Trait + SendTransactionTypes<Call<Self>> {
 	/// their reward. This used to limit the i/o cost for the nominator payout.
 	type MaxNominatorRewardedPerValidator: Get<u32>;
 
+	/// The maximum number of nominators taking part in an election.
+	///
+	/// If there are more nominators than this, only the `$MaxSnapshotNominators` with the most
+	/// stake are put in the snapshot and considered by the on-chain fallback. This bounds the
+	/// cost of the election regardless of the number of nominators.
+	type MaxSnapshotNominators: Get<u32>;
+
+	/// The maximum number of nominators.
+	///
+	/// New nominators are rejected once there are this many, which bounds the number of
+	/// nominators read when creating the snapshot. Should be above `$MaxSnapshotNominators`.
+	type MaxNominators: Get<u32>;
+
+	/// Submits `payout_stakers` for unclaimed eras of locally configured validators from the
+	/// offchain worker. See [`offchain_payout`]. Use `()` to disable.
+	type PayoutSubmitter: offchain_payout::SubmitPayout<Self>;
+
+	/// An external election provider, such as a multi-phase election module, asked for a result at
+	/// the end of every era. If it is active, the election window of this module never opens and
+	/// its result is used; if it has no result, the election runs on-chain. Use `()` to collect
+	/// solutions through the election window instead.
+	type ElectionProvider: ElectionProvider<Self::AccountId>;
+
 	/// A configuration for base priority of unsigned transactions.
 	///
 	/// This is exposed so that it can be tuned for particular runtime, when
@@ -944,6 +980,7 @@ enum Releases {
 	V2_0_0,
 	V3_0_0,
 	V4_0_0,
+	V5_0_0,
 }
 
 impl Default for Releases {
@@ -952,6 +989,17 @@ impl Default for Releases {
 	}
 }
 
+/// Count the existing nominators into `CounterForNominators`.
+fn migrate_to_counted_nominators<T: Trait>() -> Weight {
+	if StorageVersion::get() != Releases::V4_0_0 {
+		return T::DbWeight::get().reads(1)
+	}
+	let count = <Nominators<T>>::iter().count() as u32;
+	CounterForNominators::put(count);
+	StorageVersion::put(Releases::V5_0_0);
+	T::DbWeight::get().reads_writes(count as Weight + 1, 2)
+}
+
 decl_storage! {
 	trait Store for Module<T: Trait> as Staking {
 		/// Number of eras to keep in history.
@@ -994,6 +1042,13 @@ decl_storage! {
 		pub Nominators get(fn nominators):
 			map hasher(twox_64_concat) T::AccountId => Option<Nominations<T::AccountId>>;
 
+		/// The number of entries in `Nominators`.
+		pub CounterForNominators get(fn counter_for_nominators): u32;
+
+		/// The minimum active bond of a nominator. Nominators whose active bond is below this may
+		/// be chilled by anyone.
+		pub MinNominatorBond get(fn min_nominator_bond) config(): BalanceOf<T>;
+
 		/// The current era index.
 		///
 		/// This is the latest planned era, depending on how the Session pallet queues the validator
@@ -1133,8 +1188,8 @@ decl_storage! {
 		/// True if network has been upgraded to this version.
 		/// Storage version of the pallet.
 		///
-		/// This is set to v3.0.0 for new networks.
-		StorageVersion build(|_: &GenesisConfig<T>| Releases::V4_0_0): Releases;
+		/// This is set to v5.0.0 for new networks.
+		StorageVersion build(|_: &GenesisConfig<T>| Releases::V5_0_0): Releases;
 	}
 	add_extra_genesis {
 		config(stakers):
@@ -1196,6 +1251,9 @@ decl_event!(
 		/// An account has called `withdraw_unbonded` and removed unbonding chunks worth `Balance`
 		/// from the unlocking queue.
 		Withdrawn(AccountId, Balance),
+		/// A nominator has been chilled by another account for being below `MinNominatorBond`.
+		/// `AccountId` is the stash account.
+		Chilled(AccountId),
 	}
 );
 
@@ -1267,6 +1325,12 @@ decl_error! {
 		IncorrectHistoryDepth,
 		/// Incorrect number of slashing spans provided.
 		IncorrectSlashingSpans,
+		/// A nominator must have at least `MinNominatorBond` actively bonded.
+		InsufficientBond,
+		/// The account is not a nominator below `MinNominatorBond` and cannot be chilled by others.
+		CannotChillOther,
+		/// There are already `MaxNominators` nominators.
+		TooManyNominators,
 	}
 }
 
@@ -1308,6 +1372,14 @@ decl_module! {
 		/// their reward. This used to limit the i/o cost for the nominator payout.
 		const MaxNominatorRewardedPerValidator: u32 = T::MaxNominatorRewardedPerValidator::get();
 
+		/// The maximum number of nominators taking part in an election.
+		///
+		/// If there are more nominators than this, only those with the most stake are considered.
+		const MaxSnapshotNominators: u32 = T::MaxSnapshotNominators::get();
+
+		/// The maximum number of nominators.
+		const MaxNominators: u32 = T::MaxNominators::get();
+
 		type Error = Error<T>;
 
 		fn deposit_event() = default;
@@ -1324,6 +1396,8 @@ decl_module! {
 				consumed_weight += weight;
 			};
 			if
+				// solutions are collected by the election provider, if there is one.
+				!T::ElectionProvider::is_active() &&
 				// if we don't have any ongoing offchain compute.
 				Self::era_election_status().is_closed() &&
 				// either current session final based on the plan, or we're forcing.
@@ -1377,6 +1451,16 @@ decl_module! {
 					}
 				}
 			}
+
+			if Self::era_election_status().is_closed() {
+				match offchain_payout::submit_unclaimed_payout::<T>(now) {
+					Ok(Some((validator, era))) =>
+						log!(info, "💸 Submitted payout of era {} for validator {:?}.", era, validator),
+					Ok(None) => {},
+					Err(offchain_payout::OffchainPayoutError::NoSigningKey) => {},
+					Err(e) => log!(debug, "skipping payout offchain worker due to [{:?}]", e),
+				}
+			}
 		}
 
 		fn on_finalize() {
@@ -1392,6 +1476,10 @@ decl_module! {
 			// `on_finalize` weight is tracked in `on_initialize`
 		}
 
+		fn on_runtime_upgrade() -> Weight {
+			migrate_to_counted_nominators::<T>()
+		}
+
 		/// Take the origin account as a stash and lock up `value` of its balance. `controller` will
 		/// be the account that controls it.
 		///
@@ -1516,6 +1604,8 @@ decl_module! {
 		/// The dispatch origin for this call must be _Signed_ by the controller, not the stash.
 		/// And, it can be only called when [`EraElectionStatus`] is `Closed`.
 		///
+		/// A nominator cannot unbond below [`MinNominatorBond`]; they must `chill` first.
+		///
 		/// Emits `Unbonded`.
 		///
 		/// See also [`Call::withdraw_unbonded`].
@@ -1531,10 +1621,11 @@ decl_module! {
 		/// ----------
 		/// Base Weight: 50.34 µs
 		/// DB Weight:
-		/// - Read: Era Election Status, Ledger, Current Era, Locks, [Origin Account]
+		/// - Read: Era Election Status, Ledger, Current Era, Locks, [Origin Account], Nominators,
+		///   Min Nominator Bond
 		/// - Write: [Origin Account], Locks, Ledger
 		/// </weight>
-		#[weight = 50 * WEIGHT_PER_MICROS + T::DbWeight::get().reads_writes(4, 2)]
+		#[weight = 50 * WEIGHT_PER_MICROS + T::DbWeight::get().reads_writes(6, 2)]
 		fn unbond(origin, #[compact] value: BalanceOf<T>) {
 			ensure!(Self::era_election_status().is_closed(), Error::<T>::CallNotAllowed);
 			let controller = ensure_signed(origin)?;
@@ -1555,6 +1646,10 @@ decl_module! {
 					ledger.active = Zero::zero();
 				}
 
+				if <Nominators<T>>::contains_key(&ledger.stash) {
+					ensure!(ledger.active >= Self::min_nominator_bond(), Error::<T>::InsufficientBond);
+				}
+
 				// Note: in case there is no current era it is fine to bond one era more.
 				let era = Self::current_era().unwrap_or(0) + T::BondingDuration::get();
 				ledger.unlocking.push(UnlockChunk { value, era });
@@ -1588,12 +1683,14 @@ decl_module! {
 		/// - Reads: EraElectionStatus, Ledger, Current Era, Locks, [Origin Account]
 		/// - Writes: [Origin Account], Locks, Ledger
 		/// Kill: 79.41 + 2.366 * S µs
-		/// - Reads: EraElectionStatus, Ledger, Current Era, Bonded, Slashing Spans, [Origin Account], Locks
-		/// - Writes: Bonded, Slashing Spans (if S > 0), Ledger, Payee, Validators, Nominators, [Origin Account], Locks
+		/// - Reads: EraElectionStatus, Ledger, Current Era, Bonded, Slashing Spans, [Origin Account], Locks,
+		///   Nominators, CounterForNominators
+		/// - Writes: Bonded, Slashing Spans (if S > 0), Ledger, Payee, Validators, Nominators, [Origin Account], Locks,
+		///   CounterForNominators
 		/// - Writes Each: SpanSlash * S
 		/// NOTE: Weight annotation is the kill scenario, we refund otherwise.
 		/// # </weight>
-		#[weight = T::DbWeight::get().reads_writes(6, 6)
+		#[weight = T::DbWeight::get().reads_writes(8, 7)
 			.saturating_add(80 * WEIGHT_PER_MICROS)
 			.saturating_add(
 				(2 * WEIGHT_PER_MICROS).saturating_mul(Weight::from(*num_slashing_spans))
@@ -1652,16 +1749,16 @@ decl_module! {
 		/// -----------
 		/// Base Weight: 17.13 µs
 		/// DB Weight:
-		/// - Read: Era Election Status, Ledger
-		/// - Write: Nominators, Validators
+		/// - Read: Era Election Status, Ledger, Nominators, Counter For Nominators
+		/// - Write: Nominators, Validators, Counter For Nominators
 		/// # </weight>
-		#[weight = 17 * WEIGHT_PER_MICROS + T::DbWeight::get().reads_writes(2, 2)]
+		#[weight = 17 * WEIGHT_PER_MICROS + T::DbWeight::get().reads_writes(4, 3)]
 		pub fn validate(origin, prefs: ValidatorPrefs) {
 			ensure!(Self::era_election_status().is_closed(), Error::<T>::CallNotAllowed);
 			let controller = ensure_signed(origin)?;
 			let ledger = Self::ledger(&controller).ok_or(Error::<T>::NotController)?;
 			let stash = &ledger.stash;
-			<Nominators<T>>::remove(stash);
+			Self::do_remove_nominator(stash);
 			<Validators<T>>::insert(stash, prefs);
 		}
 
@@ -1673,6 +1770,8 @@ decl_module! {
 		/// The dispatch origin for this call must be _Signed_ by the controller, not the stash.
 		/// And, it can be only called when [`EraElectionStatus`] is `Closed`.
 		///
+		/// The stash must have at least [`MinNominatorBond`] actively bonded.
+		///
 		/// # <weight>
 		/// - The transaction's complexity is proportional to the size of `targets` (N)
 		/// which is capped at CompactAssignments::LIMIT (MAX_NOMINATIONS).
@@ -1681,10 +1780,11 @@ decl_module! {
 		/// Base Weight: 22.34 + .36 * N µs
 		/// where N is the number of targets
 		/// DB Weight:
-		/// - Reads: Era Election Status, Ledger, Current Era
-		/// - Writes: Validators, Nominators
+		/// - Reads: Era Election Status, Ledger, Current Era, Min Nominator Bond, Nominators,
+		///   Counter For Nominators
+		/// - Writes: Validators, Nominators, Counter For Nominators
 		/// # </weight>
-		#[weight = T::DbWeight::get().reads_writes(3, 2)
+		#[weight = T::DbWeight::get().reads_writes(6, 3)
 			.saturating_add(22 * WEIGHT_PER_MICROS)
 			.saturating_add((360 * WEIGHT_PER_NANOS).saturating_mul(targets.len() as Weight))
 		]
@@ -1694,6 +1794,12 @@ decl_module! {
 			let ledger = Self::ledger(&controller).ok_or(Error::<T>::NotController)?;
 			let stash = &ledger.stash;
 			ensure!(!targets.is_empty(), Error::<T>::EmptyTargets);
+			ensure!(ledger.active >= Self::min_nominator_bond(), Error::<T>::InsufficientBond);
+			ensure!(
+				<Nominators<T>>::contains_key(stash) ||
+					Self::counter_for_nominators() < T::MaxNominators::get(),
+				Error::<T>::TooManyNominators,
+			);
 			let targets = targets.into_iter()
 				.take(MAX_NOMINATIONS)
 				.map(|t| T::Lookup::lookup(t))
@@ -1707,7 +1813,7 @@ decl_module! {
 			};
 
 			<Validators<T>>::remove(stash);
-			<Nominators<T>>::insert(stash, &nominations);
+			Self::do_add_nominator(stash, nominations);
 		}
 
 		/// Declare no desire to either validate or nominate.
@@ -1724,10 +1830,10 @@ decl_module! {
 		/// --------
 		/// Base Weight: 16.53 µs
 		/// DB Weight:
-		/// - Read: EraElectionStatus, Ledger
-		/// - Write: Validators, Nominators
+		/// - Read: EraElectionStatus, Ledger, Nominators, CounterForNominators
+		/// - Write: Validators, Nominators, CounterForNominators
 		/// # </weight>
-		#[weight = 16 * WEIGHT_PER_MICROS + T::DbWeight::get().reads_writes(2, 2)]
+		#[weight = 16 * WEIGHT_PER_MICROS + T::DbWeight::get().reads_writes(4, 3)]
 		fn chill(origin) {
 			ensure!(Self::era_election_status().is_closed(), Error::<T>::CallNotAllowed);
 			let controller = ensure_signed(origin)?;
@@ -1889,11 +1995,12 @@ decl_module! {
 		/// # <weight>
 		/// O(S) where S is the number of slashing spans to be removed
 		/// Base Weight: 53.07 + 2.365 * S µs
-		/// Reads: Bonded, Slashing Spans, Account, Locks
-		/// Writes: Bonded, Slashing Spans (if S > 0), Ledger, Payee, Validators, Nominators, Account, Locks
+		/// Reads: Bonded, Slashing Spans, Account, Locks, Nominators, CounterForNominators
+		/// Writes: Bonded, Slashing Spans (if S > 0), Ledger, Payee, Validators, Nominators, Account, Locks,
+		/// CounterForNominators
 		/// Writes Each: SpanSlash * S
 		/// # </weight>
-		#[weight = T::DbWeight::get().reads_writes(4, 7)
+		#[weight = T::DbWeight::get().reads_writes(6, 8)
 			.saturating_add(53 * WEIGHT_PER_MICROS)
 			.saturating_add(
 				WEIGHT_PER_MICROS.saturating_mul(2).saturating_mul(Weight::from(*num_slashing_spans))
@@ -2091,11 +2198,12 @@ decl_module! {
 		/// Complexity: O(S) where S is the number of slashing spans on the account.
 		/// Base Weight: 75.94 + 2.396 * S µs
 		/// DB Weight:
-		/// - Reads: Stash Account, Bonded, Slashing Spans, Locks
-		/// - Writes: Bonded, Slashing Spans (if S > 0), Ledger, Payee, Validators, Nominators, Stash Account, Locks
+		/// - Reads: Stash Account, Bonded, Slashing Spans, Locks, Nominators, CounterForNominators
+		/// - Writes: Bonded, Slashing Spans (if S > 0), Ledger, Payee, Validators, Nominators, Stash Account, Locks,
+		///   CounterForNominators
 		/// - Writes Each: SpanSlash * S
 		/// # </weight>
-		#[weight = T::DbWeight::get().reads_writes(4, 7)
+		#[weight = T::DbWeight::get().reads_writes(6, 8)
 			.saturating_add(76 * WEIGHT_PER_MICROS)
 			.saturating_add(
 				WEIGHT_PER_MICROS.saturating_mul(2).saturating_mul(Weight::from(*num_slashing_spans))
@@ -2211,6 +2319,57 @@ decl_module! {
 			);
 			Ok(adjustments)
 		}
+
+		/// Declare that some other nominator, whose active bond is below [`MinNominatorBond`],
+		/// has no desire to nominate.
+		///
+		/// Effects will be felt at the beginning of the next era.
+		///
+		/// The dispatch origin for this call must be _Signed_, by any account. And, it can be only
+		/// called when [`EraElectionStatus`] is `Closed`.
+		///
+		/// Emits `Chilled`.
+		///
+		/// # <weight>
+		/// - Independent of the arguments. Insignificant complexity.
+		/// - Contains a limited number of reads.
+		/// - Writes are limited to the `controller` account key.
+		/// --------
+		/// Base Weight: 21.05 µs
+		/// DB Weight:
+		/// - Read: EraElectionStatus, Ledger, Nominators, Min Nominator Bond, CounterForNominators
+		/// - Write: Validators, Nominators, CounterForNominators
+		/// # </weight>
+		#[weight = 21 * WEIGHT_PER_MICROS + T::DbWeight::get().reads_writes(5, 3)]
+		fn chill_other(origin, controller: T::AccountId) {
+			ensure!(Self::era_election_status().is_closed(), Error::<T>::CallNotAllowed);
+			ensure_signed(origin)?;
+			let ledger = Self::ledger(&controller).ok_or(Error::<T>::NotController)?;
+			let stash = ledger.stash;
+			ensure!(
+				<Nominators<T>>::contains_key(&stash) && ledger.active < Self::min_nominator_bond(),
+				Error::<T>::CannotChillOther,
+			);
+			Self::chill_stash(&stash);
+			Self::deposit_event(RawEvent::Chilled(stash));
+		}
+
+		/// Set the minimum active bond of a nominator.
+		///
+		/// Existing nominators below the new minimum are not chilled automatically; see
+		/// [`Call::chill_other`].
+		///
+		/// The dispatch origin must be Root.
+		///
+		/// # <weight>
+		/// Base Weight: 1.717 µs
+		/// Write: Min Nominator Bond
+		/// # </weight>
+		#[weight = 2 * WEIGHT_PER_MICROS + T::DbWeight::get().writes(1)]
+		fn set_min_nominator_bond(origin, #[compact] value: BalanceOf<T>) {
+			ensure_root(origin)?;
+			<MinNominatorBond<T>>::put(value);
+		}
 	}
 }
 
@@ -2228,8 +2387,35 @@ impl<T: Trait> Module<T> {
 		)
 	}
 
+	/// The nominators taking part in the next election, along with their vote weight and
+	/// nominations, and the total number of nominators that were read.
+	///
+	/// If there are more than `MaxSnapshotNominators` nominators, only those with the most stake
+	/// are kept. Ties are broken by storage order.
+	///
+	/// At most `MaxNominators` nominators are read, which `nominate` ensures is all of them.
+	fn electing_nominators() -> (Vec<(T::AccountId, VoteWeight, Nominations<T::AccountId>)>, usize) {
+		let mut nominators = <Nominators<T>>::iter()
+			.take(T::MaxNominators::get() as usize)
+			.map(|(nominator, nominations)| {
+				let weight = Self::slashable_balance_of_vote_weight(&nominator);
+				(nominator, weight, nominations)
+			})
+			.collect::<Vec<_>>();
+
+		let num_nominators = nominators.len();
+		let max_nominators = T::MaxSnapshotNominators::get() as usize;
+		if num_nominators > max_nominators {
+			nominators.sort_by(|a, b| b.1.cmp(&a.1));
+			nominators.truncate(max_nominators);
+		}
+		(nominators, num_nominators)
+	}
+
 	/// Dump the list of validators and nominators into vectors and keep them on-chain.
 	///
+	/// Only the `MaxSnapshotNominators` nominators with the most stake are kept.
+	///
 	/// This data is used to efficiently evaluate election results. returns `true` if the operation
 	/// is successful.
 	pub fn create_stakers_snapshot() -> (bool, Weight) {
@@ -2238,11 +2424,13 @@ impl<T: Trait> Module<T> {
 			consumed_weight += T::DbWeight::get().reads_writes(reads, writes);
 		};
 		let validators = <Validators<T>>::iter().map(|(v, _)| v).collect::<Vec<_>>();
-		let mut nominators = <Nominators<T>>::iter().map(|(n, _)| n).collect::<Vec<_>>();
+		let (nominators, num_all_nominators) = Self::electing_nominators();
+		let mut nominators = nominators.into_iter().map(|(n, _, _)| n).collect::<Vec<_>>();
 
 		let num_validators = validators.len();
 		let num_nominators = nominators.len();
-		add_db_reads_writes((num_validators + num_nominators) as Weight, 0);
+		// `Nominators`, `Bonded` and `Ledger` for each nominator.
+		add_db_reads_writes((num_validators + 3 * num_all_nominators) as Weight, 0);
 
 		if
 			num_validators > MAX_VALIDATORS ||
@@ -2369,6 +2557,59 @@ impl<T: Trait> Module<T> {
 		Ok(())
 	}
 
+	/// The eras that can still be paid out: the finished eras within `HistoryDepth` of the
+	/// current era.
+	fn claimable_eras() -> Vec<EraIndex> {
+		let current_era = match Self::current_era() {
+			Some(era) => era,
+			None => return Vec::new(),
+		};
+		(current_era.saturating_sub(Self::history_depth())..=current_era)
+			.filter(|era| <ErasValidatorReward<T>>::contains_key(era))
+			.collect()
+	}
+
+	/// Whether the reward of `validator_stash` for `era` has not been paid out yet.
+	///
+	/// Stashes which are no longer bonded cannot be paid out, and are reported as claimed.
+	fn is_unclaimed(validator_stash: &T::AccountId, era: EraIndex) -> bool {
+		Self::bonded(validator_stash)
+			.and_then(Self::ledger)
+			.map_or(false, |ledger| ledger.claimed_rewards.binary_search(&era).is_err())
+	}
+
+	/// The eras in which `validator_stash` earned reward points that have not been paid out yet,
+	/// oldest first.
+	pub fn unclaimed_eras(validator_stash: &T::AccountId) -> Vec<EraIndex> {
+		Self::claimable_eras()
+			.into_iter()
+			.filter(|era| <ErasRewardPoints<T>>::get(era).individual.contains_key(validator_stash))
+			.filter(|era| Self::is_unclaimed(validator_stash, *era))
+			.collect()
+	}
+
+	/// The unpaid `(validator_stash, era)` payouts that would reward `stash`, either as the
+	/// validator itself or as one of its rewarded nominators, oldest era first.
+	///
+	/// This iterates over all exposures of the claimable eras, and is only meant to be called
+	/// offchain, e.g. through the runtime API.
+	pub fn unclaimed_payouts(stash: &T::AccountId) -> Vec<(T::AccountId, EraIndex)> {
+		let mut payouts = Vec::new();
+		for era in Self::claimable_eras() {
+			let points = <ErasRewardPoints<T>>::get(&era);
+			for (validator, exposure) in <ErasStakersClipped<T>>::iter_prefix(era) {
+				let exposed = validator == *stash || exposure.others.iter().any(|i| i.who == *stash);
+				if exposed
+					&& points.individual.contains_key(&validator)
+					&& Self::is_unclaimed(&validator, era)
+				{
+					payouts.push((validator, era));
+				}
+			}
+		}
+		payouts
+	}
+
 	/// Update the ledger for a controller. This will also update the stash lock. The lock will
 	/// will lock the entire funds except paying for further transactions.
 	fn update_ledger(
@@ -2384,10 +2625,26 @@ impl<T: Trait> Module<T> {
 		<Ledger<T>>::insert(controller, ledger);
 	}
 
+	/// Insert `nominations` of `stash`, counting it if it was not a nominator yet.
+	fn do_add_nominator(stash: &T::AccountId, nominations: Nominations<T::AccountId>) {
+		if !<Nominators<T>>::contains_key(stash) {
+			CounterForNominators::mutate(|count| *count = count.saturating_add(1));
+		}
+		<Nominators<T>>::insert(stash, nominations);
+	}
+
+	/// Remove the nominations of `stash`, if any, and stop counting it.
+	fn do_remove_nominator(stash: &T::AccountId) {
+		if <Nominators<T>>::contains_key(stash) {
+			<Nominators<T>>::remove(stash);
+			CounterForNominators::mutate(|count| *count = count.saturating_sub(1));
+		}
+	}
+
 	/// Chill a stash account.
 	fn chill_stash(stash: &T::AccountId) {
 		<Validators<T>>::remove(stash);
-		<Nominators<T>>::remove(stash);
+		Self::do_remove_nominator(stash);
 	}
 
 	/// Actually make a payment to a staker. This uses the currency's reward function
@@ -2855,15 +3112,21 @@ impl<T: Trait> Module<T> {
 	}
 
 	/// Select a new validator set from the assembled stakers and their role preferences. It tries
-	/// first to peek into [`QueuedElected`]. Otherwise, it runs a new on-chain phragmen election.
+	/// first to peek into [`QueuedElected`], then asks [`Trait::ElectionProvider`]. Otherwise, it
+	/// runs a new on-chain phragmen election.
 	///
 	/// If [`QueuedElected`] and [`QueuedScore`] exists, they are both removed. No further storage
 	/// is updated.
 	fn try_do_election() -> Option<ElectionResult<T::AccountId, BalanceOf<T>>> {
-		// an election result from either a stored submission or locally executed one.
-		let next_result = <QueuedElected<T>>::take().or_else(||
-			Self::do_phragmen_with_post_processing::<ChainAccuracy>(ElectionCompute::OnChain)
-		);
+		// the provider is always asked, so that it ends its round even if its result is not used.
+		let provided = T::ElectionProvider::elect();
+		// an election result from either a stored submission, the provider or a locally executed
+		// one.
+		let next_result = <QueuedElected<T>>::take()
+			.or_else(|| provided.map(Self::process_provided_election))
+			.or_else(||
+				Self::do_phragmen_with_post_processing::<ChainAccuracy>(ElectionCompute::OnChain)
+			);
 
 		// either way, kill this. We remove it here to make sure it always has the exact same
 		// lifetime as `QueuedElected`.
@@ -2872,6 +3135,18 @@ impl<T: Trait> Module<T> {
 		next_result
 	}
 
+	/// Translate the result of [`Trait::ElectionProvider`] into an `ElectionResult`.
+	fn process_provided_election(
+		election: ProvidedElection<T::AccountId>,
+	) -> ElectionResult<T::AccountId, BalanceOf<T>> {
+		let ProvidedElection { winners, supports, signed } = election;
+		ElectionResult {
+			elected_stashes: winners,
+			exposures: Self::collect_exposure(supports),
+			compute: if signed { ElectionCompute::Signed } else { ElectionCompute::Unsigned },
+		}
+	}
+
 	/// Execute election and return the new results. The edge weights are processed into support
 	/// values.
 	///
@@ -2923,12 +3198,25 @@ impl<T: Trait> Module<T> {
 	}
 
 	/// Execute phragmen election and return the new results. No post-processing is applied and the
-	/// raw edge weights are returned.
-	///
-	/// Self votes are added and nominations before the most recent slashing span are reaped.
+	/// raw edge weights are returned. The voters are taken from [`Self::election_voters`].
 	///
 	/// No storage item is updated.
 	fn do_phragmen<Accuracy: PerThing>() -> Option<PrimitiveElectionResult<T::AccountId, Accuracy>> {
+		let (all_validators, all_nominators) = Self::election_voters();
+
+		seq_phragmen::<_, Accuracy>(
+			Self::validator_count() as usize,
+			Self::minimum_validator_count().max(1) as usize,
+			all_validators,
+			all_nominators,
+		)
+	}
+
+	/// The candidates and voters of an election.
+	///
+	/// Self votes are added and nominations before the most recent slashing span are reaped. Only
+	/// the `MaxSnapshotNominators` nominators with the most stake are considered.
+	fn election_voters() -> (Vec<T::AccountId>, Vec<(T::AccountId, VoteWeight, Vec<T::AccountId>)>) {
 		let mut all_nominators: Vec<(T::AccountId, VoteWeight, Vec<T::AccountId>)> = Vec::new();
 		let mut all_validators = Vec::new();
 		for (validator, _) in <Validators<T>>::iter() {
@@ -2938,7 +3226,8 @@ impl<T: Trait> Module<T> {
 			all_validators.push(validator);
 		}
 
-		let nominator_votes = <Nominators<T>>::iter().map(|(nominator, nominations)| {
+		let (nominators, _) = Self::electing_nominators();
+		let nominator_votes = nominators.into_iter().map(|(nominator, stake, nominations)| {
 			let Nominations { submitted_in, mut targets, suppressed: _ } = nominations;
 
 			// Filter out nomination targets which were nominated before the most recent
@@ -2950,19 +3239,11 @@ impl<T: Trait> Module<T> {
 				)
 			});
 
-			(nominator, targets)
+			(nominator, stake, targets)
 		});
-		all_nominators.extend(nominator_votes.map(|(n, ns)| {
-			let s = Self::slashable_balance_of_vote_weight(&n);
-			(n, s, ns)
-		}));
+		all_nominators.extend(nominator_votes);
 
-		seq_phragmen::<_, Accuracy>(
-			Self::validator_count() as usize,
-			Self::minimum_validator_count().max(1) as usize,
-			all_validators,
-			all_nominators,
-		)
+		(all_validators, all_nominators)
 	}
 
 	/// Consume a set of [`Supports`] from [`sp_npos_elections`] and collect them into a [`Exposure`]
@@ -3014,7 +3295,7 @@ impl<T: Trait> Module<T> {
 
 		<Payee<T>>::remove(stash);
 		<Validators<T>>::remove(stash);
-		<Nominators<T>>::remove(stash);
+		Self::do_remove_nominator(stash);
 
 		system::Module::<T>::dec_ref(stash);
 
@@ -3105,6 +3386,44 @@ impl<T: Trait> Module<T> {
 
 }
 
+/// Exposes the staking system to an external [`Trait::ElectionProvider`].
+impl<T: Trait> ElectionDataProvider<T::AccountId, T::BlockNumber> for Module<T> {
+	fn targets() -> Vec<T::AccountId> {
+		<Validators<T>>::iter().map(|(v, _)| v).collect()
+	}
+
+	fn voters() -> Vec<(T::AccountId, VoteWeight, Vec<T::AccountId>)> {
+		Self::election_voters().1
+	}
+
+	fn desired_targets() -> u32 {
+		Self::validator_count()
+	}
+
+	fn weight_of_data(targets: u32, voters: u32) -> Weight {
+		// `Validators`, `Bonded` and `Ledger` for each target, the same for each nominator read to
+		// pick the voters, and `SlashingSpans` for each nomination of a voter. The counter of
+		// nominators is read here, and `ValidatorCount` by `desired_targets`.
+		let nominators_read = Self::counter_for_nominators().min(T::MaxNominators::get());
+		let reads = 3 * targets as Weight
+			+ 3 * nominators_read as Weight
+			+ voters as Weight * MAX_NOMINATIONS as Weight
+			+ 2;
+		T::DbWeight::get().reads(reads)
+	}
+
+	/// The election result is taken at the end of the final session of an era. Before that
+	/// session starts the length of the remaining sessions is unknown, and `max_value` is returned.
+	fn next_election_prediction(now: T::BlockNumber) -> T::BlockNumber {
+		if Self::is_current_session_final() {
+			T::NextNewSession::estimate_next_new_session(now)
+				.unwrap_or_else(T::BlockNumber::max_value)
+		} else {
+			T::BlockNumber::max_value()
+		}
+	}
+}
+
 /// In this implementation `new_session(session)` must be called before `end_session(session-1)`
 /// i.e. the new session must be planned before the ending of the previous session.
 ///
@@ -33,7 +33,7 @@ use frame_support::{
 use sp_io;
 use sp_npos_elections::{
 	build_support_map, evaluate_support, reduce, ExtendedBalance, StakedAssignment, ElectionScore,
-	VoteWeight,
+	VoteWeight, ElectionProvider, ProvidedElection,
 };
 use crate::*;
 
@@ -66,6 +66,8 @@ thread_local! {
 	static ELECTION_LOOKAHEAD: RefCell<BlockNumber> = RefCell::new(0);
 	static PERIOD: RefCell<BlockNumber> = RefCell::new(1);
 	static MAX_ITERATIONS: RefCell<u32> = RefCell::new(0);
+	static MAX_SNAPSHOT_NOMINATORS: RefCell<u32> = RefCell::new(100);
+	static MAX_NOMINATORS: RefCell<u32> = RefCell::new(200);
 }
 
 /// Another session handler struct to test on_disabled.
@@ -152,6 +154,20 @@ impl Get<u32> for MaxIterations {
 	}
 }
 
+pub struct MaxSnapshotNominators;
+impl Get<u32> for MaxSnapshotNominators {
+	fn get() -> u32 {
+		MAX_SNAPSHOT_NOMINATORS.with(|v| *v.borrow())
+	}
+}
+
+pub struct MaxNominators;
+impl Get<u32> for MaxNominators {
+	fn get() -> u32 {
+		MAX_NOMINATORS.with(|v| *v.borrow())
+	}
+}
+
 impl_outer_origin! {
 	pub enum Origin for Test  where system = frame_system {}
 }
@@ -305,6 +321,44 @@ impl OnUnbalanced<NegativeImbalanceOf<Test>> for RewardRemainderMock {
 	}
 }
 
+thread_local! {
+	pub static PAYOUT_SUBMITTER_ENABLED: RefCell<bool> = RefCell::new(false);
+	pub static SUBMITTED_PAYOUTS: RefCell<Vec<(AccountId, EraIndex)>> = RefCell::new(vec![]);
+}
+
+/// Records the payouts submitted by the offchain worker, if enabled.
+pub struct PayoutSubmitterMock;
+
+impl crate::offchain_payout::SubmitPayout<Test> for PayoutSubmitterMock {
+	fn can_submit() -> bool {
+		PAYOUT_SUBMITTER_ENABLED.with(|v| *v.borrow())
+	}
+
+	fn submit_payout(validator_stash: AccountId, era: EraIndex) -> Result<(), ()> {
+		SUBMITTED_PAYOUTS.with(|v| v.borrow_mut().push((validator_stash, era)));
+		Ok(())
+	}
+}
+
+thread_local! {
+	pub static ELECTION_PROVIDER_ACTIVE: RefCell<bool> = RefCell::new(false);
+	pub static ELECTIONS_PROVIDED: RefCell<u32> = RefCell::new(0);
+}
+
+/// Counts the calls to `elect`, if enabled, and never has a result.
+pub struct ElectionProviderMock;
+
+impl ElectionProvider<AccountId> for ElectionProviderMock {
+	fn elect() -> Option<ProvidedElection<AccountId>> {
+		ELECTIONS_PROVIDED.with(|v| *v.borrow_mut() += 1);
+		None
+	}
+
+	fn is_active() -> bool {
+		ELECTION_PROVIDER_ACTIVE.with(|v| *v.borrow())
+	}
+}
+
 impl Trait for Test {
 	type Currency = Balances;
 	type UnixTime = Timestamp;
@@ -325,6 +379,10 @@ impl Trait for Test {
 	type MaxIterations = MaxIterations;
 	type MinSolutionScoreBump = MinSolutionScoreBump;
 	type MaxNominatorRewardedPerValidator = MaxNominatorRewardedPerValidator;
+	type MaxSnapshotNominators = MaxSnapshotNominators;
+	type MaxNominators = MaxNominators;
+	type PayoutSubmitter = PayoutSubmitterMock;
+	type ElectionProvider = ElectionProviderMock;
 	type UnsignedPriority = UnsignedPriority;
 }
 
@@ -352,6 +410,9 @@ pub struct ExtBuilder {
 	invulnerables: Vec<AccountId>,
 	has_stakers: bool,
 	max_offchain_iterations: u32,
+	max_snapshot_nominators: u32,
+	max_nominators: u32,
+	min_nominator_bond: Balance,
 }
 
 impl Default for ExtBuilder {
@@ -371,6 +432,9 @@ impl Default for ExtBuilder {
 			invulnerables: vec![],
 			has_stakers: true,
 			max_offchain_iterations: 0,
+			max_snapshot_nominators: 100,
+			max_nominators: 200,
+			min_nominator_bond: 0,
 		}
 	}
 }
@@ -432,6 +496,18 @@ impl ExtBuilder {
 		self.max_offchain_iterations = iterations;
 		self
 	}
+	pub fn max_snapshot_nominators(mut self, max: u32) -> Self {
+		self.max_snapshot_nominators = max;
+		self
+	}
+	pub fn max_nominators(mut self, max: u32) -> Self {
+		self.max_nominators = max;
+		self
+	}
+	pub fn min_nominator_bond(mut self, bond: Balance) -> Self {
+		self.min_nominator_bond = bond;
+		self
+	}
 	pub fn offchain_phragmen_ext(self) -> Self {
 		self.session_per_era(4)
 			.session_length(5)
@@ -444,6 +520,8 @@ impl ExtBuilder {
 		ELECTION_LOOKAHEAD.with(|v| *v.borrow_mut() = self.election_lookahead);
 		PERIOD.with(|v| *v.borrow_mut() = self.session_length);
 		MAX_ITERATIONS.with(|v| *v.borrow_mut() = self.max_offchain_iterations);
+		MAX_SNAPSHOT_NOMINATORS.with(|v| *v.borrow_mut() = self.max_snapshot_nominators);
+		MAX_NOMINATORS.with(|v| *v.borrow_mut() = self.max_nominators);
 	}
 	pub fn build(self) -> sp_io::TestExternalities {
 		let _ = env_logger::try_init();
@@ -509,6 +587,7 @@ impl ExtBuilder {
 			minimum_validator_count: self.minimum_validator_count,
 			invulnerables: self.invulnerables,
 			slash_reward_fraction: Perbill::from_percent(10),
+			min_nominator_bond: self.min_nominator_bond,
 			..Default::default()
 		}
 		.assimilate_storage(&mut storage);
@@ -556,6 +635,7 @@ pub(crate) fn current_era() -> EraIndex {
 
 fn post_conditions() {
 	check_nominators();
+	check_count();
 	check_exposures();
 	check_ledgers();
 }
@@ -564,6 +644,11 @@ pub(crate) fn active_era() -> EraIndex {
 	Staking::active_era().unwrap().index
 }
 
+fn check_count() {
+	let nominator_count = <Nominators<Test>>::iter().count() as u32;
+	assert_eq!(nominator_count, CounterForNominators::get());
+}
+
 fn check_ledgers() {
 	// check the ledger of all stakers.
 	Bonded::<Test>::iter().for_each(|(_, ctrl)| assert_ledger_consistent(ctrl))
@@ -71,7 +71,16 @@ pub(crate) const DEFAULT_LONGEVITY: u64 = 25;
 pub(crate) fn set_check_offchain_execution_status<T: Trait>(
 	now: T::BlockNumber,
 ) -> Result<(), &'static str> {
-	let storage = StorageValueRef::persistent(&OFFCHAIN_HEAD_DB);
+	set_check_offchain_execution_status_at::<T>(OFFCHAIN_HEAD_DB, now)
+}
+
+/// Same as [`set_check_offchain_execution_status`], but tracks the last execution under `key`, so
+/// that independent offchain tasks of this module do not throttle each other.
+pub(crate) fn set_check_offchain_execution_status_at<T: Trait>(
+	key: &[u8],
+	now: T::BlockNumber,
+) -> Result<(), &'static str> {
+	let storage = StorageValueRef::persistent(key);
 	let threshold = T::BlockNumber::from(OFFCHAIN_REPEAT);
 
 	let mutate_stat =
@@ -0,0 +1,141 @@
+// This file is part of Substrate.
+
+// Copyright (C) 2020 Parity Technologies (UK) Ltd.
+// SPDX-License-Identifier: Apache-2.0
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// 	http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Helpers for the offchain worker that pays out staking rewards.
+//!
+//! Rewards are only paid when someone calls `payout_stakers` for a given validator and era, and
+//! they are lost once the era falls out of [`HistoryDepth`](crate::HistoryDepth). A node operator
+//! can opt into automating this: with a [`crypto`] key in the local keystore and a list of
+//! validator stashes stored under [`PAYOUT_VALIDATORS_DB`] in the persistent offchain storage, the
+//! offchain worker periodically submits a signed `payout_stakers` for the oldest unclaimed era of
+//! those validators.
+
+use crate::{Call, EraIndex, Module, Trait, offchain_election::set_check_offchain_execution_status_at};
+use frame_system::offchain::{AppCrypto, CreateSignedTransaction, SendSignedTransaction, Signer};
+use sp_runtime::{KeyTypeId, RuntimeDebug, offchain::storage::StorageValueRef};
+use sp_std::{marker::PhantomData, prelude::*};
+
+/// Key type of the keys used to sign payout transactions.
+pub const PAYOUT_KEY_TYPE: KeyTypeId = KeyTypeId(*b"stpy");
+
+/// Storage key of the SCALE-encoded `Vec<AccountId>` of validator stashes to pay out for.
+pub const PAYOUT_VALIDATORS_DB: &[u8] = b"parity/staking-payout-validators";
+/// Storage key used to store the last execution of the payout offchain worker.
+pub(crate) const PAYOUT_HEAD_DB: &[u8] = b"parity/staking-payout/";
+
+/// Crypto types of the payout signing key.
+pub mod crypto {
+	use super::PAYOUT_KEY_TYPE;
+	use sp_runtime::{MultiSignature, MultiSigner, app_crypto::{app_crypto, sr25519}};
+	app_crypto!(sr25519, PAYOUT_KEY_TYPE);
+
+	/// Identifier of the payout key, to be used with [`super::SignedPayoutSubmitter`].
+	pub struct PayoutAuthId;
+
+	impl frame_system::offchain::AppCrypto<MultiSigner, MultiSignature> for PayoutAuthId {
+		type RuntimeAppPublic = Public;
+		type GenericSignature = sr25519::Signature;
+		type GenericPublic = sr25519::Public;
+	}
+}
+
+/// Error types related to the payout offchain worker.
+#[derive(RuntimeDebug, PartialEq)]
+pub enum OffchainPayoutError {
+	/// The submitter has no key to sign with.
+	NoSigningKey,
+	/// No validators are configured in the offchain storage, or they failed to decode.
+	NotConfigured,
+	/// The worker ran too recently, or on a fork.
+	Throttled(&'static str),
+	/// Submission to the transaction pool failed.
+	PoolSubmissionFailed,
+}
+
+/// Something that can submit `payout_stakers` transactions from the offchain worker.
+pub trait SubmitPayout<T: Trait> {
+	/// Whether a payout could be submitted at all, i.e. a signing key is available.
+	fn can_submit() -> bool;
+
+	/// Submit a `payout_stakers` call for `validator_stash` and `era`.
+	fn submit_payout(validator_stash: T::AccountId, era: EraIndex) -> Result<(), ()>;
+}
+
+/// Never submits anything; this disables the payout offchain worker.
+impl<T: Trait> SubmitPayout<T> for () {
+	fn can_submit() -> bool {
+		false
+	}
+
+	fn submit_payout(_: T::AccountId, _: EraIndex) -> Result<(), ()> {
+		Err(())
+	}
+}
+
+/// Submits payouts as signed transactions, using any local key of the crypto `C`.
+pub struct SignedPayoutSubmitter<C>(PhantomData<C>);
+
+impl<T, C> SubmitPayout<T> for SignedPayoutSubmitter<C> where
+	T: Trait + CreateSignedTransaction<Call<T>>,
+	C: AppCrypto<T::Public, T::Signature>,
+{
+	fn can_submit() -> bool {
+		Signer::<T, C>::any_account().can_sign()
+	}
+
+	fn submit_payout(validator_stash: T::AccountId, era: EraIndex) -> Result<(), ()> {
+		Signer::<T, C>::any_account()
+			.send_signed_transaction(|_| Call::payout_stakers(validator_stash.clone(), era))
+			.ok_or(())?
+			.1
+	}
+}
+
+/// The validator stashes this node pays out for, as configured in the offchain storage.
+fn configured_validators<T: Trait>() -> Option<Vec<T::AccountId>> {
+	StorageValueRef::persistent(PAYOUT_VALIDATORS_DB).get::<Vec<T::AccountId>>().flatten()
+}
+
+/// The internal logic of the payout offchain worker.
+///
+/// Finds the oldest unclaimed era among the configured validators and submits a single payout
+/// for it. Only one transaction is sent per run, since all of them would be signed with the same
+/// account nonce; the remaining payouts are picked up by later runs.
+pub(crate) fn submit_unclaimed_payout<T: Trait>(
+	now: T::BlockNumber,
+) -> Result<Option<(T::AccountId, EraIndex)>, OffchainPayoutError> {
+	if !T::PayoutSubmitter::can_submit() {
+		return Err(OffchainPayoutError::NoSigningKey);
+	}
+	let validators = configured_validators::<T>().ok_or(OffchainPayoutError::NotConfigured)?;
+	set_check_offchain_execution_status_at::<T>(PAYOUT_HEAD_DB, now)
+		.map_err(OffchainPayoutError::Throttled)?;
+
+	let oldest = validators
+		.into_iter()
+		.filter_map(|v| <Module<T>>::unclaimed_eras(&v).first().map(|era| (*era, v)))
+		.min_by_key(|(era, _)| *era);
+
+	match oldest {
+		Some((era, validator)) => {
+			T::PayoutSubmitter::submit_payout(validator.clone(), era)
+				.map_err(|_| OffchainPayoutError::PoolSubmissionFailed)?;
+			Ok(Some((validator, era)))
+		}
+		None => Ok(None),
+	}
+}
@@ -2883,6 +2883,74 @@ mod offchain_phragmen {
 			})
 	}
 
+	#[test]
+	fn offchain_window_stays_closed_with_an_active_election_provider() {
+		ExtBuilder::default()
+			.session_per_era(5)
+			.session_length(10)
+			.election_lookahead(3)
+			.build()
+			.execute_with(|| {
+				ELECTION_PROVIDER_ACTIVE.with(|v| *v.borrow_mut() = true);
+				let provided = || ELECTIONS_PROVIDED.with(|v| *v.borrow());
+
+				run_to_block(39);
+				assert_session_era!(3, 0);
+				assert_eq!(Staking::era_election_status(), ElectionStatus::Closed);
+				assert!(Staking::snapshot_nominators().is_none());
+				assert!(Staking::snapshot_validators().is_none());
+				let before = provided();
+
+				// the provider is asked when the era is planned, and the on-chain fallback is used
+				// since it has no result.
+				run_to_block(40);
+				assert_session_era!(4, 0);
+				assert_eq!(provided(), before + 1);
+				assert_eq!(Staking::current_era(), Some(1));
+
+				run_to_block(89);
+				assert_eq!(Staking::era_election_status(), ElectionStatus::Closed);
+				assert!(Staking::snapshot_validators().is_none());
+				run_to_block(90);
+				assert_session_era!(9, 1);
+				assert_eq!(provided(), before + 2);
+			})
+	}
+
+	#[test]
+	fn election_provider_is_asked_even_when_a_solution_is_queued() {
+		ExtBuilder::default()
+			.offchain_phragmen_ext()
+			.build()
+			.execute_with(|| {
+				run_to_block(12);
+				assert_eq!(Staking::era_election_status(), ElectionStatus::Open(12));
+
+				let (compact, winners, score) = prepare_submission_with(true, 2, |_| {});
+				assert_ok!(submit_solution(Origin::signed(10), winners, compact, score));
+				let provided = ELECTIONS_PROVIDED.with(|v| *v.borrow());
+
+				run_to_block(15);
+				assert_eq!(ELECTIONS_PROVIDED.with(|v| *v.borrow()), provided + 1);
+				assert!(Staking::queued_elected().is_none());
+				assert_eq!(
+					System::events()
+						.into_iter()
+						.map(|r| r.event)
+						.filter_map(|e| {
+							if let MetaEvent::staking(inner) = e {
+								Some(inner)
+							} else {
+								None
+							}
+						})
+						.last()
+						.unwrap(),
+					RawEvent::StakingElection(ElectionCompute::Signed),
+				);
+			})
+	}
+
 	#[test]
 	fn offchain_window_is_triggered_when_forcing() {
 		ExtBuilder::default()
@@ -4384,6 +4452,66 @@ fn payout_stakers_handles_basic_errors() {
 	});
 }
 
+#[test]
+fn unclaimed_payouts_track_rewards_and_claims() {
+	ExtBuilder::default().build_and_execute(|| {
+		// Nothing can be claimed before an era has ended.
+		assert!(Staking::unclaimed_payouts(&101).is_empty());
+
+		Staking::reward_by_ids(vec![(11, 1)]);
+		mock::start_era(1);
+		Staking::reward_by_ids(vec![(11, 1), (21, 1)]);
+		mock::start_era(2);
+
+		// Only eras with reward points are reported.
+		assert_eq!(Staking::unclaimed_eras(&11), vec![0, 1]);
+		assert_eq!(Staking::unclaimed_eras(&21), vec![1]);
+		assert_eq!(Staking::unclaimed_payouts(&11), vec![(11, 0), (11, 1)]);
+		// 101 nominates both.
+		assert_eq_uvec!(Staking::unclaimed_payouts(&101), vec![(11, 0), (11, 1), (21, 1)]);
+		assert!(Staking::unclaimed_payouts(&1337).is_empty());
+
+		assert_ok!(Staking::payout_stakers(Origin::signed(1337), 11, 0));
+		assert_eq!(Staking::unclaimed_eras(&11), vec![1]);
+		assert_eq_uvec!(Staking::unclaimed_payouts(&101), vec![(11, 1), (21, 1)]);
+	});
+}
+
+#[test]
+fn payout_offchain_worker_submits_oldest_unclaimed_era() {
+	use offchain_payout::{OffchainPayoutError, PAYOUT_VALIDATORS_DB, submit_unclaimed_payout};
+	use sp_core::offchain::{OffchainExt, testing::TestOffchainExt};
+	use sp_runtime::offchain::storage::StorageValueRef;
+
+	let mut ext = ExtBuilder::default().build();
+	let (offchain, _) = TestOffchainExt::new();
+	ext.register_extension(OffchainExt::new(offchain));
+	ext.execute_with(|| {
+		Staking::reward_by_ids(vec![(21, 1)]);
+		mock::start_era(1);
+		Staking::reward_by_ids(vec![(11, 1)]);
+		mock::start_era(2);
+		let now = System::block_number();
+
+		// Opt-in: a signing key and a list of validators are needed.
+		assert_eq!(submit_unclaimed_payout::<Test>(now), Err(OffchainPayoutError::NoSigningKey));
+		PAYOUT_SUBMITTER_ENABLED.with(|v| *v.borrow_mut() = true);
+		assert_eq!(submit_unclaimed_payout::<Test>(now), Err(OffchainPayoutError::NotConfigured));
+		StorageValueRef::persistent(PAYOUT_VALIDATORS_DB).set(&vec![11u64, 21]);
+
+		// The oldest era goes first.
+		assert_eq!(submit_unclaimed_payout::<Test>(now), Ok(Some((21, 0))));
+		assert_eq!(
+			submit_unclaimed_payout::<Test>(now + 1),
+			Err(OffchainPayoutError::Throttled("recently executed.")),
+		);
+
+		assert_ok!(Staking::payout_stakers(Origin::signed(1337), 21, 0));
+		assert_eq!(submit_unclaimed_payout::<Test>(now + 10), Ok(Some((11, 1))));
+		assert_eq!(SUBMITTED_PAYOUTS.with(|v| v.borrow().clone()), vec![(21, 0), (11, 1)]);
+	});
+}
+
 #[test]
 fn bond_during_era_correctly_populates_claimed_rewards() {
 	ExtBuilder::default().has_stakers(false).build_and_execute(|| {
@@ -4495,9 +4623,9 @@ fn on_initialize_weight_is_correct() {
 		assert_eq!(Validators::<Test>::iter().count(), 4);
 		assert_eq!(Nominators::<Test>::iter().count(), 5);
 		// With 4 validators and 5 nominator, we should increase weight by:
-		// - (4 + 5) reads
+		// - (4 + 3 * 5) reads, since the stake of each nominator is read
 		// - 3 Writes
-		let final_weight = <Test as frame_system::Trait>::DbWeight::get().reads_writes(4 + 9, 3);
+		let final_weight = <Test as frame_system::Trait>::DbWeight::get().reads_writes(4 + 4 + 3 * 5, 3);
 		assert_eq!(final_weight, Staking::on_initialize(System::block_number()));
 	});
 }
@@ -4531,3 +4659,139 @@ fn payout_creates_controller() {
 		assert!(Balances::free_balance(1337) > 0);
 	})
 }
+
+#[test]
+fn nominate_requires_min_nominator_bond() {
+	ExtBuilder::default().min_nominator_bond(100).build_and_execute(|| {
+		let _ = Balances::make_free_balance_be(&1, 200);
+		assert_ok!(Staking::bond(Origin::signed(1), 2, 50, RewardDestination::Controller));
+		assert_noop!(Staking::nominate(Origin::signed(2), vec![11]), Error::<Test>::InsufficientBond);
+
+		assert_ok!(Staking::bond_extra(Origin::signed(1), 50));
+		assert_ok!(Staking::nominate(Origin::signed(2), vec![11]));
+		assert!(<Nominators<Test>>::contains_key(1));
+	})
+}
+
+#[test]
+fn nominator_cannot_unbond_below_min_nominator_bond() {
+	ExtBuilder::default().min_nominator_bond(100).build_and_execute(|| {
+		bond_nominator(1, 2, 150, vec![11]);
+		assert_noop!(Staking::unbond(Origin::signed(2), 51), Error::<Test>::InsufficientBond);
+		assert_ok!(Staking::unbond(Origin::signed(2), 50));
+
+		// once chilled, the rest can be unbonded.
+		assert_ok!(Staking::chill(Origin::signed(2)));
+		assert_ok!(Staking::unbond(Origin::signed(2), 100));
+	})
+}
+
+#[test]
+fn chill_other_works() {
+	ExtBuilder::default().build_and_execute(|| {
+		bond_nominator(1, 2, 150, vec![11]);
+		// nominator is above the minimum.
+		assert_noop!(Staking::chill_other(Origin::signed(1337), 2), Error::<Test>::CannotChillOther);
+		// validators cannot be chilled by others.
+		assert_noop!(Staking::chill_other(Origin::signed(1337), 10), Error::<Test>::CannotChillOther);
+		assert_noop!(Staking::chill_other(Origin::signed(1337), 1), Error::<Test>::NotController);
+
+		assert_noop!(Staking::set_min_nominator_bond(Origin::signed(1), 200), BadOrigin);
+		assert_ok!(Staking::set_min_nominator_bond(Origin::root(), 200));
+		assert_eq!(Staking::min_nominator_bond(), 200);
+
+		assert_ok!(Staking::chill_other(Origin::signed(1337), 2));
+		assert!(!<Nominators<Test>>::contains_key(1));
+		assert_eq!(
+			System::events().last().unwrap().event,
+			MetaEvent::staking(RawEvent::Chilled(1)),
+		);
+	})
+}
+
+#[test]
+fn snapshot_keeps_nominators_with_most_stake() {
+	ExtBuilder::default()
+		.offchain_phragmen_ext()
+		.max_snapshot_nominators(2)
+		.build()
+		.execute_with(|| {
+			// 101 is the genesis nominator with 500 bonded.
+			bond_nominator(1, 2, 100, vec![11]);
+			bond_nominator(3, 4, 1000, vec![21]);
+			bond_nominator(5, 6, 50, vec![11, 21]);
+
+			assert_eq!(Nominators::<Test>::iter().count(), 4);
+			assert!(Staking::create_stakers_snapshot().0);
+
+			let mut nominators = Staking::snapshot_nominators().unwrap();
+			let validators = Staking::snapshot_validators().unwrap();
+			// validators are appended as self-voting nominators.
+			nominators.truncate(nominators.len() - validators.len());
+			assert_eq!(nominators, vec![3, 101]);
+		})
+}
+
+#[test]
+fn on_chain_election_only_considers_nominators_with_most_stake() {
+	ExtBuilder::default()
+		.max_snapshot_nominators(1)
+		.nominate(false)
+		.build_and_execute(|| {
+			// 101 bonds 500 but nominates nobody; the small nominator is dropped from the election.
+			bond_nominator(1, 2, 10, vec![31]);
+			assert_ok!(Staking::nominate(Origin::signed(100), vec![11]));
+
+			let result = Staking::do_phragmen::<ChainAccuracy>().unwrap();
+			let voters = result.assignments.iter().map(|a| a.who).collect::<Vec<_>>();
+			assert!(voters.contains(&101));
+			assert!(!voters.contains(&1));
+		})
+}
+
+#[test]
+fn nominators_are_counted_and_capped() {
+	ExtBuilder::default().max_nominators(2).build_and_execute(|| {
+		// 101 is the genesis nominator.
+		assert_eq!(Staking::counter_for_nominators(), 1);
+		bond_nominator(1, 2, 100, vec![11]);
+		assert_eq!(Staking::counter_for_nominators(), 2);
+
+		let _ = Balances::make_free_balance_be(&3, 200);
+		assert_ok!(Staking::bond(Origin::signed(3), 4, 100, RewardDestination::Controller));
+		assert_noop!(Staking::nominate(Origin::signed(4), vec![11]), Error::<Test>::TooManyNominators);
+
+		// existing nominators can still change their nominations.
+		assert_ok!(Staking::nominate(Origin::signed(2), vec![21]));
+		assert_eq!(Staking::counter_for_nominators(), 2);
+
+		// once one of them stops nominating, there is room again.
+		assert_ok!(Staking::validate(Origin::signed(2), Default::default()));
+		assert_eq!(Staking::counter_for_nominators(), 1);
+		assert_ok!(Staking::nominate(Origin::signed(4), vec![11]));
+		assert_eq!(Staking::counter_for_nominators(), 2);
+
+		assert_ok!(Staking::chill(Origin::signed(4)));
+		assert_ok!(Staking::chill(Origin::signed(4)));
+		assert_eq!(Staking::counter_for_nominators(), 1);
+	})
+}
+
+#[test]
+fn migration_counts_existing_nominators() {
+	ExtBuilder::default().build_and_execute(|| {
+		assert_eq!(Nominators::<Test>::iter().count(), 1);
+		CounterForNominators::kill();
+		StorageVersion::put(Releases::V4_0_0);
+
+		migrate_to_counted_nominators::<Test>();
+		assert_eq!(Staking::counter_for_nominators(), 1);
+		assert_eq!(StorageVersion::get(), Releases::V5_0_0);
+
+		// running it again does nothing.
+		CounterForNominators::put(5);
+		migrate_to_counted_nominators::<Test>();
+		assert_eq!(Staking::counter_for_nominators(), 5);
+		CounterForNominators::put(1);
+	})
+}
@@ -1152,8 +1152,8 @@ pub trait VestingSchedule<AccountId> {
 
 	/// Adds a vesting schedule to a given account.
 	///
-	/// If there already exists a vesting schedule for the given account, an `Err` is returned
-	/// and nothing is updated.
+	/// If the account cannot be given any more vesting schedules, an `Err` is returned and
+	/// nothing is updated.
 	///
 	/// Is a no-op if the amount to be vested is zero.
 	///
@@ -1165,7 +1165,7 @@ pub trait VestingSchedule<AccountId> {
 		starting_block: Self::Moment,
 	) -> DispatchResult;
 
-	/// Remove a vesting schedule for a given account.
+	/// Remove the vesting schedules of a given account.
 	///
 	/// NOTE: This doesn't alter the free balance of the account.
 	fn remove_vesting_schedule(who: &AccountId);
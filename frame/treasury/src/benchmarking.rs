@@ -29,6 +29,14 @@ use crate::Module as Treasury;
 
 const SEED: u32 = 0;
 
+fn assert_last_event<T: Trait>(generic_event: <T as Trait>::Event) {
+	let events = frame_system::Module::<T>::events();
+	let system_event: <T as frame_system::Trait>::Event = generic_event.into();
+	// compare to the last event record
+	let frame_system::EventRecord { event, .. } = &events[events.len() - 1];
+	assert_eq!(event, &system_event);
+}
+
 // Create the pre-requisite information needed to create a treasury `propose_spend`.
 fn setup_proposal<T: Trait>(u: u32) -> (
 	T::AccountId,
@@ -107,6 +115,60 @@ fn create_approved_proposals<T: Trait>(n: u32) -> Result<(), &'static str> {
 	Ok(())
 }
 
+// Create the pre-requisite information needed to create a treasury `propose_bounty`.
+fn setup_bounty<T: Trait>(u: u32, d: u32) -> (
+	T::AccountId,
+	T::AccountId,
+	BalanceOf<T>,
+	BalanceOf<T>,
+	Vec<u8>,
+) {
+	let caller = account("caller", u, SEED);
+	let value: BalanceOf<T> = T::BountyValueMinimum::get().saturating_mul(100.into());
+	let fee = value / 2.into();
+	let deposit = T::BountyDepositBase::get() + T::BountyDepositPerByte::get() * MAX_BYTES.into();
+	let _ = T::Currency::make_free_balance_be(&caller, deposit);
+	let curator = account("curator", u, SEED);
+	let _ = T::Currency::make_free_balance_be(&curator, fee + T::Currency::minimum_balance());
+	let reason = vec![0; d as usize];
+	(caller, curator, fee, value, reason)
+}
+
+// Create a funded bounty with an active curator, returning the curator and the bounty index.
+fn create_bounty<T: Trait>() -> Result<(
+	<T::Lookup as StaticLookup>::Source,
+	BountyIndex,
+), &'static str> {
+	let (caller, curator, fee, value, reason) = setup_bounty::<T>(0, MAX_BYTES);
+	let curator_lookup = T::Lookup::unlookup(curator.clone());
+	Treasury::<T>::propose_bounty(RawOrigin::Signed(caller).into(), value, reason)?;
+	let bounty_id = BountyCount::get() - 1;
+	Treasury::<T>::approve_bounty(RawOrigin::Root.into(), bounty_id)?;
+	Treasury::<T>::on_initialize(T::BlockNumber::zero());
+	Treasury::<T>::propose_curator(RawOrigin::Root.into(), bounty_id, curator_lookup.clone(), fee)?;
+	Treasury::<T>::accept_curator(RawOrigin::Signed(curator).into(), bounty_id)?;
+	Ok((curator_lookup, bounty_id))
+}
+
+// Make sure the pot is funded.
+fn setup_pot_account<T: Trait>() {
+	let pot_account = Treasury::<T>::account_id();
+	let value = T::Currency::minimum_balance().saturating_mul(1_000_000_000.into());
+	let _ = T::Currency::make_free_balance_be(&pot_account, value);
+}
+
+// Create bounties that are approved for use in `on_initialize`.
+fn create_approved_bounties<T: Trait>(n: u32) -> Result<(), &'static str> {
+	for i in 0 .. n {
+		let (caller, _curator, _fee, value, reason) = setup_bounty::<T>(i, MAX_BYTES);
+		Treasury::<T>::propose_bounty(RawOrigin::Signed(caller).into(), value, reason)?;
+		let bounty_id = BountyCount::get() - 1;
+		Treasury::<T>::approve_bounty(RawOrigin::Root.into(), bounty_id)?;
+	}
+	ensure!(BountyApprovals::get().len() == n as usize, "Not all bounty approved");
+	Ok(())
+}
+
 const MAX_BYTES: u32 = 16384;
 const MAX_TIPPERS: u32 = 100;
 
@@ -208,15 +270,109 @@ benchmarks! {
 		let caller = account("caller", t, SEED);
 	}: _(RawOrigin::Signed(caller), hash)
 
-	on_initialize {
+	propose_bounty {
+		let d in 0 .. MAX_BYTES;
+
+		let (caller, _curator, _fee, value, description) = setup_bounty::<T>(0, d);
+	}: _(RawOrigin::Signed(caller), value, description)
+
+	approve_bounty {
+		let (caller, _curator, _fee, value, reason) = setup_bounty::<T>(0, MAX_BYTES);
+		Treasury::<T>::propose_bounty(RawOrigin::Signed(caller).into(), value, reason)?;
+		let bounty_id = BountyCount::get() - 1;
+	}: _(RawOrigin::Root, bounty_id)
+
+	propose_curator {
+		setup_pot_account::<T>();
+		let (caller, curator, fee, value, reason) = setup_bounty::<T>(0, MAX_BYTES);
+		let curator_lookup = T::Lookup::unlookup(curator.clone());
+		Treasury::<T>::propose_bounty(RawOrigin::Signed(caller).into(), value, reason)?;
+		let bounty_id = BountyCount::get() - 1;
+		Treasury::<T>::approve_bounty(RawOrigin::Root.into(), bounty_id)?;
+		Treasury::<T>::on_initialize(T::BlockNumber::zero());
+	}: _(RawOrigin::Root, bounty_id, curator_lookup, fee)
+
+	// Worst case when curator is inactive and any sender unassigns the curator.
+	unassign_curator {
+		setup_pot_account::<T>();
+		let (_curator_lookup, bounty_id) = create_bounty::<T>()?;
+		frame_system::Module::<T>::set_block_number(T::BountyUpdatePeriod::get() + 1.into());
+		let caller = account("caller", 0, SEED);
+	}: _(RawOrigin::Signed(caller), bounty_id)
+
+	accept_curator {
+		setup_pot_account::<T>();
+		let (caller, curator, fee, value, reason) = setup_bounty::<T>(0, MAX_BYTES);
+		let curator_lookup = T::Lookup::unlookup(curator.clone());
+		Treasury::<T>::propose_bounty(RawOrigin::Signed(caller).into(), value, reason)?;
+		let bounty_id = BountyCount::get() - 1;
+		Treasury::<T>::approve_bounty(RawOrigin::Root.into(), bounty_id)?;
+		Treasury::<T>::on_initialize(T::BlockNumber::zero());
+		Treasury::<T>::propose_curator(RawOrigin::Root.into(), bounty_id, curator_lookup, fee)?;
+	}: _(RawOrigin::Signed(curator), bounty_id)
+
+	award_bounty {
+		setup_pot_account::<T>();
+		let (curator_lookup, bounty_id) = create_bounty::<T>()?;
+		let curator = T::Lookup::lookup(curator_lookup)?;
+		let beneficiary = T::Lookup::unlookup(account("beneficiary", 0, SEED));
+	}: _(RawOrigin::Signed(curator), bounty_id, beneficiary)
+
+	claim_bounty {
+		setup_pot_account::<T>();
+		let (curator_lookup, bounty_id) = create_bounty::<T>()?;
+		let curator = T::Lookup::lookup(curator_lookup)?;
+
+		let beneficiary_account: T::AccountId = account("beneficiary", 0, SEED);
+		let beneficiary = T::Lookup::unlookup(beneficiary_account.clone());
+		Treasury::<T>::award_bounty(RawOrigin::Signed(curator.clone()).into(), bounty_id, beneficiary)?;
+
+		frame_system::Module::<T>::set_block_number(T::BountyDepositPayoutDelay::get());
+		ensure!(T::Currency::free_balance(&beneficiary_account).is_zero(), "Beneficiary already has balance");
+	}: _(RawOrigin::Signed(curator), bounty_id)
+	verify {
+		ensure!(!T::Currency::free_balance(&beneficiary_account).is_zero(), "Beneficiary didn't get paid");
+	}
+
+	close_bounty_proposed {
+		setup_pot_account::<T>();
+		let (caller, _curator, _fee, value, reason) = setup_bounty::<T>(0, 0);
+		Treasury::<T>::propose_bounty(RawOrigin::Signed(caller).into(), value, reason)?;
+		let bounty_id = BountyCount::get() - 1;
+	}: close_bounty(RawOrigin::Root, bounty_id)
+
+	close_bounty_active {
+		setup_pot_account::<T>();
+		let (_curator_lookup, bounty_id) = create_bounty::<T>()?;
+	}: close_bounty(RawOrigin::Root, bounty_id)
+	verify {
+		assert_last_event::<T>(RawEvent::BountyCanceled(bounty_id).into())
+	}
+
+	extend_bounty_expiry {
+		setup_pot_account::<T>();
+		let (curator_lookup, bounty_id) = create_bounty::<T>()?;
+		let curator = T::Lookup::lookup(curator_lookup)?;
+	}: _(RawOrigin::Signed(curator), bounty_id, Vec::new())
+	verify {
+		assert_last_event::<T>(RawEvent::BountyExtended(bounty_id).into())
+	}
+
+	on_initialize_proposals {
 		let p in 0 .. 100;
-		let pot_account = Treasury::<T>::account_id();
-		let value = T::Currency::minimum_balance().saturating_mul(1_000_000_000.into());
-		let _ = T::Currency::make_free_balance_be(&pot_account, value);
+		setup_pot_account::<T>();
 		create_approved_proposals::<T>(p)?;
 	}: {
 		Treasury::<T>::on_initialize(T::BlockNumber::zero());
 	}
+
+	on_initialize_bounties {
+		let b in 0 .. 100;
+		setup_pot_account::<T>();
+		create_approved_bounties::<T>(b)?;
+	}: {
+		Treasury::<T>::on_initialize(T::BlockNumber::zero());
+	}
 }
 
 #[cfg(test)]
@@ -236,7 +392,18 @@ mod tests {
 			assert_ok!(test_benchmark_tip_new::<Test>());
 			assert_ok!(test_benchmark_tip::<Test>());
 			assert_ok!(test_benchmark_close_tip::<Test>());
-			assert_ok!(test_benchmark_on_initialize::<Test>());
+			assert_ok!(test_benchmark_propose_bounty::<Test>());
+			assert_ok!(test_benchmark_approve_bounty::<Test>());
+			assert_ok!(test_benchmark_propose_curator::<Test>());
+			assert_ok!(test_benchmark_unassign_curator::<Test>());
+			assert_ok!(test_benchmark_accept_curator::<Test>());
+			assert_ok!(test_benchmark_award_bounty::<Test>());
+			assert_ok!(test_benchmark_claim_bounty::<Test>());
+			assert_ok!(test_benchmark_close_bounty_proposed::<Test>());
+			assert_ok!(test_benchmark_close_bounty_active::<Test>());
+			assert_ok!(test_benchmark_extend_bounty_expiry::<Test>());
+			assert_ok!(test_benchmark_on_initialize_proposals::<Test>());
+			assert_ok!(test_benchmark_on_initialize_bounties::<Test>());
 		});
 	}
 }
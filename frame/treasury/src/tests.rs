@@ -67,7 +67,7 @@ impl frame_system::Trait for Test {
 	type Call = ();
 	type Hash = H256;
 	type Hashing = BlakeTwo256;
-	type AccountId = u64;
+	type AccountId = u128; // u64 is not enough to hold bytes used to generate bounty account
 	type Lookup = IdentityLookup<Self::AccountId>;
 	type Header = Header;
 	type Event = Event;
@@ -96,17 +96,17 @@ impl pallet_balances::Trait for Test {
 	type AccountStore = System;
 }
 thread_local! {
-	static TEN_TO_FOURTEEN: RefCell<Vec<u64>> = RefCell::new(vec![10,11,12,13,14]);
+	static TEN_TO_FOURTEEN: RefCell<Vec<u128>> = RefCell::new(vec![10,11,12,13,14]);
 }
 pub struct TenToFourteen;
-impl Contains<u64> for TenToFourteen {
-	fn sorted_members() -> Vec<u64> {
+impl Contains<u128> for TenToFourteen {
+	fn sorted_members() -> Vec<u128> {
 		TEN_TO_FOURTEEN.with(|v| {
 			v.borrow().clone()
 		})
 	}
 	#[cfg(feature = "runtime-benchmarks")]
-	fn add(new: &u64) {
+	fn add(new: &u128) {
 		TEN_TO_FOURTEEN.with(|v| {
 			let mut members = v.borrow_mut();
 			members.push(*new);
@@ -130,12 +130,19 @@ parameter_types! {
 	pub const TipReportDepositBase: u64 = 1;
 	pub const TipReportDepositPerByte: u64 = 1;
 	pub const TreasuryModuleId: ModuleId = ModuleId(*b"py/trsry");
+	pub const BountyDepositBase: u64 = 80;
+	pub const BountyDepositPerByte: u64 = 1;
+	pub const BountyDepositPayoutDelay: u64 = 3;
+	pub const BountyUpdatePeriod: u64 = 20;
+	pub const BountyCuratorDeposit: Permill = Permill::from_percent(50);
+	pub const BountyValueMinimum: u64 = 1;
+	pub const MaximumReasonLength: u32 = 16384;
 }
 impl Trait for Test {
 	type ModuleId = TreasuryModuleId;
 	type Currency = pallet_balances::Module<Test>;
-	type ApproveOrigin = frame_system::EnsureRoot<u64>;
-	type RejectOrigin = frame_system::EnsureRoot<u64>;
+	type ApproveOrigin = frame_system::EnsureRoot<u128>;
+	type RejectOrigin = frame_system::EnsureRoot<u128>;
 	type Tippers = TenToFourteen;
 	type TipCountdown = TipCountdown;
 	type TipFindersFee = TipFindersFee;
@@ -147,6 +154,13 @@ impl Trait for Test {
 	type ProposalBondMinimum = ProposalBondMinimum;
 	type SpendPeriod = SpendPeriod;
 	type Burn = Burn;
+	type BountyDepositBase = BountyDepositBase;
+	type BountyDepositPerByte = BountyDepositPerByte;
+	type BountyDepositPayoutDelay = BountyDepositPayoutDelay;
+	type BountyUpdatePeriod = BountyUpdatePeriod;
+	type BountyCuratorDeposit = BountyCuratorDeposit;
+	type BountyValueMinimum = BountyValueMinimum;
+	type MaximumReasonLength = MaximumReasonLength;
 }
 type System = frame_system::Module<Test>;
 type Balances = pallet_balances::Module<Test>;
@@ -170,8 +184,17 @@ fn genesis_config_works() {
 	});
 }
 
+fn last_event() -> RawEvent<u64, u128, H256> {
+	System::events().into_iter().map(|r| r.event)
+		.filter_map(|e| {
+			if let Event::treasury(inner) = e { Some(inner) } else { None }
+		})
+		.last()
+		.unwrap()
+}
+
 fn tip_hash() -> H256 {
-	BlakeTwo256::hash_of(&(BlakeTwo256::hash(b"awesome.dot"), 3u64))
+	BlakeTwo256::hash_of(&(BlakeTwo256::hash(b"awesome.dot"), 3u128))
 }
 
 #[test]
@@ -220,7 +243,7 @@ fn report_awesome_from_beneficiary_and_tip_works() {
 		assert_ok!(Treasury::report_awesome(Origin::signed(0), b"awesome.dot".to_vec(), 0));
 		assert_eq!(Balances::reserved_balance(0), 12);
 		assert_eq!(Balances::free_balance(0), 88);
-		let h = BlakeTwo256::hash_of(&(BlakeTwo256::hash(b"awesome.dot"), 0u64));
+		let h = BlakeTwo256::hash_of(&(BlakeTwo256::hash(b"awesome.dot"), 0u128));
 		assert_ok!(Treasury::tip(Origin::signed(10), h.clone(), 10));
 		assert_ok!(Treasury::tip(Origin::signed(11), h.clone(), 10));
 		assert_ok!(Treasury::tip(Origin::signed(12), h.clone(), 10));
@@ -585,9 +608,9 @@ fn test_last_reward_migration() {
 	}
 
 	let reason1 = BlakeTwo256::hash(b"reason1");
-	let hash1 = BlakeTwo256::hash_of(&(reason1, 10u64));
+	let hash1 = BlakeTwo256::hash_of(&(reason1, 10u128));
 
-	let old_tip_finder = OldOpenTip::<u64, u64, u64, H256> {
+	let old_tip_finder = OldOpenTip::<u128, u64, u64, H256> {
 		reason: reason1,
 		who: 10,
 		finder: Some((20, 30)),
@@ -596,9 +619,9 @@ fn test_last_reward_migration() {
 	};
 
 	let reason2 = BlakeTwo256::hash(b"reason2");
-	let hash2 = BlakeTwo256::hash_of(&(reason2, 20u64));
+	let hash2 = BlakeTwo256::hash_of(&(reason2, 20u128));
 
-	let old_tip_no_finder = OldOpenTip::<u64, u64, u64, H256> {
+	let old_tip_no_finder = OldOpenTip::<u128, u64, u64, H256> {
 		reason: reason2,
 		who: 20,
 		finder: None,
@@ -650,3 +673,363 @@ fn test_last_reward_migration() {
 		);
 	});
 }
+
+/// Propose a bounty of `value` from account 0, approve it and fund it at block 2, then propose
+/// account 4 as curator with a fee of 4.
+fn fund_bounty_with_proposed_curator(value: u64) {
+	Balances::make_free_balance_be(&Treasury::account_id(), 101);
+	Balances::make_free_balance_be(&4, 10);
+
+	assert_ok!(Treasury::propose_bounty(Origin::signed(0), value, b"12345".to_vec()));
+	assert_ok!(Treasury::approve_bounty(Origin::root(), 0));
+
+	System::set_block_number(2);
+	<Treasury as OnInitialize<u64>>::on_initialize(2);
+
+	assert_ok!(Treasury::propose_curator(Origin::root(), 0, 4, 4));
+}
+
+#[test]
+fn propose_bounty_works() {
+	new_test_ext().execute_with(|| {
+		System::set_block_number(1);
+
+		Balances::make_free_balance_be(&Treasury::account_id(), 101);
+		assert_eq!(Treasury::pot(), 100);
+
+		assert_ok!(Treasury::propose_bounty(Origin::signed(0), 10, b"1234567890".to_vec()));
+
+		assert_eq!(last_event(), RawEvent::BountyProposed(0));
+
+		let deposit: u64 = 80 + 10;
+		assert_eq!(Balances::reserved_balance(0), deposit);
+		assert_eq!(Balances::free_balance(0), 100 - deposit);
+
+		assert_eq!(Treasury::bounties(0).unwrap(), Bounty {
+			proposer: 0,
+			fee: 0,
+			curator_deposit: 0,
+			value: 10,
+			bond: deposit,
+			status: BountyStatus::Proposed,
+		});
+
+		assert_eq!(Treasury::bounty_descriptions(0).unwrap(), b"1234567890".to_vec());
+
+		assert_eq!(Treasury::bounty_count(), 1);
+	});
+}
+
+#[test]
+fn propose_bounty_validation_works() {
+	new_test_ext().execute_with(|| {
+		System::set_block_number(1);
+
+		Balances::make_free_balance_be(&Treasury::account_id(), 101);
+		assert_eq!(Treasury::pot(), 100);
+
+		assert_noop!(
+			Treasury::propose_bounty(Origin::signed(1), 0, [0; 17_000].to_vec()),
+			Error::<Test>::ReasonTooBig
+		);
+
+		assert_noop!(
+			Treasury::propose_bounty(Origin::signed(1), 10, b"12345678901234567890".to_vec()),
+			Error::<Test>::InsufficientProposersBalance
+		);
+
+		assert_noop!(
+			Treasury::propose_bounty(Origin::signed(1), 0, b"12345678901234567890".to_vec()),
+			Error::<Test>::InvalidValue
+		);
+	});
+}
+
+#[test]
+fn close_bounty_works() {
+	new_test_ext().execute_with(|| {
+		System::set_block_number(1);
+		Balances::make_free_balance_be(&Treasury::account_id(), 101);
+		assert_noop!(Treasury::close_bounty(Origin::root(), 0), Error::<Test>::InvalidProposalIndex);
+
+		assert_ok!(Treasury::propose_bounty(Origin::signed(0), 10, b"12345".to_vec()));
+
+		assert_ok!(Treasury::close_bounty(Origin::root(), 0));
+
+		let deposit: u64 = 80 + 5;
+
+		assert_eq!(last_event(), RawEvent::BountyRejected(0, deposit));
+
+		assert_eq!(Balances::reserved_balance(0), 0);
+		assert_eq!(Balances::free_balance(0), 100 - deposit);
+
+		assert_eq!(Treasury::bounties(0), None);
+		assert!(!Bounties::<Test>::contains_key(0));
+		assert_eq!(Treasury::bounty_descriptions(0), None);
+	});
+}
+
+#[test]
+fn approve_bounty_works() {
+	new_test_ext().execute_with(|| {
+		System::set_block_number(1);
+		Balances::make_free_balance_be(&Treasury::account_id(), 101);
+		assert_noop!(Treasury::approve_bounty(Origin::root(), 0), Error::<Test>::InvalidProposalIndex);
+
+		assert_ok!(Treasury::propose_bounty(Origin::signed(0), 50, b"12345".to_vec()));
+
+		assert_ok!(Treasury::approve_bounty(Origin::root(), 0));
+
+		let deposit: u64 = 80 + 5;
+
+		assert_eq!(Treasury::bounties(0).unwrap(), Bounty {
+			proposer: 0,
+			fee: 0,
+			value: 50,
+			curator_deposit: 0,
+			bond: deposit,
+			status: BountyStatus::Approved,
+		});
+		assert_eq!(Treasury::bounty_approvals(), vec![0]);
+
+		assert_noop!(Treasury::close_bounty(Origin::root(), 0), Error::<Test>::UnexpectedStatus);
+
+		// deposit not returned yet
+		assert_eq!(Balances::reserved_balance(0), deposit);
+		assert_eq!(Balances::free_balance(0), 100 - deposit);
+
+		<Treasury as OnInitialize<u64>>::on_initialize(2);
+
+		// return deposit
+		assert_eq!(Balances::reserved_balance(0), 0);
+		assert_eq!(Balances::free_balance(0), 100);
+
+		assert_eq!(Treasury::bounties(0).unwrap(), Bounty {
+			proposer: 0,
+			fee: 0,
+			curator_deposit: 0,
+			value: 50,
+			bond: deposit,
+			status: BountyStatus::Funded,
+		});
+		assert!(System::events().into_iter().any(|r| r.event == Event::treasury(RawEvent::BountyBecameActive(0))));
+
+		assert_eq!(Treasury::pot(), 100 - 50 - 25); // burn 25
+		assert_eq!(Balances::free_balance(Treasury::bounty_account_id(0)), 50);
+		assert!(Treasury::bounty_approvals().is_empty());
+	});
+}
+
+#[test]
+fn assign_curator_works() {
+	new_test_ext().execute_with(|| {
+		System::set_block_number(1);
+		Balances::make_free_balance_be(&Treasury::account_id(), 101);
+
+		assert_noop!(Treasury::propose_curator(Origin::root(), 0, 4, 4), Error::<Test>::InvalidProposalIndex);
+
+		assert_ok!(Treasury::propose_bounty(Origin::signed(0), 50, b"12345".to_vec()));
+
+		assert_ok!(Treasury::approve_bounty(Origin::root(), 0));
+
+		System::set_block_number(2);
+		<Treasury as OnInitialize<u64>>::on_initialize(2);
+
+		assert_noop!(Treasury::propose_curator(Origin::root(), 0, 4, 50), Error::<Test>::InvalidFee);
+
+		assert_ok!(Treasury::propose_curator(Origin::root(), 0, 4, 4));
+
+		assert_eq!(Treasury::bounties(0).unwrap(), Bounty {
+			proposer: 0,
+			fee: 4,
+			curator_deposit: 0,
+			value: 50,
+			bond: 85,
+			status: BountyStatus::CuratorProposed {
+				curator: 4,
+			},
+		});
+
+		assert_noop!(Treasury::accept_curator(Origin::signed(1), 0), Error::<Test>::RequireCurator);
+		assert_noop!(
+			Treasury::accept_curator(Origin::signed(4), 0),
+			pallet_balances::Error::<Test, _>::InsufficientBalance
+		);
+
+		Balances::make_free_balance_be(&4, 10);
+
+		assert_ok!(Treasury::accept_curator(Origin::signed(4), 0));
+
+		assert_eq!(Treasury::bounties(0).unwrap(), Bounty {
+			proposer: 0,
+			fee: 4,
+			curator_deposit: 2,
+			value: 50,
+			bond: 85,
+			status: BountyStatus::Active {
+				curator: 4,
+				update_due: 22,
+			},
+		});
+
+		assert_eq!(Balances::free_balance(&4), 8);
+		assert_eq!(Balances::reserved_balance(&4), 2);
+	});
+}
+
+#[test]
+fn unassign_curator_works() {
+	new_test_ext().execute_with(|| {
+		fund_bounty_with_proposed_curator(50);
+
+		assert_noop!(Treasury::unassign_curator(Origin::signed(1), 0), BadOrigin);
+
+		assert_ok!(Treasury::unassign_curator(Origin::signed(4), 0));
+
+		assert_eq!(Treasury::bounties(0).unwrap(), Bounty {
+			proposer: 0,
+			fee: 4,
+			curator_deposit: 0,
+			value: 50,
+			bond: 85,
+			status: BountyStatus::Funded,
+		});
+
+		assert_ok!(Treasury::propose_curator(Origin::root(), 0, 4, 4));
+		assert_ok!(Treasury::accept_curator(Origin::signed(4), 0));
+
+		assert_ok!(Treasury::unassign_curator(Origin::root(), 0));
+
+		assert_eq!(Treasury::bounties(0).unwrap(), Bounty {
+			proposer: 0,
+			fee: 4,
+			curator_deposit: 0,
+			value: 50,
+			bond: 85,
+			status: BountyStatus::Funded,
+		});
+
+		// the curator deposit is slashed
+		assert_eq!(Balances::free_balance(&4), 8);
+		assert_eq!(Balances::reserved_balance(&4), 0);
+	});
+}
+
+#[test]
+fn award_and_claim_bounty_works() {
+	new_test_ext().execute_with(|| {
+		fund_bounty_with_proposed_curator(50);
+		assert_ok!(Treasury::accept_curator(Origin::signed(4), 0));
+
+		assert_eq!(Balances::free_balance(4), 8); // inital 10 - 2 deposit
+
+		assert_noop!(Treasury::award_bounty(Origin::signed(1), 0, 3), Error::<Test>::RequireCurator);
+
+		assert_ok!(Treasury::award_bounty(Origin::signed(4), 0, 3));
+		assert_eq!(last_event(), RawEvent::BountyAwarded(0, 3));
+
+		assert_eq!(Treasury::bounties(0).unwrap(), Bounty {
+			proposer: 0,
+			fee: 4,
+			curator_deposit: 2,
+			value: 50,
+			bond: 85,
+			status: BountyStatus::PendingPayout {
+				curator: 4,
+				beneficiary: 3,
+				unlock_at: 5
+			},
+		});
+
+		assert_noop!(Treasury::claim_bounty(Origin::signed(1), 0), Error::<Test>::Premature);
+		assert_noop!(Treasury::close_bounty(Origin::root(), 0), Error::<Test>::PendingPayout);
+
+		System::set_block_number(5);
+		<Treasury as OnInitialize<u64>>::on_initialize(5);
+
+		assert_ok!(Balances::transfer(Origin::signed(0), Treasury::bounty_account_id(0), 10));
+
+		assert_ok!(Treasury::claim_bounty(Origin::signed(1), 0));
+
+		assert_eq!(last_event(), RawEvent::BountyClaimed(0, 56, 3));
+
+		assert_eq!(Balances::free_balance(4), 14); // initial 10 + fee 4
+		assert_eq!(Balances::reserved_balance(4), 0);
+
+		assert_eq!(Balances::free_balance(3), 56);
+		assert_eq!(Balances::free_balance(Treasury::bounty_account_id(0)), 0);
+
+		assert_eq!(Treasury::bounties(0), None);
+		assert_eq!(Treasury::bounty_descriptions(0), None);
+	});
+}
+
+#[test]
+fn inactive_curator_can_be_unassigned_by_anyone() {
+	new_test_ext().execute_with(|| {
+		fund_bounty_with_proposed_curator(50);
+		assert_ok!(Treasury::accept_curator(Origin::signed(4), 0));
+
+		System::set_block_number(3);
+
+		assert_noop!(Treasury::unassign_curator(Origin::signed(0), 0), Error::<Test>::Premature);
+
+		assert_noop!(
+			Treasury::extend_bounty_expiry(Origin::signed(0), 0, Vec::new()),
+			Error::<Test>::RequireCurator
+		);
+		assert_ok!(Treasury::extend_bounty_expiry(Origin::signed(4), 0, Vec::new()));
+		assert_eq!(last_event(), RawEvent::BountyExtended(0));
+
+		assert_eq!(Treasury::bounties(0).unwrap(), Bounty {
+			proposer: 0,
+			fee: 4,
+			curator_deposit: 2,
+			value: 50,
+			bond: 85,
+			status: BountyStatus::Active { curator: 4, update_due: 23 },
+		});
+
+		System::set_block_number(23);
+		assert_noop!(Treasury::unassign_curator(Origin::signed(0), 0), Error::<Test>::Premature);
+
+		System::set_block_number(24);
+		assert_ok!(Treasury::unassign_curator(Origin::signed(0), 0));
+
+		assert_eq!(Treasury::bounties(0).unwrap(), Bounty {
+			proposer: 0,
+			fee: 4,
+			curator_deposit: 0,
+			value: 50,
+			bond: 85,
+			status: BountyStatus::Funded,
+		});
+
+		// the curator deposit is slashed
+		assert_eq!(Balances::free_balance(4), 8);
+		assert_eq!(Balances::reserved_balance(4), 0);
+	});
+}
+
+#[test]
+fn cancel_active_bounty_returns_funds_to_treasury() {
+	new_test_ext().execute_with(|| {
+		fund_bounty_with_proposed_curator(50);
+		assert_ok!(Treasury::accept_curator(Origin::signed(4), 0));
+
+		assert_eq!(Treasury::pot(), 25);
+
+		assert_ok!(Treasury::close_bounty(Origin::root(), 0));
+		assert_eq!(last_event(), RawEvent::BountyCanceled(0));
+
+		// the curator deposit is returned
+		assert_eq!(Balances::free_balance(4), 10);
+		assert_eq!(Balances::reserved_balance(4), 0);
+
+		assert_eq!(Balances::free_balance(Treasury::bounty_account_id(0)), 0);
+		assert_eq!(Treasury::pot(), 75);
+
+		assert_eq!(Treasury::bounties(0), None);
+		assert_eq!(Treasury::bounty_descriptions(0), None);
+	});
+}
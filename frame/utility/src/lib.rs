@@ -23,11 +23,13 @@
 //!
 //! ## Overview
 //!
-//! This module contains two basic pieces of functionality:
+//! This module contains three basic pieces of functionality:
 //! - Batch dispatch: A stateless operation, allowing any origin to execute multiple calls in a
 //!   single dispatch. This can be useful to amalgamate proposals, combining `set_code` with
 //!   corresponding `set_storage`s, for efficient multiple payouts with just a single signature
-//!   verify, or in combination with one of the other two dispatch functionality.
+//!   verify, or in combination with one of the other two dispatch functionality. `batch_all`
+//!   dispatches the calls atomically: if any of them fails, the effects of all of them are
+//!   reverted.
 //! - Pseudonymal dispatch: A stateless operation, allowing a signed origin to execute a call from
 //!   an alternative signed origin. Each account has 2 * 2**16 possible "pseudonyms" (alternative
 //!   account IDs) and these can be stacked. This can be useful as a key management tool, where you
@@ -35,6 +37,8 @@
 //!   it's perfectly fine to have each of them controlled by the same underlying keypair.
 //!   Derivative accounts are, for the purposes of proxy filtering considered exactly the same as
 //!   the oigin and are thus hampered with the origin's filters.
+//! - Origin dispatch: A root-only operation, allowing a call to be dispatched from any origin of
+//!   the runtime, e.g. for governance scripts.
 //!
 //! Since proxy filters are respected in all dispatches of this module, it should never need to be
 //! filtered by any proxy.
@@ -45,10 +49,15 @@
 //!
 //! #### For batch dispatch
 //! * `batch` - Dispatch multiple calls from the sender's origin.
+//! * `batch_all` - Dispatch multiple calls from the sender's origin, reverting all of them if
+//!   one fails.
 //!
 //! #### For pseudonymal dispatch
 //! * `as_derivative` - Dispatch a call from a derivative signed origin.
 //!
+//! #### For origin dispatch
+//! * `dispatch_as` - Dispatch a call from a given origin.
+//!
 //! [`Call`]: ./enum.Call.html
 //! [`Trait`]: ./trait.Trait.html
 
@@ -61,8 +70,10 @@ use sp_core::TypeId;
 use sp_io::hashing::blake2_256;
 use frame_support::{decl_module, decl_event, decl_storage, Parameter};
 use frame_support::{
-	traits::{OriginTrait, UnfilteredDispatchable},
-	weights::{Weight, GetDispatchInfo, DispatchClass}, dispatch::PostDispatchInfo,
+	traits::{OriginTrait, UnfilteredDispatchable, IsType},
+	weights::{Weight, GetDispatchInfo, DispatchClass, extract_actual_weight, WithPostDispatchInfo},
+	dispatch::{PostDispatchInfo, DispatchResultWithPostInfo},
+	storage::{with_transaction, TransactionOutcome},
 };
 use frame_system::{self as system, ensure_signed, ensure_root};
 use sp_runtime::{DispatchError, DispatchResult, traits::Dispatchable};
@@ -79,6 +90,10 @@ pub trait Trait: frame_system::Trait {
 	type Call: Parameter + Dispatchable<Origin=Self::Origin, PostInfo=PostDispatchInfo>
 		+ GetDispatchInfo + From<frame_system::Call<Self>>
 		+ UnfilteredDispatchable<Origin=Self::Origin>;
+
+	/// The caller origin, overarching type of all pallets origins.
+	type PalletsOrigin: Parameter + Into<<Self as frame_system::Trait>::Origin>
+		+ IsType<<<Self as frame_system::Trait>::Origin as OriginTrait>::PalletsOrigin>;
 }
 
 decl_storage! {
@@ -93,6 +108,8 @@ decl_event! {
 		BatchInterrupted(u32, DispatchError),
 		/// Batch of dispatches completed fully with no error.
 		BatchCompleted,
+		/// A call was dispatched from a given origin. \[result\]
+		DispatchedAs(DispatchResult),
 	}
 }
 
@@ -100,6 +117,15 @@ decl_event! {
 #[derive(Clone, Copy, Eq, PartialEq, Encode, Decode)]
 struct IndexedUtilityModuleId(u16);
 
+/// Base weight of a batch, before the weights of its calls.
+const BATCH_BASE_WEIGHT: Weight = 15_000_000;
+
+/// Weight of a batch for each of its calls, in addition to the weight of the call.
+const BATCH_PER_CALL_WEIGHT: Weight = 1_000_000;
+
+/// Weight of `dispatch_as`, in addition to the weight of the call.
+const DISPATCH_AS_BASE_WEIGHT: Weight = 3_000_000;
+
 impl TypeId for IndexedUtilityModuleId {
 	const TYPE_ID: [u8; 4] = *b"suba";
 }
@@ -129,21 +155,7 @@ decl_module! {
 		/// `BatchInterrupted` event is deposited, along with the number of successful calls made
 		/// and the error of the failed call. If all were successful, then the `BatchCompleted`
 		/// event is deposited.
-		#[weight = (
-			calls.iter()
-				.map(|call| call.get_dispatch_info().weight)
-				.fold(15_000_000, |a: Weight, n| a.saturating_add(n).saturating_add(1_000_000)),
-			{
-				let all_operational = calls.iter()
-					.map(|call| call.get_dispatch_info().class)
-					.all(|class| class == DispatchClass::Operational);
-				if all_operational {
-					DispatchClass::Operational
-				} else {
-					DispatchClass::Normal
-				}
-			},
-		)]
+		#[weight = (Module::<T>::batch_weight(calls), Module::<T>::batch_class(calls))]
 		fn batch(origin, calls: Vec<<T as Trait>::Call>) {
 			let is_root = ensure_root(origin.clone()).is_ok();
 			for (index, call) in calls.into_iter().enumerate() {
@@ -189,10 +201,103 @@ decl_module! {
 			origin.set_caller_from(frame_system::RawOrigin::Signed(pseudonym));
 			call.dispatch(origin).map(|_| ()).map_err(|e| e.error)
 		}
+
+		/// Send a batch of dispatch calls and atomically execute them.
+		/// The whole transaction will rollback and fail if any of the calls failed.
+		///
+		/// May be called from any origin.
+		///
+		/// - `calls`: The calls to be dispatched from the same origin.
+		///
+		/// If origin is root then call are dispatch without checking origin filter. (This includes
+		/// bypassing `frame_system::Trait::BaseCallFilter`).
+		///
+		/// # <weight>
+		/// - Base weight: 14.39 + .987 * c µs
+		/// - Plus the sum of the weights of the `calls`.
+		/// - Plus one additional event. (repeat read/write)
+		/// # </weight>
+		///
+		/// The actual weight is refunded to the base weight plus the actual weights of the calls
+		/// that were dispatched.
+		#[weight = (Module::<T>::batch_weight(calls), Module::<T>::batch_class(calls))]
+		fn batch_all(origin, calls: Vec<<T as Trait>::Call>) -> DispatchResultWithPostInfo {
+			let is_root = ensure_root(origin.clone()).is_ok();
+			let mut weight: Weight = BATCH_BASE_WEIGHT;
+			with_transaction(|| {
+				for call in calls.into_iter() {
+					let info = call.get_dispatch_info();
+					let result = if is_root {
+						call.dispatch_bypass_filter(origin.clone())
+					} else {
+						call.dispatch(origin.clone())
+					};
+					weight = weight
+						.saturating_add(extract_actual_weight(&result, &info))
+						.saturating_add(BATCH_PER_CALL_WEIGHT);
+					if let Err(e) = result {
+						return TransactionOutcome::Rollback(Err(e.error.with_weight(weight)));
+					}
+				}
+				TransactionOutcome::Commit(Ok(()))
+			})?;
+			Self::deposit_event(Event::BatchCompleted);
+			Ok(Some(weight).into())
+		}
+
+		/// Dispatches a function call with a provided origin.
+		///
+		/// The dispatch origin for this call must be _Root_.
+		///
+		/// The call is dispatched without checking the origin filter of `as_origin`. The result of
+		/// the call is deposited in a `DispatchedAs` event.
+		///
+		/// # <weight>
+		/// - Base weight: 3 µs
+		/// - Plus the weight of the `call`
+		/// # </weight>
+		///
+		/// The actual weight is refunded to the base weight plus the actual weight of the `call`.
+		#[weight = (
+			call.get_dispatch_info().weight.saturating_add(DISPATCH_AS_BASE_WEIGHT),
+			call.get_dispatch_info().class,
+		)]
+		fn dispatch_as(origin,
+			as_origin: Box<T::PalletsOrigin>,
+			call: Box<<T as Trait>::Call>,
+		) -> DispatchResultWithPostInfo {
+			ensure_root(origin)?;
+
+			let info = call.get_dispatch_info();
+			let result = call.dispatch_bypass_filter((*as_origin).into());
+			let weight = extract_actual_weight(&result, &info).saturating_add(DISPATCH_AS_BASE_WEIGHT);
+
+			Self::deposit_event(Event::DispatchedAs(result.map(|_| ()).map_err(|e| e.error)));
+			Ok(Some(weight).into())
+		}
 	}
 }
 
 impl<T: Trait> Module<T> {
+	/// The weight of a batch of `calls`.
+	fn batch_weight(calls: &[<T as Trait>::Call]) -> Weight {
+		calls.iter()
+			.map(|call| call.get_dispatch_info().weight)
+			.fold(BATCH_BASE_WEIGHT, |a: Weight, n| a.saturating_add(n).saturating_add(BATCH_PER_CALL_WEIGHT))
+	}
+
+	/// The dispatch class of a batch of `calls`: operational only if all of them are.
+	fn batch_class(calls: &[<T as Trait>::Call]) -> DispatchClass {
+		let all_operational = calls.iter()
+			.map(|call| call.get_dispatch_info().class)
+			.all(|class| class == DispatchClass::Operational);
+		if all_operational {
+			DispatchClass::Operational
+		} else {
+			DispatchClass::Normal
+		}
+	}
+
 	/// Derive a derivative account ID from the owner account and the sub-account index.
 	pub fn derivative_account_id(who: T::AccountId, index: u16) -> T::AccountId {
 		let entropy = (b"modlpy/utilisuba", who, index).using_encoded(blake2_256);
@@ -113,6 +113,7 @@ impl Filter<Call> for TestBaseCallFilter {
 impl Trait for Test {
 	type Event = TestEvent;
 	type Call = Call;
+	type PalletsOrigin = OriginCaller;
 }
 type System = frame_system::Module<Test>;
 type Balances = pallet_balances::Module<Test>;
@@ -233,3 +234,112 @@ fn batch_early_exit_works() {
 		assert_eq!(Balances::free_balance(2), 15);
 	});
 }
+
+#[test]
+fn batch_all_works() {
+	new_test_ext().execute_with(|| {
+		assert_eq!(Balances::free_balance(1), 10);
+		assert_eq!(Balances::free_balance(2), 10);
+		assert_ok!(
+			Utility::batch_all(Origin::signed(1), vec![
+				Call::Balances(BalancesCall::transfer(2, 5)),
+				Call::Balances(BalancesCall::transfer(2, 5))
+			]),
+		);
+		expect_event(Event::BatchCompleted);
+		assert_eq!(Balances::free_balance(1), 0);
+		assert_eq!(Balances::free_balance(2), 20);
+	});
+}
+
+#[test]
+fn batch_all_revert() {
+	new_test_ext().execute_with(|| {
+		let call = Call::Balances(BalancesCall::transfer(2, 5));
+		let info = call.get_dispatch_info();
+
+		assert_eq!(Balances::free_balance(1), 10);
+		assert_eq!(Balances::free_balance(2), 10);
+		assert_noop!(
+			Utility::batch_all(Origin::signed(1), vec![
+				Call::Balances(BalancesCall::transfer(2, 5)),
+				Call::Balances(BalancesCall::transfer(2, 10)),
+				Call::Balances(BalancesCall::transfer(2, 5)),
+			]),
+			BalancesError::<Test, _>::InsufficientBalance
+				.with_weight(BATCH_BASE_WEIGHT + 2 * (info.weight + BATCH_PER_CALL_WEIGHT))
+		);
+		assert_eq!(Balances::free_balance(1), 10);
+		assert_eq!(Balances::free_balance(2), 10);
+	});
+}
+
+#[test]
+fn batch_all_with_signed_filters() {
+	new_test_ext().execute_with(|| {
+		assert_noop!(
+			Utility::batch_all(Origin::signed(1), vec![
+				Call::Balances(BalancesCall::transfer(2, 5)),
+				Call::System(frame_system::Call::suicide()),
+			]),
+			DispatchError::BadOrigin.with_weight(
+				BATCH_BASE_WEIGHT + 2 * BATCH_PER_CALL_WEIGHT
+					+ Call::Balances(BalancesCall::transfer(2, 5)).get_dispatch_info().weight
+					+ Call::System(frame_system::Call::suicide()).get_dispatch_info().weight
+			)
+		);
+		assert_eq!(Balances::free_balance(1), 10);
+	});
+}
+
+#[test]
+fn batch_all_refunds_to_actual_weight() {
+	new_test_ext().execute_with(|| {
+		let call = Call::System(frame_system::Call::remark(vec![]));
+		let info = call.get_dispatch_info();
+		let batch_all = Call::Utility(crate::Call::batch_all(vec![call.clone(), call.clone()]));
+		assert_eq!(batch_all.get_dispatch_info().weight, BATCH_BASE_WEIGHT + 2 * (info.weight + BATCH_PER_CALL_WEIGHT));
+
+		let result = batch_all.dispatch(Origin::signed(1));
+		assert_eq!(
+			result.unwrap().actual_weight,
+			Some(BATCH_BASE_WEIGHT + 2 * (info.weight + BATCH_PER_CALL_WEIGHT)),
+		);
+	});
+}
+
+#[test]
+fn dispatch_as_works() {
+	new_test_ext().execute_with(|| {
+		let call = Call::Balances(BalancesCall::transfer(2, 5));
+		let info = call.get_dispatch_info();
+		let as_origin = Box::new(OriginCaller::system(frame_system::RawOrigin::Signed(1)));
+
+		let result = Utility::dispatch_as(Origin::root(), as_origin, Box::new(call));
+		assert_eq!(result.unwrap().actual_weight, Some(info.weight + DISPATCH_AS_BASE_WEIGHT));
+		expect_event(Event::DispatchedAs(Ok(())));
+		assert_eq!(Balances::free_balance(1), 5);
+		assert_eq!(Balances::free_balance(2), 15);
+	});
+}
+
+#[test]
+fn dispatch_as_reports_failed_call() {
+	new_test_ext().execute_with(|| {
+		let as_origin = Box::new(OriginCaller::system(frame_system::RawOrigin::Signed(1)));
+		let call = Box::new(Call::Balances(BalancesCall::transfer(2, 20)));
+		assert_ok!(Utility::dispatch_as(Origin::root(), as_origin, call));
+		let e = DispatchError::from(BalancesError::<Test, _>::InsufficientBalance).stripped();
+		expect_event(Event::DispatchedAs(Err(e)));
+		assert_eq!(Balances::free_balance(1), 10);
+	});
+}
+
+#[test]
+fn dispatch_as_requires_root() {
+	new_test_ext().execute_with(|| {
+		let as_origin = Box::new(OriginCaller::system(frame_system::RawOrigin::Signed(1)));
+		let call = Box::new(Call::Balances(BalancesCall::transfer(2, 5)));
+		assert_noop!(Utility::dispatch_as(Origin::signed(1), as_origin, call), DispatchError::BadOrigin);
+	});
+}
@@ -42,19 +42,25 @@ fn add_locks<T: Trait>(who: &T::AccountId, n: u8) {
 }
 
 fn add_vesting_schedule<T: Trait>(who: &T::AccountId) -> Result<(), &'static str> {
+	add_vesting_schedules::<T>(who, 1)
+}
+
+fn add_vesting_schedules<T: Trait>(who: &T::AccountId, n: u32) -> Result<(), &'static str> {
 	let locked = 100;
 	let per_block = 10;
 	let starting_block = 1;
 
 	System::<T>::set_block_number(0.into());
 
-	// Add schedule to avoid `NotVesting` error.
-	Vesting::<T>::add_vesting_schedule(
-		&who,
-		locked.into(),
-		per_block.into(),
-		starting_block.into(),
-	)?;
+	// Add schedules to avoid `NotVesting` error.
+	for _ in 0 .. n {
+		Vesting::<T>::add_vesting_schedule(
+			&who,
+			locked.into(),
+			per_block.into(),
+			starting_block.into(),
+		)?;
+	}
 	Ok(())
 }
 
@@ -193,6 +199,30 @@ benchmarks! {
 			"Lock not created",
 		);
 	}
+
+	merge_schedules {
+		let l in 0 .. MAX_LOCKS;
+		let s in 2 .. T::MaxVestingSchedules::get();
+
+		let caller = account("caller", 0, SEED);
+		T::Currency::make_free_balance_be(&caller, BalanceOf::<T>::max_value());
+		add_locks::<T>(&caller, l as u8);
+		add_vesting_schedules::<T>(&caller, s)?;
+		// At block zero, everything is vested.
+		System::<T>::set_block_number(T::BlockNumber::zero());
+	}: _(RawOrigin::Signed(caller.clone()), 0, s - 1)
+	verify {
+		assert_eq!(
+			Vesting::<T>::vesting(&caller).map(|schedules| schedules.len()),
+			Some(s as usize - 1),
+			"Schedules were not merged",
+		);
+		assert_eq!(
+			Vesting::<T>::vesting_balance(&caller),
+			Some((100 * s).into()),
+			"Lock was changed",
+		);
+	}
 }
 
 #[cfg(test)]
@@ -209,6 +239,7 @@ mod tests {
 			assert_ok!(test_benchmark_vest_other_locked::<Test>());
 			assert_ok!(test_benchmark_vest_other_unlocked::<Test>());
 			assert_ok!(test_benchmark_vested_transfer::<Test>());
+			assert_ok!(test_benchmark_merge_schedules::<Test>());
 		});
 	}
 }
@@ -26,6 +26,10 @@
 //! module ensures that there is a lock in place preventing the balance to drop below the *unvested*
 //! amount for any reason other than transaction fee payment.
 //!
+//! An account can have up to `MaxVestingSchedules` schedules at once, for instance when a token
+//! sale and a team allocation vest over different periods. The lock covers the sum of the amounts
+//! left unvested by all of them.
+//!
 //! As the amount vested increases over time, the amount unvested reduces. However, locks remain in
 //! place and explicit action is needed on behalf of the user to ensure that the amount locked is
 //! equivalent to the amount remaining to be vested. This is done through a dispatchable function,
@@ -41,6 +45,10 @@
 //! - `vest` - Update the lock, reducing it in line with the amount "vested" so far.
 //! - `vest_other` - Update the lock of another account, reducing it in line with the amount
 //!   "vested" so far.
+//! - `vested_transfer` - Transfer funds to an account, adding a vesting schedule to them.
+//! - `force_vested_transfer` - Transfer funds between accounts as Root, adding a vesting schedule
+//!   to them.
+//! - `merge_schedules` - Merge two of the sender's vesting schedules into one, freeing a slot.
 //!
 //! [`Call`]: ./enum.Call.html
 //! [`Trait`]: ./trait.Trait.html
@@ -51,9 +59,13 @@ use sp_std::prelude::*;
 use sp_std::fmt::Debug;
 use codec::{Encode, Decode};
 use sp_runtime::{DispatchResult, RuntimeDebug, traits::{
-	StaticLookup, Zero, AtLeast32BitUnsigned, MaybeSerializeDeserialize, Convert
+	StaticLookup, Zero, One, Saturating, AtLeast32BitUnsigned, MaybeSerializeDeserialize, Convert,
+	Bounded,
 }};
-use frame_support::{decl_module, decl_event, decl_storage, decl_error, ensure};
+use frame_support::{
+	decl_module, decl_event, decl_storage, decl_error, ensure, weights::Weight,
+	storage::IterableStorageMap,
+};
 use frame_support::traits::{
 	Currency, LockableCurrency, VestingSchedule, WithdrawReason, LockIdentifier,
 	ExistenceRequirement, Get
@@ -76,6 +88,9 @@ pub trait Trait: frame_system::Trait {
 
 	/// The minimum amount transferred to call `vested_transfer`.
 	type MinVestedTransfer: Get<BalanceOf<Self>>;
+
+	/// The maximum number of vesting schedules an account can have.
+	type MaxVestingSchedules: Get<u32>;
 }
 
 const VESTING_ID: LockIdentifier = *b"vesting ";
@@ -111,39 +126,94 @@ impl<
 			Zero::zero()
 		}
 	}
+
+	/// Block at which the schedule has fully vested, converted to a balance.
+	///
+	/// A schedule unlocking nothing per block never vests, its ending block is the maximum value.
+	pub fn ending_block_as_balance<
+		BlockNumberToBalance: Convert<BlockNumber, Balance>
+	>(&self) -> Balance {
+		if self.per_block.is_zero() && !self.locked.is_zero() {
+			return Bounded::max_value();
+		}
+		let starting_block = BlockNumberToBalance::convert(self.starting_block);
+		let duration = if self.per_block >= self.locked {
+			One::one()
+		} else if (self.locked % self.per_block).is_zero() {
+			self.locked / self.per_block
+		} else {
+			// The last block unlocks less than `per_block`.
+			self.locked / self.per_block + One::one()
+		};
+		starting_block.saturating_add(duration)
+	}
+}
+
+// A value placed in storage that represents the current version of the Vesting storage.
+// This value is used by the `on_runtime_upgrade` logic to determine whether we run
+// storage migration logic.
+#[derive(Encode, Decode, Clone, Copy, PartialEq, Eq, RuntimeDebug)]
+enum Releases {
+	V1,
+	V2,
+}
+
+impl Default for Releases {
+	fn default() -> Self {
+		Releases::V1
+	}
 }
 
 decl_storage! {
 	trait Store for Module<T: Trait> as Vesting {
-		/// Information regarding the vesting of a given account.
+		/// Information regarding the vesting schedules of a given account.
+		///
+		/// There are at most `MaxVestingSchedules` schedules per account.
 		pub Vesting get(fn vesting):
 			map hasher(blake2_128_concat) T::AccountId
-			=> Option<VestingInfo<BalanceOf<T>, T::BlockNumber>>;
+			=> Option<Vec<VestingInfo<BalanceOf<T>, T::BlockNumber>>>;
+
+		/// Storage version of the pallet.
+		///
+		/// New networks start with the last version.
+		StorageVersion build(|_| Releases::V2): Releases;
 	}
 	add_extra_genesis {
 		config(vesting): Vec<(T::AccountId, T::BlockNumber, T::BlockNumber, BalanceOf<T>)>;
 		build(|config: &GenesisConfig<T>| {
-			use sp_runtime::traits::Saturating;
 			// Generate initial vesting configuration
 			// * who - Account which we are generating vesting configuration for
 			// * begin - Block when the account will start to vest
 			// * length - Number of blocks from `begin` until fully vested
 			// * liquid - Number of units which can be spent before vesting begins
+			//
+			// An account may be given several schedules. Each of them locks the genesis balance
+			// minus its own `liquid`, and the lock covers the sum of what they lock.
 			for &(ref who, begin, length, liquid) in config.vesting.iter() {
 				let balance = T::Currency::free_balance(who);
 				assert!(!balance.is_zero(), "Currencies must be init'd before vesting");
 				// Total genesis `balance` minus `liquid` equals funds locked for vesting
 				let locked = balance.saturating_sub(liquid);
 				let length_as_balance = T::BlockNumberToBalance::convert(length);
-				let per_block = locked / length_as_balance.max(sp_runtime::traits::One::one());
-
-				Vesting::<T>::insert(who, VestingInfo {
-					locked: locked,
-					per_block: per_block,
-					starting_block: begin
+				let per_block = locked / length_as_balance.max(One::one());
+
+				let schedules = Vesting::<T>::mutate(who, |schedules| {
+					let schedules = schedules.get_or_insert_with(Vec::new);
+					schedules.push(VestingInfo {
+						locked: locked,
+						per_block: per_block,
+						starting_block: begin
+					});
+					schedules.clone()
 				});
+				assert!(
+					schedules.len() <= T::MaxVestingSchedules::get() as usize,
+					"Too many vesting schedules at genesis",
+				);
+				let total_locked = schedules.iter()
+					.fold(Zero::zero(), |total: BalanceOf<T>, schedule| total.saturating_add(schedule.locked));
 				let reasons = WithdrawReason::Transfer | WithdrawReason::Reserve;
-				T::Currency::set_lock(VESTING_ID, who, locked, reasons);
+				T::Currency::set_lock(VESTING_ID, who, total_locked, reasons);
 			}
 		})
 	}
@@ -164,10 +234,14 @@ decl_error! {
 	pub enum Error for Module<T: Trait> {
 		/// The account given is not vesting.
 		NotVesting,
-		/// An existing vesting schedule already exists for this account that cannot be clobbered.
-		ExistingVestingSchedule,
+		/// The account already has the maximum number of vesting schedules.
+		AtMaxVestingSchedules,
 		/// Amount being transferred is too low to create a vesting schedule.
 		AmountLow,
+		/// An index was out of bounds of the vesting schedules.
+		ScheduleIndexOutOfBounds,
+		/// The schedule does not unlock anything per block.
+		InvalidScheduleParams,
 	}
 }
 
@@ -179,8 +253,15 @@ decl_module! {
 		/// The minimum amount to be transferred to create a new vesting schedule.
 		const MinVestedTransfer: BalanceOf<T> = T::MinVestedTransfer::get();
 
+		/// The maximum number of vesting schedules an account can have.
+		const MaxVestingSchedules: u32 = T::MaxVestingSchedules::get();
+
 		fn deposit_event() = default;
 
+		fn on_runtime_upgrade() -> Weight {
+			migrate_to_multiple_schedules::<T>()
+		}
+
 		/// Unlock any vested funds of the sender account.
 		///
 		/// The dispatch origin for this call must be _Signed_ and the sender must have funds still
@@ -197,8 +278,12 @@ decl_module! {
 		///     - Unlocked: 48.76 + .048 * l µs (min square analysis)
 		///     - Locked: 44.43 + .284 * l µs (min square analysis)
 		/// - Using 50 µs fixed. Assuming less than 50 locks on any user, else we may want factor in number of locks.
+		/// - Plus 1 µs per vesting schedule, assuming the maximum number of schedules.
 		/// # </weight>
-		#[weight = 50_000_000 + T::DbWeight::get().reads_writes(2, 2)]
+		#[weight = 50_000_000
+			+ 1_000_000 * T::MaxVestingSchedules::get() as Weight
+			+ T::DbWeight::get().reads_writes(2, 2)
+		]
 		fn vest(origin) -> DispatchResult {
 			let who = ensure_signed(origin)?;
 			Self::update_lock(who)
@@ -222,8 +307,12 @@ decl_module! {
 		///     - Unlocked: 44.3 + .294 * l µs (min square analysis)
 		///     - Locked: 48.16 + .103 * l µs (min square analysis)
 		/// - Using 50 µs fixed. Assuming less than 50 locks on any user, else we may want factor in number of locks.
+		/// - Plus 1 µs per vesting schedule, assuming the maximum number of schedules.
 		/// # </weight>
-		#[weight = 50_000_000 + T::DbWeight::get().reads_writes(3, 3)]
+		#[weight = 50_000_000
+			+ 1_000_000 * T::MaxVestingSchedules::get() as Weight
+			+ T::DbWeight::get().reads_writes(3, 3)
+		]
 		fn vest_other(origin, target: <T::Lookup as StaticLookup>::Source) -> DispatchResult {
 			ensure_signed(origin)?;
 			Self::update_lock(T::Lookup::lookup(target)?)
@@ -233,6 +322,9 @@ decl_module! {
 		///
 		/// The dispatch origin for this call must be _Signed_.
 		///
+		/// The schedule is added to the schedules `target` already has, if it has less than
+		/// `MaxVestingSchedules` of them.
+		///
 		/// - `target`: The account that should be transferred the vested funds.
 		/// - `amount`: The amount of funds to transfer and will be vested.
 		/// - `schedule`: The vesting schedule attached to the transfer.
@@ -246,31 +338,29 @@ decl_module! {
 		///     - Writes: Vesting Storage, Balances Locks, Target Account, [Sender Account]
 		/// - Benchmark: 100.3 + .365 * l µs (min square analysis)
 		/// - Using 100 µs fixed. Assuming less than 50 locks on any user, else we may want factor in number of locks.
+		/// - Plus 1 µs per vesting schedule, assuming the maximum number of schedules.
 		/// # </weight>
-		#[weight = 100_000_000 + T::DbWeight::get().reads_writes(3, 3)]
+		#[weight = 100_000_000
+			+ 1_000_000 * T::MaxVestingSchedules::get() as Weight
+			+ T::DbWeight::get().reads_writes(3, 3)
+		]
 		pub fn vested_transfer(
 			origin,
 			target: <T::Lookup as StaticLookup>::Source,
 			schedule: VestingInfo<BalanceOf<T>, T::BlockNumber>,
 		) -> DispatchResult {
 			let transactor = ensure_signed(origin)?;
-			ensure!(schedule.locked >= T::MinVestedTransfer::get(), Error::<T>::AmountLow);
-
 			let who = T::Lookup::lookup(target)?;
-			ensure!(!Vesting::<T>::contains_key(&who), Error::<T>::ExistingVestingSchedule);
-
-			T::Currency::transfer(&transactor, &who, schedule.locked, ExistenceRequirement::AllowDeath)?;
-
-			Self::add_vesting_schedule(&who, schedule.locked, schedule.per_block, schedule.starting_block)
-				.expect("user does not have an existing vesting schedule; q.e.d.");
-
-			Ok(())
+			Self::do_vested_transfer(transactor, who, schedule)
 		}
 
 		/// Force a vested transfer.
 		///
 		/// The dispatch origin for this call must be _Root_.
 		///
+		/// The schedule is added to the schedules `target` already has, if it has less than
+		/// `MaxVestingSchedules` of them.
+		///
 		/// - `source`: The account whose funds should be transferred.
 		/// - `target`: The account that should be transferred the vested funds.
 		/// - `amount`: The amount of funds to transfer and will be vested.
@@ -285,8 +375,12 @@ decl_module! {
 		///     - Writes: Vesting Storage, Balances Locks, Target Account, Source Account
 		/// - Benchmark: 100.3 + .365 * l µs (min square analysis)
 		/// - Using 100 µs fixed. Assuming less than 50 locks on any user, else we may want factor in number of locks.
+		/// - Plus 1 µs per vesting schedule, assuming the maximum number of schedules.
 		/// # </weight>
-		#[weight = 100_000_000 + T::DbWeight::get().reads_writes(4, 4)]
+		#[weight = 100_000_000
+			+ 1_000_000 * T::MaxVestingSchedules::get() as Weight
+			+ T::DbWeight::get().reads_writes(4, 4)
+		]
 		pub fn force_vested_transfer(
 			origin,
 			source: <T::Lookup as StaticLookup>::Source,
@@ -294,41 +388,161 @@ decl_module! {
 			schedule: VestingInfo<BalanceOf<T>, T::BlockNumber>,
 		) -> DispatchResult {
 			ensure_root(origin)?;
-			ensure!(schedule.locked >= T::MinVestedTransfer::get(), Error::<T>::AmountLow);
-
 			let target = T::Lookup::lookup(target)?;
 			let source = T::Lookup::lookup(source)?;
-			ensure!(!Vesting::<T>::contains_key(&target), Error::<T>::ExistingVestingSchedule);
+			Self::do_vested_transfer(source, target, schedule)
+		}
 
-			T::Currency::transfer(&source, &target, schedule.locked, ExistenceRequirement::AllowDeath)?;
+		/// Merge two vesting schedules of the sender into one, freeing a schedule slot.
+		///
+		/// The merged schedule starts at the later of the current block and the starting blocks
+		/// of both schedules, and ends when the later of the two would have ended. It locks what
+		/// is still locked by both of them. Schedules that have already fully vested are dropped
+		/// rather than merged. The merged schedule is appended after the remaining schedules.
+		///
+		/// The dispatch origin for this call must be _Signed_ and the sender must have funds still
+		/// locked under this module.
+		///
+		/// - `schedule1_index`: Index of the first schedule to merge.
+		/// - `schedule2_index`: Index of the second schedule to merge.
+		///
+		/// Emits either `VestingCompleted` or `VestingUpdated`.
+		///
+		/// # <weight>
+		/// - `O(S)` where `S` is the number of schedules, bounded by `MaxVestingSchedules`.
+		/// - DbWeight: 2 Reads, 2 Writes
+		///     - Reads: Vesting Storage, Balances Locks, [Sender Account]
+		///     - Writes: Vesting Storage, Balances Locks, [Sender Account]
+		/// # </weight>
+		#[weight = 60_000_000
+			+ 1_000_000 * T::MaxVestingSchedules::get() as Weight
+			+ T::DbWeight::get().reads_writes(2, 2)
+		]
+		fn merge_schedules(origin, schedule1_index: u32, schedule2_index: u32) -> DispatchResult {
+			let who = ensure_signed(origin)?;
+			if schedule1_index == schedule2_index {
+				return Ok(());
+			}
 
-			Self::add_vesting_schedule(&target, schedule.locked, schedule.per_block, schedule.starting_block)
-				.expect("user does not have an existing vesting schedule; q.e.d.");
+			let mut schedules = Self::vesting(&who).ok_or(Error::<T>::NotVesting)?;
+			let (first, last) = if schedule1_index < schedule2_index {
+				(schedule1_index as usize, schedule2_index as usize)
+			} else {
+				(schedule2_index as usize, schedule1_index as usize)
+			};
+			ensure!(last < schedules.len(), Error::<T>::ScheduleIndexOutOfBounds);
+
+			// Remove the last one first, so that the index of the first one stays valid.
+			let schedule2 = schedules.remove(last);
+			let schedule1 = schedules.remove(first);
+			let now = <frame_system::Module<T>>::block_number();
+			if let Some(merged) = Self::merge_vesting_info(now, schedule1, schedule2) {
+				schedules.push(merged);
+			}
+			Vesting::<T>::insert(&who, schedules);
 
-			Ok(())
+			Self::update_lock(who)
 		}
 	}
 }
 
+/// Migrate from a single `VestingInfo` per account to a list of them.
+fn migrate_to_multiple_schedules<T: Trait>() -> Weight {
+	if StorageVersion::get() != Releases::V1 {
+		return 0;
+	}
+
+	Vesting::<T>::translate::<VestingInfo<BalanceOf<T>, T::BlockNumber>, _>(|_, schedule| {
+		Some(vec![schedule])
+	});
+	StorageVersion::put(Releases::V2);
+
+	let accounts = Vesting::<T>::iter().count() as Weight;
+	T::DbWeight::get().reads_writes(2 * accounts + 1, accounts + 1)
+}
+
 impl<T: Trait> Module<T> {
 	/// (Re)set or remove the module's currency lock on `who`'s account in accordance with their
-	/// current unvested amount.
+	/// current unvested amount, dropping the schedules that have fully vested.
 	fn update_lock(who: T::AccountId) -> DispatchResult {
-		let vesting = Self::vesting(&who).ok_or(Error::<T>::NotVesting)?;
+		let schedules = Self::vesting(&who).ok_or(Error::<T>::NotVesting)?;
 		let now = <frame_system::Module<T>>::block_number();
-		let locked_now = vesting.locked_at::<T::BlockNumberToBalance>(now);
-
-		if locked_now.is_zero() {
+		let mut locked_now = BalanceOf::<T>::zero();
+		let schedules = schedules.into_iter()
+			.filter(|schedule| {
+				let locked = schedule.locked_at::<T::BlockNumberToBalance>(now);
+				locked_now = locked_now.saturating_add(locked);
+				!locked.is_zero()
+			})
+			.collect::<Vec<_>>();
+
+		if schedules.is_empty() {
 			T::Currency::remove_lock(VESTING_ID, &who);
 			Vesting::<T>::remove(&who);
 			Self::deposit_event(RawEvent::VestingCompleted(who));
 		} else {
+			Vesting::<T>::insert(&who, schedules);
 			let reasons = WithdrawReason::Transfer | WithdrawReason::Reserve;
 			T::Currency::set_lock(VESTING_ID, &who, locked_now, reasons);
 			Self::deposit_event(RawEvent::VestingUpdated(who, locked_now));
 		}
 		Ok(())
 	}
+
+	/// Transfer `schedule.locked` from `source` to `target` and add `schedule` to `target`.
+	fn do_vested_transfer(
+		source: T::AccountId,
+		target: T::AccountId,
+		schedule: VestingInfo<BalanceOf<T>, T::BlockNumber>,
+	) -> DispatchResult {
+		ensure!(schedule.locked >= T::MinVestedTransfer::get(), Error::<T>::AmountLow);
+		ensure!(!schedule.per_block.is_zero(), Error::<T>::InvalidScheduleParams);
+		let schedules = Self::vesting(&target).map_or(0, |schedules| schedules.len());
+		ensure!(
+			schedules < T::MaxVestingSchedules::get() as usize,
+			Error::<T>::AtMaxVestingSchedules,
+		);
+
+		T::Currency::transfer(&source, &target, schedule.locked, ExistenceRequirement::AllowDeath)?;
+
+		Self::add_vesting_schedule(&target, schedule.locked, schedule.per_block, schedule.starting_block)
+			.expect("user has less than the maximum number of vesting schedules; q.e.d.");
+
+		Ok(())
+	}
+
+	/// Merge two vesting schedules into one that locks what both still lock at `now`.
+	///
+	/// Returns `None` if both schedules have fully vested, and the other schedule unchanged if
+	/// only one of them has.
+	fn merge_vesting_info(
+		now: T::BlockNumber,
+		schedule1: VestingInfo<BalanceOf<T>, T::BlockNumber>,
+		schedule2: VestingInfo<BalanceOf<T>, T::BlockNumber>,
+	) -> Option<VestingInfo<BalanceOf<T>, T::BlockNumber>> {
+		let now_as_balance = T::BlockNumberToBalance::convert(now);
+		let schedule1_ending = schedule1.ending_block_as_balance::<T::BlockNumberToBalance>();
+		let schedule2_ending = schedule2.ending_block_as_balance::<T::BlockNumberToBalance>();
+		match (schedule1_ending <= now_as_balance, schedule2_ending <= now_as_balance) {
+			(true, true) => return None,
+			(true, false) => return Some(schedule2),
+			(false, true) => return Some(schedule1),
+			(false, false) => {},
+		}
+
+		let locked = schedule1.locked_at::<T::BlockNumberToBalance>(now)
+			.saturating_add(schedule2.locked_at::<T::BlockNumberToBalance>(now));
+		let starting_block = now.max(schedule1.starting_block).max(schedule2.starting_block);
+		let ending = schedule1_ending.max(schedule2_ending);
+		let duration = ending.saturating_sub(T::BlockNumberToBalance::convert(starting_block));
+		let per_block = if duration.is_zero() {
+			locked
+		} else {
+			(locked / duration).max(One::one())
+		};
+
+		Some(VestingInfo { locked, per_block, starting_block })
+	}
 }
 
 impl<T: Trait> VestingSchedule<T::AccountId> for Module<T> where
@@ -338,10 +552,14 @@ impl<T: Trait> VestingSchedule<T::AccountId> for Module<T> where
 	type Currency = T::Currency;
 
 	/// Get the amount that is currently being vested and cannot be transferred out of this account.
+	///
+	/// This is the sum of the amounts still locked by all schedules of the account.
 	fn vesting_balance(who: &T::AccountId) -> Option<BalanceOf<T>> {
-		if let Some(v) = Self::vesting(who) {
+		if let Some(schedules) = Self::vesting(who) {
 			let now = <frame_system::Module<T>>::block_number();
-			let locked_now = v.locked_at::<T::BlockNumberToBalance>(now);
+			let locked_now = schedules.iter().fold(Zero::zero(), |total: BalanceOf<T>, schedule| {
+				total.saturating_add(schedule.locked_at::<T::BlockNumberToBalance>(now))
+			});
 			Some(T::Currency::free_balance(who).min(locked_now))
 		} else {
 			None
@@ -350,7 +568,7 @@ impl<T: Trait> VestingSchedule<T::AccountId> for Module<T> where
 
 	/// Adds a vesting schedule to a given account.
 	///
-	/// If there already exists a vesting schedule for the given account, an `Err` is returned
+	/// If the account already has `MaxVestingSchedules` vesting schedules, an `Err` is returned
 	/// and nothing is updated.
 	///
 	/// On success, a linearly reducing amount of funds will be locked. In order to realise any
@@ -365,21 +583,26 @@ impl<T: Trait> VestingSchedule<T::AccountId> for Module<T> where
 		starting_block: T::BlockNumber
 	) -> DispatchResult {
 		if locked.is_zero() { return Ok(()) }
-		if Vesting::<T>::contains_key(who) {
-			Err(Error::<T>::ExistingVestingSchedule)?
-		}
 		let vesting_schedule = VestingInfo {
 			locked,
 			per_block,
 			starting_block
 		};
-		Vesting::<T>::insert(who, vesting_schedule);
+		Vesting::<T>::try_mutate(who, |schedules| -> DispatchResult {
+			let schedules = schedules.get_or_insert_with(Vec::new);
+			ensure!(
+				schedules.len() < T::MaxVestingSchedules::get() as usize,
+				Error::<T>::AtMaxVestingSchedules,
+			);
+			schedules.push(vesting_schedule);
+			Ok(())
+		})?;
 		// it can't fail, but even if somehow it did, we don't really care.
 		let _ = Self::update_lock(who.clone());
 		Ok(())
 	}
 
-	/// Remove a vesting schedule for a given account.
+	/// Remove all vesting schedules of a given account.
 	fn remove_vesting_schedule(who: &T::AccountId) {
 		Vesting::<T>::remove(who);
 		// it can't fail, but even if somehow it did, we don't really care.
@@ -456,12 +679,14 @@ mod tests {
 	}
 	parameter_types! {
 		pub const MinVestedTransfer: u64 = 256 * 2;
+		pub const MaxVestingSchedules: u32 = 3;
 	}
 	impl Trait for Test {
 		type Event = ();
 		type Currency = Balances;
 		type BlockNumberToBalance = Identity;
 		type MinVestedTransfer = MinVestedTransfer;
+		type MaxVestingSchedules = MaxVestingSchedules;
 	}
 	type System = frame_system::Module<Test>;
 	type Balances = pallet_balances::Module<Test>;
@@ -542,9 +767,9 @@ mod tests {
 					per_block: 64, // Vesting over 20 blocks
 					starting_block: 10,
 				};
-				assert_eq!(Vesting::vesting(&1), Some(user1_vesting_schedule)); // Account 1 has a vesting schedule
-				assert_eq!(Vesting::vesting(&2), Some(user2_vesting_schedule)); // Account 2 has a vesting schedule
-				assert_eq!(Vesting::vesting(&12), Some(user12_vesting_schedule)); // Account 12 has a vesting schedule
+				assert_eq!(Vesting::vesting(&1), Some(vec![user1_vesting_schedule])); // Account 1 has a vesting schedule
+				assert_eq!(Vesting::vesting(&2), Some(vec![user2_vesting_schedule])); // Account 2 has a vesting schedule
+				assert_eq!(Vesting::vesting(&12), Some(vec![user12_vesting_schedule])); // Account 12 has a vesting schedule
 
 				// Account 1 has only 128 units vested from their illiquid 256 * 5 units at block 1
 				assert_eq!(Vesting::vesting_balance(&1), Some(128 * 9));
@@ -665,7 +890,7 @@ mod tests {
 					per_block: 64, // Vesting over 20 blocks
 					starting_block: 10,
 				};
-				assert_eq!(Vesting::vesting(&12), Some(user12_vesting_schedule));
+				assert_eq!(Vesting::vesting(&12), Some(vec![user12_vesting_schedule]));
 
 				// Account 12 can still send liquid funds
 				assert_ok!(Balances::transfer(Some(12).into(), 3, 256 * 5));
@@ -692,7 +917,7 @@ mod tests {
 				};
 				assert_ok!(Vesting::vested_transfer(Some(3).into(), 4, new_vesting_schedule));
 				// Now account 4 should have vesting.
-				assert_eq!(Vesting::vesting(&4), Some(new_vesting_schedule));
+				assert_eq!(Vesting::vesting(&4), Some(vec![new_vesting_schedule]));
 				// Ensure the transfer happened correctly.
 				let user3_free_balance_updated = Balances::free_balance(&3);
 				assert_eq!(user3_free_balance_updated, 256 * 25);
@@ -731,17 +956,33 @@ mod tests {
 					per_block: 256, // Vesting over 20 blocks
 					starting_block: 10,
 				};
-				assert_eq!(Vesting::vesting(&2), Some(user2_vesting_schedule));
+				assert_eq!(Vesting::vesting(&2), Some(vec![user2_vesting_schedule]));
 
-				// The vesting schedule we will try to create, fails due to pre-existence of schedule.
+				// The vesting schedule we will try to create, fails due to too many schedules.
 				let new_vesting_schedule = VestingInfo {
 					locked: 256 * 5,
 					per_block: 64, // Vesting over 20 blocks
 					starting_block: 10,
 				};
+				super::Vesting::<Test>::mutate(&2, |schedules| {
+					let schedules = schedules.as_mut().unwrap();
+					schedules.push(user2_vesting_schedule);
+					schedules.push(user2_vesting_schedule);
+				});
 				assert_noop!(
 					Vesting::vested_transfer(Some(4).into(), 2, new_vesting_schedule),
-					Error::<Test>::ExistingVestingSchedule,
+					Error::<Test>::AtMaxVestingSchedules,
+				);
+
+				// Fails due to a schedule that never vests.
+				let new_vesting_schedule_never_vests = VestingInfo {
+					locked: 256 * 5,
+					per_block: 0,
+					starting_block: 10,
+				};
+				assert_noop!(
+					Vesting::vested_transfer(Some(3).into(), 4, new_vesting_schedule_never_vests),
+					Error::<Test>::InvalidScheduleParams,
 				);
 
 				// Fails due to too low transfer amount.
@@ -782,7 +1023,7 @@ mod tests {
 				assert_noop!(Vesting::force_vested_transfer(Some(4).into(), 3, 4, new_vesting_schedule), BadOrigin);
 				assert_ok!(Vesting::force_vested_transfer(RawOrigin::Root.into(), 3, 4, new_vesting_schedule));
 				// Now account 4 should have vesting.
-				assert_eq!(Vesting::vesting(&4), Some(new_vesting_schedule));
+				assert_eq!(Vesting::vesting(&4), Some(vec![new_vesting_schedule]));
 				// Ensure the transfer happened correctly.
 				let user3_free_balance_updated = Balances::free_balance(&3);
 				assert_eq!(user3_free_balance_updated, 256 * 25);
@@ -821,17 +1062,22 @@ mod tests {
 					per_block: 256, // Vesting over 20 blocks
 					starting_block: 10,
 				};
-				assert_eq!(Vesting::vesting(&2), Some(user2_vesting_schedule));
+				assert_eq!(Vesting::vesting(&2), Some(vec![user2_vesting_schedule]));
 
-				// The vesting schedule we will try to create, fails due to pre-existence of schedule.
+				// The vesting schedule we will try to create, fails due to too many schedules.
 				let new_vesting_schedule = VestingInfo {
 					locked: 256 * 5,
 					per_block: 64, // Vesting over 20 blocks
 					starting_block: 10,
 				};
+				super::Vesting::<Test>::mutate(&2, |schedules| {
+					let schedules = schedules.as_mut().unwrap();
+					schedules.push(user2_vesting_schedule);
+					schedules.push(user2_vesting_schedule);
+				});
 				assert_noop!(
 					Vesting::force_vested_transfer(RawOrigin::Root.into(), 4, 2, new_vesting_schedule),
-					Error::<Test>::ExistingVestingSchedule,
+					Error::<Test>::AtMaxVestingSchedules,
 				);
 
 				// Fails due to too low transfer amount.
@@ -850,4 +1096,215 @@ mod tests {
 				assert_eq!(user4_free_balance, 256 * 40);
 			});
 	}
+
+	#[test]
+	fn vested_transfer_adds_to_existing_schedules() {
+		ExtBuilder::default()
+			.existential_deposit(256)
+			.build()
+			.execute_with(|| {
+				let user2_vesting_schedule = VestingInfo {
+					locked: 256 * 20,
+					per_block: 256, // Vesting over 20 blocks
+					starting_block: 10,
+				};
+				let new_vesting_schedule = VestingInfo {
+					locked: 256 * 5,
+					per_block: 256, // Vesting over 5 blocks
+					starting_block: 0,
+				};
+				assert_ok!(Vesting::vested_transfer(Some(3).into(), 2, new_vesting_schedule));
+				assert_eq!(
+					Vesting::vesting(&2),
+					Some(vec![user2_vesting_schedule, new_vesting_schedule]),
+				);
+				// Both schedules are locked at block 1, except what the new one vested.
+				assert_eq!(Vesting::vesting_balance(&2), Some(256 * 20 + 256 * 4));
+
+				// The new schedule has fully vested by block 5 and is dropped.
+				System::set_block_number(5);
+				assert_ok!(Vesting::vest(Some(2).into()));
+				assert_eq!(Vesting::vesting(&2), Some(vec![user2_vesting_schedule]));
+				assert_eq!(Vesting::vesting_balance(&2), Some(256 * 20));
+				assert_ok!(Balances::transfer(Some(2).into(), 3, 256 * 5));
+
+				System::set_block_number(30);
+				assert_ok!(Vesting::vest(Some(2).into()));
+				assert_eq!(Vesting::vesting(&2), None);
+			});
+	}
+
+	#[test]
+	fn merge_schedules_works() {
+		ExtBuilder::default()
+			.existential_deposit(256)
+			.build()
+			.execute_with(|| {
+				let user2_vesting_schedule = VestingInfo {
+					locked: 256 * 20,
+					per_block: 256, // Vesting over 20 blocks, until block 30
+					starting_block: 10,
+				};
+				let new_vesting_schedule = VestingInfo {
+					locked: 256 * 10,
+					per_block: 256, // Vesting over 10 blocks, until block 11
+					starting_block: 1,
+				};
+				assert_ok!(Vesting::vested_transfer(Some(3).into(), 2, new_vesting_schedule));
+
+				assert_noop!(
+					Vesting::merge_schedules(Some(2).into(), 0, 2),
+					Error::<Test>::ScheduleIndexOutOfBounds,
+				);
+				assert_noop!(
+					Vesting::merge_schedules(Some(4).into(), 0, 1),
+					Error::<Test>::NotVesting,
+				);
+
+				System::set_block_number(6);
+				let locked_before = Vesting::vesting_balance(&2);
+				assert_ok!(Vesting::merge_schedules(Some(2).into(), 1, 0));
+				// Merging does not unlock anything: 256 * 20 + 256 * 5 are still locked.
+				assert_eq!(locked_before, Some(256 * 25));
+				assert_eq!(Vesting::vesting_balance(&2), Some(256 * 25));
+				// The merged schedule starts at block 10 and ends at block 30.
+				assert_eq!(Vesting::vesting(&2), Some(vec![VestingInfo {
+					locked: 256 * 25,
+					per_block: 256 * 25 / 20,
+					starting_block: 10,
+				}]));
+
+				System::set_block_number(30);
+				assert_eq!(Vesting::vesting_balance(&2), Some(0));
+			});
+	}
+
+	#[test]
+	fn merge_schedules_drops_vested_schedules() {
+		ExtBuilder::default()
+			.existential_deposit(256)
+			.build()
+			.execute_with(|| {
+				let user2_vesting_schedule = VestingInfo {
+					locked: 256 * 20,
+					per_block: 256,
+					starting_block: 10,
+				};
+				let new_vesting_schedule = VestingInfo {
+					locked: 256 * 5,
+					per_block: 256, // Vesting until block 5
+					starting_block: 0,
+				};
+				assert_ok!(Vesting::vested_transfer(Some(3).into(), 2, new_vesting_schedule));
+
+				System::set_block_number(5);
+				assert_ok!(Vesting::merge_schedules(Some(2).into(), 0, 1));
+				assert_eq!(Vesting::vesting(&2), Some(vec![user2_vesting_schedule]));
+			});
+	}
+
+	#[test]
+	fn merge_schedules_handles_schedules_that_never_vest() {
+		ExtBuilder::default()
+			.existential_deposit(256)
+			.build()
+			.execute_with(|| {
+				// Genesis gives schedules which unlock nothing per block to accounts whose
+				// locked balance is smaller than the vesting length.
+				super::Vesting::<Test>::mutate(&2, |schedules| {
+					schedules.as_mut().unwrap().push(VestingInfo {
+						locked: 256,
+						per_block: 0,
+						starting_block: 0,
+					});
+				});
+
+				System::set_block_number(5);
+				assert_ok!(Vesting::merge_schedules(Some(2).into(), 0, 1));
+				assert_eq!(Vesting::vesting(&2), Some(vec![VestingInfo {
+					locked: 256 * 21,
+					per_block: 1,
+					starting_block: 10,
+				}]));
+			});
+	}
+
+	#[test]
+	fn genesis_supports_multiple_schedules() {
+		EXISTENTIAL_DEPOSIT.with(|v| *v.borrow_mut() = 1);
+		let mut t = frame_system::GenesisConfig::default().build_storage::<Test>().unwrap();
+		pallet_balances::GenesisConfig::<Test> {
+			balances: vec![(1, 1000)],
+		}.assimilate_storage(&mut t).unwrap();
+		GenesisConfig::<Test> {
+			vesting: vec![
+				// 400 sold, vesting over 10 blocks.
+				(1, 0, 10, 600),
+				// 600 for the team, vesting over 100 blocks after a cliff.
+				(1, 50, 100, 400),
+			],
+		}.assimilate_storage(&mut t).unwrap();
+
+		sp_io::TestExternalities::new(t).execute_with(|| {
+			assert_eq!(Vesting::vesting(&1), Some(vec![
+				VestingInfo { locked: 400, per_block: 40, starting_block: 0 },
+				VestingInfo { locked: 600, per_block: 6, starting_block: 50 },
+			]));
+			assert_eq!(Vesting::vesting_balance(&1), Some(1000));
+
+			System::set_block_number(10);
+			assert_eq!(Vesting::vesting_balance(&1), Some(600));
+			assert_ok!(Vesting::vest(Some(1).into()));
+			assert_ok!(Balances::transfer(Some(1).into(), 2, 400));
+		});
+	}
+
+	#[test]
+	fn migration_to_multiple_schedules_works() {
+		ExtBuilder::default()
+			.existential_deposit(256)
+			.build()
+			.execute_with(|| {
+				// Replace the schedules of the genesis, stored in the new layout, with schedules
+				// in the layout before the migration.
+				for who in &[1, 2, 12] {
+					super::Vesting::<Test>::remove(who);
+				}
+				StorageVersion::put(Releases::V1);
+				let schedule1 = VestingInfo {
+					locked: 256 * 5,
+					per_block: 128,
+					starting_block: 0,
+				};
+				let schedule4 = VestingInfo {
+					locked: 256 * 5,
+					per_block: 64,
+					starting_block: 10,
+				};
+				frame_support::storage::unhashed::put(
+					&super::Vesting::<Test>::hashed_key_for(&1),
+					&schedule1,
+				);
+				frame_support::storage::unhashed::put(
+					&super::Vesting::<Test>::hashed_key_for(&4),
+					&schedule4,
+				);
+
+				migrate_to_multiple_schedules::<Test>();
+
+				assert_eq!(StorageVersion::get(), Releases::V2);
+				assert_eq!(Vesting::vesting(&1), Some(vec![VestingInfo {
+					locked: 256 * 5,
+					per_block: 128,
+					starting_block: 0,
+				}]));
+				assert_eq!(Vesting::vesting(&4), Some(vec![VestingInfo {
+					locked: 256 * 5,
+					per_block: 64,
+					starting_block: 10,
+				}]));
+				assert_eq!(Vesting::vesting(&2), None);
+				assert_eq!(Vesting::vesting(&12), None);
+			});
+	}
 }
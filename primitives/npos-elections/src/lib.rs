@@ -317,6 +317,62 @@ pub struct Support<AccountId> {
 /// A linkage from a candidate and its [`Support`].
 pub type SupportMap<A> = BTreeMap<A, Support<A>>;
 
+/// Something that provides the data needed to run an election, typically the staking system.
+pub trait ElectionDataProvider<AccountId, BlockNumber> {
+	/// All the candidates of the election.
+	fn targets() -> Vec<AccountId>;
+
+	/// All the voters of the election, with their vote weight and the targets they vote for.
+	fn voters() -> Vec<(AccountId, VoteWeight, Vec<AccountId>)>;
+
+	/// The number of candidates to elect.
+	fn desired_targets() -> u32;
+
+	/// A best-effort prediction of the block at which the next election result will be needed,
+	/// given the current block `now`.
+	fn next_election_prediction(now: BlockNumber) -> BlockNumber;
+
+	/// The weight of calling [`Self::targets`], [`Self::voters`] and [`Self::desired_targets`]
+	/// once, given the number of targets and voters they returned.
+	fn weight_of_data(targets: u32, voters: u32) -> u64;
+}
+
+/// An election result handed out by an [`ElectionProvider`].
+pub struct ProvidedElection<AccountId> {
+	/// The elected candidates.
+	pub winners: Vec<AccountId>,
+	/// The support of each of the winners.
+	pub supports: SupportMap<AccountId>,
+	/// Whether the result was submitted via a signed transaction, rather than an unsigned one.
+	pub signed: bool,
+}
+
+/// Something that computes election results on behalf of the consumer of the election.
+pub trait ElectionProvider<AccountId> {
+	/// Take the result of the current election, if one is ready. This ends the election round.
+	///
+	/// Must be called once per election by the consumer, even if it does not use the result, so
+	/// that the provider can start its next round. Returning `None` means the consumer has to come
+	/// up with a result itself.
+	fn elect() -> Option<ProvidedElection<AccountId>>;
+
+	/// Whether this provider runs elections at all. A consumer can turn off its own mechanisms for
+	/// collecting solutions when it does.
+	fn is_active() -> bool {
+		true
+	}
+}
+
+impl<AccountId> ElectionProvider<AccountId> for () {
+	fn elect() -> Option<ProvidedElection<AccountId>> {
+		None
+	}
+
+	fn is_active() -> bool {
+		false
+	}
+}
+
 /// Perform election based on Phragmén algorithm.
 ///
 /// Returns an `Option` the set of winners and their detailed support ratio from each voter if